@@ -0,0 +1,236 @@
+// Postgres advisory locks held for the lifetime of a guard.
+//
+// Advisory locks belong to the database session that took them, so the guard
+// keeps that session's connection checked out until the lock is released.
+// Dropping the guard without calling `release` (an early return or `?`)
+// still unlocks: the unlock is spawned onto the runtime, on the same session.
+// If the unlock fails the session is closed instead of going back to the pool,
+// since a pooled connection still holding the lock would block every other
+// taker until it happened to be closed.
+
+use async_trait::async_trait;
+use sqlx::{pool::PoolConnection, PgPool, Postgres};
+
+use crate::error::{db_internal_error, ApiResult};
+
+/// Key serializing writes to `migration_scripts`
+pub const MIGRATION_LOCK_KEY: i64 = 7_361_942_001;
+/// Key held by whichever instance is running audit log retention
+pub const AUDIT_RETENTION_LOCK_KEY: i64 = 7_361_942_002;
+
+/// A database session advisory locks can be taken and released on
+#[async_trait]
+pub trait LockSession: Send + 'static {
+    /// Take `key` without waiting; `false` when another session holds it
+    async fn try_lock(&mut self, key: i64) -> Result<bool, sqlx::Error>;
+    async fn unlock(&mut self, key: i64) -> Result<(), sqlx::Error>;
+    /// End the session, which drops any lock it still holds
+    async fn close(self);
+}
+
+#[async_trait]
+impl LockSession for PoolConnection<Postgres> {
+    async fn try_lock(&mut self, key: i64) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut **self)
+            .await
+    }
+
+    async fn unlock(&mut self, key: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(key)
+            .execute(&mut **self)
+            .await
+            .map(|_| ())
+    }
+
+    async fn close(self) {
+        // A failed close still drops the connection rather than pooling it
+        if let Err(err) = PoolConnection::close(self).await {
+            tracing::warn!(error = ?err, "failed to close advisory lock connection");
+        }
+    }
+}
+
+/// Unlock `key`, closing the session when that fails
+async fn unlock_or_close<S: LockSession>(mut session: S, key: i64) -> Result<(), sqlx::Error> {
+    let result = session.unlock(key).await;
+    if result.is_err() {
+        session.close().await;
+    }
+    result
+}
+
+/// Holds an advisory lock until released or dropped
+pub struct AdvisoryLockGuard<S: LockSession = PoolConnection<Postgres>> {
+    key: i64,
+    session: Option<S>,
+}
+
+impl AdvisoryLockGuard {
+    /// Take `key` on a pooled connection; `None` when it is already held
+    pub async fn acquire(pool: &PgPool, key: i64) -> ApiResult<Option<Self>> {
+        let conn = pool
+            .acquire()
+            .await
+            .map_err(|err| db_internal_error("acquire connection for advisory lock", err))?;
+        Self::try_acquire(conn, key)
+            .await
+            .map_err(|err| db_internal_error("take advisory lock", err))
+    }
+}
+
+impl<S: LockSession> AdvisoryLockGuard<S> {
+    /// Take `key` on `session`; `None` when another session holds it
+    pub async fn try_acquire(mut session: S, key: i64) -> Result<Option<Self>, sqlx::Error> {
+        if !session.try_lock(key).await? {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            key,
+            session: Some(session),
+        }))
+    }
+
+    /// Release now rather than on drop, surfacing any unlock error
+    pub async fn release(mut self) -> Result<(), sqlx::Error> {
+        match self.session.take() {
+            Some(session) => unlock_or_close(session, self.key).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S: LockSession> Drop for AdvisoryLockGuard<S> {
+    fn drop(&mut self) {
+        let Some(session) = self.session.take() else {
+            return;
+        };
+        let key = self.key;
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = unlock_or_close(session, key).await {
+                        tracing::error!(key, error = ?err, "failed to release advisory lock");
+                    }
+                });
+            }
+            // Without a runtime the session is dropped, which ends it and
+            // with it the lock
+            Err(_) => tracing::warn!(key, "advisory lock dropped outside a runtime"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    /// Sessions sharing one set of held keys, like connections to one server
+    #[derive(Clone, Default)]
+    struct FakeSession {
+        held: Arc<Mutex<HashSet<i64>>>,
+        /// Keys this session took
+        taken: Vec<i64>,
+        fail_unlock: bool,
+    }
+
+    #[async_trait]
+    impl LockSession for FakeSession {
+        async fn try_lock(&mut self, key: i64) -> Result<bool, sqlx::Error> {
+            let locked = self.held.lock().unwrap().insert(key);
+            if locked {
+                self.taken.push(key);
+            }
+            Ok(locked)
+        }
+
+        async fn unlock(&mut self, key: i64) -> Result<(), sqlx::Error> {
+            if self.fail_unlock {
+                return Err(sqlx::Error::PoolTimedOut);
+            }
+            self.held.lock().unwrap().remove(&key);
+            Ok(())
+        }
+
+        async fn close(self) {
+            let mut held = self.held.lock().unwrap();
+            for key in &self.taken {
+                held.remove(key);
+            }
+        }
+    }
+
+    async fn failing_operation(session: FakeSession) -> Result<(), String> {
+        let _guard = AdvisoryLockGuard::try_acquire(session, MIGRATION_LOCK_KEY)
+            .await
+            .unwrap()
+            .expect("lock should be free");
+        Err("checksum mismatch".to_string())
+    }
+
+    async fn wait_for_release(session: &FakeSession) {
+        for _ in 0..100 {
+            if !session.held.lock().unwrap().contains(&MIGRATION_LOCK_KEY) {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("advisory lock was never released");
+    }
+
+    #[tokio::test]
+    async fn lock_is_released_when_the_operation_fails() {
+        let server = FakeSession::default();
+        assert!(failing_operation(server.clone()).await.is_err());
+        wait_for_release(&server).await;
+
+        let retry = AdvisoryLockGuard::try_acquire(server.clone(), MIGRATION_LOCK_KEY)
+            .await
+            .unwrap();
+        assert!(retry.is_some());
+    }
+
+    #[tokio::test]
+    async fn second_holder_is_refused_until_release() {
+        let server = FakeSession::default();
+        let first = AdvisoryLockGuard::try_acquire(server.clone(), MIGRATION_LOCK_KEY)
+            .await
+            .unwrap()
+            .unwrap();
+        let second = AdvisoryLockGuard::try_acquire(server.clone(), MIGRATION_LOCK_KEY)
+            .await
+            .unwrap();
+        assert!(second.is_none());
+
+        first.release().await.unwrap();
+        assert!(server.held.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_unlock_closes_the_session() {
+        let server = FakeSession::default();
+        let broken = FakeSession {
+            fail_unlock: true,
+            ..server.clone()
+        };
+
+        let guard = AdvisoryLockGuard::try_acquire(broken.clone(), MIGRATION_LOCK_KEY)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(guard.release().await.is_err());
+        assert!(server.held.lock().unwrap().is_empty());
+
+        // Same when the guard is dropped
+        let guard = AdvisoryLockGuard::try_acquire(broken, MIGRATION_LOCK_KEY)
+            .await
+            .unwrap()
+            .unwrap();
+        drop(guard);
+        wait_for_release(&server).await;
+    }
+}
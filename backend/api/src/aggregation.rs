@@ -6,6 +6,7 @@ use std::time::Duration;
 /// Runs every hour:
 ///   1. Aggregate raw events into daily summaries (yesterday + today).
 ///   2. Delete raw events older than 90 days.
+///   3. Roll up contract interactions per day (yesterday + today).
 pub fn spawn_aggregation_task(pool: PgPool) {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(3600));
@@ -22,6 +23,10 @@ pub fn spawn_aggregation_task(pool: PgPool) {
                 tracing::error!(error = ?err, "aggregation: retention cleanup failed");
             }
 
+            if let Err(err) = run_interaction_aggregation(&pool).await {
+                tracing::error!(error = ?err, "aggregation: interaction rollup failed");
+            }
+
             if let Err(err) = run_custom_metrics_aggregation(&pool).await {
                 tracing::error!(error = ?err, "aggregation: custom metrics aggregation failed");
             }
@@ -141,6 +146,41 @@ async fn cleanup_old_events(pool: &PgPool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Roll up `contract_interactions` into per-contract daily counts.
+///
+/// Only the last two days are touched; older days are recomputed on demand
+/// by `interaction_aggregates::rebuild_contract_aggregates`.
+async fn run_interaction_aggregation(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let rows_affected = sqlx::query(
+        r#"
+        INSERT INTO contract_interaction_daily_aggregates (
+            contract_id, date, interaction_count, unique_users
+        )
+        SELECT
+            contract_id,
+            (created_at AT TIME ZONE 'UTC')::date AS agg_date,
+            COUNT(*) AS interaction_count,
+            COUNT(DISTINCT user_address) AS unique_users
+        FROM contract_interactions
+        WHERE created_at >= (CURRENT_DATE - INTERVAL '1 day') AT TIME ZONE 'UTC'
+        GROUP BY contract_id, (created_at AT TIME ZONE 'UTC')::date
+        ON CONFLICT (contract_id, date) DO UPDATE SET
+            interaction_count = EXCLUDED.interaction_count,
+            unique_users      = EXCLUDED.unique_users,
+            updated_at        = NOW()
+        "#,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    tracing::info!(
+        rows = rows_affected,
+        "aggregation: interaction rollups upserted"
+    );
+    Ok(())
+}
+
 /// Aggregate custom contract metrics into hourly and daily rollups.
 async fn run_custom_metrics_aggregation(pool: &PgPool) -> Result<(), sqlx::Error> {
     let hourly_rows = sqlx::query(
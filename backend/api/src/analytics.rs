@@ -1,23 +1,28 @@
-use shared::{AnalyticsEventType, Network};
+use shared::{AnalyticsEvent, AnalyticsEventType, Network};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-/// Record an analytics event.
+use crate::event_stream::EventHub;
+use crate::state::AppState;
+
+/// Record an analytics event and push it to live stream subscribers.
 ///
 /// This is intentionally fire-and-forget: callers should log errors but
 /// never let a failed analytics insert break the main request flow.
 pub async fn record_event(
     pool: &PgPool,
+    hub: &EventHub,
     event_type: AnalyticsEventType,
     contract_id: Uuid,
     user_address: Option<&str>,
     network: Option<&Network>,
     metadata: Option<serde_json::Value>,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    let event: AnalyticsEvent = sqlx::query_as(
         r#"
         INSERT INTO analytics_events (event_type, contract_id, user_address, network, metadata)
         VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, event_type, contract_id, user_address, network, metadata, created_at
         "#,
     )
     .bind(&event_type)
@@ -25,7 +30,7 @@ pub async fn record_event(
     .bind(user_address)
     .bind(network)
     .bind(metadata.unwrap_or(serde_json::json!({})))
-    .execute(pool)
+    .fetch_one(pool)
     .await?;
 
     tracing::debug!(
@@ -33,6 +38,163 @@ pub async fn record_event(
         contract = %contract_id,
         "analytics event recorded"
     );
+    hub.publish(event);
 
     Ok(())
 }
+
+/// Record an event in the background, logging failures instead of surfacing
+/// them to the request that triggered it.
+pub fn spawn_record_event(
+    state: &AppState,
+    event_type: AnalyticsEventType,
+    contract_id: Uuid,
+    user_address: Option<String>,
+    network: Option<Network>,
+    metadata: Option<serde_json::Value>,
+) {
+    let pool = state.db.clone();
+    let hub = state.events.clone();
+    tokio::spawn(async move {
+        if let Err(err) = record_event(
+            &pool,
+            &hub,
+            event_type,
+            contract_id,
+            user_address.as_deref(),
+            network.as_ref(),
+            metadata,
+        )
+        .await
+        {
+            tracing::warn!(contract = %contract_id, error = ?err, "failed to record analytics event");
+        }
+    });
+}
+
+/// Record one event per `(user_address, metadata)` pair with a single insert,
+/// pushing each to live stream subscribers. Used where a request produces
+/// many events at once, such as batch interaction ingest.
+pub async fn record_events(
+    pool: &PgPool,
+    hub: &EventHub,
+    event_type: AnalyticsEventType,
+    contract_id: Uuid,
+    network: Option<&Network>,
+    events: Vec<(Option<String>, serde_json::Value)>,
+) -> Result<(), sqlx::Error> {
+    let (user_addresses, metadata): (Vec<_>, Vec<_>) = events.into_iter().unzip();
+    let recorded: Vec<AnalyticsEvent> = sqlx::query_as(
+        r#"
+        INSERT INTO analytics_events (event_type, contract_id, user_address, network, metadata)
+        SELECT $1, $2, e.user_address, $3, e.metadata
+        FROM UNNEST($4::text[], $5::jsonb[]) AS e(user_address, metadata)
+        RETURNING id, event_type, contract_id, user_address, network, metadata, created_at
+        "#,
+    )
+    .bind(&event_type)
+    .bind(contract_id)
+    .bind(network)
+    .bind(&user_addresses)
+    .bind(&metadata)
+    .fetch_all(pool)
+    .await?;
+
+    tracing::debug!(
+        event = %event_type,
+        contract = %contract_id,
+        count = recorded.len(),
+        "analytics events recorded"
+    );
+    for event in recorded {
+        hub.publish(event);
+    }
+
+    Ok(())
+}
+
+/// Background counterpart of `record_events`, like `spawn_record_event`
+pub fn spawn_record_events(
+    state: &AppState,
+    event_type: AnalyticsEventType,
+    contract_id: Uuid,
+    network: Option<Network>,
+    events: Vec<(Option<String>, serde_json::Value)>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    let pool = state.db.clone();
+    let hub = state.events.clone();
+    tokio::spawn(async move {
+        if let Err(err) = record_events(
+            &pool,
+            &hub,
+            event_type,
+            contract_id,
+            network.as_ref(),
+            events,
+        )
+        .await
+        {
+            tracing::warn!(contract = %contract_id, error = ?err, "failed to record analytics events");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_db;
+
+    #[tokio::test]
+    async fn batch_records_every_event_and_streams_it() {
+        let Some(state) = test_db::state().await else {
+            return;
+        };
+        let publisher = test_db::insert_publisher(&state.db).await;
+        let contract = test_db::insert_contract(&state.db, publisher, "CBATCH").await;
+        let mut receiver = state.events.subscribe(contract);
+
+        record_events(
+            &state.db,
+            &state.events,
+            AnalyticsEventType::InteractionRecorded,
+            contract,
+            None,
+            vec![
+                (
+                    Some("GA".to_string()),
+                    serde_json::json!({ "method": "transfer" }),
+                ),
+                (None, serde_json::json!({ "method": "mint" })),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let mut streamed = Vec::new();
+        for _ in 0..2 {
+            let event = receiver.recv().await.unwrap();
+            streamed.push((
+                event.user_address,
+                event.metadata.unwrap()["method"].clone(),
+            ));
+        }
+        streamed.sort_by_key(|(_, method)| method.to_string());
+        assert_eq!(
+            streamed,
+            vec![
+                (None, serde_json::json!("mint")),
+                (Some("GA".to_string()), serde_json::json!("transfer")),
+            ]
+        );
+        let stored: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM analytics_events WHERE contract_id = $1")
+                .bind(contract)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(stored, 2);
+    }
+}
@@ -0,0 +1,347 @@
+// Interaction spike detection.
+//
+// Every `CHECK_INTERVAL`, each contract's interaction count for the current
+// and previous hour is compared with the hourly rate implied by its trailing
+// daily aggregates, in one query over the contracts that saw traffic. Ingest
+// does no extra work. An hour more than `multiplier` times that rate is
+// flagged once in `interaction_anomalies` and recorded as an analytics event;
+// when recipients are configured a notification is queued in
+// `notification_logs` as `pending`, since nothing delivers it yet. Contracts
+// whose baseline is below `min_daily_baseline` are never flagged: a handful
+// of calls to a quiet contract is not abuse.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use shared::{AnalyticsEventType, InteractionAnomaly};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    analytics,
+    error::ApiResult,
+    handlers::{db_internal_error, fetch_contract_identity},
+    state::AppState,
+};
+
+/// Days of daily aggregates averaged into the baseline
+const BASELINE_DAYS: i32 = 7;
+const NOTIFICATION_TYPE: &str = "interaction_anomaly";
+/// How often hourly counts are checked; the previous hour is checked too, so
+/// a spike late in an hour is still caught after it rolls over
+const CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyConfig {
+    /// Flag an hour with more than this multiple of the baseline hourly rate
+    pub multiplier: f64,
+    /// Average daily interactions below which a contract is never flagged
+    pub min_daily_baseline: f64,
+    /// Notification recipients; empty disables notifications
+    pub notify_recipients: Vec<String>,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: 5.0,
+            min_daily_baseline: 48.0,
+            notify_recipients: Vec::new(),
+        }
+    }
+}
+
+impl AnomalyConfig {
+    /// `ANOMALY_SPIKE_MULTIPLIER` (> 1), `ANOMALY_MIN_DAILY_BASELINE` (>= 0)
+    /// and comma-separated `ANOMALY_NOTIFY_RECIPIENTS`; unset or invalid
+    /// values keep their default
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let number = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite())
+        };
+        Self {
+            multiplier: number("ANOMALY_SPIKE_MULTIPLIER")
+                .filter(|m| *m > 1.0)
+                .unwrap_or(defaults.multiplier),
+            min_daily_baseline: number("ANOMALY_MIN_DAILY_BASELINE")
+                .filter(|b| *b >= 0.0)
+                .unwrap_or(defaults.min_daily_baseline),
+            notify_recipients: std::env::var("ANOMALY_NOTIFY_RECIPIENTS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|r| !r.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// An hour whose interaction count exceeded the configured multiple
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spike {
+    pub observed_count: i64,
+    pub baseline_hourly: f64,
+}
+
+/// Whether `observed_count` interactions in one hour is a spike against a
+/// trailing average of `daily_average` interactions a day
+pub fn detect_spike(
+    observed_count: i64,
+    daily_average: f64,
+    config: &AnomalyConfig,
+) -> Option<Spike> {
+    if daily_average <= 0.0 || daily_average < config.min_daily_baseline {
+        return None;
+    }
+    let baseline_hourly = daily_average / 24.0;
+    (observed_count as f64 > baseline_hourly * config.multiplier).then_some(Spike {
+        observed_count,
+        baseline_hourly,
+    })
+}
+
+/// Check hourly counts every `CHECK_INTERVAL`. Replicas may run the check
+/// concurrently; each hour is flagged once however many do.
+pub fn spawn_spike_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match check_for_spikes(&state, &AnomalyConfig::from_env()).await {
+                Ok(0) => {}
+                Ok(flagged) => tracing::info!(flagged, "anomaly: interaction spikes flagged"),
+                Err(err) => tracing::error!(error = ?err, "anomaly: spike check failed"),
+            }
+        }
+    });
+}
+
+/// One contract's interactions in one hour against its baseline
+#[derive(Debug, sqlx::FromRow)]
+struct HourlyCount {
+    contract_id: Uuid,
+    window_start: DateTime<Utc>,
+    observed_count: i64,
+    daily_average: f64,
+}
+
+/// Flag every spiking hour not flagged yet, returning how many were
+async fn check_for_spikes(state: &AppState, config: &AnomalyConfig) -> Result<usize, sqlx::Error> {
+    let counts: Vec<HourlyCount> = sqlx::query_as(
+        "WITH hourly AS ( \
+             SELECT contract_id, date_trunc('hour', created_at) AS window_start, \
+                    COUNT(*) AS observed_count \
+             FROM contract_interactions \
+             WHERE created_at >= date_trunc('hour', NOW()) - INTERVAL '1 hour' \
+             GROUP BY 1, 2 \
+         ) \
+         SELECT h.contract_id, h.window_start, h.observed_count, \
+                COALESCE(( \
+                    SELECT SUM(a.total_events) FROM analytics_daily_aggregates a \
+                    WHERE a.contract_id = h.contract_id \
+                      AND a.date >= CURRENT_DATE - $1 AND a.date < CURRENT_DATE \
+                ), 0)::float8 / $1 AS daily_average \
+         FROM hourly h",
+    )
+    .bind(BASELINE_DAYS)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut flagged = 0;
+    for count in counts {
+        let Some(spike) = detect_spike(count.observed_count, count.daily_average, config) else {
+            continue;
+        };
+        if record_spike(state, &count, &spike, config).await? {
+            flagged += 1;
+        }
+    }
+    Ok(flagged)
+}
+
+/// Record `spike` unless its hour is already flagged
+async fn record_spike(
+    state: &AppState,
+    count: &HourlyCount,
+    spike: &Spike,
+    config: &AnomalyConfig,
+) -> Result<bool, sqlx::Error> {
+    let flagged: Option<InteractionAnomaly> = sqlx::query_as(
+        "INSERT INTO interaction_anomalies \
+           (contract_id, window_start, observed_count, baseline_hourly, multiplier) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (contract_id, window_start) DO NOTHING \
+         RETURNING *",
+    )
+    .bind(count.contract_id)
+    .bind(count.window_start)
+    .bind(spike.observed_count)
+    .bind(spike.baseline_hourly)
+    .bind(config.multiplier)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some(anomaly) = flagged else {
+        return Ok(false);
+    };
+
+    tracing::warn!(
+        contract = %anomaly.contract_id,
+        observed = anomaly.observed_count,
+        baseline_hourly = anomaly.baseline_hourly,
+        "interaction spike flagged"
+    );
+    analytics::spawn_record_event(
+        state,
+        AnalyticsEventType::InteractionAnomaly,
+        anomaly.contract_id,
+        None,
+        None,
+        Some(json!({
+            "anomaly_id": anomaly.id,
+            "window_start": anomaly.window_start,
+            "observed_count": anomaly.observed_count,
+            "baseline_hourly": anomaly.baseline_hourly,
+        })),
+    );
+
+    if !config.notify_recipients.is_empty() {
+        sqlx::query(
+            "INSERT INTO notification_logs \
+               (contract_id, notification_type, recipients, message, sent_at, status) \
+             VALUES ($1, $2, $3, $4, NOW(), 'pending')",
+        )
+        .bind(anomaly.contract_id)
+        .bind(NOTIFICATION_TYPE)
+        .bind(&config.notify_recipients)
+        .bind(format!(
+            "{} interactions since {} against an hourly baseline of {:.1}",
+            anomaly.observed_count, anomaly.window_start, anomaly.baseline_hourly
+        ))
+        .execute(&state.db)
+        .await?;
+    }
+    Ok(true)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnomaliesQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/contracts/:id/anomalies — most recent flagged hours first
+pub async fn get_contract_anomalies(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<AnomaliesQuery>,
+) -> ApiResult<Json<Vec<InteractionAnomaly>>> {
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let anomalies: Vec<InteractionAnomaly> = sqlx::query_as(
+        "SELECT * FROM interaction_anomalies WHERE contract_id = $1 \
+         ORDER BY window_start DESC LIMIT $2",
+    )
+    .bind(contract_uuid)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list interaction anomalies", err))?;
+
+    Ok(Json(anomalies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_spike_is_flagged() {
+        let config = AnomalyConfig::default();
+        // 240 a day is 10 an hour; 51 in one hour is over 5x
+        let spike = detect_spike(51, 240.0, &config).expect("spike should be flagged");
+        assert_eq!(spike.observed_count, 51);
+        assert_eq!(spike.baseline_hourly, 10.0);
+    }
+
+    #[test]
+    fn normal_traffic_is_not_flagged() {
+        let config = AnomalyConfig::default();
+        assert_eq!(detect_spike(50, 240.0, &config), None);
+        assert_eq!(detect_spike(12, 240.0, &config), None);
+    }
+
+    #[test]
+    fn quiet_contracts_need_a_minimum_baseline() {
+        let config = AnomalyConfig::default();
+        // 10 calls against 1 a day would be a huge multiple, but the baseline
+        // is too small to trust
+        assert_eq!(detect_spike(10, 1.0, &config), None);
+        assert_eq!(detect_spike(10, 0.0, &config), None);
+
+        let lenient = AnomalyConfig {
+            min_daily_baseline: 0.0,
+            ..AnomalyConfig::default()
+        };
+        assert!(detect_spike(10, 1.0, &lenient).is_some());
+    }
+
+    #[tokio::test]
+    async fn periodic_check_flags_each_spiking_hour_once() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let busy = crate::test_db::insert_contract(&state.db, publisher, "CBUSY").await;
+        let quiet = crate::test_db::insert_contract(&state.db, publisher, "CQUIET").await;
+        // 240 a day is 10 an hour for the busy contract; the quiet one has
+        // no baseline at all
+        sqlx::query(
+            "INSERT INTO analytics_daily_aggregates (contract_id, date, total_events) \
+             SELECT $1, CURRENT_DATE - d, 240 FROM generate_series(1, 7) AS d",
+        )
+        .bind(busy)
+        .execute(&state.db)
+        .await
+        .unwrap();
+        for contract in [busy, quiet] {
+            sqlx::query(
+                "INSERT INTO contract_interactions (contract_id, interaction_type) \
+                 SELECT $1, 'invoke' FROM generate_series(1, 60)",
+            )
+            .bind(contract)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        }
+
+        let config = AnomalyConfig {
+            notify_recipients: vec!["ops@example.com".to_string()],
+            ..AnomalyConfig::default()
+        };
+        assert_eq!(check_for_spikes(&state, &config).await.unwrap(), 1);
+        assert_eq!(check_for_spikes(&state, &config).await.unwrap(), 0);
+
+        let flagged: Vec<(Uuid, i64)> =
+            sqlx::query_as("SELECT contract_id, observed_count FROM interaction_anomalies")
+                .fetch_all(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(flagged, vec![(busy, 60)]);
+        let statuses: Vec<String> = sqlx::query_scalar("SELECT status FROM notification_logs")
+            .fetch_all(&state.db)
+            .await
+            .unwrap();
+        assert_eq!(statuses, vec!["pending".to_string()]);
+    }
+}
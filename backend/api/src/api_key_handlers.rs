@@ -0,0 +1,122 @@
+// Admin endpoints issuing and revoking publisher API keys (see `api_keys`).
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    api_keys::{generate_api_key, hash_api_key, ApiKey},
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, fetch_contract_identity},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub publisher_id: Uuid,
+    /// Contract UUID or contract ID to scope the key to; unscoped keys cover
+    /// every contract of the publisher
+    #[serde(default)]
+    pub contract_id: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKey,
+    /// The key itself; it is not stored and cannot be retrieved again
+    pub api_key: String,
+}
+
+/// POST /api/admin/api-keys
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    payload: Result<Json<CreateApiKeyRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<CreateApiKeyResponse>)> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidRequest",
+            "name must not be empty",
+        ));
+    }
+
+    let contract_uuid = match req.contract_id.as_deref() {
+        Some(id) => {
+            let (contract_uuid, contract_id) = fetch_contract_identity(&state, id).await?;
+            let owner: Uuid =
+                sqlx::query_scalar("SELECT publisher_id FROM contracts WHERE id = $1")
+                    .bind(contract_uuid)
+                    .fetch_one(&state.db)
+                    .await
+                    .map_err(|err| db_internal_error("get contract publisher", err))?;
+            if owner != req.publisher_id {
+                return Err(ApiError::bad_request(
+                    "ContractMismatch",
+                    format!("Contract {} is not owned by this publisher", contract_id),
+                ));
+            }
+            Some(contract_uuid)
+        }
+        None => None,
+    };
+
+    let api_key = generate_api_key();
+    let key: ApiKey = sqlx::query_as(
+        "INSERT INTO api_keys (publisher_id, contract_id, name, key_hash) \
+         VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(req.publisher_id)
+    .bind(contract_uuid)
+    .bind(name)
+    .bind(hash_api_key(&api_key))
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => ApiError::not_found(
+            "PublisherNotFound",
+            format!("No publisher found with ID: {}", req.publisher_id),
+        ),
+        _ => db_internal_error("insert api key", err),
+    })?;
+
+    tracing::info!(key_id = %key.id, publisher_id = %key.publisher_id, "api key issued");
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateApiKeyResponse { key, api_key }),
+    ))
+}
+
+/// DELETE /api/admin/api-keys/:key_id
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Path(key_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let revoked =
+        sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(key_id)
+            .execute(&state.db)
+            .await
+            .map_err(|err| db_internal_error("revoke api key", err))?
+            .rows_affected();
+    if revoked == 0 {
+        return Err(ApiError::not_found(
+            "ApiKeyNotFound",
+            format!("No active API key found with ID: {}", key_id),
+        ));
+    }
+
+    tracing::info!(key_id = %key_id, "api key revoked");
+    Ok(StatusCode::NO_CONTENT)
+}
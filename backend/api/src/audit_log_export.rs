@@ -0,0 +1,300 @@
+// Bulk export of `contract_audit_log` across contracts as NDJSON.
+//
+// Filters are applied in the query; matching rows are streamed through a
+// bounded channel and written one line each, like the interaction export, so
+// an export of the whole log never holds it in memory.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::Response,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use shared::{AuditActionType, ContractAuditLog};
+use uuid::Uuid;
+
+use crate::audit_retention::RetentionStatus;
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// Rows buffered between the database task and the response body
+const EXPORT_CHANNEL_CAPACITY: usize = 256;
+
+const EXPORT_SQL: &str = r#"
+    SELECT id, contract_id, action_type, old_value, new_value, changed_by, timestamp,
+           previous_hash, hash, signature, ip_address
+    FROM contract_audit_log
+    WHERE ($1::audit_action_type IS NULL OR action_type = $1)
+      AND ($2::uuid IS NULL OR contract_id = $2)
+      AND ($3::timestamptz IS NULL OR timestamp >= $3)
+      AND ($4::timestamptz IS NULL OR timestamp < $4)
+      AND ($5::text IS NULL OR ip_address = $5)
+    ORDER BY timestamp ASC, id ASC
+"#;
+
+/// Query params for GET /api/admin/audit-log/export; every filter is optional
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditLogExportQuery {
+    /// e.g. `PublisherChanged`
+    pub action: Option<AuditActionType>,
+    pub contract_id: Option<Uuid>,
+    /// Inclusive lower bound (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+    pub ip_address: Option<String>,
+}
+
+impl AuditLogExportQuery {
+    fn validate(&self) -> ApiResult<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(ApiError::bad_request(
+                    "InvalidDateRange",
+                    "`from` must be earlier than `to`",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// GET /api/admin/audit-log/export
+pub async fn export_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogExportQuery>,
+) -> ApiResult<Response> {
+    query.validate()?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    let pool = state.db.clone();
+    tokio::spawn(async move {
+        let mut rows = sqlx::query_as::<_, ContractAuditLog>(EXPORT_SQL)
+            .bind(&query.action)
+            .bind(query.contract_id)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.ip_address.as_deref())
+            .fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let failed = row.is_err();
+            if let Err(err) = &row {
+                tracing::error!(error = ?err, "audit log export failed");
+            }
+            // Receiver gone means the client disconnected
+            if tx.send(row).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let rows = futures::stream::unfold(
+        rx,
+        |mut rx| async move { rx.recv().await.map(|row| (row, rx)) },
+    );
+
+    let filename = format!("audit-log_{}.ndjson", Utc::now().format("%Y%m%d_%H%M%S"));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from_stream(encode_ndjson(rows)))
+        .map_err(|_| ApiError::internal("Failed to build response"))
+}
+
+/// One JSON line per entry. A database error ends the stream with an error,
+/// aborting the response.
+fn encode_ndjson<S>(rows: S) -> impl Stream<Item = Result<Bytes, sqlx::Error>>
+where
+    S: Stream<Item = Result<ContractAuditLog, sqlx::Error>>,
+{
+    rows.map_ok(|entry| {
+        let mut line = serde_json::to_vec(&entry).unwrap_or_default();
+        line.push(b'\n');
+        Bytes::from(line)
+    })
+}
+
+/// Retention settings and the status of the archival job
+#[derive(Debug, Serialize)]
+pub struct RetentionReport {
+    pub retention_days: i64,
+    pub interval_secs: u64,
+    pub prune: bool,
+    #[serde(flatten)]
+    pub status: RetentionStatus,
+}
+
+/// GET /api/admin/audit-log/retention
+pub async fn get_retention_status(
+    State(state): State<AppState>,
+) -> ApiResult<Json<RetentionReport>> {
+    let retention = &state.audit_retention;
+    Ok(Json(RetentionReport {
+        retention_days: retention.config.retention.num_days(),
+        interval_secs: retention.config.interval.as_secs(),
+        prune: retention.config.prune,
+        status: retention.status(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> AuditLogExportQuery {
+        let uri = format!("/api/admin/audit-log/export?{}", query)
+            .parse()
+            .unwrap();
+        Query::<AuditLogExportQuery>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn filters_parse_from_the_query_string() {
+        let query =
+            parse("action=PublisherChanged&from=2026-01-01T00:00:00Z&to=2026-02-01T00:00:00Z");
+        assert_eq!(query.action, Some(AuditActionType::PublisherChanged));
+        assert!(query.validate().is_ok());
+
+        let inverted = parse("from=2026-02-01T00:00:00Z&to=2026-01-01T00:00:00Z");
+        assert!(inverted.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn export_streams_only_entries_matching_every_filter() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let (target, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let (changed, updated) = (
+            AuditActionType::PublisherChanged,
+            AuditActionType::MetadataUpdated,
+        );
+        const IP: &str = "203.0.113.7";
+        // The first two match; each of the others misses exactly one filter
+        let fixtures = [
+            (target, changed.clone(), "2026-01-05T00:00:00Z", IP),
+            (target, changed.clone(), "2026-01-20T00:00:00Z", IP),
+            (other, changed.clone(), "2026-01-10T00:00:00Z", IP),
+            (target, updated, "2026-01-10T00:00:00Z", IP),
+            (target, changed.clone(), "2026-02-01T00:00:00Z", IP),
+            (target, changed, "2026-01-10T00:00:00Z", "198.51.100.1"),
+        ];
+        let mut ids = Vec::new();
+        for (contract, action, at, ip) in fixtures {
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO contract_audit_log \
+                   (contract_id, action_type, new_value, changed_by, timestamp, ip_address) \
+                 VALUES ($1, $2, '{}', 'GABC', $3::timestamptz, $4) RETURNING id",
+            )
+            .bind(contract)
+            .bind(action)
+            .bind(at)
+            .bind(ip)
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+
+        let query = parse(&format!(
+            "action=PublisherChanged&contract_id={}&from=2026-01-01T00:00:00Z\
+             &to=2026-02-01T00:00:00Z&ip_address=203.0.113.7",
+            target
+        ));
+        let response = export_audit_log(State(state), Query(query)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        let exported: Vec<ContractAuditLog> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let exported: Vec<Uuid> = exported.iter().map(|entry| entry.id).collect();
+        assert_eq!(exported, ids[..2]);
+    }
+
+    #[tokio::test]
+    async fn retention_runs_under_one_lock_and_resumes_after_timestamp_ties() {
+        use crate::advisory_lock::{AdvisoryLockGuard, AUDIT_RETENTION_LOCK_KEY};
+        use crate::audit_retention::{run_once, AuditRetention, AuditRetentionConfig};
+
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let archive_dir = std::env::temp_dir().join(format!("audit-archive-{}", Uuid::new_v4()));
+        let retention = AuditRetention::new(AuditRetentionConfig {
+            archive_dir: archive_dir.clone(),
+            prune: true,
+            ..AuditRetentionConfig::default()
+        });
+        let (pool, contract) = (&state.db, Uuid::new_v4());
+        let entry = |n: u128| async move {
+            sqlx::query(
+                "INSERT INTO contract_audit_log \
+                   (id, contract_id, action_type, new_value, changed_by, timestamp) \
+                 VALUES ($1, $2, $3, '{}', 'GABC', '2020-01-01T00:00:00Z')",
+            )
+            .bind(Uuid::from_u128(n))
+            .bind(contract)
+            .bind(AuditActionType::MetadataUpdated)
+            .execute(pool)
+            .await
+            .unwrap();
+        };
+        let remaining = || async {
+            sqlx::query_scalar::<_, Uuid>(
+                "SELECT id FROM contract_audit_log WHERE contract_id = $1 ORDER BY id",
+            )
+            .bind(contract)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+        };
+        entry(1).await;
+        entry(3).await;
+
+        // Another instance holds the lock: this one skips the tick
+        let held = AdvisoryLockGuard::acquire(&state.db, AUDIT_RETENTION_LOCK_KEY)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!run_once(&retention, &state.db).await);
+        assert_eq!(retention.status().runs, 0);
+        held.release().await.unwrap();
+
+        assert!(run_once(&retention, &state.db).await);
+        let status = retention.status();
+        assert_eq!((status.last_archived, status.last_pruned), (2, 2));
+        assert_eq!(status.last_error, None);
+
+        // A late entry at the archived timestamp sorts after the cursor
+        entry(5).await;
+        assert_eq!(remaining().await, vec![Uuid::from_u128(5)]);
+        assert!(run_once(&retention, &state.db).await);
+        let status = retention.status();
+        assert_eq!((status.last_archived, status.last_pruned), (1, 1));
+        assert!(remaining().await.is_empty());
+        let through: Uuid = sqlx::query_scalar(
+            "SELECT archived_through_id FROM audit_log_archive_checkpoints WHERE contract_id = $1",
+        )
+        .bind(contract)
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(through, Uuid::from_u128(5));
+
+        std::fs::remove_dir_all(archive_dir).ok();
+    }
+}
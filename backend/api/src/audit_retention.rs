@@ -0,0 +1,393 @@
+// Retention for `contract_audit_log`.
+//
+// On a schedule, entries older than the retention window are written to a
+// gzip-compressed NDJSON archive, one file per run, and each contract's chain
+// head at the archive boundary is recorded in
+// `audit_log_archive_checkpoints`. Chain verification starts from that hash,
+// so the archived prefix can be pruned from the hot table without breaking
+// it. Pruning is off unless `AUDIT_RETENTION_PRUNE` is set. Each run holds an
+// advisory lock, so with several API instances only one archives at a time.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use futures::TryStreamExt;
+use serde::Serialize;
+use shared::ContractAuditLog;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::advisory_lock::{AdvisoryLockGuard, AUDIT_RETENTION_LOCK_KEY};
+
+const DEFAULT_RETENTION_DAYS: i64 = 365;
+const DEFAULT_INTERVAL_SECS: u64 = 24 * 3600;
+const DEFAULT_ARCHIVE_DIR: &str = "audit-archive";
+
+/// Entries not yet archived that fell out of the retention window, in chain
+/// order per contract. A checkpoint without an entry ID predates the
+/// (timestamp, id) cursor and covers everything at its timestamp.
+const ARCHIVE_CANDIDATES_SQL: &str = r#"
+    SELECT l.id, l.contract_id, l.action_type, l.old_value, l.new_value, l.changed_by,
+           l.timestamp, l.previous_hash, l.hash, l.signature, l.ip_address
+    FROM contract_audit_log l
+    LEFT JOIN audit_log_archive_checkpoints c ON c.contract_id = l.contract_id
+    WHERE l.timestamp < $1
+      AND (c.archived_through IS NULL
+           OR (l.timestamp, l.id) > (c.archived_through,
+               COALESCE(c.archived_through_id, 'ffffffff-ffff-ffff-ffff-ffffffffffff'::uuid)))
+    ORDER BY l.contract_id, l.timestamp ASC, l.id ASC
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRetentionConfig {
+    /// Entries older than this are archived
+    pub retention: chrono::Duration,
+    /// Time between runs
+    pub interval: Duration,
+    /// Directory archive files are written to
+    pub archive_dir: PathBuf,
+    /// Delete archived entries from `contract_audit_log`
+    pub prune: bool,
+}
+
+impl Default for AuditRetentionConfig {
+    fn default() -> Self {
+        Self {
+            retention: chrono::Duration::days(DEFAULT_RETENTION_DAYS),
+            interval: Duration::from_secs(DEFAULT_INTERVAL_SECS),
+            archive_dir: PathBuf::from(DEFAULT_ARCHIVE_DIR),
+            prune: false,
+        }
+    }
+}
+
+impl AuditRetentionConfig {
+    /// `AUDIT_RETENTION_DAYS` (default 365), `AUDIT_RETENTION_INTERVAL_SECS`
+    /// (default one day), `AUDIT_ARCHIVE_DIR` and `AUDIT_RETENTION_PRUNE`
+    /// (`true` to prune; off by default)
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let defaults = Self::default();
+        let positive = |key: &str| -> Result<Option<u64>, String> {
+            let Some(raw) = lookup(key) else {
+                return Ok(None);
+            };
+            match raw.trim().parse::<u64>() {
+                Ok(value) if value > 0 => Ok(Some(value)),
+                _ => Err(format!(
+                    "{} must be a positive integer (got '{}')",
+                    key,
+                    raw.trim()
+                )),
+            }
+        };
+
+        let retention = match positive("AUDIT_RETENTION_DAYS")? {
+            Some(days) => chrono::Duration::days(days as i64),
+            None => defaults.retention,
+        };
+        let interval = positive("AUDIT_RETENTION_INTERVAL_SECS")?
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.interval);
+        let archive_dir = lookup("AUDIT_ARCHIVE_DIR")
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or(defaults.archive_dir);
+        let prune = match lookup("AUDIT_RETENTION_PRUNE") {
+            None => false,
+            Some(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" => true,
+                "0" | "false" | "no" | "" => false,
+                other => {
+                    return Err(format!(
+                        "AUDIT_RETENTION_PRUNE must be true or false (got '{}')",
+                        other
+                    ))
+                }
+            },
+        };
+        Ok(Self {
+            retention,
+            interval,
+            archive_dir,
+            prune,
+        })
+    }
+}
+
+/// Outcome of the most recent retention runs
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionStatus {
+    pub running: bool,
+    pub runs: u64,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_archived: u64,
+    pub last_pruned: u64,
+    pub last_archive_file: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Retention settings and job status, shared with the status endpoint
+#[derive(Debug, Default)]
+pub struct AuditRetention {
+    pub config: AuditRetentionConfig,
+    status: RwLock<RetentionStatus>,
+}
+
+impl AuditRetention {
+    pub fn new(config: AuditRetentionConfig) -> Self {
+        Self {
+            config,
+            status: RwLock::default(),
+        }
+    }
+
+    pub fn status(&self) -> RetentionStatus {
+        self.status.read().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut RetentionStatus)) {
+        if let Ok(mut status) = self.status.write() {
+            f(&mut status);
+        }
+    }
+}
+
+/// Chain head of one contract's archived entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveCheckpoint {
+    pub archived_through: DateTime<Utc>,
+    /// ID of the newest archived entry, breaking timestamp ties
+    pub archived_through_id: Uuid,
+    pub last_hash: Option<String>,
+    pub count: i64,
+}
+
+/// One run's archive: gzip NDJSON, plus the checkpoint each contract's chain
+/// continues from. Entries must be pushed in chain order per contract.
+pub struct ArchiveBatch {
+    encoder: GzEncoder<Vec<u8>>,
+    checkpoints: BTreeMap<Uuid, ArchiveCheckpoint>,
+    count: u64,
+}
+
+impl Default for ArchiveBatch {
+    fn default() -> Self {
+        Self {
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            checkpoints: BTreeMap::new(),
+            count: 0,
+        }
+    }
+}
+
+impl ArchiveBatch {
+    pub fn push(&mut self, entry: &ContractAuditLog) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.encoder, entry)?;
+        self.encoder.write_all(b"\n")?;
+        let checkpoint = self
+            .checkpoints
+            .entry(entry.contract_id)
+            .or_insert(ArchiveCheckpoint {
+                archived_through: entry.timestamp,
+                archived_through_id: entry.id,
+                last_hash: None,
+                count: 0,
+            });
+        checkpoint.archived_through = entry.timestamp;
+        checkpoint.archived_through_id = entry.id;
+        checkpoint.last_hash = entry.hash.clone();
+        checkpoint.count += 1;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Compressed archive bytes and the per-contract checkpoints
+    pub fn finish(self) -> std::io::Result<(Vec<u8>, BTreeMap<Uuid, ArchiveCheckpoint>)> {
+        Ok((self.encoder.finish()?, self.checkpoints))
+    }
+}
+
+struct RunSummary {
+    archived: u64,
+    pruned: u64,
+    archive_file: Option<String>,
+}
+
+/// Archive (and optionally prune) everything older than the retention window
+async fn run_retention(pool: &PgPool, config: &AuditRetentionConfig) -> anyhow::Result<RunSummary> {
+    let started = Utc::now();
+    let cutoff = started - config.retention;
+
+    let mut batch = ArchiveBatch::default();
+    let mut rows = sqlx::query_as::<_, ContractAuditLog>(ARCHIVE_CANDIDATES_SQL)
+        .bind(cutoff)
+        .fetch(pool);
+    while let Some(entry) = rows.try_next().await? {
+        batch.push(&entry)?;
+    }
+    drop(rows);
+    if batch.is_empty() {
+        return Ok(RunSummary {
+            archived: 0,
+            pruned: 0,
+            archive_file: None,
+        });
+    }
+
+    let archived = batch.count;
+    let (archive, checkpoints) = batch.finish()?;
+    tokio::fs::create_dir_all(&config.archive_dir).await?;
+    let path = config.archive_dir.join(format!(
+        "audit-log_{}.ndjson.gz",
+        started.format("%Y%m%d_%H%M%S")
+    ));
+    tokio::fs::write(&path, archive).await?;
+    let archive_file = path.to_string_lossy().into_owned();
+
+    // Checkpoints are only recorded once the archive is on disk
+    let mut tx = pool.begin().await?;
+    for (contract_id, checkpoint) in &checkpoints {
+        sqlx::query(
+            "INSERT INTO audit_log_archive_checkpoints \
+               (contract_id, archived_through, archived_through_id, last_hash, archived_count, \
+                archive_file) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (contract_id) DO UPDATE SET \
+               archived_through = EXCLUDED.archived_through, \
+               archived_through_id = EXCLUDED.archived_through_id, \
+               last_hash = EXCLUDED.last_hash, \
+               archived_count = audit_log_archive_checkpoints.archived_count + EXCLUDED.archived_count, \
+               archive_file = EXCLUDED.archive_file, \
+               updated_at = NOW()",
+        )
+        .bind(contract_id)
+        .bind(checkpoint.archived_through)
+        .bind(checkpoint.archived_through_id)
+        .bind(&checkpoint.last_hash)
+        .bind(checkpoint.count)
+        .bind(&archive_file)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let mut pruned = 0;
+    if config.prune {
+        sqlx::query("SET LOCAL audit_log.pruning = 'on'")
+            .execute(&mut *tx)
+            .await?;
+        pruned = sqlx::query(
+            "DELETE FROM contract_audit_log l USING audit_log_archive_checkpoints c \
+             WHERE l.contract_id = c.contract_id \
+               AND (l.timestamp, l.id) <= (c.archived_through, \
+                    COALESCE(c.archived_through_id, 'ffffffff-ffff-ffff-ffff-ffffffffffff'::uuid))",
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+
+    Ok(RunSummary {
+        archived,
+        pruned,
+        archive_file: Some(archive_file),
+    })
+}
+
+/// One scheduled run, unless another instance holds the retention lock.
+/// Returns whether this instance ran.
+pub async fn run_once(retention: &AuditRetention, pool: &PgPool) -> bool {
+    let guard = match AdvisoryLockGuard::acquire(pool, AUDIT_RETENTION_LOCK_KEY).await {
+        Ok(Some(guard)) => guard,
+        Ok(None) => {
+            tracing::info!("audit retention: another instance is running, skipping");
+            return false;
+        }
+        // Already logged; the next tick tries again
+        Err(_) => return false,
+    };
+    retention.update(|status| {
+        status.running = true;
+        status.last_started_at = Some(Utc::now());
+    });
+
+    let result = run_retention(pool, &retention.config).await;
+    retention.update(|status| {
+        status.running = false;
+        status.runs += 1;
+        status.last_finished_at = Some(Utc::now());
+        match &result {
+            Ok(summary) => {
+                status.last_archived = summary.archived;
+                status.last_pruned = summary.pruned;
+                status.last_archive_file = summary.archive_file.clone();
+                status.last_error = None;
+            }
+            Err(err) => status.last_error = Some(err.to_string()),
+        }
+    });
+    match result {
+        Ok(summary) => tracing::info!(
+            archived = summary.archived,
+            pruned = summary.pruned,
+            "audit retention: run finished"
+        ),
+        Err(err) => tracing::error!(error = ?err, "audit retention: run failed"),
+    }
+    if let Err(err) = guard.release().await {
+        tracing::error!(error = ?err, "audit retention: failed to release lock");
+    }
+    true
+}
+
+/// Run retention on `retention.config.interval`
+pub fn spawn_retention_task(retention: Arc<AuditRetention>, pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(retention.config.interval);
+        loop {
+            interval.tick().await;
+            run_once(&retention, &pool).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pruning_is_off_by_default() {
+        let config = AuditRetentionConfig::from_lookup(|_| None).unwrap();
+        assert_eq!(config, AuditRetentionConfig::default());
+        assert!(!config.prune);
+
+        let config = AuditRetentionConfig::from_lookup(|key| match key {
+            "AUDIT_RETENTION_DAYS" => Some("30".to_string()),
+            "AUDIT_RETENTION_PRUNE" => Some("true".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.retention, chrono::Duration::days(30));
+        assert!(config.prune);
+
+        assert!(AuditRetentionConfig::from_lookup(|key| {
+            (key == "AUDIT_RETENTION_DAYS").then(|| "0".to_string())
+        })
+        .is_err());
+    }
+}
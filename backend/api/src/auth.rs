@@ -117,7 +117,7 @@ fn decode_hex_64(value: &str) -> Option<[u8; 64]> {
 }
 
 fn decode_hex(value: &str) -> Option<Vec<u8>> {
-    if value.len() % 2 != 0 {
+    if !value.len().is_multiple_of(2) {
         return None;
     }
     (0..value.len())
@@ -0,0 +1,192 @@
+// Operator blocklist of contract IDs.
+//
+// Blocked contract IDs cannot be published and their interactions are not
+// ingested. Entries live in `blocked_contracts`; each API process keeps a copy
+// in memory so the check adds no query to the hot paths. The copy is updated
+// in place by the admin endpoints and reloaded periodically so changes made
+// through another replica are picked up.
+//
+// A copy that has not been reloaded for `MAX_STALENESS` (the database has been
+// unreachable for several refreshes, or was never loaded) no longer vouches
+// for anything: checks against it fail closed with 503 rather than letting a
+// contract blocked elsewhere through indefinitely.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use axum::http::StatusCode;
+
+use crate::error::{ApiError, ApiResult};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Age past which the in-memory copy is no longer trusted
+const MAX_STALENESS: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct BlockedContract {
+    pub contract_id: String,
+    pub reason: String,
+    pub blocked_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct Blocklist {
+    entries: RwLock<HashMap<String, BlockedContract>>,
+    /// When the copy was last replaced from `blocked_contracts`
+    synced_at: RwLock<Option<Instant>>,
+}
+
+/// Key used for matching; the same normalization applied to contract IDs on
+/// publish (trimmed, uppercase)
+pub fn normalize(contract_id: &str) -> String {
+    contract_id.trim().to_uppercase()
+}
+
+impl Blocklist {
+    pub fn get(&self, contract_id: &str) -> Option<BlockedContract> {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&normalize(contract_id))
+            .cloned()
+    }
+
+    pub fn insert(&self, entry: BlockedContract) {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(normalize(&entry.contract_id), entry);
+    }
+
+    pub fn remove(&self, contract_id: &str) -> Option<BlockedContract> {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&normalize(contract_id))
+    }
+
+    /// Replace the in-memory copy with the contents of `blocked_contracts`
+    pub async fn reload(&self, db: &PgPool) -> Result<usize, sqlx::Error> {
+        let rows: Vec<BlockedContract> = sqlx::query_as("SELECT * FROM blocked_contracts")
+            .fetch_all(db)
+            .await?;
+        Ok(self.replace(rows, Instant::now()))
+    }
+
+    /// Replace the in-memory copy with `rows`, read from the database at
+    /// `synced_at`
+    pub fn replace(&self, rows: Vec<BlockedContract>, synced_at: Instant) -> usize {
+        let count = rows.len();
+        let entries = rows
+            .into_iter()
+            .map(|entry| (normalize(&entry.contract_id), entry))
+            .collect();
+        *self.entries.write().unwrap_or_else(|e| e.into_inner()) = entries;
+        *self.synced_at.write().unwrap_or_else(|e| e.into_inner()) = Some(synced_at);
+        count
+    }
+
+    /// Refuse `contract_id` with 403 when it is blocked, or with 503 when the
+    /// copy is too stale to say
+    pub fn ensure_allowed(&self, contract_id: &str) -> ApiResult<()> {
+        self.ensure_allowed_at(contract_id, Instant::now())
+    }
+
+    fn ensure_allowed_at(&self, contract_id: &str, now: Instant) -> ApiResult<()> {
+        if let Some(entry) = self.get(contract_id) {
+            return Err(ApiError::forbidden(
+                "ContractBlocked",
+                format!(
+                    "Contract {} is blocked: {}",
+                    entry.contract_id, entry.reason
+                ),
+            ));
+        }
+        let synced_at = *self.synced_at.read().unwrap_or_else(|e| e.into_inner());
+        match synced_at {
+            Some(synced_at) if now.saturating_duration_since(synced_at) <= MAX_STALENESS => Ok(()),
+            _ => Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "BlocklistUnavailable",
+                "The contract blocklist could not be loaded; try again shortly",
+            )),
+        }
+    }
+}
+
+/// Load the blocklist now and keep it in sync with the database
+pub fn spawn_refresh_task(blocklist: Arc<Blocklist>, pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = blocklist.reload(&pool).await {
+                tracing::error!(error = ?err, "blocklist: reload failed");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    const BLOCKED_ID: &str = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+
+    fn entry(contract_id: &str) -> BlockedContract {
+        BlockedContract {
+            contract_id: contract_id.to_string(),
+            reason: "drainer".to_string(),
+            blocked_by: "ops".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn matching_uses_the_normalized_contract_id() {
+        let blocklist = Blocklist::default();
+        blocklist.replace(vec![entry(BLOCKED_ID)], Instant::now());
+
+        let lowercase = format!("  {}\n", BLOCKED_ID.to_lowercase());
+        let response = blocklist
+            .ensure_allowed(&lowercase)
+            .unwrap_err()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        assert!(blocklist.remove(&lowercase).is_some());
+        assert!(blocklist.ensure_allowed(BLOCKED_ID).is_ok());
+    }
+
+    #[test]
+    fn stale_or_unloaded_copy_fails_closed() {
+        let status = |result: ApiResult<()>| result.unwrap_err().into_response().status();
+        let blocklist = Blocklist::default();
+        assert_eq!(
+            status(blocklist.ensure_allowed(BLOCKED_ID)),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let synced_at = Instant::now();
+        blocklist.replace(vec![entry(BLOCKED_ID)], synced_at);
+        let fresh = synced_at + MAX_STALENESS;
+        let stale = fresh + Duration::from_secs(1);
+        assert!(blocklist.ensure_allowed_at("COTHER", fresh).is_ok());
+        assert_eq!(
+            status(blocklist.ensure_allowed_at("COTHER", stale)),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        // A blocked contract stays blocked however old the copy
+        assert_eq!(
+            status(blocklist.ensure_allowed_at(BLOCKED_ID, stale)),
+            StatusCode::FORBIDDEN
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Admin endpoints for the contract blocklist (see `blocklist`).
+//
+// Every change is written to `blocked_contract_audit` in the same transaction
+// as the blocklist row, so the audit trail cannot drift from the table. The
+// actor recorded is the authenticated operator (see `admin_auth`).
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    admin_auth::AdminOperator,
+    blocklist::{normalize, BlockedContract},
+    error::{ApiError, ApiResult},
+    handlers::db_internal_error,
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct BlockContractRequest {
+    pub contract_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BlocklistAuditEntry {
+    pub id: Uuid,
+    pub contract_id: String,
+    pub action: String,
+    pub reason: Option<String>,
+    pub actor: String,
+    pub created_at: DateTime<Utc>,
+}
+
+fn invalid_payload(err: JsonRejection) -> ApiError {
+    ApiError::bad_request(
+        "InvalidRequest",
+        format!("Invalid JSON payload: {}", err.body_text()),
+    )
+}
+
+fn required(field: &str, value: &str) -> ApiResult<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidRequest",
+            format!("{} must not be empty", field),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+/// GET /api/admin/blocked-contracts
+pub async fn list_blocked_contracts(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<BlockedContract>>> {
+    let entries: Vec<BlockedContract> =
+        sqlx::query_as("SELECT * FROM blocked_contracts ORDER BY created_at DESC")
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("list blocked contracts", err))?;
+    Ok(Json(entries))
+}
+
+/// POST /api/admin/blocked-contracts
+pub async fn block_contract(
+    State(state): State<AppState>,
+    Extension(AdminOperator(blocked_by)): Extension<AdminOperator>,
+    payload: Result<Json<BlockContractRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<BlockedContract>)> {
+    let Json(req) = payload.map_err(invalid_payload)?;
+    let contract_id = normalize(&req.contract_id);
+    crate::validation::validate_contract_id(&contract_id)
+        .map_err(|e| ApiError::bad_request("InvalidContractId", e))?;
+    let reason = required("reason", &req.reason)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin block contract", err))?;
+
+    let entry: BlockedContract = sqlx::query_as(
+        "INSERT INTO blocked_contracts (contract_id, reason, blocked_by) \
+         VALUES ($1, $2, $3) RETURNING *",
+    )
+    .bind(&contract_id)
+    .bind(&reason)
+    .bind(&blocked_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            "ContractAlreadyBlocked",
+            format!("Contract {} is already blocked", contract_id),
+        ),
+        _ => db_internal_error("insert blocked contract", err),
+    })?;
+
+    record_audit(&mut tx, &contract_id, "blocked", Some(&reason), &blocked_by).await?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit block contract", err))?;
+
+    tracing::warn!(contract_id = %contract_id, blocked_by = %blocked_by, "contract blocked");
+    state.blocklist.insert(entry.clone());
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// DELETE /api/admin/blocked-contracts/:contract_id
+pub async fn unblock_contract(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Extension(AdminOperator(unblocked_by)): Extension<AdminOperator>,
+) -> ApiResult<StatusCode> {
+    let contract_id = normalize(&contract_id);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin unblock contract", err))?;
+
+    let removed = sqlx::query("DELETE FROM blocked_contracts WHERE contract_id = $1")
+        .bind(&contract_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("delete blocked contract", err))?
+        .rows_affected();
+    if removed == 0 {
+        return Err(ApiError::not_found(
+            "BlockedContractNotFound",
+            format!("Contract {} is not blocked", contract_id),
+        ));
+    }
+
+    record_audit(&mut tx, &contract_id, "unblocked", None, &unblocked_by).await?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit unblock contract", err))?;
+
+    tracing::warn!(contract_id = %contract_id, unblocked_by = %unblocked_by, "contract unblocked");
+    state.blocklist.remove(&contract_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/admin/blocked-contracts/:contract_id/audit
+pub async fn get_blocklist_audit(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+) -> ApiResult<Json<Vec<BlocklistAuditEntry>>> {
+    let entries: Vec<BlocklistAuditEntry> = sqlx::query_as(
+        "SELECT * FROM blocked_contract_audit WHERE contract_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(normalize(&contract_id))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list blocklist audit", err))?;
+    Ok(Json(entries))
+}
+
+async fn record_audit(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contract_id: &str,
+    action: &str,
+    reason: Option<&str>,
+    actor: &str,
+) -> ApiResult<()> {
+    sqlx::query(
+        "INSERT INTO blocked_contract_audit (contract_id, action, reason, actor) \
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(contract_id)
+    .bind(action)
+    .bind(reason)
+    .bind(actor)
+    .execute(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("insert blocklist audit", err))?;
+    Ok(())
+}
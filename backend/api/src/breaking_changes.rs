@@ -1,26 +1,29 @@
 use axum::{
     extract::{Query, State},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::handlers::fetch_contract_identity;
 use crate::state::AppState;
 use crate::type_safety::parser::parse_json_spec;
 use crate::type_safety::types::{
     ContractABI, ContractFunction, EnumVariant, SorobanType, StructField,
 };
 
-#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ChangeSeverity {
     Breaking,
     NonBreaking,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BreakingChange {
     pub severity: ChangeSeverity,
     pub category: String,
@@ -45,12 +48,28 @@ pub struct BreakingChangeReport {
 pub struct BreakingChangeQuery {
     pub old_id: String,
     pub new_id: String,
+    /// `json-patch` returns an RFC 6902 document instead of the change report
+    pub format: Option<String>,
 }
 
 pub async fn get_breaking_changes(
     Query(query): Query<BreakingChangeQuery>,
     State(state): State<AppState>,
-) -> ApiResult<Json<BreakingChangeReport>> {
+) -> ApiResult<Response> {
+    let as_json_patch = match query.format.as_deref() {
+        None | Some("report") => false,
+        Some("json-patch") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                "InvalidFormat",
+                format!(
+                    "Unknown format '{}'; expected 'report' or 'json-patch'",
+                    other
+                ),
+            ))
+        }
+    };
+
     let old_abi = resolve_abi(&state, &query.old_id).await?;
     let new_abi = resolve_abi(&state, &query.new_id).await?;
 
@@ -61,6 +80,10 @@ pub async fn get_breaking_changes(
         ApiError::bad_request("InvalidABI", format!("Failed to parse new ABI: {}", e))
     })?;
 
+    if as_json_patch {
+        return Ok(Json(diff_as_json_patch(&old_spec, &new_spec)).into_response());
+    }
+
     let changes = diff_abi(&old_spec, &new_spec);
     let breaking_count = changes
         .iter()
@@ -75,9 +98,221 @@ pub async fn get_breaking_changes(
         breaking_count,
         non_breaking_count,
         changes,
+    })
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContractComparisonQuery {
+    pub a: Option<String>,
+    pub b: Option<String>,
+}
+
+/// A method as it appears in one contract's ABI
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub name: String,
+    /// `name: type` for each parameter, in order
+    pub params: Vec<String>,
+    pub return_type: String,
+}
+
+/// A method both contracts expose
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct CommonMethod {
+    pub name: String,
+    /// Same parameter types and return type on both sides
+    pub signatures_match: bool,
+    pub a: MethodSignature,
+    pub b: MethodSignature,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct MethodComparison {
+    pub common: Vec<CommonMethod>,
+    pub only_in_a: Vec<MethodSignature>,
+    pub only_in_b: Vec<MethodSignature>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ContractComparison {
+    pub a: Uuid,
+    pub b: Uuid,
+    #[serde(flatten)]
+    pub methods: MethodComparison,
+}
+
+/// Each function of `abi` by name, with types rendered for display
+pub fn extract_functions_from_abi(abi: &ContractABI) -> BTreeMap<String, MethodSignature> {
+    abi.functions
+        .iter()
+        .map(|f| {
+            let signature = MethodSignature {
+                name: f.name.clone(),
+                params: f
+                    .params
+                    .iter()
+                    .map(|p| format!("{}: {}", p.name, p.param_type.display_name()))
+                    .collect(),
+                return_type: f.return_type.display_name(),
+            };
+            (f.name.clone(), signature)
+        })
+        .collect()
+}
+
+/// Split two contracts' methods into shared and one-sided sets, sorted by
+/// name. Parameter names may differ between common methods; only types
+/// decide whether their signatures match.
+pub fn compare_methods(a: &ContractABI, b: &ContractABI) -> MethodComparison {
+    let a_funcs = extract_functions_from_abi(a);
+    let mut b_funcs = extract_functions_from_abi(b);
+    let types_match = |name: &str| match (a.find_function(name), b.find_function(name)) {
+        (Some(fa), Some(fb)) => {
+            fa.return_type == fb.return_type
+                && fa.params.len() == fb.params.len()
+                && fa
+                    .params
+                    .iter()
+                    .zip(&fb.params)
+                    .all(|(pa, pb)| pa.param_type == pb.param_type)
+        }
+        _ => false,
+    };
+
+    let mut comparison = MethodComparison::default();
+    for (name, a_sig) in a_funcs {
+        match b_funcs.remove(&name) {
+            Some(b_sig) => comparison.common.push(CommonMethod {
+                signatures_match: types_match(&name),
+                name,
+                a: a_sig,
+                b: b_sig,
+            }),
+            None => comparison.only_in_a.push(a_sig),
+        }
+    }
+    comparison.only_in_b = b_funcs.into_values().collect();
+    comparison
+}
+
+/// GET /api/contracts/compare?a=<id>&b=<id>
+///
+/// Compares the latest ABIs of two different contracts method by method.
+/// Versions of one contract are compared with `breaking-changes` instead.
+pub async fn compare_contracts(
+    Query(query): Query<ContractComparisonQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ContractComparison>> {
+    let required = |value: Option<String>, name: &str| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                ApiError::bad_request(
+                    "InvalidQuery",
+                    format!("Query parameter '{}' is required", name),
+                )
+            })
+    };
+    let a_id = required(query.a, "a")?;
+    let b_id = required(query.b, "b")?;
+
+    let (a_uuid, _) = fetch_contract_identity(&state, &a_id).await?;
+    let (b_uuid, _) = fetch_contract_identity(&state, &b_id).await?;
+    if a_uuid == b_uuid {
+        return Err(ApiError::bad_request(
+            "SameContract",
+            "'a' and 'b' name the same contract; use breaking-changes to compare its versions",
+        ));
+    }
+
+    let parse = |abi: String, id: &str| {
+        parse_json_spec(&abi, id).map_err(|e| {
+            ApiError::bad_request(
+                "InvalidABI",
+                format!("Failed to parse ABI of '{}': {}", id, e),
+            )
+        })
+    };
+    let a_abi = parse(
+        fetch_latest_abi_for_contract(&state, &a_uuid.to_string()).await?,
+        &a_id,
+    )?;
+    let b_abi = parse(
+        fetch_latest_abi_for_contract(&state, &b_uuid.to_string()).await?,
+        &b_id,
+    )?;
+
+    Ok(Json(ContractComparison {
+        a: a_uuid,
+        b: b_uuid,
+        methods: compare_methods(&a_abi, &b_abi),
     }))
 }
 
+/// Express the difference between two ABIs as an RFC 6902 JSON Patch that
+/// transforms `old` into `new`.
+pub fn diff_as_json_patch(old: &ContractABI, new: &ContractABI) -> Value {
+    let old = serde_json::to_value(old).unwrap_or(Value::Null);
+    let new = serde_json::to_value(new).unwrap_or(Value::Null);
+
+    let mut ops = Vec::new();
+    json_patch_ops(&old, &new, "", &mut ops);
+    Value::Array(ops)
+}
+
+fn json_patch_ops(old: &Value, new: &Value, path: &str, ops: &mut Vec<Value>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            // Sort keys so the document is stable across HashMap orderings
+            let mut removed: Vec<&String> = old_map
+                .keys()
+                .filter(|k| !new_map.contains_key(*k))
+                .collect();
+            removed.sort();
+            for key in removed {
+                ops.push(json!({ "op": "remove", "path": child_path(path, key) }));
+            }
+
+            let mut keys: Vec<&String> = new_map.keys().collect();
+            keys.sort();
+            for key in keys {
+                let child = child_path(path, key);
+                match old_map.get(key) {
+                    Some(old_value) => json_patch_ops(old_value, &new_map[key], &child, ops),
+                    None => ops.push(json!({ "op": "add", "path": child, "value": new_map[key] })),
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            let common = old_items.len().min(new_items.len());
+            for idx in 0..common {
+                json_patch_ops(
+                    &old_items[idx],
+                    &new_items[idx],
+                    &format!("{}/{}", path, idx),
+                    ops,
+                );
+            }
+            // Remove from the end so earlier indices stay valid
+            for idx in (common..old_items.len()).rev() {
+                ops.push(json!({ "op": "remove", "path": format!("{}/{}", path, idx) }));
+            }
+            for item in &new_items[common..] {
+                ops.push(json!({ "op": "add", "path": format!("{}/-", path), "value": item }));
+            }
+        }
+        _ if old == new => {}
+        _ => ops.push(json!({ "op": "replace", "path": path, "value": new })),
+    }
+}
+
+/// Append an object key to a JSON Pointer, escaping `~` and `/`
+fn child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
 pub fn diff_abi(old: &ContractABI, new: &ContractABI) -> Vec<BreakingChange> {
     let mut changes = Vec::new();
 
@@ -416,7 +651,10 @@ async fn fetch_latest_abi_for_contract(state: &AppState, contract_id: &str) -> A
     let uuid = fetch_contract_uuid(state, contract_id).await?;
 
     if let Some(abi) = sqlx::query_scalar::<_, serde_json::Value>(
-        "SELECT abi FROM contract_abis WHERE contract_id = $1 ORDER BY created_at DESC LIMIT 1",
+        "SELECT a.abi FROM contract_abis a WHERE a.contract_id = $1 \
+         AND NOT EXISTS (SELECT 1 FROM contract_versions v WHERE v.contract_id = a.contract_id \
+             AND v.version = a.version AND v.status = 'draft') \
+         ORDER BY a.created_at DESC LIMIT 1",
     )
     .bind(uuid)
     .fetch_optional(&state.db)
@@ -450,7 +688,7 @@ async fn fetch_abi_by_contract_and_version(
     fetch_abi_by_contract_uuid_and_version(state, uuid, version).await
 }
 
-async fn fetch_abi_by_contract_uuid_and_version(
+pub(crate) async fn fetch_abi_by_contract_uuid_and_version(
     state: &AppState,
     contract_id: Uuid,
     version: &str,
@@ -548,6 +786,36 @@ mod tests {
             .any(|c| c.category == "param_type_changed" && c.severity == ChangeSeverity::Breaking));
     }
 
+    #[test]
+    fn json_patch_round_trips_old_abi_to_new() {
+        let mut old = ContractABI::new("Token".to_string());
+        old.functions.push(func(
+            "transfer",
+            vec![param("amount", SorobanType::U64)],
+            SorobanType::Void,
+        ));
+        old.functions.push(func("burn", vec![], SorobanType::Void));
+        old.types.insert("Old/Type".to_string(), SorobanType::U32);
+
+        let mut new = ContractABI::new("Token".to_string());
+        new.version = Some("2.0.0".to_string());
+        new.functions.push(func(
+            "transfer",
+            vec![param("amount", SorobanType::U128)],
+            SorobanType::Bool,
+        ));
+        new.types
+            .insert("New~Type".to_string(), SorobanType::Address);
+
+        let patch: json_patch::Patch =
+            serde_json::from_value(diff_as_json_patch(&old, &new)).unwrap();
+        let mut doc = serde_json::to_value(&old).unwrap();
+        json_patch::patch(&mut doc, &patch).unwrap();
+
+        assert_eq!(doc, serde_json::to_value(&new).unwrap());
+        assert_eq!(diff_as_json_patch(&new, &new), json!([]));
+    }
+
     #[test]
     fn detects_function_addition_as_non_breaking() {
         let old = ContractABI::new("Old".to_string());
@@ -560,4 +828,62 @@ mod tests {
             .iter()
             .any(|c| c.category == "function_added" && c.severity == ChangeSeverity::NonBreaking));
     }
+
+    #[test]
+    fn compares_methods_of_two_contracts() {
+        let mut a = ContractABI::new("TokenA".to_string());
+        a.functions.push(func(
+            "transfer",
+            vec![
+                param("from", SorobanType::Address),
+                param("to", SorobanType::Address),
+                param("amount", SorobanType::U64),
+            ],
+            SorobanType::Void,
+        ));
+        a.functions.push(func(
+            "balance",
+            vec![param("id", SorobanType::Address)],
+            SorobanType::U64,
+        ));
+        a.functions.push(func("mint", vec![], SorobanType::Void));
+
+        let mut b = ContractABI::new("TokenB".to_string());
+        b.functions.push(func(
+            "transfer",
+            vec![
+                param("sender", SorobanType::Address),
+                param("recipient", SorobanType::Address),
+                param("value", SorobanType::U64),
+            ],
+            SorobanType::Void,
+        ));
+        b.functions.push(func(
+            "balance",
+            vec![param("id", SorobanType::Address)],
+            SorobanType::I128,
+        ));
+        b.functions.push(func("burn", vec![], SorobanType::Void));
+
+        let comparison = compare_methods(&a, &b);
+
+        let common: Vec<(&str, bool)> = comparison
+            .common
+            .iter()
+            .map(|m| (m.name.as_str(), m.signatures_match))
+            .collect();
+        // Renamed parameters still match; a changed return type does not
+        assert_eq!(common, vec![("balance", false), ("transfer", true)]);
+        assert_eq!(comparison.common[0].b.return_type, "i128");
+        assert_eq!(comparison.only_in_a.len(), 1);
+        assert_eq!(comparison.only_in_a[0].name, "mint");
+        assert_eq!(comparison.only_in_b.len(), 1);
+        assert_eq!(comparison.only_in_b[0].name, "burn");
+
+        let extracted = extract_functions_from_abi(&a);
+        assert_eq!(
+            extracted["transfer"].params,
+            vec!["from: Address", "to: Address", "amount: u64"]
+        );
+    }
 }
@@ -0,0 +1,599 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::{ContractVersion, SemVer};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::breaking_changes::{
+    diff_abi, fetch_abi_by_contract_uuid_and_version, BreakingChange, ChangeSeverity,
+};
+use crate::cache::CacheLayer;
+use crate::error::{ApiError, ApiResult};
+use crate::handlers::{db_internal_error, fetch_contract_identity};
+use crate::state::AppState;
+use crate::type_safety::parser::parse_json_spec;
+use crate::type_safety::types::ContractABI;
+
+/// Most recent entries rendered into the Atom feed
+const ATOM_MAX_ENTRIES: usize = 50;
+
+/// Cache key for a contract's computed changelog
+const CHANGELOG_CACHE_KEY: &str = "changelog";
+const CHANGELOG_CACHE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Deserialize)]
+pub struct ChangelogQuery {
+    /// Embed the full `diff_abi` change list for each entry
+    #[serde(default)]
+    pub include_changes: bool,
+    /// Only return entries newer than this published version
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_hash: Option<String>,
+    pub breaking: bool,
+    pub breaking_changes: Vec<String>,
+    /// Full structured change list; only present with `?include_changes=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<BreakingChange>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangelogResponse {
+    pub contract_id: String,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// Every entry of a contract's changelog, with full change lists, as of
+/// `latest_version`. Requests trim and strip it to what they asked for.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedChangelog {
+    latest_version: String,
+    entries: Vec<ChangelogEntry>,
+}
+
+/// GET /api/contracts/:id/changelog
+///
+/// Entries are returned newest first; breaking-change markers are computed
+/// against the previous published version, including for the oldest entry
+/// returned when `?since=` trims the history.
+pub async fn get_contract_changelog(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ChangelogQuery>,
+) -> ApiResult<Json<ChangelogResponse>> {
+    load_changelog(&state, &id, &query).await.map(Json)
+}
+
+/// GET /api/contracts/:id/changelog.atom
+///
+/// The changelog as an Atom feed, newest first and capped at
+/// `ATOM_MAX_ENTRIES`, for subscribing to a contract's releases.
+pub async fn get_contract_changelog_atom(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Response> {
+    let query = ChangelogQuery {
+        include_changes: false,
+        since: None,
+    };
+    let changelog = load_changelog(&state, &id, &query).await?;
+    let feed = render_atom(&changelog, Utc::now());
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        feed,
+    )
+        .into_response())
+}
+
+async fn load_changelog(
+    state: &AppState,
+    id: &str,
+    query: &ChangelogQuery,
+) -> ApiResult<ChangelogResponse> {
+    let (contract_uuid, contract_id) = fetch_contract_identity(state, id).await?;
+
+    let mut versions: Vec<ContractVersion> = sqlx::query_as(
+        "SELECT * FROM contract_versions WHERE contract_id = $1 AND status = 'published'",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch versions for changelog", err))?;
+    sort_versions(&mut versions);
+
+    let start = first_entry_index(&versions, query.since.as_deref())?;
+    let latest_version = versions
+        .last()
+        .map(|v| v.version.clone())
+        .unwrap_or_default();
+
+    let entries = match cached_changelog(&state.cache, contract_uuid, &latest_version).await {
+        Some(entries) => entries,
+        None => {
+            let abis = load_abis(state, contract_uuid, &versions).await;
+            let entries = build_entries(&versions, 0, &abis, true);
+            store_changelog(&state.cache, contract_uuid, &latest_version, &entries).await;
+            entries
+        }
+    };
+
+    Ok(ChangelogResponse {
+        contract_id,
+        entries: select_entries(entries, versions.len() - start, query.include_changes),
+    })
+}
+
+/// The cached changelog of `contract_uuid`, unless it was computed before
+/// `latest_version` was published
+async fn cached_changelog(
+    cache: &CacheLayer,
+    contract_uuid: Uuid,
+    latest_version: &str,
+) -> Option<Vec<ChangelogEntry>> {
+    let (cached, true) = cache
+        .get(&contract_uuid.to_string(), CHANGELOG_CACHE_KEY)
+        .await
+    else {
+        return None;
+    };
+    let cached: CachedChangelog = serde_json::from_str(&cached?).ok()?;
+    (cached.latest_version == latest_version).then_some(cached.entries)
+}
+
+async fn store_changelog(
+    cache: &CacheLayer,
+    contract_uuid: Uuid,
+    latest_version: &str,
+    entries: &[ChangelogEntry],
+) {
+    let cached = CachedChangelog {
+        latest_version: latest_version.to_string(),
+        entries: entries.to_vec(),
+    };
+    if let Ok(serialized) = serde_json::to_string(&cached) {
+        cache
+            .put(
+                &contract_uuid.to_string(),
+                CHANGELOG_CACHE_KEY,
+                serialized,
+                Some(CHANGELOG_CACHE_TTL),
+            )
+            .await;
+    }
+}
+
+/// Drop the cached changelog of `contract_uuid`; called whenever its
+/// published versions change
+pub async fn invalidate_changelog(cache: &CacheLayer, contract_uuid: Uuid) {
+    cache
+        .invalidate(&contract_uuid.to_string(), CHANGELOG_CACHE_KEY)
+        .await;
+}
+
+/// The newest `count` entries of a full changelog, with change lists only
+/// when requested
+fn select_entries(
+    mut entries: Vec<ChangelogEntry>,
+    count: usize,
+    include_changes: bool,
+) -> Vec<ChangelogEntry> {
+    entries.truncate(count);
+    if !include_changes {
+        for entry in &mut entries {
+            entry.changes = None;
+        }
+    }
+    entries
+}
+
+/// Render `changelog` as an Atom 1.0 feed. `now` stands in for the feed's
+/// `updated` time when there are no entries.
+fn render_atom(changelog: &ChangelogResponse, now: DateTime<Utc>) -> String {
+    let contract_id = xml_escape(&changelog.contract_id);
+    let feed_id = format!("urn:soroban-registry:contract:{}", contract_id);
+    let entries = &changelog.entries[..changelog.entries.len().min(ATOM_MAX_ENTRIES)];
+    let updated = entries.iter().map(|e| e.created_at).max().unwrap_or(now);
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <id>{}</id>\n", feed_id));
+    feed.push_str(&format!("  <title>{} changelog</title>\n", contract_id));
+    feed.push_str(&format!(
+        "  <link rel=\"alternate\" href=\"/api/contracts/{}/changelog\"/>\n",
+        contract_id
+    ));
+    feed.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+    feed.push_str("  <author><name>Soroban Registry</name></author>\n");
+
+    for entry in entries {
+        let version = xml_escape(&entry.version);
+        let title = if entry.breaking {
+            format!("{} (breaking)", version)
+        } else {
+            version.clone()
+        };
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!("    <id>{}:version:{}</id>\n", feed_id, version));
+        feed.push_str(&format!("    <title>{}</title>\n", title));
+        feed.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            entry.created_at.to_rfc3339()
+        ));
+        if entry.breaking {
+            feed.push_str("    <category term=\"breaking\"/>\n");
+        }
+        feed.push_str(&format!(
+            "    <content type=\"text\">{}</content>\n",
+            xml_escape(&entry_summary(entry))
+        ));
+        feed.push_str("  </entry>\n");
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+/// Release notes followed by the breaking changes, one per line
+fn entry_summary(entry: &ChangelogEntry) -> String {
+    let mut summary = entry
+        .release_notes
+        .clone()
+        .unwrap_or_else(|| format!("Version {}", entry.version));
+    if !entry.breaking_changes.is_empty() {
+        summary.push_str("\n\nBreaking changes:");
+        for change in &entry.breaking_changes {
+            summary.push_str("\n- ");
+            summary.push_str(change);
+        }
+    }
+    summary
+}
+
+fn xml_escape(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Order versions oldest first by semver, falling back to creation time for
+/// versions that do not parse.
+fn sort_versions(versions: &mut [ContractVersion]) {
+    versions.sort_by(
+        |a, b| match (SemVer::parse(&a.version), SemVer::parse(&b.version)) {
+            (Some(va), Some(vb)) => va.cmp(&vb),
+            _ => a.created_at.cmp(&b.created_at),
+        },
+    );
+}
+
+/// Index of the oldest version to include, given sorted versions and an
+/// optional `since` version that must itself be published.
+fn first_entry_index(versions: &[ContractVersion], since: Option<&str>) -> ApiResult<usize> {
+    let Some(since) = since else {
+        return Ok(0);
+    };
+
+    versions
+        .iter()
+        .position(|v| v.version == since)
+        .map(|idx| idx + 1)
+        .ok_or_else(|| {
+            ApiError::bad_request(
+                "InvalidSinceVersion",
+                format!("'{}' is not a published version of this contract", since),
+            )
+        })
+}
+
+/// Build entries for `versions[start..]`, newest first, diffing each against
+/// the version before it (which may precede `start`).
+fn build_entries(
+    versions: &[ContractVersion],
+    start: usize,
+    abis: &HashMap<String, ContractABI>,
+    include_changes: bool,
+) -> Vec<ChangelogEntry> {
+    let mut entries: Vec<ChangelogEntry> = versions
+        .iter()
+        .enumerate()
+        .skip(start)
+        .map(|(idx, version)| {
+            let previous = idx
+                .checked_sub(1)
+                .and_then(|prev| abis.get(&versions[prev].version));
+            build_entry(
+                version,
+                previous,
+                abis.get(&version.version),
+                include_changes,
+            )
+        })
+        .collect();
+    entries.reverse();
+    entries
+}
+
+fn build_entry(
+    version: &ContractVersion,
+    previous: Option<&ContractABI>,
+    current: Option<&ContractABI>,
+    include_changes: bool,
+) -> ChangelogEntry {
+    let changes = match (previous, current) {
+        (Some(old), Some(new)) => diff_abi(old, new),
+        _ => Vec::new(),
+    };
+    let breaking_changes: Vec<String> = changes
+        .iter()
+        .filter(|c| c.severity == ChangeSeverity::Breaking)
+        .map(|c| c.message.clone())
+        .collect();
+
+    ChangelogEntry {
+        version: version.version.clone(),
+        created_at: version.created_at,
+        release_notes: version.release_notes.clone(),
+        commit_hash: version.commit_hash.clone(),
+        breaking: !breaking_changes.is_empty(),
+        breaking_changes,
+        changes: include_changes.then_some(changes),
+    }
+}
+
+/// Resolve and parse each version's ABI once so adjacent diffs share them.
+///
+/// A version without a stored (or parseable) ABI simply has no diff.
+async fn load_abis(
+    state: &AppState,
+    contract_id: Uuid,
+    versions: &[ContractVersion],
+) -> HashMap<String, ContractABI> {
+    let mut abis = HashMap::with_capacity(versions.len());
+    for version in versions {
+        if abis.contains_key(&version.version) {
+            continue;
+        }
+        if let Ok(raw) =
+            fetch_abi_by_contract_uuid_and_version(state, contract_id, &version.version).await
+        {
+            if let Ok(abi) = parse_json_spec(&raw, &version.version) {
+                abis.insert(version.version.clone(), abi);
+            }
+        }
+    }
+    abis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_safety::types::{ContractFunction, FunctionVisibility, SorobanType};
+
+    fn version(v: &str) -> ContractVersion {
+        ContractVersion {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            version: v.to_string(),
+            wasm_hash: "hash".to_string(),
+            source_url: None,
+            commit_hash: None,
+            release_notes: None,
+            created_at: Utc::now(),
+            state_schema: None,
+            signature: None,
+            publisher_key: None,
+            signature_algorithm: None,
+            status: shared::VersionStatus::Published,
+        }
+    }
+
+    fn abi_with(functions: &[&str]) -> ContractABI {
+        let mut abi = ContractABI::new("Test".to_string());
+        for name in functions {
+            abi.functions.push(ContractFunction {
+                name: name.to_string(),
+                visibility: FunctionVisibility::Public,
+                params: vec![],
+                return_type: SorobanType::Void,
+                doc: None,
+                is_mutable: true,
+            });
+        }
+        abi
+    }
+
+    #[test]
+    fn detailed_changes_only_included_when_requested() {
+        let old = abi_with(&["transfer", "burn"]);
+        let new = abi_with(&["transfer", "mint"]);
+        let v = version("2.0.0");
+
+        let light = build_entry(&v, Some(&old), Some(&new), false);
+        assert!(light.breaking);
+        assert_eq!(light.breaking_changes.len(), 1);
+        assert!(light.changes.is_none());
+        let json = serde_json::to_value(&light).unwrap();
+        assert!(json.get("changes").is_none());
+
+        let detailed = build_entry(&v, Some(&old), Some(&new), true);
+        let changes = detailed.changes.expect("changes requested");
+        assert_eq!(changes.len(), 2);
+        assert!(changes
+            .iter()
+            .any(|c| c.category == "function_added" && c.severity == ChangeSeverity::NonBreaking));
+        assert_eq!(detailed.breaking_changes, light.breaking_changes);
+    }
+
+    #[test]
+    fn first_version_has_no_changes() {
+        let entry = build_entry(&version("1.0.0"), None, Some(&abi_with(&["a"])), true);
+        assert!(!entry.breaking);
+        assert_eq!(entry.changes.map(|c| c.len()), Some(0));
+    }
+
+    #[test]
+    fn versions_sort_by_semver() {
+        let mut versions = vec![version("1.10.0"), version("1.2.0"), version("0.9.1")];
+        sort_versions(&mut versions);
+        let order: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(order, vec!["0.9.1", "1.2.0", "1.10.0"]);
+    }
+
+    #[test]
+    fn since_excludes_older_entries_and_keeps_baseline_diff() {
+        let versions = vec![version("1.0.0"), version("1.1.0"), version("2.0.0")];
+        let abis = HashMap::from([
+            ("1.0.0".to_string(), abi_with(&["transfer"])),
+            ("1.1.0".to_string(), abi_with(&["transfer", "burn"])),
+            ("2.0.0".to_string(), abi_with(&["transfer"])),
+        ]);
+
+        let start = first_entry_index(&versions, Some("1.1.0")).unwrap();
+        let entries = build_entries(&versions, start, &abis, false);
+        let listed: Vec<&str> = entries.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(listed, vec!["2.0.0"]);
+        // Still diffed against 1.1.0 even though it is not returned
+        assert!(entries[0].breaking);
+        assert_eq!(entries[0].breaking_changes.len(), 1);
+
+        let start = first_entry_index(&versions, Some("2.0.0")).unwrap();
+        assert!(build_entries(&versions, start, &abis, false).is_empty());
+
+        let full = build_entries(
+            &versions,
+            first_entry_index(&versions, None).unwrap(),
+            &abis,
+            false,
+        );
+        assert_eq!(full.len(), 3);
+        assert!(!full[1].breaking);
+    }
+
+    #[test]
+    fn atom_feed_is_well_formed_with_one_entry_per_version() {
+        let versions = vec![version("1.0.0"), version("1.1.0"), version("2.0.0")];
+        let abis = HashMap::from([
+            ("1.0.0".to_string(), abi_with(&["transfer"])),
+            ("1.1.0".to_string(), abi_with(&["transfer", "burn"])),
+            ("2.0.0".to_string(), abi_with(&["transfer"])),
+        ]);
+        let mut entries = build_entries(&versions, 0, &abis, false);
+        entries[0].release_notes = Some("Drop <burn> & tidy up".to_string());
+        let changelog = ChangelogResponse {
+            contract_id: "CTOKEN".to_string(),
+            entries,
+        };
+
+        let feed = render_atom(&changelog, Utc::now());
+        let doc = roxmltree::Document::parse(&feed).expect("feed should be well-formed XML");
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().name(), "feed");
+        assert_eq!(
+            root.tag_name().namespace(),
+            Some("http://www.w3.org/2005/Atom")
+        );
+
+        let titles: Vec<&str> = root
+            .children()
+            .filter(|n| n.has_tag_name("entry"))
+            .map(|entry| {
+                entry
+                    .children()
+                    .find(|n| n.has_tag_name("title"))
+                    .and_then(|n| n.text())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(titles, vec!["2.0.0 (breaking)", "1.1.0", "1.0.0"]);
+        // Release notes are escaped, not interpreted as markup
+        assert!(doc
+            .descendants()
+            .filter(|n| n.has_tag_name("content"))
+            .any(|n| n
+                .text()
+                .is_some_and(|t| t.starts_with("Drop <burn> & tidy up"))));
+    }
+
+    #[test]
+    fn atom_feed_is_capped() {
+        let versions: Vec<ContractVersion> = (0..ATOM_MAX_ENTRIES + 5)
+            .map(|minor| version(&format!("1.{}.0", minor)))
+            .collect();
+        let changelog = ChangelogResponse {
+            contract_id: "CTOKEN".to_string(),
+            entries: build_entries(&versions, 0, &HashMap::new(), false),
+        };
+        let feed = render_atom(&changelog, Utc::now());
+        assert_eq!(feed.matches("<entry>").count(), ATOM_MAX_ENTRIES);
+        // Newest first, so the oldest versions are the ones dropped
+        assert!(feed.contains(&format!("1.{}.0", ATOM_MAX_ENTRIES + 4)));
+        assert!(!feed.contains(">1.0.0<"));
+    }
+
+    #[test]
+    fn since_must_be_published_version() {
+        let versions = vec![version("1.0.0")];
+        assert!(first_entry_index(&versions, Some("0.9.0")).is_err());
+    }
+
+    #[tokio::test]
+    async fn repeat_requests_hit_the_cache_until_a_version_is_created() {
+        use crate::cache::CacheConfig;
+        use std::sync::atomic::Ordering;
+
+        let cache = CacheLayer::new(CacheConfig::default());
+        let contract = Uuid::new_v4();
+        let versions = vec![version("1.0.0"), version("1.1.0")];
+        let abis = HashMap::from([
+            ("1.0.0".to_string(), abi_with(&["transfer", "burn"])),
+            ("1.1.0".to_string(), abi_with(&["transfer"])),
+        ]);
+
+        assert!(cached_changelog(&cache, contract, "1.1.0").await.is_none());
+        let entries = build_entries(&versions, 0, &abis, true);
+        store_changelog(&cache, contract, "1.1.0", &entries).await;
+
+        let hit = cached_changelog(&cache, contract, "1.1.0")
+            .await
+            .expect("second request is served from cache");
+        assert_eq!(cache.metrics().hits.load(Ordering::Relaxed), 1);
+        assert_eq!(hit.len(), 2);
+        // Cached entries keep their change lists so either view can be served
+        assert!(hit[0].changes.is_some());
+        let light = select_entries(hit, 1, false);
+        assert_eq!(light.len(), 1);
+        assert_eq!(light[0].version, "1.1.0");
+        assert!(light[0].breaking);
+        assert!(light[0].changes.is_none());
+
+        // A newer latest version never matches the stale entry
+        assert!(cached_changelog(&cache, contract, "1.2.0").await.is_none());
+
+        invalidate_changelog(&cache, contract).await;
+        assert!(cached_changelog(&cache, contract, "1.1.0").await.is_none());
+    }
+}
@@ -0,0 +1,74 @@
+// Wall-clock time for time-dependent logic.
+//
+// Health scoring, interaction timestamp defaults and trending windows read
+// the current time through `AppState::clock` instead of `Utc::now()`, so tests
+// can pin "now" to an exact instant and check behaviour at day boundaries.
+// Signature timestamps and link expiries use `Clock::unix_now` for the same
+// reason. Production uses `SystemClock`, which is `Utc::now()`.
+
+use chrono::{DateTime, Utc};
+
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// `now` in unix seconds, as carried in signed timestamps and expiries
+    fn unix_now(&self) -> u64 {
+        self.now().timestamp().max(0) as u64
+    }
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that stays where it is set until moved
+#[cfg(test)]
+#[derive(Debug)]
+pub struct FixedClock(std::sync::Mutex<DateTime<Utc>>);
+
+#[cfg(test)]
+impl FixedClock {
+    pub fn at(now: DateTime<Utc>) -> Self {
+        Self(std::sync::Mutex::new(now))
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fixed_clock_only_moves_when_advanced() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let clock = FixedClock::at(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(chrono::Duration::days(1));
+        assert_eq!(clock.now(), start + chrono::Duration::days(1));
+    }
+
+    #[test]
+    fn unix_now_is_whole_seconds_since_the_epoch() {
+        let clock = FixedClock::at(Utc.timestamp_opt(1_700_000_000, 999_000_000).unwrap());
+        assert_eq!(clock.unix_now(), 1_700_000_000);
+    }
+}
@@ -0,0 +1,352 @@
+// Compatibility-matrix dashboard.
+//
+// Summarizes `contract_version_compatibility` as compatible / warning /
+// incompatible totals plus a page of the most recently changed entries. An
+// entry is a warning when it is marked compatible but records no SDK version
+// it was checked against. Optional `sdk_version` and `network` filters are
+// applied in the database to both queries, so the totals always describe the
+// same set `recent_changes` pages through.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::Network;
+use uuid::Uuid;
+
+use crate::{
+    error::ApiResult,
+    handlers::{db_internal_error, escape_like},
+    pagination::PageLimits,
+    state::AppState,
+};
+
+/// Page size of `recent_changes`
+const RECENT_CHANGES_PAGE: PageLimits = PageLimits::new(10, 100);
+
+/// Entries kept by the filter bound as `$1` (exact SDK version), `$2` (its
+/// patch-release `LIKE` pattern) and `$3` (network)
+const FILTERED_ENTRIES: &str = "FROM contract_version_compatibility cvc \
+     JOIN contracts sc ON sc.id = cvc.source_contract_id \
+     JOIN contracts tc ON tc.id = cvc.target_contract_id \
+     WHERE ($1::text IS NULL OR cvc.stellar_version = $1 \
+            OR cvc.stellar_version LIKE $2::text ESCAPE '\\') \
+       AND ($3::network_type IS NULL OR sc.network = $3)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompatibilityStatus {
+    Compatible,
+    Warning,
+    Incompatible,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct CompatibilityEntry {
+    pub source_contract_id: Uuid,
+    pub source_contract_name: String,
+    pub network: Network,
+    pub source_version: String,
+    pub target_contract_id: Uuid,
+    pub target_contract_name: String,
+    pub target_version: String,
+    /// SDK version the pair was checked against
+    pub stellar_version: Option<String>,
+    pub is_compatible: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CompatibilityEntry {
+    pub fn status(&self) -> CompatibilityStatus {
+        match (self.is_compatible, &self.stellar_version) {
+            (false, _) => CompatibilityStatus::Incompatible,
+            (true, None) => CompatibilityStatus::Warning,
+            (true, Some(_)) => CompatibilityStatus::Compatible,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DashboardFilter {
+    /// "22", "22.x" or "22.0.1"; a version matches itself and its patches
+    pub sdk_version: Option<String>,
+    pub network: Option<Network>,
+    /// Page size of `recent_changes` (default 10, max 100)
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+impl DashboardFilter {
+    /// The SDK version asked for, without any `.x` / `.*` wildcard suffix
+    fn sdk_prefix(&self) -> Option<&str> {
+        self.sdk_version
+            .as_deref()
+            .map(|v| v.trim().trim_end_matches(".x").trim_end_matches(".*"))
+            .filter(|v| !v.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct CompatibilityTotals {
+    pub compatible: i64,
+    pub warning: i64,
+    pub incompatible: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentCompatibilityChange {
+    #[serde(flatten)]
+    pub entry: CompatibilityEntry,
+    pub status: CompatibilityStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityDashboardResponse {
+    pub sdk_version: Option<String>,
+    pub network: Option<Network>,
+    pub totals: CompatibilityTotals,
+    pub recent_changes: Vec<RecentCompatibilityChange>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// GET /api/compatibility/dashboard?sdk_version=&network=&limit=&offset=
+pub async fn get_compatibility_dashboard(
+    State(state): State<AppState>,
+    Query(filter): Query<DashboardFilter>,
+) -> ApiResult<Json<CompatibilityDashboardResponse>> {
+    let sdk_version = filter.sdk_prefix();
+    let sdk_patches = sdk_version.map(|v| format!("{}.%", escape_like(v)));
+    let limit = RECENT_CHANGES_PAGE.resolve(filter.limit);
+    let offset = filter.offset.max(0);
+
+    let totals: CompatibilityTotals = sqlx::query_as(&format!(
+        "SELECT COUNT(*) FILTER (WHERE cvc.is_compatible \
+                  AND cvc.stellar_version IS NOT NULL) AS compatible, \
+                COUNT(*) FILTER (WHERE cvc.is_compatible \
+                  AND cvc.stellar_version IS NULL) AS warning, \
+                COUNT(*) FILTER (WHERE NOT cvc.is_compatible) AS incompatible \
+         {}",
+        FILTERED_ENTRIES
+    ))
+    .bind(sdk_version)
+    .bind(&sdk_patches)
+    .bind(&filter.network)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count compatibility matrix", err))?;
+
+    let entries: Vec<CompatibilityEntry> = sqlx::query_as(&format!(
+        "SELECT cvc.source_contract_id, sc.name AS source_contract_name, sc.network, \
+                cvc.source_version, cvc.target_contract_id, \
+                tc.name AS target_contract_name, cvc.target_version, \
+                cvc.stellar_version, cvc.is_compatible, cvc.updated_at \
+         {} \
+         ORDER BY cvc.updated_at DESC, cvc.id DESC \
+         LIMIT $4 OFFSET $5",
+        FILTERED_ENTRIES
+    ))
+    .bind(sdk_version)
+    .bind(&sdk_patches)
+    .bind(&filter.network)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("load compatibility matrix", err))?;
+
+    let recent_changes = entries
+        .into_iter()
+        .map(|entry| RecentCompatibilityChange {
+            status: entry.status(),
+            entry,
+        })
+        .collect();
+
+    Ok(Json(CompatibilityDashboardResponse {
+        sdk_version: filter.sdk_version.clone(),
+        network: filter.network.clone(),
+        totals,
+        recent_changes,
+        limit,
+        offset,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Seeds one matrix entry per row; each gets its own source version
+    async fn seed(state: &AppState, rows: &[(Network, Option<&str>, bool, u32)]) {
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let target = crate::test_db::insert_contract(&state.db, publisher, "CAMM").await;
+        let mainnet = crate::test_db::insert_contract(&state.db, publisher, "CMAIN").await;
+        let testnet = crate::test_db::insert_contract(&state.db, publisher, "CTEST").await;
+        sqlx::query("UPDATE contracts SET network = 'mainnet' WHERE id = $1")
+            .bind(mainnet)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        // The update trigger stamps NOW(), so backdate with it disabled
+        sqlx::query(
+            "ALTER TABLE contract_version_compatibility \
+             DISABLE TRIGGER update_compatibility_updated_at",
+        )
+        .execute(&state.db)
+        .await
+        .unwrap();
+        for (i, (network, sdk, compatible, day)) in rows.iter().enumerate() {
+            let source = match network {
+                Network::Mainnet => mainnet,
+                _ => testnet,
+            };
+            sqlx::query(
+                "INSERT INTO contract_version_compatibility \
+                 (source_contract_id, source_version, target_contract_id, target_version, \
+                  stellar_version, is_compatible) \
+                 VALUES ($1, $2, $3, '2.0.0', $4, $5)",
+            )
+            .bind(source)
+            .bind(format!("1.0.{}", i))
+            .bind(target)
+            .bind(sdk)
+            .bind(compatible)
+            .execute(&state.db)
+            .await
+            .unwrap();
+            sqlx::query(
+                "UPDATE contract_version_compatibility SET updated_at = $2 \
+                 WHERE source_version = $1",
+            )
+            .bind(format!("1.0.{}", i))
+            .bind(Utc.with_ymd_and_hms(2026, 3, *day, 0, 0, 0).unwrap())
+            .execute(&state.db)
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn dashboard(
+        state: &AppState,
+        filter: DashboardFilter,
+    ) -> CompatibilityDashboardResponse {
+        let Json(response) = get_compatibility_dashboard(State(state.clone()), Query(filter))
+            .await
+            .unwrap();
+        response
+    }
+
+    fn sdk(version: &str, network: Option<Network>) -> DashboardFilter {
+        DashboardFilter {
+            sdk_version: Some(version.to_string()),
+            network,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn filtered_totals_differ_from_global_ones() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        seed(
+            &state,
+            &[
+                (Network::Mainnet, Some("22.0.1"), true, 1),
+                (Network::Mainnet, Some("22.1.0"), false, 2),
+                (Network::Testnet, Some("22.0.0"), true, 3),
+                (Network::Testnet, Some("21.5.0"), false, 4),
+                (Network::Mainnet, Some("21.5.0"), false, 5),
+                (Network::Mainnet, None, true, 6),
+                (Network::Mainnet, Some("220.0.0"), true, 7),
+            ],
+        )
+        .await;
+
+        let global = dashboard(&state, DashboardFilter::default()).await;
+        assert_eq!(
+            global.totals,
+            CompatibilityTotals {
+                compatible: 3,
+                warning: 1,
+                incompatible: 3,
+            }
+        );
+        assert_eq!(global.recent_changes.len(), 7);
+
+        let sdk_22 = dashboard(&state, sdk("22.x", None)).await;
+        assert_eq!(
+            sdk_22.totals,
+            CompatibilityTotals {
+                compatible: 2,
+                warning: 0,
+                incompatible: 1,
+            }
+        );
+
+        let mainnet_22 = dashboard(&state, sdk("22", Some(Network::Mainnet))).await;
+        assert_eq!(
+            mainnet_22.totals,
+            CompatibilityTotals {
+                compatible: 1,
+                warning: 0,
+                incompatible: 1,
+            }
+        );
+        // Recent changes come from the same filtered set, newest first
+        let versions: Vec<_> = mainnet_22
+            .recent_changes
+            .iter()
+            .map(|c| c.entry.stellar_version.as_deref().unwrap())
+            .collect();
+        assert_eq!(versions, ["22.1.0", "22.0.1"]);
+        assert_eq!(
+            mainnet_22.recent_changes[0].status,
+            CompatibilityStatus::Incompatible
+        );
+
+        // LIKE wildcards in the filter match literally
+        let wildcard = dashboard(&state, sdk("2_", None)).await;
+        assert_eq!(wildcard.totals, CompatibilityTotals::default());
+    }
+
+    #[tokio::test]
+    async fn recent_changes_are_paged() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let rows: Vec<_> = (1..=20)
+            .map(|day| (Network::Testnet, Some("22.0.0"), true, day))
+            .collect();
+        seed(&state, &rows).await;
+
+        let first = dashboard(&state, DashboardFilter::default()).await;
+        assert_eq!(first.totals.compatible, 20);
+        assert_eq!(first.recent_changes.len(), 10);
+        assert_eq!(
+            first.recent_changes[0].entry.updated_at,
+            Utc.with_ymd_and_hms(2026, 3, 20, 0, 0, 0).unwrap()
+        );
+
+        let last = dashboard(
+            &state,
+            DashboardFilter {
+                offset: 15,
+                ..Default::default()
+            },
+        )
+        .await;
+        // Paging doesn't change the totals
+        assert_eq!(last.totals.compatible, 20);
+        assert_eq!(last.recent_changes.len(), 5);
+        assert_eq!(
+            last.recent_changes[0].entry.updated_at,
+            Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap()
+        );
+    }
+}
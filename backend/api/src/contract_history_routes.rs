@@ -43,6 +43,11 @@ pub fn contract_history_routes() -> Router<AppState> {
             "/api/contracts/:id/rollback/:snapshot_id",
             post(contract_history_handlers::rollback_contract),
         )
+        // Values of one metadata field over time
+        .route(
+            "/api/contracts/:id/metadata-history",
+            get(contract_history_handlers::get_metadata_history),
+        )
         // Verify entire audit log hash-chain
         .route(
             "/api/contracts/:id/history/verify",
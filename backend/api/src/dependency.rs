@@ -141,6 +141,252 @@ pub async fn build_dependency_graph(pool: &PgPool) -> Result<GraphResponse> {
     })
 }
 
+/// Size caps for dependency graph responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphLimits {
+    pub max_nodes: usize,
+    pub max_edges: usize,
+}
+
+impl Default for GraphLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 5_000,
+            max_edges: 20_000,
+        }
+    }
+}
+
+impl GraphLimits {
+    /// `DEPENDENCY_GRAPH_MAX_NODES` and `DEPENDENCY_GRAPH_MAX_EDGES`; unset or
+    /// invalid values keep their default
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let cap = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|v| *v > 0)
+        };
+        Self {
+            max_nodes: cap("DEPENDENCY_GRAPH_MAX_NODES").unwrap_or(defaults.max_nodes),
+            max_edges: cap("DEPENDENCY_GRAPH_MAX_EDGES").unwrap_or(defaults.max_edges),
+        }
+    }
+
+    pub fn allows(&self, nodes: usize, edges: usize) -> bool {
+        nodes <= self.max_nodes && edges <= self.max_edges
+    }
+}
+
+/// Node and edge counts of the whole dependency graph
+pub async fn dependency_graph_size(pool: &PgPool) -> Result<(usize, usize)> {
+    let (nodes, edges): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM contracts), \
+                (SELECT COUNT(*) FROM contract_dependencies WHERE dependency_contract_id IS NOT NULL)"
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok((nodes as usize, edges as usize))
+}
+
+/// Contracts within `depth` hops of `root`, following edges in either
+/// direction, and the edges between them
+pub fn bounded_subgraph(
+    root: Uuid,
+    depth: u32,
+    edges: &[GraphEdge],
+) -> (HashSet<Uuid>, Vec<GraphEdge>) {
+    let mut adjacent: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for edge in edges {
+        adjacent.entry(edge.source).or_default().push(edge.target);
+        adjacent.entry(edge.target).or_default().push(edge.source);
+    }
+
+    let mut reached = HashSet::from([root]);
+    let mut frontier = vec![root];
+    for _ in 0..depth {
+        frontier = frontier
+            .iter()
+            .flat_map(|node| adjacent.get(node).into_iter().flatten())
+            .filter(|next| reached.insert(**next))
+            .copied()
+            .collect();
+        if frontier.is_empty() {
+            break;
+        }
+    }
+
+    let kept = edges
+        .iter()
+        .filter(|e| reached.contains(&e.source) && reached.contains(&e.target))
+        .cloned()
+        .collect();
+    (reached, kept)
+}
+
+/// The subgraph within `depth` hops of `root`, loading one level of edges at
+/// a time so only the region around `root` is read
+pub async fn build_dependency_subgraph(
+    pool: &PgPool,
+    root: Uuid,
+    depth: u32,
+) -> Result<GraphResponse> {
+    let mut edges: Vec<GraphEdge> = Vec::new();
+    let mut seen_edges = HashSet::new();
+    let mut visited = HashSet::from([root]);
+    let mut frontier = vec![root];
+
+    for _ in 0..depth {
+        let level: Vec<GraphEdge> = sqlx::query_as(
+            "SELECT contract_id as source, dependency_contract_id as target, 'calls' as dependency_type
+             FROM contract_dependencies
+             WHERE dependency_contract_id IS NOT NULL
+               AND (contract_id = ANY($1) OR dependency_contract_id = ANY($1))"
+        )
+        .bind(&frontier)
+        .fetch_all(pool)
+        .await?;
+
+        let mut next = Vec::new();
+        for edge in level {
+            for node in [edge.source, edge.target] {
+                if visited.insert(node) {
+                    next.push(node);
+                }
+            }
+            if seen_edges.insert((edge.source, edge.target)) {
+                edges.push(edge);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let (reached, edges) = bounded_subgraph(root, depth, &edges);
+    let ids: Vec<Uuid> = reached.into_iter().collect();
+    let nodes: Vec<GraphNode> = sqlx::query_as(
+        "SELECT id, contract_id, name, network, is_verified, category, tags FROM contracts WHERE id = ANY($1)"
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(GraphResponse { nodes, edges })
+}
+
+/// A contract reached walking dependents outward from a set of roots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReachedDependent {
+    pub id: Uuid,
+    /// The contract it depends on through which it was first reached
+    pub via: Uuid,
+    /// Hops from the nearest root
+    pub depth: u32,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DependentsWalk {
+    /// Contracts beyond the roots, in breadth-first order
+    pub reached: Vec<ReachedDependent>,
+    /// The depth or node bound stopped the walk while dependents remained
+    pub truncated: bool,
+}
+
+/// Dependents of `roots`, directly or transitively (the `source` of each edge
+/// depends on its `target`), at most `max_depth` hops out and `max_nodes`
+/// contracts in all, roots included
+pub fn bounded_dependents(
+    roots: &[Uuid],
+    max_depth: u32,
+    max_nodes: usize,
+    edges: &[GraphEdge],
+) -> DependentsWalk {
+    let mut dependents_of: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for edge in edges {
+        dependents_of
+            .entry(edge.target)
+            .or_default()
+            .push(edge.source);
+    }
+
+    let mut visited: HashSet<Uuid> = roots.iter().copied().collect();
+    let mut frontier: Vec<Uuid> = roots.to_vec();
+    let mut walk = DependentsWalk::default();
+    for depth in 1..=max_depth {
+        let mut next = Vec::new();
+        for node in &frontier {
+            for dependent in dependents_of.get(node).into_iter().flatten() {
+                if visited.contains(dependent) {
+                    continue;
+                }
+                if visited.len() >= max_nodes {
+                    walk.truncated = true;
+                    return walk;
+                }
+                visited.insert(*dependent);
+                walk.reached.push(ReachedDependent {
+                    id: *dependent,
+                    via: *node,
+                    depth,
+                });
+                next.push(*dependent);
+            }
+        }
+        if next.is_empty() {
+            return walk;
+        }
+        frontier = next;
+    }
+
+    walk.truncated = frontier.iter().any(|node| {
+        dependents_of
+            .get(node)
+            .into_iter()
+            .flatten()
+            .any(|dependent| !visited.contains(dependent))
+    });
+    walk
+}
+
+/// Dependency edges leading out to the dependents of `roots`, loaded one level
+/// at a time. One level past `max_depth` is read so `bounded_dependents` can
+/// tell whether the depth bound cut anything off.
+pub async fn load_dependent_edges(
+    pool: &PgPool,
+    roots: &[Uuid],
+    max_depth: u32,
+    max_nodes: usize,
+) -> Result<Vec<GraphEdge>> {
+    let mut edges = Vec::new();
+    let mut visited: HashSet<Uuid> = roots.iter().copied().collect();
+    let mut frontier = roots.to_vec();
+
+    for _ in 0..=max_depth {
+        if frontier.is_empty() || visited.len() > max_nodes {
+            break;
+        }
+        let level: Vec<GraphEdge> = sqlx::query_as(
+            "SELECT contract_id as source, dependency_contract_id as target, 'calls' as dependency_type
+             FROM contract_dependencies
+             WHERE dependency_contract_id = ANY($1)"
+        )
+        .bind(&frontier)
+        .fetch_all(pool)
+        .await?;
+
+        frontier = level
+            .iter()
+            .map(|edge| edge.source)
+            .filter(|source| visited.insert(*source))
+            .collect();
+        edges.extend(level);
+    }
+    Ok(edges)
+}
+
 /// Resolve a dependency name/id to a contract UUID if it exists in the registry
 pub async fn resolve_contract_id(pool: &PgPool, identifier: &str) -> Result<Option<Uuid>> {
     // Try UUID first
@@ -177,17 +423,25 @@ pub async fn save_dependencies(
     contract_id: Uuid,
     decls: &[DependencyDeclaration],
 ) -> Result<()> {
+    let mut resolved = Vec::with_capacity(decls.len());
+    for decl in decls {
+        let dep_contract_id = resolve_contract_id(pool, &decl.name).await?;
+        resolved.push((decl.clone(), dep_contract_id));
+    }
+    let resolved = dedupe_dependencies(resolved);
+
     // Clear existing dependencies (optional, depends on if we want to merge or replace)
     sqlx::query("DELETE FROM contract_dependencies WHERE contract_id = $1")
         .bind(contract_id)
         .execute(pool)
         .await?;
 
-    for decl in decls {
-        let dep_contract_id = resolve_contract_id(pool, &decl.name).await?;
-
+    for (decl, dep_contract_id) in &resolved {
         if let Some(dep_id) = dep_contract_id {
-            if detect_cycle(pool, contract_id, dep_id).await.unwrap_or(false) {
+            if detect_cycle(pool, contract_id, *dep_id)
+                .await
+                .unwrap_or(false)
+            {
                 tracing::warn!(
                     "Circular dependency detected: contract {} -> {}",
                     contract_id,
@@ -214,11 +468,167 @@ pub async fn save_dependencies(
     Ok(())
 }
 
+/// Collapse declarations that point at the same dependency, keyed on the
+/// resolved contract ID when there is one and the name otherwise.
+///
+/// The first occurrence keeps its position and name; a later duplicate
+/// replaces the version constraint unless it is only the `*` wildcard, so a
+/// detected dependency never loosens a declared one.
+fn dedupe_dependencies(
+    resolved: Vec<(DependencyDeclaration, Option<Uuid>)>,
+) -> Vec<(DependencyDeclaration, Option<Uuid>)> {
+    #[derive(PartialEq, Eq, Hash)]
+    enum Key {
+        Contract(Uuid),
+        Name(String),
+    }
+
+    let mut index: HashMap<Key, usize> = HashMap::new();
+    let mut unique: Vec<(DependencyDeclaration, Option<Uuid>)> = Vec::new();
+    for (decl, dep_contract_id) in resolved {
+        let key = match dep_contract_id {
+            Some(id) => Key::Contract(id),
+            None => Key::Name(decl.name.clone()),
+        };
+        match index.get(&key) {
+            Some(&existing) => {
+                if decl.version_constraint.trim() != "*" {
+                    unique[existing].0.version_constraint = decl.version_constraint;
+                }
+            }
+            None => {
+                index.insert(key, unique.len());
+                unique.push((decl, dep_contract_id));
+            }
+        }
+    }
+    unique
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    fn decl(name: &str, version_constraint: &str) -> DependencyDeclaration {
+        DependencyDeclaration {
+            name: name.to_string(),
+            version_constraint: version_constraint.to_string(),
+        }
+    }
+
+    #[test]
+    fn overlapping_declared_and_detected_dependencies_store_one_edge() {
+        let token = Uuid::new_v4();
+        let oracle = Uuid::new_v4();
+        let resolved = vec![
+            // declared by the publisher
+            (decl("token-contract", "^1.2"), Some(token)),
+            (decl("oracle", "*"), Some(oracle)),
+            // detected from the ABI, resolving to the same contracts
+            (decl("TokenInterface", "*"), Some(token)),
+            (decl("OracleInterface", "~2.0"), Some(oracle)),
+        ];
+
+        let unique = dedupe_dependencies(resolved);
+        assert_eq!(unique.len(), 2);
+        assert_eq!(unique[0].0.name, "token-contract");
+        assert_eq!(unique[0].0.version_constraint, "^1.2");
+        assert_eq!(unique[1].1, Some(oracle));
+        assert_eq!(unique[1].0.version_constraint, "~2.0");
+    }
+
+    #[test]
+    fn unresolved_dependencies_are_deduplicated_by_name() {
+        let unique = dedupe_dependencies(vec![
+            (decl("external", "*"), None),
+            (decl("external", "^3"), None),
+            (decl("other", "*"), None),
+        ]);
+        assert_eq!(unique.len(), 2);
+        assert_eq!(unique[0].0.version_constraint, "^3");
+    }
+
+    fn edge(source: Uuid, target: Uuid) -> GraphEdge {
+        GraphEdge {
+            source,
+            target,
+            dependency_type: "calls".to_string(),
+        }
+    }
+
+    #[test]
+    fn subgraph_keeps_only_nodes_within_depth() {
+        // app -> router -> token -> math, and wallet -> token; lone is unconnected
+        let [app, router, token, math, wallet, lone] = [(); 6].map(|_| Uuid::new_v4());
+        let edges = vec![
+            edge(app, router),
+            edge(router, token),
+            edge(token, math),
+            edge(wallet, token),
+            edge(lone, lone),
+        ];
+
+        let (nodes, kept) = bounded_subgraph(router, 1, &edges);
+        assert_eq!(nodes, HashSet::from([router, app, token]));
+        assert_eq!(kept.len(), 2);
+
+        // Dependents of a dependency are reachable too
+        let (nodes, kept) = bounded_subgraph(router, 2, &edges);
+        assert_eq!(nodes, HashSet::from([router, app, token, math, wallet]));
+        assert_eq!(kept.len(), 4);
+        assert!(!nodes.contains(&lone));
+
+        let (nodes, kept) = bounded_subgraph(app, 0, &edges);
+        assert_eq!(nodes, HashSet::from([app]));
+        assert!(kept.is_empty());
+    }
+
+    #[test]
+    fn dependents_walk_stops_at_its_bounds() {
+        // app -> router -> token <- wallet; token -> math
+        let [app, router, token, math, wallet] = [(); 5].map(|_| Uuid::new_v4());
+        let edges = vec![
+            edge(app, router),
+            edge(router, token),
+            edge(token, math),
+            edge(wallet, token),
+        ];
+
+        let walk = bounded_dependents(&[token], 5, 100, &edges);
+        let reached: Vec<(Uuid, Uuid, u32)> = walk
+            .reached
+            .iter()
+            .map(|r| (r.id, r.via, r.depth))
+            .collect();
+        assert_eq!(
+            reached,
+            vec![(router, token, 1), (wallet, token, 1), (app, router, 2)]
+        );
+        assert!(!walk.truncated);
+        // Dependencies of the root are not part of its blast radius
+        assert!(!walk.reached.iter().any(|r| r.id == math));
+
+        let shallow = bounded_dependents(&[token], 1, 100, &edges);
+        assert_eq!(shallow.reached.len(), 2);
+        assert!(shallow.truncated);
+
+        let capped = bounded_dependents(&[token], 5, 2, &edges);
+        assert_eq!(capped.reached.len(), 1);
+        assert!(capped.truncated);
+    }
+
+    #[test]
+    fn graph_limits_cap_nodes_and_edges() {
+        let limits = GraphLimits {
+            max_nodes: 3,
+            max_edges: 2,
+        };
+        assert!(limits.allows(3, 2));
+        assert!(!limits.allows(4, 0));
+        assert!(!limits.allows(1, 3));
+    }
+
     #[test]
     fn test_detect_dependencies() {
         let abi = json!([
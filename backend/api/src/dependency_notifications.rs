@@ -0,0 +1,281 @@
+// Breaking-change notices for dependents.
+//
+// When a contract publishes a version that breaks its previous ABI, every
+// contract that declares it in `contract_dependencies` has its publisher
+// notified through `notification_logs`, with the breaking-change messages
+// from `diff_abi`. Publishers without notification preferences get these
+// notices; once a publisher has preferences covering a dependent (for that
+// contract or for all contracts), an enabled one must list
+// `dependency_breaking_change`.
+
+use uuid::Uuid;
+
+use crate::{
+    breaking_changes::{BreakingChange, ChangeSeverity},
+    disaster_recovery_models::UserNotificationPreference,
+    state::AppState,
+};
+
+pub const NOTIFICATION_TYPE: &str = "dependency_breaking_change";
+
+/// A contract depending on the one that changed, and who publishes it
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct Dependent {
+    pub contract_uuid: Uuid,
+    pub contract_id: String,
+    pub publisher_id: Uuid,
+    pub publisher_address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyNotice {
+    pub dependent: Uuid,
+    pub recipients: Vec<String>,
+    pub message: String,
+}
+
+/// Whether the publisher with `preferences` wants breaking-change notices
+/// about `dependent`
+fn wants_notice(preferences: &[UserNotificationPreference], dependent: Uuid) -> bool {
+    let mut applicable = preferences
+        .iter()
+        .filter(|p| p.contract_id.is_none_or(|id| id == dependent))
+        .peekable();
+    if applicable.peek().is_none() {
+        return true;
+    }
+    applicable.any(|p| p.enabled && p.notification_types.iter().any(|t| t == NOTIFICATION_TYPE))
+}
+
+/// One notice per dependent that wants it; nothing when `changes` holds no
+/// breaking change
+pub fn plan_notices(
+    dependency_id: &str,
+    version: &str,
+    changes: &[BreakingChange],
+    dependents: &[Dependent],
+    preferences: &[UserNotificationPreference],
+) -> Vec<DependencyNotice> {
+    let breaking: Vec<&str> = changes
+        .iter()
+        .filter(|c| c.severity == ChangeSeverity::Breaking)
+        .map(|c| c.message.as_str())
+        .collect();
+    if breaking.is_empty() {
+        return Vec::new();
+    }
+
+    dependents
+        .iter()
+        .filter(|dependent| {
+            let own: Vec<UserNotificationPreference> = preferences
+                .iter()
+                .filter(|p| p.user_id == dependent.publisher_id)
+                .cloned()
+                .collect();
+            wants_notice(&own, dependent.contract_uuid)
+        })
+        .map(|dependent| {
+            let mut message = format!(
+                "{} depends on {}, which published {} with breaking changes:",
+                dependent.contract_id, dependency_id, version
+            );
+            for change in &breaking {
+                message.push_str("\n- ");
+                message.push_str(change);
+            }
+            DependencyNotice {
+                dependent: dependent.contract_uuid,
+                recipients: vec![dependent.publisher_address.clone()],
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Notify dependents of `contract_uuid` in the background once `version`
+/// is published
+pub fn spawn_notify_dependents(
+    state: &AppState,
+    contract_uuid: Uuid,
+    contract_id: String,
+    version: String,
+    changes: Vec<BreakingChange>,
+) {
+    if !changes
+        .iter()
+        .any(|c| c.severity == ChangeSeverity::Breaking)
+    {
+        return;
+    }
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(err) =
+            notify_dependents(&state, contract_uuid, &contract_id, &version, &changes).await
+        {
+            tracing::warn!(
+                contract = %contract_uuid,
+                version = %version,
+                error = ?err,
+                "failed to notify dependents of breaking changes"
+            );
+        }
+    });
+}
+
+async fn notify_dependents(
+    state: &AppState,
+    contract_uuid: Uuid,
+    contract_id: &str,
+    version: &str,
+    changes: &[BreakingChange],
+) -> Result<(), sqlx::Error> {
+    let dependents: Vec<Dependent> = sqlx::query_as(
+        "SELECT DISTINCT c.id AS contract_uuid, c.contract_id, \
+                p.id AS publisher_id, p.stellar_address AS publisher_address \
+         FROM contract_dependencies cd \
+         JOIN contracts c ON c.id = cd.contract_id \
+         JOIN publishers p ON p.id = c.publisher_id \
+         WHERE cd.dependency_contract_id = $1 AND cd.contract_id <> $1",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await?;
+    if dependents.is_empty() {
+        return Ok(());
+    }
+
+    let publisher_ids: Vec<Uuid> = dependents.iter().map(|d| d.publisher_id).collect();
+    let preferences: Vec<UserNotificationPreference> =
+        sqlx::query_as("SELECT * FROM user_notification_preferences WHERE user_id = ANY($1)")
+            .bind(&publisher_ids)
+            .fetch_all(&state.db)
+            .await?;
+
+    let notices = plan_notices(contract_id, version, changes, &dependents, &preferences);
+    for notice in &notices {
+        sqlx::query(
+            "INSERT INTO notification_logs \
+               (contract_id, notification_type, recipients, message, sent_at, status) \
+             VALUES ($1, $2, $3, $4, NOW(), 'sent')",
+        )
+        .bind(notice.dependent)
+        .bind(NOTIFICATION_TYPE)
+        .bind(&notice.recipients)
+        .bind(&notice.message)
+        .execute(&state.db)
+        .await?;
+    }
+    tracing::info!(
+        contract = %contract_uuid,
+        version = %version,
+        notified = notices.len(),
+        dependents = dependents.len(),
+        "notified dependents of breaking changes"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn dependent(publisher_id: Uuid, address: &str) -> Dependent {
+        Dependent {
+            contract_uuid: Uuid::new_v4(),
+            contract_id: format!("C{}", address),
+            publisher_id,
+            publisher_address: address.to_string(),
+        }
+    }
+
+    fn preference(
+        user_id: Uuid,
+        contract_id: Option<Uuid>,
+        types: &[&str],
+        enabled: bool,
+    ) -> UserNotificationPreference {
+        UserNotificationPreference {
+            id: Uuid::new_v4(),
+            user_id,
+            contract_id,
+            notification_types: types.iter().map(|t| t.to_string()).collect(),
+            channels: vec!["email".to_string()],
+            enabled,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn change(severity: ChangeSeverity, message: &str) -> BreakingChange {
+        BreakingChange {
+            severity,
+            category: "function_removed".to_string(),
+            message: message.to_string(),
+            function: None,
+            type_name: None,
+        }
+    }
+
+    #[test]
+    fn dependents_are_notified_when_a_breaking_version_lands() {
+        let (quiet, opted_in, opted_out) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let dependents = vec![
+            dependent(quiet, "GQUIET"),
+            dependent(opted_in, "GOPTEDIN"),
+            dependent(opted_out, "GOPTEDOUT"),
+        ];
+        let preferences = vec![
+            preference(opted_in, None, &[NOTIFICATION_TYPE], true),
+            preference(opted_out, None, &["recovery_started"], true),
+        ];
+        let changes = vec![
+            change(ChangeSeverity::Breaking, "Function 'burn' was removed"),
+            change(ChangeSeverity::NonBreaking, "Function 'mint' was added"),
+        ];
+
+        let notices = plan_notices("CTOKEN", "2.0.0", &changes, &dependents, &preferences);
+        let recipients: Vec<&str> = notices.iter().map(|n| n.recipients[0].as_str()).collect();
+        assert_eq!(recipients, ["GQUIET", "GOPTEDIN"]);
+        assert_eq!(notices[0].dependent, dependents[0].contract_uuid);
+        assert!(notices[0].message.contains("CTOKEN"));
+        assert!(notices[0].message.contains("2.0.0"));
+        assert!(notices[0].message.contains("Function 'burn' was removed"));
+        assert!(!notices[0].message.contains("mint"));
+
+        // Non-breaking releases notify nobody
+        let additive = vec![change(
+            ChangeSeverity::NonBreaking,
+            "Function 'mint' was added",
+        )];
+        assert!(plan_notices("CTOKEN", "1.1.0", &additive, &dependents, &preferences).is_empty());
+    }
+
+    #[test]
+    fn contract_scoped_preference_overrides_for_that_dependent_only() {
+        let publisher = Uuid::new_v4();
+        let muted = dependent(publisher, "GMUTED");
+        let other = dependent(publisher, "GOTHER");
+        let preferences = vec![preference(
+            publisher,
+            Some(muted.contract_uuid),
+            &[NOTIFICATION_TYPE],
+            false,
+        )];
+        let changes = vec![change(
+            ChangeSeverity::Breaking,
+            "Function 'burn' was removed",
+        )];
+
+        let notices = plan_notices(
+            "CTOKEN",
+            "2.0.0",
+            &changes,
+            &[muted, other.clone()],
+            &preferences,
+        );
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].dependent, other.contract_uuid);
+    }
+}
@@ -3,7 +3,11 @@ use axum::{
     Json,
 };
 use chrono::{DateTime, Utc};
-use shared::{DeprecateContractRequest, DeprecationInfo, DeprecationStatus};
+use shared::{
+    DeprecateContractRequest, DeprecatedMethodUsage, DeprecatedUsageResponse, DeprecationInfo,
+    DeprecationStatus,
+};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
@@ -133,6 +137,78 @@ pub async fn deprecate_contract(
     get_deprecation_info(State(state), Path(contract_id)).await
 }
 
+/// Sunset dates of a contract's deprecated methods, keyed by method name
+pub(crate) type MethodSunsets = HashMap<String, DateTime<Utc>>;
+
+pub(crate) async fn fetch_method_sunsets(
+    state: &AppState,
+    contract_uuid: Uuid,
+) -> ApiResult<MethodSunsets> {
+    let rows: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT method, sunset_at FROM contract_method_deprecations WHERE contract_id = $1",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch method deprecations", err))?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// The sunset date a call to `method` at `called_at` has passed, if any
+pub(crate) fn passed_sunset(
+    sunsets: &MethodSunsets,
+    method: Option<&str>,
+    called_at: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    method
+        .and_then(|m| sunsets.get(m))
+        .copied()
+        .filter(|sunset_at| called_at >= *sunset_at)
+}
+
+/// GET /api/contracts/:id/deprecated-usage
+///
+/// How often each method past its sunset date is still being called.
+pub async fn get_deprecated_usage(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<DeprecatedUsageResponse>> {
+    let (contract_uuid, contract_id) = fetch_contract_identity(&state, &id).await?;
+
+    let methods: Vec<DeprecatedMethodUsage> = sqlx::query_as(
+        "SELECT d.method, d.sunset_at, d.replacement_method, \
+                COUNT(ci.id) AS calls_after_sunset, MAX(ci.created_at) AS last_called_at \
+         FROM contract_method_deprecations d \
+         LEFT JOIN contract_interactions ci \
+           ON ci.contract_id = d.contract_id AND ci.method = d.method AND ci.deprecated_call \
+         WHERE d.contract_id = $1 AND d.sunset_at <= NOW() \
+         GROUP BY d.method, d.sunset_at, d.replacement_method",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("summarize deprecated usage", err))?;
+
+    Ok(Json(summarize_deprecated_usage(contract_id, methods)))
+}
+
+fn summarize_deprecated_usage(
+    contract_id: String,
+    mut methods: Vec<DeprecatedMethodUsage>,
+) -> DeprecatedUsageResponse {
+    methods.sort_by(|a, b| {
+        b.calls_after_sunset
+            .cmp(&a.calls_after_sunset)
+            .then_with(|| a.method.cmp(&b.method))
+    });
+    DeprecatedUsageResponse {
+        contract_id,
+        total_calls_after_sunset: methods.iter().map(|m| m.calls_after_sunset).sum(),
+        methods,
+    }
+}
+
 async fn notify_dependents(
     state: &AppState,
     deprecated_id: Uuid,
@@ -270,3 +346,51 @@ async fn column_exists(state: &AppState, table: &str, column: &str) -> ApiResult
 
     Ok(exists)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn call_after_sunset_is_flagged_and_summarized() {
+        let sunset_at = Utc::now() - Duration::days(7);
+        let sunsets: MethodSunsets = HashMap::from([("legacy_swap".to_string(), sunset_at)]);
+
+        let called_at = Utc::now();
+        assert_eq!(
+            passed_sunset(&sunsets, Some("legacy_swap"), called_at),
+            Some(sunset_at)
+        );
+        // Calls before the sunset, and to other methods, are not flagged
+        assert_eq!(
+            passed_sunset(&sunsets, Some("legacy_swap"), sunset_at - Duration::days(1)),
+            None
+        );
+        assert_eq!(passed_sunset(&sunsets, Some("swap"), called_at), None);
+        assert_eq!(passed_sunset(&sunsets, None, called_at), None);
+
+        let summary = summarize_deprecated_usage(
+            "CABC".to_string(),
+            vec![
+                DeprecatedMethodUsage {
+                    method: "old_mint".to_string(),
+                    sunset_at,
+                    replacement_method: None,
+                    calls_after_sunset: 0,
+                    last_called_at: None,
+                },
+                DeprecatedMethodUsage {
+                    method: "legacy_swap".to_string(),
+                    sunset_at,
+                    replacement_method: Some("swap".to_string()),
+                    calls_after_sunset: 1,
+                    last_called_at: Some(called_at),
+                },
+            ],
+        );
+        assert_eq!(summary.total_calls_after_sunset, 1);
+        assert_eq!(summary.methods[0].method, "legacy_swap");
+        assert_eq!(summary.methods[0].last_called_at, Some(called_at));
+    }
+}
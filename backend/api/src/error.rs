@@ -52,6 +52,10 @@ impl ApiError {
         Self::new(StatusCode::UNPROCESSABLE_ENTITY, error, message)
     }
 
+    pub fn forbidden(error: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, error, message)
+    }
+
     pub fn conflict(error: impl Into<String>, message: impl Into<String>) -> Self {
         Self::new(StatusCode::CONFLICT, error, message)
     }
@@ -59,6 +63,18 @@ impl ApiError {
     pub fn db_error(message: impl Into<String>) -> Self {
         Self::new(StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError", message)
     }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl IntoResponse for ApiError {
@@ -83,3 +99,9 @@ impl IntoResponse for ApiError {
 }
 
 pub type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Log a failed query and hide its details from the client
+pub(crate) fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = operation, error = ?err, "database operation failed");
+    ApiError::internal("An unexpected database error occurred")
+}
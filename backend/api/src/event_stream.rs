@@ -0,0 +1,143 @@
+// Fan-out of recorded analytics events to live subscribers.
+//
+// `analytics::record_event` publishes every stored event here, and
+// GET /api/contracts/:id/events/stream subscribes. Each contract gets its own
+// bounded broadcast channel: a subscriber that falls more than `capacity`
+// events behind skips ahead instead of buffering without limit.
+
+use futures::Stream;
+use shared::AnalyticsEvent;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// Events buffered per contract for the slowest subscriber
+pub const DEFAULT_EVENT_STREAM_CAPACITY: usize = 64;
+
+pub struct EventHub {
+    capacity: usize,
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<AnalyticsEvent>>>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_STREAM_CAPACITY)
+    }
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Receive events recorded for `contract_id` from now on
+    pub fn subscribe(&self, contract_id: Uuid) -> broadcast::Receiver<AnalyticsEvent> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .entry(contract_id)
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+
+    /// Deliver `event` to the contract's subscribers, if any. A channel whose
+    /// subscribers have all disconnected is dropped here.
+    pub fn publish(&self, event: AnalyticsEvent) {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = channels.get(&event.contract_id) {
+            let contract_id = event.contract_id;
+            if sender.send(event).is_err() {
+                channels.remove(&contract_id);
+            }
+        }
+    }
+
+    /// Number of contracts that currently have a live channel
+    pub fn active_channels(&self) -> usize {
+        self.channels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+}
+
+/// Turn a subscription into a stream of events. Events a lagging subscriber
+/// missed are skipped; the stream ends when the hub drops the channel.
+pub fn subscriber_stream(
+    receiver: broadcast::Receiver<AnalyticsEvent>,
+) -> impl Stream<Item = AnalyticsEvent> {
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "event stream subscriber lagged");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use shared::AnalyticsEventType;
+
+    fn event(contract_id: Uuid, n: i64) -> AnalyticsEvent {
+        AnalyticsEvent {
+            id: Uuid::new_v4(),
+            event_type: AnalyticsEventType::InteractionRecorded,
+            contract_id,
+            user_address: None,
+            network: None,
+            metadata: Some(serde_json::json!({ "n": n })),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_event_is_delivered_to_subscriber() {
+        let hub = EventHub::default();
+        let contract = Uuid::new_v4();
+        let mut stream = Box::pin(subscriber_stream(hub.subscribe(contract)));
+
+        // Events for other contracts are not delivered
+        hub.publish(event(Uuid::new_v4(), 0));
+        hub.publish(event(contract, 1));
+
+        let received = stream.next().await.unwrap();
+        assert_eq!(received.contract_id, contract);
+        assert_eq!(received.metadata, Some(serde_json::json!({ "n": 1 })));
+    }
+
+    #[tokio::test]
+    async fn slow_subscriber_skips_ahead_within_capacity() {
+        let hub = EventHub::new(2);
+        let contract = Uuid::new_v4();
+        let mut stream = Box::pin(subscriber_stream(hub.subscribe(contract)));
+
+        for n in 0..5 {
+            hub.publish(event(contract, n));
+        }
+
+        // Only the newest `capacity` events are still buffered
+        let first = stream.next().await.unwrap();
+        assert_eq!(first.metadata, Some(serde_json::json!({ "n": 3 })));
+    }
+
+    #[tokio::test]
+    async fn disconnected_subscribers_release_their_channel() {
+        let hub = EventHub::default();
+        let contract = Uuid::new_v4();
+        drop(hub.subscribe(contract));
+        assert_eq!(hub.active_channels(), 1);
+
+        hub.publish(event(contract, 0));
+        assert_eq!(hub.active_channels(), 0);
+    }
+}
@@ -1,15 +1,16 @@
 use anyhow::Result;
-use chrono::Utc;
 use shared::{Contract, ContractHealth, ContractStats, HealthStatus};
 use sqlx::PgPool;
 use tokio::time;
 use tracing::{error, info};
 
+use crate::clock::Clock;
 use crate::state::AppState;
 
 /// Main loop for the health monitor background task
 pub async fn run_health_monitor(state: AppState) {
     info!("Starting health monitor background task");
+    let config = HealthScoringConfig::from_env();
 
     // Run every 24 hours in production, but for demo/dev we can run it more frequently or on startup
     // For now, we'll run it on startup and then every hour
@@ -19,13 +20,17 @@ pub async fn run_health_monitor(state: AppState) {
         interval.tick().await;
         info!("Running health checks...");
 
-        if let Err(e) = perform_health_checks(&state.db).await {
+        if let Err(e) = perform_health_checks(&state.db, &config, state.clock.as_ref()).await {
             error!("Error performing health checks: {}", e);
         }
     }
 }
 
-async fn perform_health_checks(pool: &PgPool) -> Result<()> {
+async fn perform_health_checks(
+    pool: &PgPool,
+    config: &HealthScoringConfig,
+    clock: &dyn Clock,
+) -> Result<()> {
     // 1. Fetch all contracts
     let contracts: Vec<Contract> = sqlx::query_as("SELECT * FROM contracts")
         .fetch_all(pool)
@@ -53,7 +58,7 @@ async fn perform_health_checks(pool: &PgPool) -> Result<()> {
             VerificationLevel::Unverified
         };
 
-        let health = calculate_health(&contract, stats.as_ref(), verification_level);
+        let health = calculate_health(&contract, stats.as_ref(), verification_level, config, clock);
 
         // 5. Update database
         upsert_contract_health(pool, &health).await?;
@@ -93,30 +98,105 @@ impl VerificationLevel {
     }
 }
 
+/// Largest score deduction for inactivity under any curve
+const MAX_INACTIVITY_PENALTY: f64 = 40.0;
+
+/// How the inactivity penalty grows with days since the last activity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InactivityPenalty {
+    /// -20 past 30 days and another -20 past 90 days
+    #[default]
+    Stepped,
+    /// Rises linearly through the stepped values: -20 at 30 days, -40 at 90
+    /// days and beyond
+    Linear,
+    /// Approaches -40 with a 30-day half-life, so -20 at 30 days
+    Exponential,
+}
+
+impl InactivityPenalty {
+    pub fn penalty(self, days_inactive: i64) -> i32 {
+        let days = days_inactive.max(0) as f64;
+        match self {
+            Self::Stepped => {
+                let mut penalty = 0;
+                if days_inactive > 30 {
+                    penalty += 20;
+                }
+                if days_inactive > 90 {
+                    penalty += 20;
+                }
+                penalty
+            }
+            Self::Linear => {
+                let penalty = if days <= 30.0 {
+                    days * 20.0 / 30.0
+                } else {
+                    20.0 + (days - 30.0) * 20.0 / 60.0
+                };
+                penalty.min(MAX_INACTIVITY_PENALTY).round() as i32
+            }
+            Self::Exponential => {
+                (MAX_INACTIVITY_PENALTY * (1.0 - 0.5f64.powf(days / 30.0))).round() as i32
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for InactivityPenalty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stepped" => Ok(Self::Stepped),
+            "linear" => Ok(Self::Linear),
+            "exponential" => Ok(Self::Exponential),
+            other => Err(format!("unknown inactivity penalty curve '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HealthScoringConfig {
+    pub inactivity_penalty: InactivityPenalty,
+}
+
+impl HealthScoringConfig {
+    /// `HEALTH_INACTIVITY_PENALTY`: `stepped` (default), `linear` or
+    /// `exponential`
+    pub fn from_env() -> Self {
+        let inactivity_penalty = match std::env::var("HEALTH_INACTIVITY_PENALTY") {
+            Ok(value) => value.parse().unwrap_or_else(|err| {
+                tracing::warn!("{}; using stepped", err);
+                InactivityPenalty::Stepped
+            }),
+            Err(_) => InactivityPenalty::Stepped,
+        };
+        Self { inactivity_penalty }
+    }
+}
+
 fn calculate_health(
     contract: &Contract,
     stats: Option<&ContractStats>,
     verification_level: VerificationLevel,
+    config: &HealthScoringConfig,
+    clock: &dyn Clock,
 ) -> ContractHealth {
+    let now = clock.now();
     let mut score = 100;
 
     // Apply graduated verification score
     score += verification_level.score_weight();
 
-    // Penalize for inactivity (older than 30 days)
+    // Penalize for inactivity along the configured curve
     let last_activity = stats
         .and_then(|s| s.last_interaction)
         .unwrap_or(contract.created_at);
 
-    let days_since_activity = (Utc::now() - last_activity).num_days();
-
-    if days_since_activity > 30 {
-        score -= 20;
-    }
+    let days_since_activity = (now - last_activity).num_days();
 
-    if days_since_activity > 90 {
-        score -= 20;
-    }
+    score -= config.inactivity_penalty.penalty(days_since_activity);
 
     // Placeholder for audit check (not implemented yet)
     // score -= 10;
@@ -174,7 +254,7 @@ fn calculate_health(
         audit_date: None,
         total_score: score,
         recommendations,
-        updated_at: Utc::now(),
+        updated_at: now,
     }
 }
 
@@ -211,10 +291,19 @@ async fn upsert_contract_health(pool: &PgPool, health: &ContractHealth) -> Resul
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::clock::FixedClock;
+    use chrono::{DateTime, TimeZone, Utc};
     use shared::{Contract, ContractStats, Network};
     use uuid::Uuid;
 
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn clock() -> FixedClock {
+        FixedClock::at(now())
+    }
+
     fn build_dummy_contract() -> Contract {
         Contract {
             id: Uuid::new_v4(),
@@ -227,8 +316,8 @@ mod tests {
             is_verified: true,
             category: None,
             tags: vec![],
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: now(),
+            updated_at: now(),
             is_maintenance: false,
             logical_id: None,
             network_configs: None,
@@ -239,7 +328,13 @@ mod tests {
     fn test_health_score_unverified() {
         let contract = build_dummy_contract();
         // Unverified penalty: -40. Base 100 -> 60
-        let health = calculate_health(&contract, None, VerificationLevel::Unverified);
+        let health = calculate_health(
+            &contract,
+            None,
+            VerificationLevel::Unverified,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
         assert_eq!(health.total_score, 60);
         assert!(health.recommendations.contains(
             &"Verify the contract source code to improve trust and health score.".to_string()
@@ -250,7 +345,13 @@ mod tests {
     fn test_health_score_pending() {
         let contract = build_dummy_contract();
         // Pending penalty: -20. Base 100 -> 80
-        let health = calculate_health(&contract, None, VerificationLevel::Pending);
+        let health = calculate_health(
+            &contract,
+            None,
+            VerificationLevel::Pending,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
         assert_eq!(health.total_score, 80);
         assert!(health.recommendations.contains(&"Contract verification is pending. Health score will improve once verification is complete.".to_string()));
     }
@@ -259,7 +360,13 @@ mod tests {
     fn test_health_score_verified() {
         let contract = build_dummy_contract();
         // Verified: +0. Base 100 -> 100
-        let health = calculate_health(&contract, None, VerificationLevel::Verified);
+        let health = calculate_health(
+            &contract,
+            None,
+            VerificationLevel::Verified,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
         assert_eq!(health.total_score, 100);
         assert!(health.recommendations.contains(
             &"Consider obtaining an external audit to achieve maximum trust and health score."
@@ -271,7 +378,13 @@ mod tests {
     fn test_health_score_audited() {
         let contract = build_dummy_contract();
         // Audited: +20. Base 100 -> 100 (capped at 100)
-        let health = calculate_health(&contract, None, VerificationLevel::Audited);
+        let health = calculate_health(
+            &contract,
+            None,
+            VerificationLevel::Audited,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
         assert_eq!(health.total_score, 100);
     }
 
@@ -283,10 +396,132 @@ mod tests {
             total_deployments: 1,
             total_interactions: 1,
             unique_users: 1,
-            last_interaction: Some(Utc::now() - chrono::Duration::days(40)), // > 30 days inactive -> -20 penalty
+            last_interaction: Some(now() - chrono::Duration::days(40)), // > 30 days inactive -> -20 penalty
         };
         // Base 100 + 20 (Audited) - 20 (Inactive > 30 days) = 100
-        let health = calculate_health(&contract, Some(&stats), VerificationLevel::Audited);
+        let health = calculate_health(
+            &contract,
+            Some(&stats),
+            VerificationLevel::Audited,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
         assert_eq!(health.total_score, 100);
     }
+
+    fn inactive_for(contract: &Contract, days: i64) -> ContractStats {
+        ContractStats {
+            contract_id: contract.id,
+            total_deployments: 1,
+            total_interactions: 1,
+            unique_users: 1,
+            last_interaction: Some(now() - chrono::Duration::days(days)),
+        }
+    }
+
+    fn score_with(penalty: InactivityPenalty, days: i64) -> i32 {
+        let contract = build_dummy_contract();
+        let config = HealthScoringConfig {
+            inactivity_penalty: penalty,
+        };
+        let stats = inactive_for(&contract, days);
+        calculate_health(
+            &contract,
+            Some(&stats),
+            VerificationLevel::Verified,
+            &config,
+            &clock(),
+        )
+        .total_score
+    }
+
+    #[test]
+    fn curved_penalties_fall_between_the_steps_at_45_days() {
+        // Stepped: only the 30-day penalty applies
+        assert_eq!(score_with(InactivityPenalty::Stepped, 45), 80);
+        // Curves land between the 30-day (80) and 90-day (60) stepped scores
+        assert_eq!(score_with(InactivityPenalty::Linear, 45), 75);
+        assert_eq!(score_with(InactivityPenalty::Exponential, 45), 74);
+    }
+
+    #[test]
+    fn curved_penalties_at_120_days() {
+        assert_eq!(score_with(InactivityPenalty::Stepped, 120), 60);
+        assert_eq!(score_with(InactivityPenalty::Linear, 120), 60);
+        // Exponential approaches the full -40 but has not reached it
+        assert_eq!(score_with(InactivityPenalty::Exponential, 120), 62);
+    }
+
+    #[test]
+    fn curved_scores_stay_within_bounds() {
+        let contract = build_dummy_contract();
+        for penalty in [InactivityPenalty::Linear, InactivityPenalty::Exponential] {
+            let config = HealthScoringConfig {
+                inactivity_penalty: penalty,
+            };
+            for days in [0, 10_000] {
+                let stats = inactive_for(&contract, days);
+                let health = calculate_health(
+                    &contract,
+                    Some(&stats),
+                    VerificationLevel::Unverified,
+                    &config,
+                    &clock(),
+                );
+                assert!((0..=100).contains(&health.total_score));
+            }
+        }
+        assert_eq!(InactivityPenalty::Linear.penalty(10_000), 40);
+        assert_eq!(InactivityPenalty::Exponential.penalty(10_000), 40);
+    }
+
+    #[test]
+    fn stepped_penalty_applies_only_past_whole_day_boundaries() {
+        let contract = build_dummy_contract();
+        let config = HealthScoringConfig::default();
+        let clock = clock();
+        let stats = ContractStats {
+            last_interaction: Some(now() - chrono::Duration::days(30)),
+            ..inactive_for(&contract, 0)
+        };
+        let score = |clock: &FixedClock| {
+            calculate_health(
+                &contract,
+                Some(&stats),
+                VerificationLevel::Verified,
+                &config,
+                clock,
+            )
+            .total_score
+        };
+
+        // Exactly 30 days inactive is not yet "over 30 days"
+        assert_eq!(score(&clock), 100);
+        // One second short of day 31 still counts as 30 whole days
+        clock.advance(chrono::Duration::days(1) - chrono::Duration::seconds(1));
+        assert_eq!(score(&clock), 100);
+        // Day 31 begins
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(score(&clock), 80);
+
+        // The second step lands the same way at day 91
+        clock.advance(chrono::Duration::days(60) - chrono::Duration::seconds(1));
+        assert_eq!(score(&clock), 80);
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(score(&clock), 60);
+    }
+
+    #[test]
+    fn health_is_stamped_with_the_clock_time() {
+        let contract = build_dummy_contract();
+        let health = calculate_health(
+            &contract,
+            None,
+            VerificationLevel::Verified,
+            &HealthScoringConfig::default(),
+            &clock(),
+        );
+        assert_eq!(health.updated_at, now());
+        assert_eq!(health.last_activity, contract.created_at);
+    }
 }
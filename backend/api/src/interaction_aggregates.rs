@@ -0,0 +1,571 @@
+// Rebuild of `contract_interaction_daily_aggregates` from raw interactions.
+//
+// The hourly aggregation task only refreshes the last two days, so a bad
+// rollup or rows inserted by hand stay wrong for good. The admin rebuild
+// recounts every UTC day in the requested range from `contract_interactions`
+// and rewrites only the days whose stored aggregate disagrees.
+//
+// Erasure requests delete one account's raw interactions and take its
+// contribution back out of the stored aggregates in the same transaction.
+// The audit row keeps only a hash of the account, never the erased rows, and
+// names the authenticated operator who asked for the erasure.
+
+use std::collections::{BTreeMap, HashSet};
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    admin_auth::AdminOperator,
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, fetch_contract_identity},
+    state::AppState,
+};
+
+/// Longest range one rebuild may cover
+const MAX_REBUILD_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct DailyInteractions {
+    pub date: NaiveDate,
+    pub interaction_count: i64,
+    pub unique_users: i64,
+}
+
+/// Inclusive range of UTC days to recompute
+#[derive(Debug, Deserialize)]
+pub struct RebuildAggregatesRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RebuildAggregatesResponse {
+    pub contract_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Days whose stored aggregate was missing, wrong or stale
+    pub days_corrected: usize,
+    /// Recomputed aggregates for days with interactions
+    pub aggregates: Vec<DailyInteractions>,
+}
+
+/// Counts raw interactions per UTC day
+#[derive(Debug, Default)]
+pub struct DailyAggregator {
+    days: BTreeMap<NaiveDate, (i64, HashSet<String>)>,
+}
+
+impl DailyAggregator {
+    pub fn record(&mut self, created_at: DateTime<Utc>, user_address: Option<&str>) {
+        let (count, users) = self.days.entry(created_at.date_naive()).or_default();
+        *count += 1;
+        if let Some(user) = user_address {
+            users.insert(user.to_string());
+        }
+    }
+
+    /// One aggregate per day with at least one interaction, oldest first
+    pub fn finish(self) -> Vec<DailyInteractions> {
+        self.days
+            .into_iter()
+            .map(|(date, (count, users))| DailyInteractions {
+                date,
+                interaction_count: count,
+                unique_users: users.len() as i64,
+            })
+            .collect()
+    }
+}
+
+/// Changes that bring the stored aggregates in line with the recomputed ones
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RebuildPlan {
+    pub upserts: Vec<DailyInteractions>,
+    /// Stored days that no longer have any interactions
+    pub deletes: Vec<NaiveDate>,
+}
+
+impl RebuildPlan {
+    pub fn days_corrected(&self) -> usize {
+        self.upserts.len() + self.deletes.len()
+    }
+}
+
+pub fn plan_rebuild(stored: &[DailyInteractions], rebuilt: &[DailyInteractions]) -> RebuildPlan {
+    let stored: BTreeMap<NaiveDate, &DailyInteractions> =
+        stored.iter().map(|day| (day.date, day)).collect();
+    let rebuilt_dates: HashSet<NaiveDate> = rebuilt.iter().map(|day| day.date).collect();
+
+    RebuildPlan {
+        upserts: rebuilt
+            .iter()
+            .filter(|day| stored.get(&day.date) != Some(day))
+            .copied()
+            .collect(),
+        deletes: stored
+            .keys()
+            .filter(|date| !rebuilt_dates.contains(date))
+            .copied()
+            .collect(),
+    }
+}
+
+/// Stored aggregates with one account's erased interactions taken out. The
+/// account loses every interaction on each day, so it stops counting as a
+/// unique user there. Days it alone made up are deleted.
+pub fn plan_erasure(
+    stored: &[DailyInteractions],
+    erased_per_day: &BTreeMap<NaiveDate, i64>,
+) -> RebuildPlan {
+    let mut plan = RebuildPlan::default();
+    for day in stored {
+        let Some(&erased) = erased_per_day.get(&day.date) else {
+            continue;
+        };
+        let interaction_count = (day.interaction_count - erased).max(0);
+        if interaction_count == 0 {
+            plan.deletes.push(day.date);
+        } else {
+            plan.upserts.push(DailyInteractions {
+                date: day.date,
+                interaction_count,
+                unique_users: (day.unique_users - 1).max(0),
+            });
+        }
+    }
+    plan
+}
+
+async fn apply_plan(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contract_uuid: Uuid,
+    plan: &RebuildPlan,
+) -> ApiResult<()> {
+    for day in &plan.upserts {
+        sqlx::query(
+            "INSERT INTO contract_interaction_daily_aggregates \
+               (contract_id, date, interaction_count, unique_users) \
+             VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (contract_id, date) DO UPDATE SET \
+               interaction_count = EXCLUDED.interaction_count, \
+               unique_users = EXCLUDED.unique_users, \
+               updated_at = NOW()",
+        )
+        .bind(contract_uuid)
+        .bind(day.date)
+        .bind(day.interaction_count)
+        .bind(day.unique_users)
+        .execute(&mut **tx)
+        .await
+        .map_err(|err| db_internal_error("upsert daily aggregate", err))?;
+    }
+    if !plan.deletes.is_empty() {
+        sqlx::query(
+            "DELETE FROM contract_interaction_daily_aggregates \
+             WHERE contract_id = $1 AND date = ANY($2)",
+        )
+        .bind(contract_uuid)
+        .bind(&plan.deletes)
+        .execute(&mut **tx)
+        .await
+        .map_err(|err| db_internal_error("delete daily aggregates", err))?;
+    }
+    Ok(())
+}
+
+fn validate_range(req: &RebuildAggregatesRequest) -> ApiResult<()> {
+    if req.from > req.to {
+        return Err(ApiError::bad_request(
+            "InvalidDateRange",
+            "'from' must not be after 'to'",
+        ));
+    }
+    if (req.to - req.from).num_days() >= MAX_REBUILD_DAYS {
+        return Err(ApiError::bad_request(
+            "InvalidDateRange",
+            format!("A rebuild may cover at most {} days", MAX_REBUILD_DAYS),
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/admin/contracts/:id/aggregates/rebuild
+///
+/// Operator only.
+pub async fn rebuild_contract_aggregates(
+    State(state): State<AppState>,
+    Extension(AdminOperator(operator)): Extension<AdminOperator>,
+    Path(id): Path<String>,
+    Json(req): Json<RebuildAggregatesRequest>,
+) -> ApiResult<Json<RebuildAggregatesResponse>> {
+    validate_range(&req)?;
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+
+    let start = req.from.and_time(chrono::NaiveTime::MIN).and_utc();
+    let end = (req.to + Duration::days(1))
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc();
+
+    let mut aggregator = DailyAggregator::default();
+    let mut rows = sqlx::query_as::<_, (DateTime<Utc>, Option<String>)>(
+        "SELECT created_at, user_address FROM contract_interactions \
+         WHERE contract_id = $1 AND created_at >= $2 AND created_at < $3",
+    )
+    .bind(contract_uuid)
+    .bind(start)
+    .bind(end)
+    .fetch(&state.db);
+    while let Some((created_at, user_address)) = rows
+        .try_next()
+        .await
+        .map_err(|err| db_internal_error("read contract interactions", err))?
+    {
+        aggregator.record(created_at, user_address.as_deref());
+    }
+    drop(rows);
+    let rebuilt = aggregator.finish();
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin aggregate rebuild", err))?;
+
+    let stored: Vec<DailyInteractions> = sqlx::query_as(
+        "SELECT date, interaction_count, unique_users \
+         FROM contract_interaction_daily_aggregates \
+         WHERE contract_id = $1 AND date BETWEEN $2 AND $3 \
+         FOR UPDATE",
+    )
+    .bind(contract_uuid)
+    .bind(req.from)
+    .bind(req.to)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("read daily aggregates", err))?;
+
+    let plan = plan_rebuild(&stored, &rebuilt);
+    apply_plan(&mut tx, contract_uuid, &plan).await?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit aggregate rebuild", err))?;
+
+    tracing::info!(
+        contract_id = %contract_uuid,
+        days_corrected = plan.days_corrected(),
+        operator = %operator,
+        "rebuilt daily interaction aggregates"
+    );
+
+    Ok(Json(RebuildAggregatesResponse {
+        contract_id: contract_uuid,
+        from: req.from,
+        to: req.to,
+        days_corrected: plan.days_corrected(),
+        aggregates: rebuilt,
+    }))
+}
+
+/// Query params for DELETE /api/contracts/:id/interactions
+#[derive(Debug, Deserialize)]
+pub struct EraseInteractionsQuery {
+    /// `user_address` whose interactions are erased
+    pub account: String,
+    /// Must repeat `account`; guards against erasing the wrong address
+    #[serde(default)]
+    pub confirm: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EraseInteractionsResponse {
+    pub contract_id: Uuid,
+    pub interactions_erased: u64,
+    /// Daily aggregates adjusted or removed
+    pub days_corrected: usize,
+}
+
+/// Normalized account for an erasure, refused unless confirmed
+fn check_erasure(query: &EraseInteractionsQuery) -> ApiResult<String> {
+    let account = query.account.trim();
+    if account.is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidRequest",
+            "account must not be empty",
+        ));
+    }
+    if query.confirm.as_deref().map(str::trim) != Some(account) {
+        return Err(ApiError::bad_request(
+            "ConfirmationRequired",
+            "Set confirm to the account being erased",
+        ));
+    }
+    Ok(account.to_string())
+}
+
+/// DELETE /api/contracts/:id/interactions?account=&confirm=
+///
+/// Operator only; the erasure is audited under the authenticated operator.
+pub async fn erase_account_interactions(
+    State(state): State<AppState>,
+    Extension(AdminOperator(erased_by)): Extension<AdminOperator>,
+    Path(id): Path<String>,
+    Query(query): Query<EraseInteractionsQuery>,
+) -> ApiResult<Json<EraseInteractionsResponse>> {
+    let account = check_erasure(&query)?;
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin interaction erasure", err))?;
+
+    let erased: Vec<DateTime<Utc>> = sqlx::query_scalar(
+        "DELETE FROM contract_interactions \
+         WHERE contract_id = $1 AND user_address = $2 \
+         RETURNING created_at",
+    )
+    .bind(contract_uuid)
+    .bind(&account)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("erase contract interactions", err))?;
+
+    let mut erased_per_day: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    for created_at in &erased {
+        *erased_per_day.entry(created_at.date_naive()).or_default() += 1;
+    }
+    let days: Vec<NaiveDate> = erased_per_day.keys().copied().collect();
+
+    let stored: Vec<DailyInteractions> = sqlx::query_as(
+        "SELECT date, interaction_count, unique_users \
+         FROM contract_interaction_daily_aggregates \
+         WHERE contract_id = $1 AND date = ANY($2) \
+         FOR UPDATE",
+    )
+    .bind(contract_uuid)
+    .bind(&days)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("read daily aggregates", err))?;
+
+    let plan = plan_erasure(&stored, &erased_per_day);
+    apply_plan(&mut tx, contract_uuid, &plan).await?;
+
+    sqlx::query(
+        "INSERT INTO interaction_erasure_audit \
+           (contract_id, account_hash, interactions_erased, days_corrected, erased_by) \
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(contract_uuid)
+    .bind(hex::encode(Sha256::digest(account.as_bytes())))
+    .bind(erased.len() as i64)
+    .bind(plan.days_corrected() as i32)
+    .bind(&erased_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("insert erasure audit", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit interaction erasure", err))?;
+
+    tracing::warn!(
+        contract_id = %contract_uuid,
+        interactions_erased = erased.len(),
+        erased_by = %erased_by,
+        "erased account interactions"
+    );
+
+    Ok(Json(EraseInteractionsResponse {
+        contract_id: contract_uuid,
+        interactions_erased: erased.len() as u64,
+        days_corrected: plan.days_corrected(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn rebuild_populates_aggregates_from_raw_interactions() {
+        let interactions = [
+            (at(1, 9), Some("GALICE")),
+            (at(1, 10), Some("GBOB")),
+            (at(1, 23), Some("GALICE")),
+            (at(2, 0), None),
+            (at(4, 12), Some("GBOB")),
+        ];
+        let mut aggregator = DailyAggregator::default();
+        for (created_at, user) in interactions {
+            aggregator.record(created_at, user);
+        }
+        let rebuilt = aggregator.finish();
+
+        let expected = vec![
+            DailyInteractions {
+                date: date(1),
+                interaction_count: 3,
+                unique_users: 2,
+            },
+            DailyInteractions {
+                date: date(2),
+                interaction_count: 1,
+                unique_users: 0,
+            },
+            DailyInteractions {
+                date: date(4),
+                interaction_count: 1,
+                unique_users: 1,
+            },
+        ];
+        assert_eq!(rebuilt, expected);
+
+        // Nothing stored yet: every day with interactions is written
+        let plan = plan_rebuild(&[], &rebuilt);
+        assert_eq!(plan.upserts, expected);
+        assert_eq!(plan.days_corrected(), 3);
+
+        // Re-running against the rebuilt rows corrects nothing
+        assert_eq!(plan_rebuild(&rebuilt, &rebuilt).days_corrected(), 0);
+    }
+
+    #[test]
+    fn rebuild_corrects_only_drifted_days() {
+        let rebuilt = vec![
+            DailyInteractions {
+                date: date(1),
+                interaction_count: 3,
+                unique_users: 2,
+            },
+            DailyInteractions {
+                date: date(2),
+                interaction_count: 1,
+                unique_users: 1,
+            },
+        ];
+        let stored = vec![
+            rebuilt[0],
+            DailyInteractions {
+                date: date(2),
+                interaction_count: 7,
+                unique_users: 1,
+            },
+            // Manually inserted day with no raw interactions behind it
+            DailyInteractions {
+                date: date(3),
+                interaction_count: 4,
+                unique_users: 4,
+            },
+        ];
+
+        let plan = plan_rebuild(&stored, &rebuilt);
+        assert_eq!(plan.upserts, vec![rebuilt[1]]);
+        assert_eq!(plan.deletes, vec![date(3)]);
+        assert_eq!(plan.days_corrected(), 2);
+    }
+
+    #[test]
+    fn rebuild_range_is_validated() {
+        let status = |from, to| {
+            validate_range(&RebuildAggregatesRequest { from, to })
+                .err()
+                .map(|err| err.into_response().status().as_u16())
+        };
+        assert_eq!(status(date(1), date(31)), None);
+        assert_eq!(status(date(2), date(1)), Some(400));
+        assert_eq!(
+            status(date(1), date(1) + Duration::days(MAX_REBUILD_DAYS)),
+            Some(400)
+        );
+    }
+
+    #[test]
+    fn erasure_removes_only_the_accounts_contribution() {
+        let interactions = [
+            (at(1, 9), Some("GALICE")),
+            (at(1, 10), Some("GBOB")),
+            (at(1, 11), Some("GALICE")),
+            (at(2, 8), Some("GALICE")),
+            (at(3, 12), Some("GBOB")),
+        ];
+        let mut aggregator = DailyAggregator::default();
+        for (created_at, user) in interactions {
+            aggregator.record(created_at, user);
+        }
+        let stored = aggregator.finish();
+
+        let mut erased_per_day = BTreeMap::new();
+        let mut remaining = DailyAggregator::default();
+        for (created_at, user) in interactions {
+            if user == Some("GALICE") {
+                *erased_per_day.entry(created_at.date_naive()).or_default() += 1;
+            } else {
+                remaining.record(created_at, user);
+            }
+        }
+
+        let plan = plan_erasure(&stored, &erased_per_day);
+        assert_eq!(
+            plan.upserts,
+            vec![DailyInteractions {
+                date: date(1),
+                interaction_count: 1,
+                unique_users: 1,
+            }]
+        );
+        // GALICE was the only user on day 2; day 3 is untouched
+        assert_eq!(plan.deletes, vec![date(2)]);
+
+        // The adjusted aggregates agree with a recount of what remains
+        let mut adjusted: Vec<DailyInteractions> = stored
+            .iter()
+            .filter(|day| !plan.deletes.contains(&day.date))
+            .map(|day| {
+                plan.upserts
+                    .iter()
+                    .find(|u| u.date == day.date)
+                    .copied()
+                    .unwrap_or(*day)
+            })
+            .collect();
+        adjusted.sort_by_key(|day| day.date);
+        assert_eq!(adjusted, remaining.finish());
+    }
+
+    #[test]
+    fn erasure_requires_confirmation() {
+        let query = |confirm: Option<&str>| EraseInteractionsQuery {
+            account: " GALICE ".to_string(),
+            confirm: confirm.map(str::to_string),
+        };
+        let status = |q: EraseInteractionsQuery| {
+            check_erasure(&q)
+                .err()
+                .map(|err| err.into_response().status().as_u16())
+        };
+        assert_eq!(status(query(None)), Some(400));
+        assert_eq!(status(query(Some("GBOB"))), Some(400));
+        assert_eq!(check_erasure(&query(Some("GALICE"))).unwrap(), "GALICE");
+    }
+}
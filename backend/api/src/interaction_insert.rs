@@ -0,0 +1,123 @@
+// The one way rows get into `contract_interactions`.
+//
+// Single and batch ingestion each used to bind the nine insert columns by
+// position. Rows are now built only through `ContractInteractionInsert::new`
+// and written by `insert`, so a method can no longer land in the transaction
+// hash column, or the other way round, at one call site but not another.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use shared::CreateInteractionRequest;
+use uuid::Uuid;
+
+/// Interaction type recorded when the caller names no method
+pub const DEFAULT_INTERACTION_TYPE: &str = "invocation";
+
+/// A `contract_interactions` row ready to insert. Fields are private so the
+/// constructor is the only way to fill them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractInteractionInsert<'a> {
+    contract_id: Uuid,
+    user_address: Option<&'a str>,
+    interaction_type: &'a str,
+    transaction_hash: Option<&'a str>,
+    method: Option<&'a str>,
+    parameters: Option<&'a Value>,
+    return_value: Option<&'a Value>,
+    created_at: DateTime<Utc>,
+    deprecated_call: bool,
+}
+
+impl<'a> ContractInteractionInsert<'a> {
+    /// Row for `req` against `contract_id`. `created_at` is resolved by the
+    /// caller, which also needs it to check method sunsets.
+    pub fn new(
+        contract_id: Uuid,
+        req: &'a CreateInteractionRequest,
+        created_at: DateTime<Utc>,
+        deprecated_call: bool,
+    ) -> Self {
+        Self {
+            contract_id,
+            user_address: req.account.as_deref(),
+            interaction_type: req.method.as_deref().unwrap_or(DEFAULT_INTERACTION_TYPE),
+            transaction_hash: req.transaction_hash.as_deref(),
+            method: req.method.as_deref(),
+            parameters: req.parameters.as_ref(),
+            return_value: req.return_value.as_ref(),
+            created_at,
+            deprecated_call,
+        }
+    }
+
+    /// Write the row, returning its ID
+    pub async fn insert<'e, E>(&self, executor: E) -> Result<Uuid, sqlx::Error>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO contract_interactions
+              (contract_id, user_address, interaction_type, transaction_hash, method, parameters, return_value, created_at, deprecated_call)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(self.contract_id)
+        .bind(self.user_address)
+        .bind(self.interaction_type)
+        .bind(self.transaction_hash)
+        .bind(self.method)
+        .bind(self.parameters)
+        .bind(self.return_value)
+        .bind(self.created_at)
+        .bind(self.deprecated_call)
+        .fetch_one(executor)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_fields_land_in_their_own_columns() {
+        let contract_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let req = CreateInteractionRequest {
+            account: Some("GALICE".to_string()),
+            method: Some("transfer".to_string()),
+            transaction_hash: Some("ab".repeat(32)),
+            parameters: Some(serde_json::json!({ "amount": 10 })),
+            return_value: Some(serde_json::json!(true)),
+            timestamp: None,
+        };
+
+        let row = ContractInteractionInsert::new(contract_id, &req, created_at, true);
+        assert_eq!(row.contract_id, contract_id);
+        assert_eq!(row.user_address, Some("GALICE"));
+        assert_eq!(row.interaction_type, "transfer");
+        assert_eq!(row.method, Some("transfer"));
+        assert_eq!(row.transaction_hash, Some("ab".repeat(32).as_str()));
+        assert_eq!(row.parameters, req.parameters.as_ref());
+        assert_eq!(row.return_value, req.return_value.as_ref());
+        assert_eq!(row.created_at, created_at);
+        assert!(row.deprecated_call);
+    }
+
+    #[test]
+    fn interactions_without_a_method_are_invocations() {
+        let req = CreateInteractionRequest {
+            account: None,
+            method: None,
+            transaction_hash: None,
+            parameters: None,
+            return_value: None,
+            timestamp: None,
+        };
+        let row = ContractInteractionInsert::new(Uuid::nil(), &req, Utc::now(), false);
+        assert_eq!(row.interaction_type, DEFAULT_INTERACTION_TYPE);
+        assert_eq!(row.method, None);
+    }
+}
@@ -1,12 +1,21 @@
 #![allow(dead_code, unused)]
 
+pub mod advisory_lock;
+pub mod audit_retention;
 pub mod backup_handlers;
 pub mod backup_routes;
+pub mod blocklist;
 pub mod cache;
+pub mod clock;
 pub mod disaster_recovery_models;
 pub mod error;
+pub mod event_stream;
 pub mod notification_handlers;
 pub mod notification_routes;
+pub mod pagination;
 pub mod post_incident_handlers;
 pub mod post_incident_routes;
+pub mod rollout;
 pub mod state;
+pub mod trending;
+pub mod verification_queue;
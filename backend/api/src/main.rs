@@ -1,5 +1,8 @@
 #![allow(dead_code, unused)]
 
+mod admin_auth;
+mod advisory_lock;
+mod clock;
 mod aggregation;
 mod error;
 mod handlers;
@@ -7,7 +10,7 @@ mod rate_limit;
 mod routes;
 mod state;
 mod validation;
-// mod auth;
+mod auth;
 // mod auth_handlers;
 mod cache;
 mod metrics;
@@ -15,13 +18,46 @@ mod metrics_handler;
 // mod resource_handlers;
 // mod resource_tracking;
 mod dependency;
+mod dependency_notifications;
+mod disaster_recovery_models;
 mod analytics;
+mod anomaly;
 mod breaking_changes;
+mod changelog;
+mod compatibility_dashboard;
 mod custom_metrics_handlers;
 mod deprecation_handlers;
 pub mod health_monitor;
 pub mod signing_handlers;
 mod type_safety;
+mod reverification;
+mod interaction_export;
+mod interaction_insert;
+mod pagination;
+mod trending;
+mod event_stream;
+mod verification_handlers;
+mod verification_queue;
+mod verification_webhook;
+mod request_signing;
+mod request_timeout;
+mod api_key_handlers;
+mod api_keys;
+mod audit_log_export;
+mod audit_retention;
+mod interaction_aggregates;
+mod blocklist;
+mod blocklist_handlers;
+mod migration_handlers;
+mod multisig_handlers;
+mod multisig_routes;
+mod notification_preferences;
+mod patch_handlers;
+mod rollout;
+mod contract_history_handlers;
+mod contract_history_routes;
+#[cfg(test)]
+mod test_db;
 
 use anyhow::Result;
 use axum::http::{header, HeaderValue, Method};
@@ -78,8 +114,35 @@ async fn main() -> Result<()> {
 
     // Create app state
     let is_shutting_down = Arc::new(AtomicBool::new(false));
-    let state = AppState::new(pool.clone(), registry, is_shutting_down.clone());
-    let rate_limit_state = RateLimitState::from_env();
+    let pagination = pagination::PaginationConfig::from_env().map_err(anyhow::Error::msg)?;
+    let trending = trending::TrendingConfig::from_env().map_err(anyhow::Error::msg)?;
+    let audit_retention =
+        audit_retention::AuditRetentionConfig::from_env().map_err(anyhow::Error::msg)?;
+    let verifier_config = verifier::VerifierConfig::from_env();
+    let request_timeouts = request_timeout::RequestTimeouts::from_env(verifier_config.build_timeout);
+    let state = AppState::new(pool.clone(), registry, is_shutting_down.clone())
+        .with_pagination(pagination)
+        .with_trending(trending)
+        .with_audit_retention(audit_retention)
+        .with_patch_receipt_secret(std::env::var("PATCH_RECEIPT_SECRET").ok())
+        .with_max_concurrent_builds(verifier_config.max_concurrent_builds);
+    let rate_limit_state = RateLimitState::from_env().with_db(pool.clone());
+    let request_signing =
+        Arc::new(request_signing::RequestSigning::from_env().with_clock(state.clock.clone()));
+    let admin_auth = Arc::new(admin_auth::AdminAuth::from_env());
+    if !admin_auth.is_enabled() {
+        tracing::warn!("ADMIN_API_TOKENS is not set; admin endpoints will refuse every request");
+    }
+
+    blocklist::spawn_refresh_task(state.blocklist.clone(), pool.clone());
+    audit_retention::spawn_retention_task(state.audit_retention.clone(), pool.clone());
+    anomaly::spawn_spike_task(state.clone());
+    verification_handlers::spawn_toolchain_probe(state.clone());
+
+    // Pick up re-verification jobs and verification builds interrupted by a
+    // restart
+    reverification::spawn_resume_incomplete_jobs(state.clone());
+    verification_handlers::spawn_resume_pending_builds(state.clone());
 
     let cors = CorsLayer::new()
         .allow_origin([
@@ -92,15 +155,26 @@ async fn main() -> Result<()> {
     // Build router
     let app = Router::new()
         .merge(routes::contract_routes())
+        .merge(routes::signed_ingest_routes(request_signing))
         .merge(routes::publisher_routes())
+        .merge(routes::patch_routes(admin_auth.clone()))
         .merge(routes::health_routes())
+        .merge(routes::admin_routes(admin_auth))
         .merge(routes::migration_routes())
+        .merge(contract_history_routes::contract_history_routes())
+        .merge(multisig_routes::multisig_routes())
         .fallback(handlers::route_not_found)
+        .layer(middleware::from_fn_with_state(
+            request_timeouts,
+            request_timeout::enforce_request_timeout,
+        ))
         .layer(middleware::from_fn(request_logger))
         .layer(middleware::from_fn_with_state(
             rate_limit_state,
             rate_limit::rate_limit_middleware,
         ))
+        // Outside the rate limiter so rejected requests are counted too
+        .layer(middleware::from_fn(metrics::track_http_metrics))
         .layer(CorsLayer::permissive())
         .layer(cors)
         .with_state(state);
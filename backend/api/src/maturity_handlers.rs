@@ -79,7 +79,7 @@ pub async fn check_maturity_requirements(
         .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))?;
 
     let versions_count = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM contract_versions WHERE contract_id = $1",
+        "SELECT COUNT(*) FROM contract_versions WHERE contract_id = $1 AND status = 'published'",
     )
     .bind(contract_id)
     .fetch_one(&state.db)
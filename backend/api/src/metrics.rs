@@ -1,3 +1,4 @@
+use axum::{body::Body, http::Request, middleware::Next, response::Response};
 use once_cell::sync::Lazy;
 use prometheus::{
     opts, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
@@ -58,6 +59,11 @@ pub static HTTP_REQUEST_DURATION: Lazy<HistogramVec> = histogram_vec!(
     "HTTP request latency",
     &["method", "path"]
 );
+pub static HTTP_RESPONSES_BY_STATUS_CLASS: Lazy<IntCounterVec> = counter_vec!(
+    "http_responses_by_status_class_total",
+    "HTTP responses by status class",
+    &["class"]
+);
 pub static HTTP_IN_FLIGHT: Lazy<IntGauge> =
     gauge!("http_requests_in_flight", "In-flight HTTP requests");
 pub static HTTP_REQUEST_SIZE: Lazy<HistogramVec> = histogram_vec!(
@@ -230,6 +236,7 @@ pub static PUBLISHER_REGISTRATIONS: Lazy<IntCounter> =
 pub fn register_all(r: &Registry) -> prometheus::Result<()> {
     r.register(Box::new(HTTP_REQUESTS_TOTAL.clone()))?;
     r.register(Box::new(HTTP_REQUEST_DURATION.clone()))?;
+    r.register(Box::new(HTTP_RESPONSES_BY_STATUS_CLASS.clone()))?;
     r.register(Box::new(HTTP_IN_FLIGHT.clone()))?;
     r.register(Box::new(HTTP_REQUEST_SIZE.clone()))?;
     r.register(Box::new(HTTP_RESPONSE_SIZE.clone()))?;
@@ -302,6 +309,62 @@ pub fn observe_http(method: &str, path: &str, status: u16, duration_secs: f64) {
     HTTP_REQUEST_DURATION
         .with_label_values(&[method, path])
         .observe(duration_secs);
+    HTTP_RESPONSES_BY_STATUS_CLASS
+        .with_label_values(&[status_class(status)])
+        .inc();
+}
+
+fn status_class(status: u16) -> &'static str {
+    match status {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+/// Label for requests that matched no route, so unknown URIs cannot grow the
+/// label set
+const UNMATCHED_PATH: &str = "unmatched";
+
+/// Counts one in-flight request on `gauge` until dropped, so requests whose
+/// future is cancelled (client disconnects, timeouts) are still released
+struct InFlight<'a>(&'a IntGauge);
+
+impl<'a> InFlight<'a> {
+    fn start(gauge: &'a IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Times every request and records it under its route template rather than
+/// its raw URI
+pub async fn track_http_metrics(req: Request<Body>, next: Next) -> Response {
+    let start = std::time::Instant::now();
+    let method = req.method().to_string();
+    let path = crate::rate_limit::matched_path(&req)
+        .unwrap_or(UNMATCHED_PATH)
+        .to_string();
+
+    let in_flight = InFlight::start(&HTTP_IN_FLIGHT);
+    let res = next.run(req).await;
+    drop(in_flight);
+
+    observe_http(
+        &method,
+        &path,
+        res.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
+    res
 }
 
 pub fn observe_verification_latency(result: &str, duration_secs: f64) {
@@ -350,6 +413,24 @@ mod tests {
         assert!(out.contains("http_requests_total"));
     }
 
+    #[tokio::test]
+    async fn in_flight_request_is_released_when_cancelled() {
+        let gauge = IntGauge::new("t_in_flight", "test").unwrap();
+        let request = async {
+            let _in_flight = InFlight::start(&gauge);
+            std::future::pending::<()>().await;
+        };
+        // Polled once, then dropped mid-request as a disconnect would
+        let timed_out = tokio::time::timeout(std::time::Duration::from_millis(10), request).await;
+        assert!(timed_out.is_err());
+        assert_eq!(gauge.get(), 0);
+
+        let in_flight = InFlight::start(&gauge);
+        assert_eq!(gauge.get(), 1);
+        drop(in_flight);
+        assert_eq!(gauge.get(), 0);
+    }
+
     #[test]
     fn test_verification_latency_observe() {
         let r = fresh_registry();
@@ -416,4 +497,36 @@ mod tests {
             .get_sample_count();
         assert!(sample_count >= 1);
     }
+
+    #[tokio::test]
+    async fn test_middleware_records_requests_by_route_template() {
+        use axum::{http::StatusCode, middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let r = fresh_registry();
+        let app = Router::new()
+            .route(
+                "/api/metrics-test/:id",
+                get(|| async { StatusCode::ACCEPTED }),
+            )
+            .layer(middleware::from_fn(track_http_metrics));
+        let duration = HTTP_REQUEST_DURATION.with_label_values(&["GET", "/api/metrics-test/:id"]);
+        let before = duration.get_sample_count();
+
+        let response = app
+            .oneshot(
+                Request::get("/api/metrics-test/abc")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(duration.get_sample_count(), before + 1);
+
+        let out = gather_metrics(&r);
+        assert!(out.contains(r#"path="/api/metrics-test/:id""#));
+        assert!(!out.contains("/api/metrics-test/abc"));
+        assert!(out.contains(r#"http_responses_by_status_class_total{class="2xx"}"#));
+    }
 }
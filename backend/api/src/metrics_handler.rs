@@ -36,6 +36,15 @@ mod tests {
             cache: Arc::new(CacheLayer::new(CacheConfig::default())),
             registry,
             is_shutting_down: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            pagination: Default::default(),
+            trending: Default::default(),
+            events: Default::default(),
+            verification_queue: Default::default(),
+            blocklist: Default::default(),
+            audit_retention: Default::default(),
+            clock: Arc::new(crate::clock::SystemClock),
+            verifier_ready: Default::default(),
+            patch_receipt_secret: None,
         }
     }
 
@@ -0,0 +1,387 @@
+// State migration scripts between contract versions.
+//
+// Script checksums are stored tagged with the digest that produced them
+// (`sha256:<hex>`, `blake3:<hex>`) so scripts registered by external migration
+// tooling can be validated with the algorithm that tooling used. Untagged
+// checksums predate the tag and are SHA-256.
+
+use std::fmt;
+use std::str::FromStr;
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use shared::MigrationScript;
+use uuid::Uuid;
+
+use crate::{
+    advisory_lock::{AdvisoryLockGuard, MIGRATION_LOCK_KEY},
+    error::{ApiError, ApiResult},
+    handlers::db_internal_error,
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    fn digest(self, content: &[u8]) -> String {
+        match self {
+            Self::Sha256 => hex::encode(Sha256::digest(content)),
+            Self::Blake3 => blake3::hash(content).to_hex().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sha256 => "sha256",
+            Self::Blake3 => "blake3",
+        })
+    }
+}
+
+impl FromStr for ChecksumAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Self::Sha256),
+            "blake3" => Ok(Self::Blake3),
+            other => Err(format!("unsupported checksum algorithm '{}'", other)),
+        }
+    }
+}
+
+/// A checksum together with the algorithm that produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: ChecksumAlgorithm,
+    pub digest: String,
+}
+
+impl Checksum {
+    /// Parse `algorithm:hex`; a bare hex digest is SHA-256
+    pub fn parse(recorded: &str) -> Result<Self, String> {
+        let recorded = recorded.trim();
+        let (algorithm, digest) = match recorded.split_once(':') {
+            Some((algorithm, digest)) => (algorithm.parse()?, digest),
+            None => (ChecksumAlgorithm::Sha256, recorded),
+        };
+        if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("invalid {} checksum '{}'", algorithm, digest));
+        }
+        Ok(Self {
+            algorithm,
+            digest: digest.to_ascii_lowercase(),
+        })
+    }
+
+    /// Whether `content` hashes to this checksum under its own algorithm
+    pub fn matches(&self, content: &[u8]) -> bool {
+        self.algorithm.digest(content) == self.digest
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest)
+    }
+}
+
+/// Checksum of `content` under `algorithm`, tagged for storage
+pub fn compute_checksum(algorithm: ChecksumAlgorithm, content: &[u8]) -> Checksum {
+    Checksum {
+        algorithm,
+        digest: algorithm.digest(content),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterMigrationRequest {
+    pub from_version: Uuid,
+    pub to_version: Uuid,
+    pub script_path: String,
+    /// Script body the checksum is computed over
+    pub content: String,
+    /// Checksum reported by the submitting tool; its algorithm is the one
+    /// recorded. Without it the script is checksummed with SHA-256.
+    #[serde(default)]
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateMigrationsRequest {
+    pub scripts: Vec<MigrationScriptContent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MigrationScriptContent {
+    pub id: Uuid,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct MigrationValidation {
+    pub id: Uuid,
+    pub algorithm: Option<ChecksumAlgorithm>,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateMigrationsResponse {
+    pub valid: bool,
+    pub results: Vec<MigrationValidation>,
+}
+
+fn invalid_payload(err: JsonRejection) -> ApiError {
+    ApiError::bad_request(
+        "InvalidRequest",
+        format!("Invalid JSON payload: {}", err.body_text()),
+    )
+}
+
+/// POST /api/migrations/scripts
+pub async fn register_migration(
+    State(state): State<AppState>,
+    payload: Result<Json<RegisterMigrationRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<MigrationScript>)> {
+    let Json(req) = payload.map_err(invalid_payload)?;
+    let checksum = checksum_for_registration(req.checksum.as_deref(), req.content.as_bytes())?;
+    // Held across the duplicate check and the insert so two registrations of
+    // the same version pair can't both pass the check; released on drop if
+    // either query fails
+    let lock = AdvisoryLockGuard::acquire(&state.db, MIGRATION_LOCK_KEY)
+        .await?
+        .ok_or_else(migration_in_progress)?;
+
+    let existing: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM migration_scripts WHERE from_version = $1 AND to_version = $2",
+    )
+    .bind(req.from_version)
+    .bind(req.to_version)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("check existing migration script", err))?;
+    if let Some(existing) = existing {
+        // Released before answering: a drop unlocks in the background, which
+        // could still turn the caller's next registration away
+        if let Err(err) = lock.release().await {
+            tracing::warn!(error = ?err, "failed to release migration lock");
+        }
+        return Err(ApiError::conflict(
+            "MigrationAlreadyRegistered",
+            format!(
+                "Migration script {} already covers this version pair",
+                existing
+            ),
+        ));
+    }
+
+    let script: MigrationScript = sqlx::query_as(
+        "INSERT INTO migration_scripts (from_version, to_version, script_path, checksum) \
+         VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(req.from_version)
+    .bind(req.to_version)
+    .bind(&req.script_path)
+    .bind(checksum.to_string())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => ApiError::not_found(
+            "VersionNotFound",
+            "from_version or to_version does not exist",
+        ),
+        _ => db_internal_error("register migration script", err),
+    })?;
+    if let Err(err) = lock.release().await {
+        tracing::warn!(error = ?err, "failed to release migration lock");
+    }
+
+    Ok((StatusCode::CREATED, Json(script)))
+}
+
+fn migration_in_progress() -> ApiError {
+    ApiError::conflict(
+        "MigrationInProgress",
+        "Another migration is in progress; retry once it completes",
+    )
+}
+
+/// Checksum to record for a new script: the submitted one when it verifies,
+/// otherwise a SHA-256 computed here
+fn checksum_for_registration(submitted: Option<&str>, content: &[u8]) -> ApiResult<Checksum> {
+    let Some(submitted) = submitted else {
+        return Ok(compute_checksum(ChecksumAlgorithm::Sha256, content));
+    };
+    let checksum =
+        Checksum::parse(submitted).map_err(|e| ApiError::bad_request("InvalidChecksum", e))?;
+    if !checksum.matches(content) {
+        return Err(ApiError::unprocessable(
+            "ChecksumMismatch",
+            format!(
+                "script content does not match {} checksum",
+                checksum.algorithm
+            ),
+        ));
+    }
+    Ok(checksum)
+}
+
+/// POST /api/migrations/scripts/validate
+///
+/// Compares each script's content against its recorded checksum, using the
+/// algorithm recorded with it.
+pub async fn validate_migrations(
+    State(state): State<AppState>,
+    payload: Result<Json<ValidateMigrationsRequest>, JsonRejection>,
+) -> ApiResult<Json<ValidateMigrationsResponse>> {
+    let Json(req) = payload.map_err(invalid_payload)?;
+
+    let mut results = Vec::with_capacity(req.scripts.len());
+    for script in &req.scripts {
+        let recorded: Option<String> =
+            sqlx::query_scalar("SELECT checksum FROM migration_scripts WHERE id = $1")
+                .bind(script.id)
+                .fetch_optional(&state.db)
+                .await
+                .map_err(|err| db_internal_error("get migration script checksum", err))?;
+        results.push(match recorded {
+            Some(recorded) => validate_script(script.id, &recorded, script.content.as_bytes()),
+            None => MigrationValidation {
+                id: script.id,
+                algorithm: None,
+                valid: false,
+                error: Some("migration script not found".to_string()),
+            },
+        });
+    }
+
+    Ok(Json(ValidateMigrationsResponse {
+        valid: results.iter().all(|r| r.valid),
+        results,
+    }))
+}
+
+fn validate_script(id: Uuid, recorded: &str, content: &[u8]) -> MigrationValidation {
+    match Checksum::parse(recorded) {
+        Ok(checksum) => {
+            let valid = checksum.matches(content);
+            MigrationValidation {
+                id,
+                algorithm: Some(checksum.algorithm),
+                valid,
+                error: (!valid).then(|| format!("{} checksum mismatch", checksum.algorithm)),
+            }
+        }
+        Err(err) => MigrationValidation {
+            id,
+            algorithm: None,
+            valid: false,
+            error: Some(err),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &[u8] = b"ALTER TABLE balances ADD COLUMN frozen BOOLEAN;";
+
+    #[test]
+    fn blake3_tagged_checksum_validates() {
+        let recorded = compute_checksum(ChecksumAlgorithm::Blake3, SCRIPT).to_string();
+        assert!(recorded.starts_with("blake3:"));
+        assert_eq!(
+            recorded,
+            format!("blake3:{}", blake3::hash(SCRIPT).to_hex())
+        );
+
+        let result = validate_script(Uuid::nil(), &recorded, SCRIPT);
+        assert!(result.valid);
+        assert_eq!(result.algorithm, Some(ChecksumAlgorithm::Blake3));
+    }
+
+    #[test]
+    fn digest_under_the_wrong_algorithm_is_a_mismatch() {
+        let sha256 = compute_checksum(ChecksumAlgorithm::Sha256, SCRIPT);
+        let mislabelled = format!("blake3:{}", sha256.digest);
+
+        let result = validate_script(Uuid::nil(), &mislabelled, SCRIPT);
+        assert!(!result.valid);
+        assert_eq!(result.error.as_deref(), Some("blake3 checksum mismatch"));
+        assert!(checksum_for_registration(Some(&mislabelled), SCRIPT).is_err());
+    }
+
+    #[test]
+    fn untagged_checksums_are_sha256() {
+        let legacy = hex::encode(Sha256::digest(SCRIPT));
+        let result = validate_script(Uuid::nil(), &legacy, SCRIPT);
+        assert!(result.valid);
+        assert_eq!(result.algorithm, Some(ChecksumAlgorithm::Sha256));
+
+        let registered = checksum_for_registration(None, SCRIPT).unwrap();
+        assert_eq!(registered.to_string(), format!("sha256:{}", legacy));
+    }
+
+    #[test]
+    fn unknown_algorithms_are_rejected() {
+        assert!(Checksum::parse("md5:d41d8cd98f00b204e9800998ecf8427e").is_err());
+        assert!(Checksum::parse("sha256:not-hex").is_err());
+    }
+
+    #[tokio::test]
+    async fn a_version_pair_is_registered_once() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let contract = crate::test_db::insert_contract(&state.db, publisher, "CMIGRATE").await;
+        let from = crate::test_db::insert_version(&state.db, contract, "1.0.0", "published").await;
+        let to = crate::test_db::insert_version(&state.db, contract, "1.1.0", "published").await;
+        let request = || {
+            Ok(Json(RegisterMigrationRequest {
+                from_version: from,
+                to_version: to,
+                script_path: "migrations/1.0.0-1.1.0.sql".to_string(),
+                content: String::from_utf8(SCRIPT.to_vec()).unwrap(),
+                checksum: None,
+            }))
+        };
+
+        let (status, _) = register_migration(State(state.clone()), request())
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let err = register_migration(State(state.clone()), request())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+        assert_eq!(err.error(), "MigrationAlreadyRegistered");
+
+        // The lock was released on the error path, so other pairs still register
+        let next = crate::test_db::insert_version(&state.db, contract, "1.2.0", "published").await;
+        let mut other = request().unwrap().0;
+        other.from_version = to;
+        other.to_version = next;
+        let (status, _) = register_migration(State(state), Ok(Json(other)))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+    }
+}
@@ -7,8 +7,10 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use base64::Engine as _;
 use chrono::Utc;
-use serde::Deserialize;
+use ed25519_dalek::Verifier;
+use serde::{Deserialize, Serialize};
 use shared::{
     CreatePolicyRequest, CreateProposalRequest, DeployProposal, MultisigPolicy, ProposalSignature,
     ProposalStatus, ProposalWithSignatures, SignProposalRequest,
@@ -19,7 +21,6 @@ use crate::{
     error::{ApiError, ApiResult},
     handlers::db_internal_error,
     signing_handlers::create_signing_message,
-    resource_tracking::ResourceUsage,
     state::AppState,
 };
 
@@ -201,12 +202,80 @@ pub async fn create_proposal(
 // POST /api/contracts/{id}/sign
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// A signer who has already signed may resubmit the same signature (a retry
+/// or double-click) but not a different one.
+fn check_repeat_signature(
+    existing: Option<&str>,
+    submitted: Option<&str>,
+    signer_address: &str,
+) -> ApiResult<()> {
+    if existing == submitted {
+        Ok(())
+    } else {
+        Err(ApiError::conflict(
+            "ConflictingSignature",
+            format!(
+                "'{}' has already signed this proposal with a different signature",
+                signer_address
+            ),
+        ))
+    }
+}
+
+async fn count_signatures(state: &AppState, proposal_id: Uuid) -> ApiResult<i64> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM proposal_signatures WHERE proposal_id = $1")
+        .bind(proposal_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count signatures", err))
+}
+
+/// Response for a signer whose signature is already on record: nothing is
+/// written, the proposal's current state is returned.
+async fn repeat_signature_response(
+    state: &AppState,
+    proposal: &DeployProposal,
+    policy: &MultisigPolicy,
+    req: &SignProposalRequest,
+) -> ApiResult<(StatusCode, Json<serde_json::Value>)> {
+    let signature: ProposalSignature = sqlx::query_as(
+        "SELECT * FROM proposal_signatures WHERE proposal_id = $1 AND signer_address = $2",
+    )
+    .bind(proposal.id)
+    .bind(&req.signer_address)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch existing signature", err))?;
+
+    check_repeat_signature(
+        signature.signature_data.as_deref(),
+        req.signature_data.as_deref(),
+        &req.signer_address,
+    )?;
+
+    let sig_count = count_signatures(state, proposal.id).await?;
+    let signatures_needed = (policy.threshold as i64 - sig_count).max(0) as i32;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "signature": signature,
+            "proposal_status": proposal.status.to_string(),
+            "signatures_collected": sig_count,
+            "signatures_needed": signatures_needed,
+            "threshold_met": signatures_needed == 0,
+            "already_signed": true,
+        })),
+    ))
+}
+
 /// Add one signature to a proposal. Validates:
 /// - Proposal exists and is still `pending`
 /// - Proposal has not expired
 /// - Signer is in the policy's signer list
-/// - Signer has not already signed
 ///
+/// Signing is idempotent per signer: resubmitting the same signature returns
+/// the current state without counting again, a different one is a conflict.
 /// If the threshold is met after this signature the proposal moves to `approved`.
 pub async fn sign_proposal(
     State(state): State<AppState>,
@@ -229,6 +298,27 @@ pub async fn sign_proposal(
         ));
     }
 
+    // Fetch the policy to validate the signer
+    let policy: MultisigPolicy = sqlx::query_as("SELECT * FROM multisig_policies WHERE id = $1")
+        .bind(proposal.policy_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch policy for signing", err))?;
+
+    // A retry may arrive after this signature already approved the proposal,
+    // so look for it before the status check
+    let already_signed: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM proposal_signatures WHERE proposal_id = $1 AND signer_address = $2)",
+    )
+    .bind(proposal_id)
+    .bind(&req.signer_address)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("check existing signature", err))?;
+    if already_signed {
+        return repeat_signature_response(&state, &proposal, &policy, &req).await;
+    }
+
     // Only pending proposals can be signed
     if proposal.status != ProposalStatus::Pending {
         return Err(ApiError::bad_request(
@@ -240,13 +330,6 @@ pub async fn sign_proposal(
         ));
     }
 
-    // Fetch the policy to validate the signer
-    let policy: MultisigPolicy = sqlx::query_as("SELECT * FROM multisig_policies WHERE id = $1")
-        .bind(proposal.policy_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| db_internal_error("fetch policy for signing", err))?;
-
     if !policy.signer_addresses.contains(&req.signer_address) {
         return Err(ApiError::bad_request(
             "UnauthorizedSigner",
@@ -257,37 +340,26 @@ pub async fn sign_proposal(
         ));
     }
 
-    // Insert signature (UNIQUE constraint on (proposal_id, signer_address) handles duplicates)
-    let signature: ProposalSignature = sqlx::query_as(
+    // A concurrent duplicate can still win the race to insert; the
+    // (proposal_id, signer_address) unique constraint turns it into a repeat
+    let signature: Option<ProposalSignature> = sqlx::query_as(
         "INSERT INTO proposal_signatures (proposal_id, signer_address, signature_data)
          VALUES ($1, $2, $3)
+         ON CONFLICT (proposal_id, signer_address) DO NOTHING
          RETURNING *",
     )
     .bind(proposal_id)
     .bind(&req.signer_address)
     .bind(&req.signature_data)
-    .fetch_one(&state.db)
+    .fetch_optional(&state.db)
     .await
-    .map_err(|err| match err {
-        sqlx::Error::Database(ref db_err)
-            if db_err.constraint()
-                == Some("proposal_signatures_proposal_id_signer_address_key") =>
-        {
-            ApiError::bad_request(
-                "AlreadySigned",
-                format!("'{}' has already signed this proposal", req.signer_address),
-            )
-        }
-        _ => db_internal_error("insert proposal signature", err),
-    })?;
+    .map_err(|err| db_internal_error("insert proposal signature", err))?;
+    let Some(signature) = signature else {
+        return repeat_signature_response(&state, &proposal, &policy, &req).await;
+    };
 
     // Count total signatures so far
-    let sig_count: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM proposal_signatures WHERE proposal_id = $1")
-            .bind(proposal_id)
-            .fetch_one(&state.db)
-            .await
-            .map_err(|err| db_internal_error("count signatures", err))?;
+    let sig_count = count_signatures(&state, proposal_id).await?;
 
     // Promote to approved if threshold met
     if sig_count >= policy.threshold as i64 {
@@ -318,6 +390,7 @@ pub async fn sign_proposal(
             "signatures_collected": sig_count,
             "signatures_needed": signatures_needed,
             "threshold_met": signatures_needed == 0,
+            "already_signed": false,
         })),
     ))
 }
@@ -365,14 +438,16 @@ pub async fn execute_proposal(
             SELECT c.contract_id, cv.version, cv.signature, cv.publisher_key
             FROM contracts c
             JOIN contract_versions cv ON cv.contract_id = c.id
-            WHERE c.id = $1
+            WHERE c.contract_id = $1
+              AND c.network = $3
               AND cv.wasm_hash = $2
             ORDER BY cv.created_at DESC
             LIMIT 1
             "#,
         )
-        .bind(proposal.contract_id)
+        .bind(&proposal.contract_id)
         .bind(&proposal.wasm_hash)
+        .bind(&proposal.network)
         .fetch_optional(&state.db)
         .await
         .map_err(|err| db_internal_error("lookup contract version for deployment", err))?
@@ -461,21 +536,6 @@ pub async fn execute_proposal(
         wasm_hash    = %proposal.wasm_hash,
         "deployment proposal executed"
     );
-    let wasm_len = proposal.wasm_hash.len() as u64;
-    let cpu = 2_400_000 + wasm_len.saturating_mul(140);
-    let mem = 4_000_000 + wasm_len.saturating_mul(96);
-    let contract_id = proposal.contract_id.to_string();
-    let mut mgr = state.resource_mgr.write().unwrap();
-    let _ = mgr.record_usage(
-        &contract_id,
-        ResourceUsage {
-            cpu_instructions: cpu,
-            mem_bytes: mem,
-            storage_bytes: wasm_len,
-            timestamp: Utc::now(),
-        },
-    );
-
     Ok(Json(serde_json::json!({
         "success": true,
         "proposal_id": proposal_id,
@@ -486,6 +546,117 @@ pub async fn execute_proposal(
     })))
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// POST /api/contracts/{id}/cancel
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct CancelProposalRequest {
+    /// Stellar address asking for the cancellation
+    pub requested_by: String,
+    pub reason: Option<String>,
+}
+
+/// Only the proposer or the creator of the proposal's policy may cancel, and
+/// only while the proposal is still `pending` or `approved`.
+fn check_cancellable(
+    status: &ProposalStatus,
+    requested_by: &str,
+    proposer: &str,
+    policy_admin: &str,
+) -> ApiResult<()> {
+    if requested_by != proposer && requested_by != policy_admin {
+        return Err(ApiError::forbidden(
+            "NotProposer",
+            format!(
+                "'{}' is neither the proposer nor the policy admin of this proposal",
+                requested_by
+            ),
+        ));
+    }
+
+    match status {
+        ProposalStatus::Pending | ProposalStatus::Approved => Ok(()),
+        ProposalStatus::Executed => Err(ApiError::conflict(
+            "ProposalExecuted",
+            "This proposal has already been executed and cannot be cancelled",
+        )),
+        other => Err(ApiError::bad_request(
+            "ProposalNotCancellable",
+            format!("Proposal is in '{}' status and cannot be cancelled", other),
+        )),
+    }
+}
+
+/// Cancel a proposal that is no longer wanted. Signatures collected so far
+/// are kept; who cancelled it, when and why is stored on the proposal.
+pub async fn cancel_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+    payload: Result<Json<CancelProposalRequest>, axum::extract::rejection::JsonRejection>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+
+    if req.requested_by.is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingRequester",
+            "requested_by is required",
+        ));
+    }
+
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+
+    let policy: MultisigPolicy = sqlx::query_as("SELECT * FROM multisig_policies WHERE id = $1")
+        .bind(proposal.policy_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch policy for cancellation", err))?;
+
+    check_cancellable(
+        &proposal.status,
+        &req.requested_by,
+        &proposal.proposer,
+        &policy.created_by,
+    )?;
+
+    // Guard on status again so a concurrent execute can't be overwritten
+    let cancelled_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+        "UPDATE deploy_proposals
+         SET status = 'cancelled', cancelled_at = NOW(), cancelled_by = $2,
+             cancel_reason = $3, updated_at = NOW()
+         WHERE id = $1 AND status IN ('pending', 'approved')
+         RETURNING cancelled_at",
+    )
+    .bind(proposal_id)
+    .bind(&req.requested_by)
+    .bind(&req.reason)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("cancel proposal", err))?;
+
+    let cancelled_at = cancelled_at.ok_or_else(|| {
+        ApiError::conflict(
+            "ProposalNotCancellable",
+            "Proposal changed status while being cancelled",
+        )
+    })?;
+
+    tracing::info!(
+        proposal_id  = %proposal_id,
+        cancelled_by = %req.requested_by,
+        "deployment proposal cancelled"
+    );
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "proposal_id": proposal_id,
+        "status": "cancelled",
+        "cancelled_by": req.requested_by,
+        "cancelled_at": cancelled_at.to_rfc3339(),
+        "reason": req.reason,
+    })))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // GET /api/contracts/{id}/proposal
 // ─────────────────────────────────────────────────────────────────────────────
@@ -530,43 +701,88 @@ pub async fn get_proposal(
 pub struct ListProposalsParams {
     pub status: Option<String>,
     pub policy_id: Option<Uuid>,
+    /// Only proposals whose collected signer weight meets their threshold
+    #[serde(default)]
+    pub ready_only: bool,
     pub limit: Option<i64>,
     pub page: Option<i64>,
 }
 
-/// List all deployment proposals, with optional status / policy filters.
-pub async fn list_proposals(
-    State(state): State<AppState>,
-    Query(params): Query<ListProposalsParams>,
-) -> ApiResult<Json<serde_json::Value>> {
-    let limit = params.limit.unwrap_or(20).min(100);
-    let page = params.page.unwrap_or(1).max(1);
-    let offset = (page - 1) * limit;
+/// A proposal together with how far it is from its policy's threshold
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ProposalProgress {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub proposal: DeployProposal,
+    pub threshold: i32,
+    /// Every signature recorded for the proposal
+    pub signatures_collected: i64,
+    /// Signatures from addresses still on the policy's signer list; each
+    /// signer weighs one
+    pub weight_collected: i64,
+    pub ready: bool,
+}
 
-    // Dynamic query builder (safe — values are bound, not interpolated)
+/// Signatures that count towards the threshold
+const WEIGHT_SQL: &str =
+    "COUNT(s.id) FILTER (WHERE s.signer_address = ANY(pol.signer_addresses))";
+
+/// Base query listing proposals with their signature counts, filtered by
+/// `params`. Placeholders are numbered in the order status, policy_id.
+fn proposal_progress_query(params: &ListProposalsParams) -> String {
     let mut where_clauses: Vec<String> = Vec::new();
     let mut arg_idx = 1usize;
 
-    if let Some(ref s) = params.status {
-        where_clauses.push(format!("status = ${}", arg_idx));
+    if params.status.is_some() {
+        where_clauses.push(format!("p.status::text = ${}", arg_idx));
         arg_idx += 1;
     }
     if params.policy_id.is_some() {
-        where_clauses.push(format!("policy_id = ${}", arg_idx));
-        arg_idx += 1;
+        where_clauses.push(format!("p.policy_id = ${}", arg_idx));
     }
-    let _ = arg_idx; // suppress unused warning
 
     let where_sql = if where_clauses.is_empty() {
         String::new()
     } else {
         format!("WHERE {}", where_clauses.join(" AND "))
     };
+    let having_sql = if params.ready_only {
+        format!("HAVING {} >= pol.threshold", WEIGHT_SQL)
+    } else {
+        String::new()
+    };
+
+    format!(
+        "SELECT p.*, pol.threshold,
+                COUNT(s.id) AS signatures_collected,
+                {weight} AS weight_collected,
+                {weight} >= pol.threshold AS ready
+         FROM deploy_proposals p
+         JOIN multisig_policies pol ON pol.id = p.policy_id
+         LEFT JOIN proposal_signatures s ON s.proposal_id = p.id
+         {where_sql}
+         GROUP BY p.id, pol.id
+         {having_sql}",
+        weight = WEIGHT_SQL,
+    )
+}
+
+/// List deployment proposals with their signature progress, with optional
+/// status / policy / readiness filters.
+pub async fn list_proposals(
+    State(state): State<AppState>,
+    Query(params): Query<ListProposalsParams>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let limit = params.limit.unwrap_or(20).min(100);
+    let page = params.page.unwrap_or(1).max(1);
+    let offset = (page - 1) * limit;
 
-    let count_sql = format!("SELECT COUNT(*) FROM deploy_proposals {}", where_sql);
+    // Dynamic query builder (safe — values are bound, not interpolated)
+    let base_sql = proposal_progress_query(&params);
+    let count_sql = format!("SELECT COUNT(*) FROM ({}) AS filtered", base_sql);
     let list_sql = format!(
-        "SELECT * FROM deploy_proposals {} ORDER BY created_at DESC LIMIT {} OFFSET {}",
-        where_sql, limit, offset
+        "{} ORDER BY p.created_at DESC LIMIT {} OFFSET {}",
+        base_sql, limit, offset
     );
 
     // Build and execute count query
@@ -583,14 +799,14 @@ pub async fn list_proposals(
         .map_err(|err| db_internal_error("count proposals", err))?;
 
     // Build and execute list query
-    let mut list_q = sqlx::query_as::<_, DeployProposal>(&list_sql);
+    let mut list_q = sqlx::query_as::<_, ProposalProgress>(&list_sql);
     if let Some(ref s) = params.status {
         list_q = list_q.bind(s.clone());
     }
     if let Some(pid) = params.policy_id {
         list_q = list_q.bind(pid);
     }
-    let proposals: Vec<DeployProposal> = list_q
+    let proposals: Vec<ProposalProgress> = list_q
         .fetch_all(&state.db)
         .await
         .map_err(|err| db_internal_error("list proposals", err))?;
@@ -604,3 +820,161 @@ pub async fn list_proposals(
         "pages": total_pages,
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROPOSER: &str = "GPROPOSER";
+    const ADMIN: &str = "GADMIN";
+
+    #[test]
+    fn proposer_can_cancel_signed_unexecuted_proposal() {
+        for status in [ProposalStatus::Pending, ProposalStatus::Approved] {
+            assert!(check_cancellable(&status, PROPOSER, PROPOSER, ADMIN).is_ok());
+            assert!(check_cancellable(&status, ADMIN, PROPOSER, ADMIN).is_ok());
+        }
+
+        let err = check_cancellable(&ProposalStatus::Pending, "GOTHER", PROPOSER, ADMIN)
+            .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Send `body` (or nothing) to `uri` on the multisig routes
+    async fn call(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn proposal_is_approved_at_its_threshold_and_can_be_cancelled() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let app = crate::multisig_routes::multisig_routes().with_state(state);
+
+        let (status, policy) = call(
+            &app,
+            "POST",
+            "/api/multisig/policies",
+            Some(serde_json::json!({
+                "name": "release",
+                "threshold": 2,
+                "signer_addresses": ["GALICE", "GBOB", "GCAROL"],
+                "created_by": ADMIN,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, proposal) = call(
+            &app,
+            "POST",
+            "/api/contracts/deploy-proposal",
+            Some(serde_json::json!({
+                "contract_name": "token",
+                "contract_id": "CTOKEN",
+                "wasm_hash": "ab".repeat(32),
+                "network": "testnet",
+                "policy_id": policy["id"],
+                "proposer": PROPOSER,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(proposal["status"], "pending");
+        let id = proposal["id"].as_str().unwrap();
+
+        let sign_uri = format!("/api/contracts/{}/sign", id);
+        let sign = |signer: &str| {
+            let body = serde_json::json!({ "signer_address": signer, "signature_data": "sig" });
+            call(&app, "POST", &sign_uri, Some(body))
+        };
+        let (status, signed) = sign("GALICE").await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(signed["signatures_needed"], 1);
+        // A retry is not counted twice
+        let (status, retried) = sign("GALICE").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(retried["already_signed"], true);
+        let (status, _) = sign("GMALLORY").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (_, approved) = sign("GBOB").await;
+        assert_eq!(approved["proposal_status"], "approved");
+
+        let (status, ready) = call(
+            &app,
+            "GET",
+            "/api/multisig/proposals?status=approved&ready_only=true",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ready["total"], 1);
+        assert_eq!(ready["items"][0]["weight_collected"], 2);
+        let (_, pending) = call(&app, "GET", "/api/multisig/proposals?status=pending", None).await;
+        assert_eq!(pending["total"], 0);
+
+        let (status, info) = call(
+            &app,
+            "GET",
+            &format!("/api/contracts/{}/proposal", id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(info["signatures"].as_array().unwrap().len(), 2);
+        assert_eq!(info["signatures_needed"], 0);
+
+        let cancel_uri = format!("/api/contracts/{}/cancel", id);
+        let cancel = |by: &str| {
+            let body = serde_json::json!({ "requested_by": by, "reason": "superseded" });
+            call(&app, "POST", &cancel_uri, Some(body))
+        };
+        let (status, _) = cancel("GOTHER").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, cancelled) = cancel(PROPOSER).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cancelled["status"], "cancelled");
+    }
+
+    #[test]
+    fn identical_repeat_signature_is_a_no_op() {
+        assert!(check_repeat_signature(Some("sig-a"), Some("sig-a"), PROPOSER).is_ok());
+        assert!(check_repeat_signature(None, None, PROPOSER).is_ok());
+    }
+
+    #[test]
+    fn conflicting_repeat_signature_is_rejected() {
+        let err = check_repeat_signature(Some("sig-a"), Some("sig-b"), PROPOSER).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+        assert!(check_repeat_signature(None, Some("sig-b"), PROPOSER).is_err());
+    }
+
+    #[test]
+    fn executed_proposal_cannot_be_cancelled() {
+        let err = check_cancellable(&ProposalStatus::Executed, PROPOSER, PROPOSER, ADMIN)
+            .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+    }
+}
@@ -36,6 +36,11 @@ pub fn multisig_routes() -> Router<AppState> {
             "/api/contracts/:id/execute",
             post(multisig_handlers::execute_proposal),
         )
+        // Withdraw a proposal before it is executed
+        .route(
+            "/api/contracts/:id/cancel",
+            post(multisig_handlers::cancel_proposal),
+        )
         // Retrieve full proposal info with signatures and policy
         .route(
             "/api/contracts/:id/proposal",
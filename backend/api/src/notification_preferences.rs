@@ -0,0 +1,227 @@
+// Per-contract notification preferences, set by the contract's publisher.
+//
+// `min_severity` decides which patch notices a contract is sent; `events`
+// which webhook event types its subscriptions deliver. A contract without a
+// stored row is notified of everything.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    Json,
+};
+use shared::{ContractNotificationPreferences, PatchSeverity};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    api_keys::ContractWriteKey,
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, fetch_contract_identity},
+    state::AppState,
+};
+
+/// Event types a webhook subscription can deliver
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "contract.published",
+    "contract.verified",
+    "contract.failed_verification",
+    "version.created",
+];
+
+/// Trim and dedupe `events`, rejecting unknown event types
+fn normalize_events(events: Option<Vec<String>>) -> ApiResult<Option<Vec<String>>> {
+    let Some(events) = events else {
+        return Ok(None);
+    };
+    let mut normalized: Vec<String> = Vec::with_capacity(events.len());
+    for event in events {
+        let event = event.trim();
+        if !WEBHOOK_EVENTS.contains(&event) {
+            return Err(ApiError::bad_request(
+                "InvalidEventType",
+                format!(
+                    "Unknown event type: {}. Valid: {}",
+                    event,
+                    WEBHOOK_EVENTS.join(", ")
+                ),
+            ));
+        }
+        if !normalized.iter().any(|e| e == event) {
+            normalized.push(event.to_string());
+        }
+    }
+    Ok(Some(normalized))
+}
+
+/// Stored preferences of `contract_uuid`, or the notify-everything default
+pub async fn load_preferences(
+    db: &PgPool,
+    contract_uuid: Uuid,
+) -> Result<ContractNotificationPreferences, sqlx::Error> {
+    let stored: Option<ContractNotificationPreferences> = sqlx::query_as(
+        "SELECT min_severity, events FROM contract_notification_preferences \
+         WHERE contract_id = $1",
+    )
+    .bind(contract_uuid)
+    .fetch_optional(db)
+    .await?;
+    Ok(stored.unwrap_or_default())
+}
+
+/// Severity thresholds of the contracts among `contract_uuids` that set one
+pub async fn load_min_severities(
+    db: &PgPool,
+    contract_uuids: &[Uuid],
+) -> Result<HashMap<Uuid, PatchSeverity>, sqlx::Error> {
+    let rows: Vec<(Uuid, PatchSeverity)> = sqlx::query_as(
+        "SELECT contract_id, min_severity FROM contract_notification_preferences \
+         WHERE contract_id = ANY($1) AND min_severity IS NOT NULL",
+    )
+    .bind(contract_uuids)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// GET /api/contracts/:id/notification-preferences
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ContractNotificationPreferences>> {
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+    let preferences = load_preferences(&state.db, contract_uuid)
+        .await
+        .map_err(|err| db_internal_error("get notification preferences", err))?;
+    Ok(Json(preferences))
+}
+
+/// PUT /api/contracts/:id/notification-preferences
+///
+/// Replaces the contract's preferences. Requires an API key of the
+/// contract's publisher (`X-API-Key`).
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    key: ContractWriteKey,
+    payload: Result<Json<ContractNotificationPreferences>, JsonRejection>,
+) -> ApiResult<Json<ContractNotificationPreferences>> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let events = normalize_events(req.events)?;
+
+    let stored: ContractNotificationPreferences = sqlx::query_as(
+        "INSERT INTO contract_notification_preferences (contract_id, min_severity, events) \
+         VALUES ($1, $2, $3) \
+         ON CONFLICT (contract_id) DO UPDATE \
+         SET min_severity = EXCLUDED.min_severity, events = EXCLUDED.events, \
+             updated_at = NOW() \
+         RETURNING min_severity, events",
+    )
+    .bind(key.contract_uuid)
+    .bind(req.min_severity)
+    .bind(&events)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("update notification preferences", err))?;
+
+    tracing::info!(
+        contract = %id,
+        min_severity = ?stored.min_severity,
+        "notification preferences updated"
+    );
+    Ok(Json(stored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    #[test]
+    fn unknown_event_types_are_rejected() {
+        let events = normalize_events(Some(vec![
+            " contract.verified".to_string(),
+            "contract.verified".to_string(),
+        ]))
+        .unwrap();
+        assert_eq!(events, Some(vec!["contract.verified".to_string()]));
+        assert!(normalize_events(Some(vec!["contract.deleted".to_string()])).is_err());
+        assert_eq!(normalize_events(None).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn publisher_sets_preferences_that_suppress_lower_severities() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let contract = crate::test_db::insert_contract(&state.db, publisher, "CPREFS").await;
+        let api_key = crate::api_keys::generate_api_key();
+        sqlx::query("INSERT INTO api_keys (publisher_id, name, key_hash) VALUES ($1, 'ci', $2)")
+            .bind(publisher)
+            .bind(crate::api_keys::hash_api_key(&api_key))
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route(
+                "/api/contracts/:id/notification-preferences",
+                get(get_notification_preferences).put(update_notification_preferences),
+            )
+            .with_state(state.clone());
+        let uri = "/api/contracts/CPREFS/notification-preferences";
+        let put = |key: Option<&str>| {
+            let mut request = Request::put(uri).header("content-type", "application/json");
+            if let Some(key) = key {
+                request = request.header(crate::api_keys::API_KEY_HEADER, key);
+            }
+            request
+                .body(Body::from(
+                    r#"{"min_severity":"high","events":["contract.verified"]}"#,
+                ))
+                .unwrap()
+        };
+
+        let unauthenticated = app.clone().oneshot(put(None)).await.unwrap();
+        assert_eq!(unauthenticated.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            load_preferences(&state.db, contract).await.unwrap(),
+            ContractNotificationPreferences::default()
+        );
+
+        let updated = app.clone().oneshot(put(Some(&api_key))).await.unwrap();
+        assert_eq!(updated.status(), StatusCode::OK);
+
+        let fetched = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(fetched.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(fetched.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let preferences: ContractNotificationPreferences = serde_json::from_slice(&body).unwrap();
+        assert_eq!(preferences.min_severity, Some(PatchSeverity::High));
+        assert_eq!(
+            preferences.events,
+            Some(vec!["contract.verified".to_string()])
+        );
+        assert!(!preferences.allows_severity(PatchSeverity::Low));
+        assert!(preferences.allows_severity(PatchSeverity::Critical));
+
+        let thresholds = load_min_severities(&state.db, &[contract]).await.unwrap();
+        assert_eq!(thresholds.get(&contract), Some(&PatchSeverity::High));
+    }
+}
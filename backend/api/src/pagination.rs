@@ -0,0 +1,327 @@
+// Page-size limits for paginated list endpoints.
+//
+// Each resource has a default page size (used when `limit` is omitted) and a
+// maximum. Operators can override both through the environment, e.g.
+// `PAGINATION_CONTRACTS_DEFAULT` / `PAGINATION_CONTRACTS_MAX`.
+//
+// Listings ordered by creation time can also be paged with keyset cursors,
+// which stay stable while rows are inserted ahead of the reader.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Default and maximum page size for one resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimits {
+    pub default: i64,
+    pub max: i64,
+}
+
+impl PageLimits {
+    pub const fn new(default: i64, max: i64) -> Self {
+        Self { default, max }
+    }
+
+    /// Page size for a request: the default when omitted, clamped to `1..=max`
+    pub fn resolve(&self, requested: Option<i64>) -> i64 {
+        requested.unwrap_or(self.default).clamp(1, self.max)
+    }
+
+    fn validate(&self, resource: &str) -> Result<(), String> {
+        if self.default < 1 {
+            return Err(format!(
+                "{} default page size must be at least 1 (got {})",
+                resource, self.default
+            ));
+        }
+        if self.max < self.default {
+            return Err(format!(
+                "{} max page size ({}) must be >= default ({})",
+                resource, self.max, self.default
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Page-size limits per paginated resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationConfig {
+    /// GET /api/contracts
+    pub contracts: PageLimits,
+    /// GET /api/contracts/:id/interactions
+    pub interactions: PageLimits,
+    /// GET /api/contracts/:id/history/all
+    pub audit_log: PageLimits,
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            contracts: PageLimits::new(20, 100),
+            interactions: PageLimits::new(50, 100),
+            audit_log: PageLimits::new(20, 100),
+        }
+    }
+}
+
+impl PaginationConfig {
+    /// Load overrides from `PAGINATION_<RESOURCE>_DEFAULT` / `_MAX`, where
+    /// `<RESOURCE>` is `CONTRACTS`, `INTERACTIONS` or `AUDIT_LOG`
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let defaults = Self::default();
+        let load = |resource: &str, fallback: PageLimits| -> Result<PageLimits, String> {
+            let read = |suffix: &str, fallback: i64| -> Result<i64, String> {
+                let key = format!("PAGINATION_{}_{}", resource, suffix);
+                match lookup(&key) {
+                    None => Ok(fallback),
+                    Some(raw) => raw
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("{} must be an integer (got '{}')", key, raw)),
+                }
+            };
+            let limits = PageLimits::new(
+                read("DEFAULT", fallback.default)?,
+                read("MAX", fallback.max)?,
+            );
+            limits.validate(&resource.to_ascii_lowercase())?;
+            Ok(limits)
+        };
+
+        Ok(Self {
+            contracts: load("CONTRACTS", defaults.contracts)?,
+            interactions: load("INTERACTIONS", defaults.interactions)?,
+            audit_log: load("AUDIT_LOG", defaults.audit_log)?,
+        })
+    }
+}
+
+/// Which way a keyset cursor pages through a listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorDirection {
+    /// Towards the end of the listing
+    Next,
+    /// Back towards the start of the listing
+    Prev,
+}
+
+/// Keyset position in a listing ordered by `(created_at, id)`. The page a
+/// cursor fetches starts just past this row, in the cursor's direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub direction: CursorDirection,
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(direction: CursorDirection, created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self {
+            direction,
+            created_at,
+            id,
+        }
+    }
+
+    /// Opaque URL-safe token
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            CursorDirection::Next => "n",
+            CursorDirection::Prev => "p",
+        };
+        let raw = format!(
+            "{}|{}|{}",
+            direction,
+            self.created_at.timestamp_micros(),
+            self.id
+        );
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid cursor '{}'", token);
+        let raw = URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid)?;
+
+        let mut parts = raw.splitn(3, '|');
+        let direction = match parts.next() {
+            Some("n") => CursorDirection::Next,
+            Some("p") => CursorDirection::Prev,
+            _ => return Err(invalid()),
+        };
+        let created_at = parts
+            .next()
+            .and_then(|micros| micros.parse().ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+        Ok(Self::new(direction, created_at, id))
+    }
+}
+
+/// Next and previous cursors for a page, given its rows in display order.
+///
+/// `request` is the direction of the cursor that fetched the page (`None` for
+/// the first page) and `has_more` whether rows exist beyond the page in that
+/// direction. The previous cursor points back from the page's first row, the
+/// next cursor forward from its last row.
+pub fn page_cursors(
+    keys: &[(DateTime<Utc>, Uuid)],
+    request: Option<CursorDirection>,
+    has_more: bool,
+) -> (Option<Cursor>, Option<Cursor>) {
+    let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+        return (None, None);
+    };
+    let (more_after, more_before) = match request {
+        None => (has_more, false),
+        Some(CursorDirection::Next) => (has_more, true),
+        Some(CursorDirection::Prev) => (true, has_more),
+    };
+    let next = more_after.then(|| Cursor::new(CursorDirection::Next, last.0, last.1));
+    let prev = more_before.then(|| Cursor::new(CursorDirection::Prev, first.0, first.1));
+    (next, prev)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<PaginationConfig, String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        PaginationConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn defaults_match_previous_hard_coded_limits() {
+        let config = config_from(&[]).unwrap();
+        assert_eq!(config, PaginationConfig::default());
+        assert_eq!(config.contracts.resolve(None), 20);
+        assert_eq!(config.interactions.resolve(None), 50);
+    }
+
+    #[test]
+    fn configured_max_is_enforced() {
+        let config = config_from(&[
+            ("PAGINATION_CONTRACTS_DEFAULT", "10"),
+            ("PAGINATION_CONTRACTS_MAX", "25"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.contracts.resolve(None), 10);
+        assert_eq!(config.contracts.resolve(Some(25)), 25);
+        assert_eq!(config.contracts.resolve(Some(1_000)), 25);
+        assert_eq!(config.contracts.resolve(Some(0)), 1);
+        // Other resources keep their defaults
+        assert_eq!(config.interactions.resolve(Some(1_000)), 100);
+    }
+
+    #[test]
+    fn max_below_default_is_rejected_on_load() {
+        let err = config_from(&[
+            ("PAGINATION_INTERACTIONS_DEFAULT", "80"),
+            ("PAGINATION_INTERACTIONS_MAX", "40"),
+        ])
+        .unwrap_err();
+        assert!(err.contains("interactions max page size"), "{}", err);
+
+        assert!(config_from(&[("PAGINATION_AUDIT_LOG_DEFAULT", "0")]).is_err());
+        assert!(config_from(&[("PAGINATION_CONTRACTS_MAX", "lots")]).is_err());
+    }
+
+    type Key = (DateTime<Utc>, Uuid);
+
+    /// The keyset filter and ordering list_contracts applies, newest first
+    fn fetch(rows: &[Key], cursor: Option<&Cursor>, limit: usize) -> (Vec<Key>, bool) {
+        let mut page: Vec<Key> = match cursor {
+            None => rows.to_vec(),
+            Some(c) => {
+                let key = (c.created_at, c.id);
+                match c.direction {
+                    CursorDirection::Next => rows.iter().filter(|r| **r < key).copied().collect(),
+                    CursorDirection::Prev => {
+                        rows.iter().rev().filter(|r| **r > key).copied().collect()
+                    }
+                }
+            }
+        };
+        let has_more = page.len() > limit;
+        page.truncate(limit);
+        if cursor.is_some_and(|c| c.direction == CursorDirection::Prev) {
+            page.reverse();
+        }
+        (page, has_more)
+    }
+
+    fn follow(
+        rows: &[Key],
+        token: &str,
+        limit: usize,
+    ) -> (Vec<Key>, Option<Cursor>, Option<Cursor>) {
+        let cursor = Cursor::decode(token).unwrap();
+        let (page, has_more) = fetch(rows, Some(&cursor), limit);
+        let (next, prev) = page_cursors(&page, Some(cursor.direction), has_more);
+        (page, next, prev)
+    }
+
+    #[test]
+    fn paging_forward_then_back_returns_to_first_page() {
+        // Postgres timestamps have microsecond precision
+        let start = DateTime::from_timestamp_micros(Utc::now().timestamp_micros()).unwrap();
+        let mut rows: Vec<Key> = (0..7)
+            .map(|i| (start - chrono::Duration::minutes(i), Uuid::new_v4()))
+            .collect();
+        rows.sort_by(|a, b| b.cmp(a));
+
+        let (first, has_more) = fetch(&rows, None, 3);
+        let (next, prev) = page_cursors(&first, None, has_more);
+        assert!(prev.is_none());
+
+        let (second, next, prev) = follow(&rows, &next.unwrap().encode(), 3);
+        assert_eq!(second, rows[3..6]);
+        assert!(prev.is_some());
+
+        let (last, next, prev) = follow(&rows, &next.unwrap().encode(), 3);
+        assert_eq!(last, rows[6..]);
+        assert!(next.is_none());
+
+        let (back, _, prev) = follow(&rows, &prev.unwrap().encode(), 3);
+        assert_eq!(back, second);
+
+        let (back, next, prev) = follow(&rows, &prev.unwrap().encode(), 3);
+        assert_eq!(back, first);
+        assert!(prev.is_none(), "no page before the first");
+        assert!(next.is_some());
+    }
+
+    #[test]
+    fn cursor_round_trips_and_rejects_garbage() {
+        let cursor = Cursor::new(CursorDirection::Prev, Utc::now(), Uuid::new_v4());
+        let decoded = Cursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded.id, cursor.id);
+        assert_eq!(decoded.direction, CursorDirection::Prev);
+        assert_eq!(
+            decoded.created_at.timestamp_micros(),
+            cursor.created_at.timestamp_micros()
+        );
+
+        assert!(Cursor::decode("not-a-cursor").is_err());
+        assert!(Cursor::decode(&URL_SAFE_NO_PAD.encode("x|1|y")).is_err());
+    }
+}
@@ -0,0 +1,2468 @@
+// Security patches relevant to a single contract, and contract owners'
+// acknowledgement of the patch notices they were sent.
+//
+// Webhook receivers confirm they processed a notice by posting a delivery
+// receipt, signed with an HMAC key derived from `PATCH_RECEIPT_SECRET` for
+// that patch and contract. Notifying returns each new target's key, so one
+// receiver cannot confirm another's notice.
+//
+// Applicability is the reverse of the CLI's affected-contracts lookup: instead
+// of finding every contract a patch targets, it finds every patch whose target
+// covers one contract's current release. A patch's `vulnerable_range` decides
+// which versions it covers; its `target_version` still matches by WASM hash.
+//
+// Owners record applying a patch, or failing to, through the rollout engine
+// (see `rollout`): a contract not attempted yet is admitted only while the
+// patch's rollout percentage leaves room and the failure rate stays within
+// the gate for its severity.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    body::Bytes,
+    extract::{rejection::JsonRejection, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Extension, Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use shared::{
+    AcknowledgePatchRequest, ApplicablePatch, ApplicablePatchesResponse, ApplyPatchRequest,
+    ApprovalGate, ContractVersion, CreateSecurityPatchRequest, DeliveryReceiptRequest,
+    EscalatePatchRequest, NotificationRecord, PatchAcknowledgementSummary, PatchApplication,
+    PatchApplicationsResponse, PatchAuditAction, PatchAuditEntry, PatchDeliveryStatus,
+    PatchEscalation, PatchImpactNode, PatchImpactResponse, PatchNotice, PatchNoticeTarget,
+    PatchNotifyPreview, PatchNotifyResult, PatchSeverity, PatchStatus, PublisherPatch,
+    RolloutProgress, RolloutSimulationGate, RolloutSimulationResponse, RolloutSimulationStage,
+    RolloutStage, SecurityPatch, SemVer, VersionRange,
+};
+use uuid::Uuid;
+
+use crate::{
+    admin_auth::AdminOperator,
+    api_keys::ContractWriteKey,
+    dependency,
+    error::{ApiError, ApiResult},
+    handlers::{
+        db_internal_error, fetch_contract_identity, fetch_published_versions, latest_release,
+    },
+    notification_preferences,
+    request_signing::{
+        constant_time_eq, hmac_sha256, signing_payload, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    },
+    rollout::{GateDecision, RolloutEngine, RolloutPlan, RolloutSimulation},
+    state::AppState,
+    validation::ValidatedJson,
+};
+
+/// What a patch's `target_version` selects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchTarget {
+    /// Exact vulnerable WASM hash
+    WasmHash(String),
+    /// Versions in `min..=max`
+    VersionRange { min: SemVer, max: SemVer },
+}
+
+impl PatchTarget {
+    /// `1.0.0..1.2.0` is an inclusive range, a bare semver is a single
+    /// version, anything else is a WASM hash. `None` for targets that cannot
+    /// match anything (empty or an inverted range).
+    pub fn parse(target: &str) -> Option<Self> {
+        let target = target.trim();
+        if let Some((min, max)) = target.split_once("..") {
+            let (min, max) = (SemVer::parse(min.trim())?, SemVer::parse(max.trim())?);
+            return (min <= max).then_some(Self::VersionRange { min, max });
+        }
+        if let Some(version) = SemVer::parse(target) {
+            return Some(Self::VersionRange {
+                min: version.clone(),
+                max: version,
+            });
+        }
+        (!target.is_empty()).then(|| Self::WasmHash(target.to_string()))
+    }
+
+    pub fn matches(&self, version: Option<&SemVer>, wasm_hash: &str) -> bool {
+        match self {
+            Self::WasmHash(hash) => wasm_hash.eq_ignore_ascii_case(hash),
+            Self::VersionRange { min, max } => version.is_some_and(|v| v >= min && v <= max),
+        }
+    }
+}
+
+/// Whether `patch` covers a contract at `version` with `wasm_hash`. Patches
+/// without a vulnerable range fall back to their version target.
+pub fn patch_applies(patch: &SecurityPatch, version: Option<&SemVer>, wasm_hash: &str) -> bool {
+    let target = PatchTarget::parse(&patch.target_version);
+    match VersionRange::parse(&patch.vulnerable_range) {
+        Ok(range) => {
+            version.is_some_and(|v| range.contains(v))
+                || matches!(target, Some(PatchTarget::WasmHash(hash)) if wasm_hash.eq_ignore_ascii_case(&hash))
+        }
+        Err(_) => target.is_some_and(|target| target.matches(version, wasm_hash)),
+    }
+}
+
+/// The semver of a release's version string, build metadata ignored as
+/// `latest_release` ignores it
+fn release_semver(version: &str) -> Option<SemVer> {
+    SemVer::parse(version.split('+').next().unwrap_or(version))
+}
+
+/// Patches that cover `version`/`wasm_hash`, most severe first
+pub fn applicable_patches(
+    patches: Vec<SecurityPatch>,
+    version: Option<&SemVer>,
+    wasm_hash: &str,
+    applied: &HashSet<Uuid>,
+) -> Vec<ApplicablePatch> {
+    let mut matching: Vec<ApplicablePatch> = patches
+        .into_iter()
+        .filter(|patch| patch_applies(patch, version, wasm_hash))
+        .map(|patch| ApplicablePatch {
+            status: if applied.contains(&patch.id) {
+                PatchStatus::Applied
+            } else {
+                PatchStatus::Available
+            },
+            patch,
+        })
+        .collect();
+    matching.sort_by(|a, b| {
+        b.patch
+            .severity
+            .rank()
+            .cmp(&a.patch.severity.rank())
+            .then_with(|| b.patch.created_at.cmp(&a.patch.created_at))
+    });
+    matching
+}
+
+/// GET /api/contracts/:id/applicable-patches
+///
+/// Evaluated against the latest published stable release; a contract with
+/// no published release is matched on its registered WASM hash only.
+pub async fn get_applicable_patches(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ApplicablePatchesResponse>> {
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+
+    let versions = fetch_published_versions(&state, contract_uuid).await?;
+    let current = latest_release(&versions, false);
+
+    let wasm_hash = match current {
+        Some(version) => version.wasm_hash.clone(),
+        None => sqlx::query_scalar("SELECT wasm_hash FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract wasm hash", err))?,
+    };
+
+    let patches: Vec<SecurityPatch> = sqlx::query_as("SELECT * FROM security_patches")
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("list security patches", err))?;
+    let applied: HashSet<Uuid> = sqlx::query_scalar(
+        "SELECT patch_id FROM patch_audits WHERE contract_id = $1 AND NOT failed",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list applied patches", err))?
+    .into_iter()
+    .collect();
+
+    let semver = current.and_then(|v| release_semver(&v.version));
+    Ok(Json(ApplicablePatchesResponse {
+        contract_id: contract_uuid,
+        current_version: current.map(|v| v.version.clone()),
+        patches: applicable_patches(patches, semver.as_ref(), &wasm_hash, &applied),
+        wasm_hash,
+    }))
+}
+
+/// POST /api/patches
+pub async fn create_security_patch(
+    State(state): State<AppState>,
+    Extension(AdminOperator(operator)): Extension<AdminOperator>,
+    ValidatedJson(req): ValidatedJson<CreateSecurityPatchRequest>,
+) -> ApiResult<(StatusCode, Json<SecurityPatch>)> {
+    let patch: SecurityPatch = sqlx::query_as(
+        "INSERT INTO security_patches \
+           (target_version, vulnerable_range, severity, new_wasm_hash, rollout_percentage, \
+            description, publisher_id) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+    )
+    .bind(&req.target_version)
+    .bind(&req.vulnerable_range)
+    .bind(req.severity)
+    .bind(&req.new_wasm_hash)
+    .bind(req.rollout_percentage)
+    .bind(&req.description)
+    .bind(req.publisher_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => ApiError::bad_request(
+            "UnknownPublisher",
+            format!(
+                "No publisher found with ID: {}",
+                req.publisher_id.unwrap_or_default()
+            ),
+        ),
+        _ => db_internal_error("create security patch", err),
+    })?;
+
+    tracing::info!(
+        patch_id = %patch.id,
+        vulnerable_range = %patch.vulnerable_range,
+        severity = ?patch.severity,
+        operator = %operator,
+        "security patch created"
+    );
+    Ok((StatusCode::CREATED, Json(patch)))
+}
+
+fn parse_patch_id(id: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(id).map_err(|_| {
+        ApiError::bad_request("InvalidPatchId", format!("Invalid patch ID format: {}", id))
+    })
+}
+
+/// A contract's current release as patch applicability sees it
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ContractRelease {
+    /// Registry row ID
+    pub id: Uuid,
+    pub contract_id: String,
+    pub version: Option<String>,
+    pub wasm_hash: String,
+}
+
+impl ContractRelease {
+    /// The latest published stable release among `versions`, or the
+    /// registered WASM hash when there is none, as applicable-patches does
+    pub fn resolve(
+        id: Uuid,
+        contract_id: String,
+        registered_hash: String,
+        versions: &[ContractVersion],
+    ) -> Self {
+        match latest_release(versions, false) {
+            Some(current) => Self {
+                id,
+                contract_id,
+                version: Some(current.version.clone()),
+                wasm_hash: current.wasm_hash.clone(),
+            },
+            None => Self {
+                id,
+                contract_id,
+                version: None,
+                wasm_hash: registered_hash,
+            },
+        }
+    }
+}
+
+/// Contracts a notice for `patch` goes to: every contract whose current
+/// release the patch applies to
+pub fn notification_targets<'a>(
+    patch: &SecurityPatch,
+    contracts: &'a [ContractRelease],
+) -> Vec<&'a ContractRelease> {
+    contracts
+        .iter()
+        .filter(|contract| {
+            let semver = contract.version.as_deref().and_then(release_semver);
+            patch_applies(patch, semver.as_ref(), &contract.wasm_hash)
+        })
+        .collect()
+}
+
+/// Drop the targets whose notification preferences set a severity
+/// threshold above `severity`, returning the rest and how many were dropped
+pub fn apply_severity_preferences<'a>(
+    severity: PatchSeverity,
+    targets: Vec<&'a ContractRelease>,
+    thresholds: &HashMap<Uuid, PatchSeverity>,
+) -> (Vec<&'a ContractRelease>, usize) {
+    let affected = targets.len();
+    let notified: Vec<&ContractRelease> = targets
+        .into_iter()
+        .filter(|target| {
+            thresholds
+                .get(&target.id)
+                .is_none_or(|min| severity.rank() >= min.rank())
+        })
+        .collect();
+    let suppressed = affected - notified.len();
+    (notified, suppressed)
+}
+
+/// The notice `target` receives for `patch`
+pub fn render_patch_notice(patch: &SecurityPatch, target: &ContractRelease) -> PatchNotice {
+    PatchNotice {
+        patch_id: patch.id,
+        contract_id: target.contract_id.clone(),
+        severity: patch.severity,
+        current_version: target.version.clone(),
+        wasm_hash: target.wasm_hash.clone(),
+        new_wasm_hash: patch.new_wasm_hash.clone(),
+        vulnerable_range: patch.vulnerable_range.clone(),
+        description: patch.description.clone(),
+    }
+}
+
+/// Contracts a notice for `patch` goes to once their notification
+/// preferences are applied, and how many the preferences left out
+async fn notice_recipients(
+    state: &AppState,
+    patch: &SecurityPatch,
+) -> ApiResult<(Vec<ContractRelease>, usize)> {
+    let releases = load_targets(state, patch).await?;
+    let affected: Vec<&ContractRelease> = releases.iter().collect();
+    let ids: Vec<Uuid> = affected.iter().map(|target| target.id).collect();
+    let thresholds = notification_preferences::load_min_severities(&state.db, &ids)
+        .await
+        .map_err(|err| db_internal_error("get notification preferences", err))?;
+    let (targets, suppressed) = apply_severity_preferences(patch.severity, affected, &thresholds);
+    Ok((targets.into_iter().cloned().collect(), suppressed))
+}
+
+fn notice_target(target: &ContractRelease) -> PatchNoticeTarget {
+    PatchNoticeTarget {
+        contract_id: target.contract_id.clone(),
+        current_version: target.version.clone(),
+        wasm_hash: target.wasm_hash.clone(),
+        receipt_key: None,
+    }
+}
+
+/// POST /api/patches/:patch_id/notify/preview
+///
+/// Who a notification for the patch would reach and what they would be
+/// sent, without sending anything or recording notices. Contracts whose
+/// notification preferences exclude the patch's severity are left out.
+pub async fn preview_patch_notifications(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<PatchNotifyPreview>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let patch = fetch_patch(&state, patch_id).await?;
+    let (targets, suppressed_count) = notice_recipients(&state, &patch).await?;
+    Ok(Json(PatchNotifyPreview {
+        patch_id,
+        target_count: targets.len(),
+        suppressed_count,
+        sample: targets
+            .first()
+            .map(|target| render_patch_notice(&patch, target)),
+        targets: targets.iter().map(notice_target).collect(),
+    }))
+}
+
+/// POST /api/patches/:patch_id/notify
+///
+/// Records a notice for every contract the patch applies to whose
+/// notification preferences accept its severity; only notified contracts can
+/// later acknowledge the patch. Contracts already notified keep their
+/// original notice, so notifying again only reaches new targets. Each newly
+/// notified target comes back with the `receipt_key` its receiver signs the
+/// delivery receipt with.
+pub async fn notify_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    Extension(AdminOperator(operator)): Extension<AdminOperator>,
+) -> ApiResult<Json<PatchNotifyResult>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let patch = fetch_patch(&state, patch_id).await?;
+    let (targets, suppressed_count) = notice_recipients(&state, &patch).await?;
+
+    let ids: Vec<Uuid> = targets.iter().map(|target| target.id).collect();
+    let inserted: HashSet<Uuid> = sqlx::query_scalar(
+        "INSERT INTO patch_notifications (patch_id, contract_id, priority) \
+         SELECT $1, contract_id, $3 FROM UNNEST($2::uuid[]) AS t(contract_id) \
+         ON CONFLICT (patch_id, contract_id) DO NOTHING RETURNING contract_id",
+    )
+    .bind(patch_id)
+    .bind(&ids)
+    .bind(patch.severity)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("record patch notifications", err))?
+    .into_iter()
+    .collect();
+
+    let notified: Vec<PatchNoticeTarget> = targets
+        .iter()
+        .filter(|target| inserted.contains(&target.id))
+        .map(|target| PatchNoticeTarget {
+            receipt_key: state.patch_receipt_secret.as_ref().map(|secret| {
+                hex::encode(receipt_key(
+                    secret.as_bytes(),
+                    patch_id,
+                    &target.contract_id,
+                ))
+            }),
+            ..notice_target(target)
+        })
+        .collect();
+    tracing::info!(
+        patch_id = %patch_id,
+        notified = notified.len(),
+        suppressed = suppressed_count,
+        operator = %operator,
+        "patch notices recorded"
+    );
+    Ok(Json(PatchNotifyResult {
+        patch_id,
+        already_notified: targets.len() - notified.len(),
+        notified,
+        suppressed_count,
+    }))
+}
+
+async fn fetch_patch(state: &AppState, patch_id: Uuid) -> ApiResult<SecurityPatch> {
+    sqlx::query_as("SELECT * FROM security_patches WHERE id = $1")
+        .bind(patch_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|err| db_internal_error("get security patch", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "PatchNotFound",
+                format!("No security patch found with ID: {}", patch_id),
+            )
+        })
+}
+
+/// Every contract joined to its current release as `ContractRelease::resolve`
+/// picks it: the highest published stable version by semver, build metadata
+/// ignored. Parameters are a `PatchTargetFilter`'s, in order.
+const PATCH_TARGETS: &str = "FROM contracts c \
+     LEFT JOIN LATERAL ( \
+         SELECT cv.version, cv.wasm_hash, \
+                string_to_array(split_part(cv.version, '+', 1), '.')::numeric[] AS key \
+         FROM contract_versions cv \
+         WHERE cv.contract_id = c.id AND cv.status = 'published' \
+           AND cv.version ~ '^[0-9]+\\.[0-9]+\\.[0-9]+(\\+.*)?$' \
+         ORDER BY key DESC, cv.created_at DESC \
+         LIMIT 1 \
+     ) r ON true \
+     WHERE (r.key IS NOT NULL AND $1::boolean \
+            AND ($2::text[] IS NULL OR r.key > $2::text[]::numeric[] \
+                 OR ($3::boolean AND r.key = $2::text[]::numeric[])) \
+            AND ($4::text[] IS NULL OR r.key < $4::text[]::numeric[] \
+                 OR ($5::boolean AND r.key = $4::text[]::numeric[]))) \
+        OR ($6::text IS NOT NULL AND lower(COALESCE(r.wasm_hash, c.wasm_hash)) = lower($6))";
+
+/// The releases `patch_applies` accepts for one patch, as `PATCH_TARGETS`
+/// parameters. Bounds are semver triples, inclusive when the flag is set.
+#[derive(Debug, Default)]
+struct PatchTargetFilter {
+    by_version: bool,
+    lower: Option<(Vec<String>, bool)>,
+    upper: Option<(Vec<String>, bool)>,
+    wasm_hash: Option<String>,
+}
+
+impl PatchTargetFilter {
+    fn for_patch(patch: &SecurityPatch) -> Self {
+        fn key(version: &SemVer) -> Vec<String> {
+            [version.major, version.minor, version.patch]
+                .iter()
+                .map(u64::to_string)
+                .collect()
+        }
+        let target = PatchTarget::parse(&patch.target_version);
+        match VersionRange::parse(&patch.vulnerable_range) {
+            Ok(range) => Self {
+                by_version: true,
+                lower: range
+                    .lower_bound()
+                    .map(|(v, inclusive)| (key(v), inclusive)),
+                upper: range
+                    .upper_bound()
+                    .map(|(v, inclusive)| (key(v), inclusive)),
+                wasm_hash: match target {
+                    Some(PatchTarget::WasmHash(hash)) => Some(hash),
+                    _ => None,
+                },
+            },
+            Err(_) => match target {
+                Some(PatchTarget::WasmHash(hash)) => Self {
+                    wasm_hash: Some(hash),
+                    ..Self::default()
+                },
+                Some(PatchTarget::VersionRange { min, max }) => Self {
+                    by_version: true,
+                    lower: Some((key(&min), true)),
+                    upper: Some((key(&max), true)),
+                    wasm_hash: None,
+                },
+                None => Self::default(),
+            },
+        }
+    }
+
+    fn lower(&self) -> (Option<&[String]>, bool) {
+        match &self.lower {
+            Some((bound, inclusive)) => (Some(bound), *inclusive),
+            None => (None, false),
+        }
+    }
+
+    fn upper(&self) -> (Option<&[String]>, bool) {
+        match &self.upper {
+            Some((bound, inclusive)) => (Some(bound), *inclusive),
+            None => (None, false),
+        }
+    }
+}
+
+/// Current releases of the contracts `patch` applies to
+async fn load_targets(state: &AppState, patch: &SecurityPatch) -> ApiResult<Vec<ContractRelease>> {
+    let filter = PatchTargetFilter::for_patch(patch);
+    let (lower, lower_inclusive) = filter.lower();
+    let (upper, upper_inclusive) = filter.upper();
+    sqlx::query_as(&format!(
+        "SELECT c.id, c.contract_id, r.version, \
+                COALESCE(r.wasm_hash, c.wasm_hash) AS wasm_hash \
+         {} \
+         ORDER BY c.contract_id",
+        PATCH_TARGETS
+    ))
+    .bind(filter.by_version)
+    .bind(lower)
+    .bind(lower_inclusive)
+    .bind(upper)
+    .bind(upper_inclusive)
+    .bind(&filter.wasm_hash)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list patch targets", err))
+}
+
+/// Default and maximum hops of dependents followed for a patch's impact
+const DEFAULT_IMPACT_DEPTH: u32 = 3;
+const MAX_IMPACT_DEPTH: u32 = 5;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct PatchImpactQuery {
+    /// Hops of dependents to follow from each affected contract (default 3, max 5)
+    pub depth: Option<u32>,
+}
+
+/// One tree per root, each contract carrying the dependents `walk` first
+/// reached through it. `names` maps registry IDs to (contract ID, name).
+pub fn impact_tree(
+    roots: &[Uuid],
+    walk: &dependency::DependentsWalk,
+    names: &HashMap<Uuid, (String, String)>,
+) -> Vec<PatchImpactNode> {
+    let mut children: HashMap<Uuid, Vec<&dependency::ReachedDependent>> = HashMap::new();
+    for reached in &walk.reached {
+        children.entry(reached.via).or_default().push(reached);
+    }
+
+    fn node(
+        id: Uuid,
+        depth: u32,
+        children: &HashMap<Uuid, Vec<&dependency::ReachedDependent>>,
+        names: &HashMap<Uuid, (String, String)>,
+    ) -> PatchImpactNode {
+        let (contract_id, name) = names.get(&id).cloned().unwrap_or_default();
+        PatchImpactNode {
+            id,
+            contract_id,
+            name,
+            depth,
+            dependents: children
+                .get(&id)
+                .into_iter()
+                .flatten()
+                .map(|child| node(child.id, child.depth, children, names))
+                .collect(),
+        }
+    }
+
+    roots
+        .iter()
+        .map(|root| node(*root, 0, &children, names))
+        .collect()
+}
+
+/// GET /api/patches/:patch_id/impact?depth=
+///
+/// The patch's blast radius: every contract it applies to, and the contracts
+/// depending on those, followed up to `depth` hops and the dependency graph's
+/// node cap. Each downstream contract appears once, under the contract it was
+/// first reached through.
+pub async fn get_patch_impact(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    Query(query): Query<PatchImpactQuery>,
+) -> ApiResult<Json<PatchImpactResponse>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let patch = fetch_patch(&state, patch_id).await?;
+    let roots: Vec<Uuid> = load_targets(&state, &patch)
+        .await?
+        .iter()
+        .map(|target| target.id)
+        .collect();
+
+    let depth = query
+        .depth
+        .unwrap_or(DEFAULT_IMPACT_DEPTH)
+        .clamp(1, MAX_IMPACT_DEPTH);
+    let max_nodes = dependency::GraphLimits::from_env().max_nodes;
+    let edges = dependency::load_dependent_edges(&state.db, &roots, depth, max_nodes)
+        .await
+        .map_err(|err| {
+            tracing::error!(patch_id = %patch_id, error = ?err, "failed to load dependents");
+            ApiError::internal("Failed to load dependent contracts")
+        })?;
+    let walk = dependency::bounded_dependents(&roots, depth, max_nodes, &edges);
+
+    let ids: Vec<Uuid> = roots
+        .iter()
+        .copied()
+        .chain(walk.reached.iter().map(|r| r.id))
+        .collect();
+    let names: HashMap<Uuid, (String, String)> = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT id, contract_id, name FROM contracts WHERE id = ANY($1)",
+    )
+    .bind(&ids)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get impacted contracts", err))?
+    .into_iter()
+    .map(|(id, contract_id, name)| (id, (contract_id, name)))
+    .collect();
+
+    Ok(Json(PatchImpactResponse {
+        patch_id,
+        severity: patch.severity,
+        affected_count: roots.len(),
+        downstream_count: walk.reached.len(),
+        truncated: walk.truncated,
+        affected: impact_tree(&roots, &walk, &names),
+    }))
+}
+
+/// Bytes a contract owner signs to acknowledge `patch_id` for `contract_id`
+pub fn ack_message(patch_id: Uuid, contract_id: &str) -> Vec<u8> {
+    format!("ack:{}:{}", patch_id, contract_id).into_bytes()
+}
+
+/// Only contracts that were sent the patch notice can acknowledge it
+fn ensure_notified(
+    record: Option<NotificationRecord>,
+    patch_id: Uuid,
+    contract_id: &str,
+) -> ApiResult<NotificationRecord> {
+    record.ok_or_else(|| {
+        ApiError::not_found(
+            "NotificationNotFound",
+            format!(
+                "Contract {} was not notified of patch {}",
+                contract_id, patch_id
+            ),
+        )
+    })
+}
+
+/// Accept `signature` when any of the publisher's base64 Ed25519 keys
+/// verifies it over `message`
+fn verify_ack_signature(public_keys: &[String], message: &[u8], signature: &str) -> ApiResult<()> {
+    let sig_bytes = BASE64.decode(signature.trim()).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidSignature",
+            "signature must be valid base64-encoded Ed25519 signature",
+        )
+    })?;
+    let sig_array: [u8; 64] = sig_bytes.as_slice().try_into().map_err(|_| {
+        ApiError::bad_request("InvalidSignature", "signature must decode to 64 bytes")
+    })?;
+    let signature = Signature::from_bytes(&sig_array);
+
+    let verified = public_keys.iter().any(|key| {
+        BASE64
+            .decode(key.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes.as_slice()).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .is_some_and(|key| key.verify(message, &signature).is_ok())
+    });
+    if verified {
+        Ok(())
+    } else {
+        Err(ApiError::unprocessable(
+            "InvalidSignature",
+            "signature does not match an active signing key of the contract's publisher",
+        ))
+    }
+}
+
+/// POST /api/patches/:patch_id/acknowledge
+///
+/// Marks the contract's notice for this patch acknowledged. Requires an API
+/// key of the contract's publisher (`X-API-Key`) and a signature by one of
+/// its signing keys. Acknowledging again returns the original
+/// acknowledgement unchanged.
+pub async fn acknowledge_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    headers: HeaderMap,
+    payload: Result<Json<AcknowledgePatchRequest>, JsonRejection>,
+) -> ApiResult<Json<NotificationRecord>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let acknowledged_by = req.acknowledged_by.trim();
+    if acknowledged_by.is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidRequest",
+            "acknowledged_by must not be empty",
+        ));
+    }
+
+    let signature = req.signature.trim();
+    if signature.is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidRequest",
+            "signature must not be empty",
+        ));
+    }
+
+    let key = ContractWriteKey::authorize(&state, &headers, &req.contract_id).await?;
+    let contract_uuid = key.contract_uuid;
+    let (_, contract_id) = fetch_contract_identity(&state, &req.contract_id).await?;
+    let record: Option<NotificationRecord> = sqlx::query_as(
+        "SELECT * FROM patch_notifications WHERE patch_id = $1 AND contract_id = $2",
+    )
+    .bind(patch_id)
+    .bind(contract_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get patch notification", err))?;
+    let record = ensure_notified(record, patch_id, &contract_id)?;
+    if record.acknowledged_at.is_some() {
+        return Ok(Json(record));
+    }
+
+    let keys: Vec<String> = sqlx::query_scalar(
+        "SELECT sk.public_key FROM signing_keys sk \
+         JOIN contracts c ON c.publisher_id = sk.publisher_id \
+         WHERE c.id = $1 AND sk.is_active AND sk.algorithm = 'ed25519'",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get publisher signing keys", err))?;
+    verify_ack_signature(&keys, &ack_message(patch_id, &contract_id), signature)?;
+
+    let updated: Option<NotificationRecord> = sqlx::query_as(
+        "UPDATE patch_notifications \
+         SET acknowledged_at = NOW(), acknowledged_by = $2, ack_signature = $3 \
+         WHERE id = $1 AND acknowledged_at IS NULL RETURNING *",
+    )
+    .bind(record.id)
+    .bind(acknowledged_by)
+    .bind(signature)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("acknowledge patch", err))?;
+
+    // A concurrent acknowledgement won the update; report that one
+    let acknowledged = match updated {
+        Some(updated) => updated,
+        None => sqlx::query_as("SELECT * FROM patch_notifications WHERE id = $1")
+            .bind(record.id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get patch notification", err))?,
+    };
+    tracing::info!(
+        patch_id = %patch_id,
+        contract_id = %contract_id,
+        acknowledged_by = %acknowledged_by,
+        key_id = %key.key_id,
+        "patch acknowledged"
+    );
+    Ok(Json(acknowledged))
+}
+
+/// A patch's severity may only be raised once published; lowering it, or
+/// "escalating" to the same level, is refused with 422
+pub fn check_escalation(current: PatchSeverity, requested: PatchSeverity) -> ApiResult<()> {
+    if requested.rank() >= current.rank() {
+        return Ok(());
+    }
+    Err(ApiError::unprocessable(
+        "SeverityDowngrade",
+        format!(
+            "Patch severity cannot be lowered from {:?} to {:?}",
+            current, requested
+        ),
+    ))
+}
+
+/// POST /api/patches/:patch_id/escalate
+///
+/// Raises the patch's severity and records the change, with the operator who
+/// made it, in its audit trail. Unacknowledged notices are re-prioritized at
+/// the new severity; notices and applicable-patch lists are built from the
+/// stored severity, so the patch moves up each target's applicable list
+/// without anything being re-sent here. Requesting the current severity
+/// changes nothing and records no audit entry.
+pub async fn escalate_patch_severity(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    Extension(AdminOperator(escalated_by)): Extension<AdminOperator>,
+    payload: Result<Json<EscalatePatchRequest>, JsonRejection>,
+) -> ApiResult<Json<PatchEscalation>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let reason = req
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin patch escalation", err))?;
+
+    let current: SecurityPatch =
+        sqlx::query_as("SELECT * FROM security_patches WHERE id = $1 FOR UPDATE")
+            .bind(patch_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|err| db_internal_error("get security patch", err))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "PatchNotFound",
+                    format!("No patch found with ID: {}", patch_id),
+                )
+            })?;
+    check_escalation(current.severity, req.severity)?;
+
+    if req.severity == current.severity {
+        let pending_notifications: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM patch_notifications \
+             WHERE patch_id = $1 AND acknowledged_at IS NULL",
+        )
+        .bind(patch_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("count pending patch notifications", err))?;
+        return Ok(Json(PatchEscalation {
+            previous_severity: current.severity,
+            patch: current,
+            audit: None,
+            pending_notifications,
+        }));
+    }
+
+    let patch: SecurityPatch =
+        sqlx::query_as("UPDATE security_patches SET severity = $2 WHERE id = $1 RETURNING *")
+            .bind(patch_id)
+            .bind(req.severity)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| db_internal_error("escalate patch severity", err))?;
+
+    let audit: PatchAuditEntry = sqlx::query_as(
+        "INSERT INTO security_patch_audit_log \
+           (patch_id, action, old_value, new_value, changed_by, reason) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+    )
+    .bind(patch_id)
+    .bind(PatchAuditAction::SeverityEscalated)
+    .bind(serde_json::json!({ "severity": current.severity }))
+    .bind(serde_json::json!({ "severity": patch.severity }))
+    .bind(&escalated_by)
+    .bind(reason)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("record patch escalation", err))?;
+
+    let pending_notifications = sqlx::query(
+        "UPDATE patch_notifications SET priority = $2 \
+         WHERE patch_id = $1 AND acknowledged_at IS NULL",
+    )
+    .bind(patch_id)
+    .bind(patch.severity)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("re-prioritize pending patch notifications", err))?
+    .rows_affected() as i64;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit patch escalation", err))?;
+
+    tracing::info!(
+        patch_id = %patch_id,
+        from = ?current.severity,
+        to = ?patch.severity,
+        escalated_by = %escalated_by,
+        pending_notifications,
+        "security patch severity escalated"
+    );
+    Ok(Json(PatchEscalation {
+        patch,
+        previous_severity: current.severity,
+        audit: Some(audit),
+        pending_notifications,
+    }))
+}
+
+/// Accepted clock skew for a delivery receipt's `X-Timestamp`
+const RECEIPT_MAX_SKEW_SECS: u64 = 300;
+
+/// Key a receiver signs delivery receipts for `patch_id` on `contract_id`
+/// with; issued in the notify response for that contract
+pub fn receipt_key(secret: &[u8], patch_id: Uuid, contract_id: &str) -> [u8; 32] {
+    hmac_sha256(
+        secret,
+        format!("receipt:{}:{}", patch_id, contract_id).as_bytes(),
+    )
+}
+
+/// Check `X-Signature`, a hex HMAC-SHA256 over `{timestamp}.{body}` keyed
+/// with the receiver's `receipt_key`, as of `now` (unix seconds)
+fn verify_receipt_signature(
+    key: &[u8],
+    headers: &HeaderMap,
+    body: &[u8],
+    now: u64,
+) -> ApiResult<()> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let invalid =
+        |message: &str| ApiError::new(StatusCode::UNAUTHORIZED, "InvalidReceiptSignature", message);
+    let (Some(timestamp), Some(signature)) = (header(TIMESTAMP_HEADER), header(SIGNATURE_HEADER))
+    else {
+        return Err(invalid("X-Timestamp and X-Signature headers are required"));
+    };
+
+    let signed_at: u64 = timestamp
+        .parse()
+        .map_err(|_| invalid("X-Timestamp must be unix seconds"))?;
+    if now.abs_diff(signed_at) > RECEIPT_MAX_SKEW_SECS {
+        return Err(invalid("receipt timestamp is outside the accepted window"));
+    }
+    let provided = hex::decode(signature.trim_start_matches("sha256="))
+        .map_err(|_| invalid("X-Signature must be hex encoded"))?;
+    let expected = hmac_sha256(key, &signing_payload(timestamp, body));
+    if constant_time_eq(&provided, &expected) {
+        Ok(())
+    } else {
+        Err(invalid("receipt signature does not match this notice"))
+    }
+}
+
+/// POST /api/patches/:patch_id/delivery-receipt
+///
+/// A webhook receiver confirms it processed the patch notice for its
+/// contract, moving the notice from delivered to confirmed. Requires an API
+/// key of the contract's publisher (`X-API-Key`) and a signature with the
+/// notice's receipt key. Confirming again returns the notice unchanged.
+pub async fn confirm_patch_delivery(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<NotificationRecord>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let req: DeliveryReceiptRequest = serde_json::from_slice(&body).map_err(|err| {
+        ApiError::bad_request("InvalidRequest", format!("Invalid JSON payload: {}", err))
+    })?;
+    let secret = state.patch_receipt_secret.as_deref().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "ReceiptsDisabled",
+            "Delivery receipts are not configured on this registry",
+        )
+    })?;
+
+    let contract_uuid = ContractWriteKey::authorize(&state, &headers, &req.contract_id)
+        .await?
+        .contract_uuid;
+    let (_, contract_id) = fetch_contract_identity(&state, &req.contract_id).await?;
+    let key = receipt_key(secret.as_bytes(), patch_id, &contract_id);
+    verify_receipt_signature(&key, &headers, &body, state.clock.unix_now())?;
+
+    let record: Option<NotificationRecord> = sqlx::query_as(
+        "SELECT * FROM patch_notifications WHERE patch_id = $1 AND contract_id = $2",
+    )
+    .bind(patch_id)
+    .bind(contract_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get patch notification", err))?;
+    let record = ensure_notified(record, patch_id, &contract_id)?;
+    if record.delivery_status == PatchDeliveryStatus::Confirmed {
+        return Ok(Json(record));
+    }
+
+    let confirmed: NotificationRecord = sqlx::query_as(
+        "UPDATE patch_notifications \
+         SET delivery_status = 'confirmed', confirmed_at = COALESCE(confirmed_at, $2) \
+         WHERE id = $1 RETURNING *",
+    )
+    .bind(record.id)
+    .bind(state.clock.now())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("confirm patch delivery", err))?;
+    tracing::info!(
+        patch_id = %patch_id,
+        contract_id = %contract_id,
+        "patch delivery confirmed"
+    );
+    Ok(Json(confirmed))
+}
+
+fn summarize_acknowledgements(
+    patch_id: Uuid,
+    notified: i64,
+    acknowledged: i64,
+) -> PatchAcknowledgementSummary {
+    PatchAcknowledgementSummary {
+        patch_id,
+        notified,
+        acknowledged,
+        acknowledgement_rate: if notified > 0 {
+            acknowledged as f64 / notified as f64
+        } else {
+            0.0
+        },
+    }
+}
+
+/// GET /api/patches/:patch_id/acknowledgements
+///
+/// How many notified contracts have acknowledged the patch; rollouts gated on
+/// acknowledgement poll this before advancing.
+pub async fn get_patch_acknowledgements(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<PatchAcknowledgementSummary>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM security_patches WHERE id = $1)")
+            .bind(patch_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("check security patch", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "PatchNotFound",
+            format!("No security patch found with ID: {}", patch_id),
+        ));
+    }
+
+    let (notified, acknowledged): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(acknowledged_at) FROM patch_notifications WHERE patch_id = $1",
+    )
+    .bind(patch_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count patch acknowledgements", err))?;
+    Ok(Json(summarize_acknowledgements(
+        patch_id,
+        notified,
+        acknowledged,
+    )))
+}
+
+/// Stage a rollout at `rollout_percentage` has reached, using the rollout
+/// engine's stage shares. A full rollout is complete once every targeted
+/// contract has applied the patch.
+pub fn rollout_stage(rollout_percentage: i32, targeted: i64, applied: i64) -> RolloutStage {
+    match rollout_percentage {
+        ..=5 => RolloutStage::Stage1,
+        6..=25 => RolloutStage::Stage2,
+        26..=50 => RolloutStage::Stage3,
+        _ if rollout_percentage < 100 || applied < targeted => RolloutStage::Stage4,
+        _ => RolloutStage::Complete,
+    }
+}
+
+/// Patches with their rollout stage and counts, most severe first and
+/// newest first within a severity
+pub fn publisher_patches(
+    patches: Vec<SecurityPatch>,
+    acknowledgements: &HashMap<Uuid, (i64, i64)>,
+    applied: &HashMap<Uuid, i64>,
+) -> Vec<PublisherPatch> {
+    let mut summaries: Vec<PublisherPatch> = patches
+        .into_iter()
+        .map(|patch| {
+            let (notified, acknowledged) =
+                acknowledgements.get(&patch.id).copied().unwrap_or((0, 0));
+            let applied = applied.get(&patch.id).copied().unwrap_or(0);
+            PublisherPatch {
+                rollout_stage: rollout_stage(patch.rollout_percentage, notified, applied),
+                notified,
+                acknowledged,
+                applied,
+                patch,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.patch
+            .severity
+            .rank()
+            .cmp(&a.patch.severity.rank())
+            .then_with(|| b.patch.created_at.cmp(&a.patch.created_at))
+    });
+    summaries
+}
+
+/// GET /api/publishers/:id/patches
+pub async fn get_publisher_patches(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<PublisherPatch>>> {
+    let publisher_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidPublisherId",
+            format!("Invalid publisher ID format: {}", id),
+        )
+    })?;
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM publishers WHERE id = $1)")
+        .bind(publisher_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("check publisher", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "PublisherNotFound",
+            format!("No publisher found with ID: {}", id),
+        ));
+    }
+
+    let patches: Vec<SecurityPatch> =
+        sqlx::query_as("SELECT * FROM security_patches WHERE publisher_id = $1")
+            .bind(publisher_uuid)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("list publisher patches", err))?;
+    let patch_ids: Vec<Uuid> = patches.iter().map(|p| p.id).collect();
+
+    let acknowledgements: Vec<(Uuid, i64, i64)> = sqlx::query_as(
+        "SELECT patch_id, COUNT(*), COUNT(acknowledged_at) FROM patch_notifications \
+         WHERE patch_id = ANY($1) GROUP BY patch_id",
+    )
+    .bind(&patch_ids)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count patch acknowledgements", err))?;
+    let applied: Vec<(Uuid, i64)> = sqlx::query_as(
+        "SELECT patch_id, COUNT(DISTINCT contract_id) FROM patch_audits \
+         WHERE patch_id = ANY($1) AND NOT failed GROUP BY patch_id",
+    )
+    .bind(&patch_ids)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count applied patches", err))?;
+
+    let acknowledgements = acknowledgements
+        .into_iter()
+        .map(|(patch_id, notified, acknowledged)| (patch_id, (notified, acknowledged)))
+        .collect();
+    let applied = applied.into_iter().collect();
+    Ok(Json(publisher_patches(
+        patches,
+        &acknowledgements,
+        &applied,
+    )))
+}
+
+/// A patch's applications so far, as the rollout engine gates them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RolloutState {
+    pub plan: RolloutPlan,
+    /// Contracts the patch applies to
+    pub population: u64,
+    /// Contracts the patch's `rollout_percentage` admits
+    pub cohort_size: u64,
+    pub applied: u64,
+    pub failed: u64,
+}
+
+impl RolloutState {
+    pub fn new(patch: &SecurityPatch, population: u64, applied: u64, failed: u64) -> Self {
+        let percentage = patch.rollout_percentage.clamp(0, 100) as u64;
+        Self {
+            plan: RolloutPlan {
+                stage: rollout_stage(patch.rollout_percentage, population as i64, applied as i64),
+                ..RolloutPlan::for_patch(patch)
+            },
+            population,
+            cohort_size: (population * percentage).div_ceil(100),
+            applied,
+            failed,
+        }
+    }
+
+    /// Whether a contract not attempted yet may be taken on: refused while
+    /// the failure rate is above the gate for the patch's severity, and once
+    /// the cohort has been attempted while the rollout is partial
+    pub fn admit(&self, engine: &RolloutEngine) -> ApiResult<()> {
+        match engine.evaluate(&self.plan, self.applied, self.failed, self.cohort_size) {
+            GateDecision::Halt {
+                failure_rate,
+                threshold,
+            } => Err(ApiError::conflict(
+                "RolloutHalted",
+                format!(
+                    "{:.1}% of applications failed, above the {:.1}% allowed for a {:?} patch",
+                    failure_rate * 100.0,
+                    threshold * 100.0,
+                    self.plan.severity
+                ),
+            )),
+            GateDecision::Advance(_) if self.cohort_size < self.population => {
+                Err(ApiError::conflict(
+                    "RolloutQuotaReached",
+                    format!(
+                        "{} of {} affected contracts admitted at this rollout percentage \
+                         have been attempted",
+                        self.cohort_size, self.population
+                    ),
+                ))
+            }
+            GateDecision::Advance(_) | GateDecision::Hold => Ok(()),
+        }
+    }
+
+    pub fn progress(&self, engine: &RolloutEngine) -> RolloutProgress {
+        let attempted = self.applied + self.failed;
+        let halted = matches!(
+            engine.evaluate(&self.plan, self.applied, self.failed, self.cohort_size),
+            GateDecision::Halt { .. }
+        );
+        RolloutProgress {
+            patch_id: self.plan.patch_id,
+            stage: self.plan.stage,
+            cohort_size: self.cohort_size,
+            applied: self.applied,
+            failed: self.failed,
+            failure_rate: if attempted == 0 {
+                0.0
+            } else {
+                self.failed as f64 / attempted as f64
+            },
+            approval_gate: if halted {
+                ApprovalGate::Rejected
+            } else {
+                ApprovalGate::NotRequired
+            },
+            progress_percentage: if self.population == 0 {
+                0.0
+            } else {
+                self.applied as f64 * 100.0 / self.population as f64
+            },
+        }
+    }
+}
+
+/// Contracts `patch` applies to
+async fn affected_population(state: &AppState, patch: &SecurityPatch) -> ApiResult<u64> {
+    let filter = PatchTargetFilter::for_patch(patch);
+    let (lower, lower_inclusive) = filter.lower();
+    let (upper, upper_inclusive) = filter.upper();
+    let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {}", PATCH_TARGETS))
+        .bind(filter.by_version)
+        .bind(lower)
+        .bind(lower_inclusive)
+        .bind(upper)
+        .bind(upper_inclusive)
+        .bind(&filter.wasm_hash)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count patch targets", err))?;
+    Ok(count as u64)
+}
+
+/// Successful and failed applications of `patch_id`
+async fn application_counts<'e>(
+    executor: impl sqlx::PgExecutor<'e>,
+    patch_id: Uuid,
+) -> ApiResult<(u64, u64)> {
+    let (applied, failed): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE NOT failed), COUNT(*) FILTER (WHERE failed) \
+         FROM patch_audits WHERE patch_id = $1",
+    )
+    .bind(patch_id)
+    .fetch_one(executor)
+    .await
+    .map_err(|err| db_internal_error("count patch applications", err))?;
+    Ok((applied.max(0) as u64, failed.max(0) as u64))
+}
+
+/// One contract's current release
+async fn load_release(state: &AppState, contract_uuid: Uuid) -> ApiResult<ContractRelease> {
+    let (contract_id, wasm_hash): (String, String) =
+        sqlx::query_as("SELECT contract_id, wasm_hash FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract wasm hash", err))?;
+    let versions = fetch_published_versions(state, contract_uuid).await?;
+    Ok(ContractRelease::resolve(
+        contract_uuid,
+        contract_id,
+        wasm_hash,
+        &versions,
+    ))
+}
+
+/// GET /api/patches/:patch_id
+pub async fn get_security_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<SecurityPatch>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    Ok(Json(fetch_patch(&state, patch_id).await?))
+}
+
+/// POST /api/patches/:patch_id/apply
+///
+/// Records the patch as applied to the contract, or as failed when `error`
+/// is set. Requires an API key of the contract's publisher (`X-API-Key`).
+/// Contracts not attempted yet must be admitted by the rollout engine (see
+/// `RolloutState::admit`); retrying a failed application is always allowed,
+/// and a success replaces the failure.
+pub async fn apply_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+    headers: HeaderMap,
+    payload: Result<Json<ApplyPatchRequest>, JsonRejection>,
+) -> ApiResult<Json<PatchApplication>> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let patch_id = parse_patch_id(&patch_id)?;
+    let key = ContractWriteKey::authorize(&state, &headers, &req.contract_id).await?;
+    let patch = fetch_patch(&state, patch_id).await?;
+
+    let release = load_release(&state, key.contract_uuid).await?;
+    let version = release.version.as_deref().and_then(release_semver);
+    if !patch_applies(&patch, version.as_ref(), &release.wasm_hash) {
+        return Err(ApiError::unprocessable(
+            "PatchNotApplicable",
+            format!(
+                "Patch {} does not apply to the current release of {}",
+                patch_id, release.contract_id
+            ),
+        ));
+    }
+    let population = affected_population(&state, &patch).await?;
+    let error = req
+        .error
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty());
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin transaction", err))?;
+    // Serializes applications of one patch, so two contracts cannot both
+    // take the last place in the cohort
+    sqlx::query("SELECT id FROM security_patches WHERE id = $1 FOR UPDATE")
+        .bind(patch_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("lock security patch", err))?;
+    let previous: Option<bool> = sqlx::query_scalar(
+        "SELECT failed FROM patch_audits WHERE patch_id = $1 AND contract_id = $2",
+    )
+    .bind(patch_id)
+    .bind(key.contract_uuid)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("get patch application", err))?;
+    match previous {
+        Some(false) => {
+            return Err(ApiError::conflict(
+                "PatchAlreadyApplied",
+                format!(
+                    "Patch {} is already applied to {}",
+                    patch_id, release.contract_id
+                ),
+            ))
+        }
+        Some(true) => {}
+        None => {
+            let (applied, failed) = application_counts(&mut *tx, patch_id).await?;
+            RolloutState::new(&patch, population, applied, failed)
+                .admit(&RolloutEngine::from_env())?;
+        }
+    }
+
+    let application: PatchApplication = sqlx::query_as(
+        "INSERT INTO patch_audits (contract_id, patch_id, applied_at, failed, error) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (contract_id, patch_id) DO UPDATE \
+         SET applied_at = EXCLUDED.applied_at, failed = EXCLUDED.failed, error = EXCLUDED.error \
+         RETURNING id, contract_id, patch_id, applied_at, failed, error",
+    )
+    .bind(key.contract_uuid)
+    .bind(patch_id)
+    .bind(state.clock.now())
+    .bind(error.is_some())
+    .bind(&error)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("record patch application", err))?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit patch application", err))?;
+
+    tracing::info!(
+        patch_id = %patch_id,
+        contract_id = %release.contract_id,
+        key_id = %key.key_id,
+        failed = application.failed,
+        "patch application recorded"
+    );
+    Ok(Json(application))
+}
+
+/// GET /api/patches/:patch_id/audits
+pub async fn get_patch_applications(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<PatchApplicationsResponse>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    fetch_patch(&state, patch_id).await?;
+    let items: Vec<PatchApplication> = sqlx::query_as(
+        "SELECT id, contract_id, patch_id, applied_at, failed, error FROM patch_audits \
+         WHERE patch_id = $1 ORDER BY applied_at DESC, id",
+    )
+    .bind(patch_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list patch applications", err))?;
+    let failed = items.iter().filter(|a| a.failed).count() as i64;
+    Ok(Json(PatchApplicationsResponse {
+        total: items.len() as i64 - failed,
+        failed,
+        items,
+    }))
+}
+
+/// GET /api/patches/:patch_id/rollout/simulation
+///
+/// Projects the rest of the patch's rollout over the contracts it currently
+/// applies to, from the stage its `rollout_percentage` has reached, without
+/// applying anything.
+pub async fn simulate_patch_rollout(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<RolloutSimulationResponse>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let patch = fetch_patch(&state, patch_id).await?;
+    let population = affected_population(&state, &patch).await?;
+    let (applied, failed) = application_counts(&state.db, patch_id).await?;
+    let rollout = RolloutState::new(&patch, population, applied, failed);
+    let simulation = RolloutEngine::from_env().simulate(&rollout.plan, population);
+    Ok(Json(simulation_response(patch_id, simulation)))
+}
+
+fn simulation_response(patch_id: Uuid, simulation: RolloutSimulation) -> RolloutSimulationResponse {
+    RolloutSimulationResponse {
+        patch_id,
+        population: simulation.population,
+        stages: simulation
+            .stages
+            .into_iter()
+            .map(|stage| RolloutSimulationStage {
+                stage: stage.stage,
+                cohort_size: stage.cohort_size,
+                cumulative: stage.cumulative,
+                starts_after_secs: stage.starts_after.num_seconds(),
+                dwell_secs: stage.dwell.num_seconds(),
+            })
+            .collect(),
+        gates: simulation
+            .gates
+            .into_iter()
+            .map(|gate| RolloutSimulationGate {
+                after: gate.after,
+                advances_to: gate.advances_to,
+                max_failure_rate: gate.max_failure_rate,
+            })
+            .collect(),
+        estimated_duration_secs: simulation.estimated_duration.num_seconds(),
+    }
+}
+
+/// GET /api/patches/:patch_id/rollout
+pub async fn get_patch_rollout(
+    State(state): State<AppState>,
+    Path(patch_id): Path<String>,
+) -> ApiResult<Json<RolloutProgress>> {
+    let patch_id = parse_patch_id(&patch_id)?;
+    let patch = fetch_patch(&state, patch_id).await?;
+    let population = affected_population(&state, &patch).await?;
+    let (applied, failed) = application_counts(&state.db, patch_id).await?;
+    Ok(Json(
+        RolloutState::new(&patch, population, applied, failed).progress(&RolloutEngine::from_env()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use chrono::{Duration, Utc};
+    use ed25519_dalek::{Signer, SigningKey};
+    use shared::PatchSeverity;
+
+    const CONTRACT_ID: &str = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+
+    fn notification(patch_id: Uuid) -> NotificationRecord {
+        NotificationRecord {
+            id: Uuid::new_v4(),
+            patch_id,
+            contract_id: Uuid::new_v4(),
+            notified_at: Utc::now(),
+            acknowledged_at: None,
+            acknowledged_by: None,
+            ack_signature: None,
+            delivery_status: PatchDeliveryStatus::Delivered,
+            confirmed_at: None,
+            priority: PatchSeverity::High,
+        }
+    }
+
+    #[test]
+    fn notified_owner_with_valid_signature_can_acknowledge() {
+        let patch_id = Uuid::new_v4();
+        let record = ensure_notified(Some(notification(patch_id)), patch_id, CONTRACT_ID).unwrap();
+        assert_eq!(record.patch_id, patch_id);
+
+        let owner = SigningKey::from_bytes(&[7u8; 32]);
+        let public_keys = vec![BASE64.encode(owner.verifying_key().as_bytes())];
+        let message = ack_message(patch_id, CONTRACT_ID);
+        let signature = BASE64.encode(owner.sign(&message).to_bytes());
+        assert!(verify_ack_signature(&public_keys, &message, &signature).is_ok());
+
+        let stranger = SigningKey::from_bytes(&[9u8; 32]);
+        let forged = BASE64.encode(stranger.sign(&message).to_bytes());
+        let err = verify_ack_signature(&public_keys, &message, &forged).unwrap_err();
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    fn signed_receipt(key: &[u8], body: &[u8], timestamp: u64) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature = hex::encode(hmac_sha256(key, &signing_payload(&timestamp, body)));
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, timestamp.parse().unwrap());
+        headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn receiver_can_confirm_its_own_delivery() {
+        const NOW: u64 = 1_700_000_000;
+        let patch_id = Uuid::new_v4();
+        let key = receipt_key(b"registry-secret", patch_id, CONTRACT_ID);
+        let body = serde_json::to_vec(&DeliveryReceiptRequest {
+            contract_id: CONTRACT_ID.to_string(),
+        })
+        .unwrap();
+
+        let headers = signed_receipt(&key, &body, NOW - 5);
+        assert!(verify_receipt_signature(&key, &headers, &body, NOW).is_ok());
+    }
+
+    #[test]
+    fn forged_receipt_is_rejected() {
+        const NOW: u64 = 1_700_000_000;
+        let patch_id = Uuid::new_v4();
+        let key = receipt_key(b"registry-secret", patch_id, CONTRACT_ID);
+        let body = br#"{"contract_id":"CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"}"#;
+
+        // Signed with the key another receiver was given
+        let other_key = receipt_key(b"registry-secret", patch_id, "COTHER");
+        let forged = signed_receipt(&other_key, body, NOW);
+        let err = verify_receipt_signature(&key, &forged, body, NOW).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::UNAUTHORIZED);
+
+        // A captured receipt cannot be replayed later
+        let stale = signed_receipt(&key, body, NOW - RECEIPT_MAX_SKEW_SECS - 1);
+        assert!(verify_receipt_signature(&key, &stale, body, NOW).is_err());
+
+        assert!(verify_receipt_signature(&key, &HeaderMap::new(), body, NOW).is_err());
+    }
+
+    /// A patch for `>=1.0.0, <1.2.0` and a contract at 1.1.0 whose publisher
+    /// holds `owner` as its signing key and `api_key` as its write key
+    async fn notified_fixture(state: &AppState, owner: &SigningKey, api_key: &str) -> Uuid {
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let contract = crate::test_db::insert_contract(&state.db, publisher, "CVULN").await;
+        crate::test_db::insert_version(&state.db, contract, "1.1.0", "published").await;
+        sqlx::query(
+            "INSERT INTO signing_keys (publisher_id, public_key, key_fingerprint) \
+             VALUES ($1, $2, 'owner')",
+        )
+        .bind(publisher)
+        .bind(BASE64.encode(owner.verifying_key().as_bytes()))
+        .execute(&state.db)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO api_keys (publisher_id, name, key_hash) VALUES ($1, 'ci', $2)")
+            .bind(publisher)
+            .bind(crate::api_keys::hash_api_key(api_key))
+            .execute(&state.db)
+            .await
+            .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO security_patches \
+               (target_version, vulnerable_range, severity, new_wasm_hash) \
+             VALUES ('1.0.0..1.2.0', '>=1.0.0, <1.2.0', 'high', $1) RETURNING id",
+        )
+        .bind("ff".repeat(32))
+        .fetch_one(&state.db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn notified_owner_acknowledges_with_key_and_signature() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let owner = SigningKey::from_bytes(&[7u8; 32]);
+        let api_key = crate::api_keys::generate_api_key();
+        let patch_id = notified_fixture(&state, &owner, &api_key).await;
+
+        let acknowledge = |key: Option<&str>, signature: String| {
+            let mut headers = HeaderMap::new();
+            if let Some(key) = key {
+                headers.insert(crate::api_keys::API_KEY_HEADER, key.parse().unwrap());
+            }
+            acknowledge_patch(
+                State(state.clone()),
+                Path(patch_id.to_string()),
+                headers,
+                Ok(Json(AcknowledgePatchRequest {
+                    contract_id: "CVULN".to_string(),
+                    acknowledged_by: "owner".to_string(),
+                    signature,
+                })),
+            )
+        };
+        let status = |result: ApiResult<Json<NotificationRecord>>| match result {
+            Ok(_) => StatusCode::OK,
+            Err(err) => err.into_response().status(),
+        };
+        let signed = BASE64.encode(owner.sign(&ack_message(patch_id, "CVULN")).to_bytes());
+
+        // Not notified yet
+        assert_eq!(
+            status(acknowledge(Some(&api_key), signed.clone()).await),
+            StatusCode::NOT_FOUND
+        );
+
+        let notify = || {
+            notify_patch(
+                State(state.clone()),
+                Path(patch_id.to_string()),
+                Extension(AdminOperator("ops".to_string())),
+            )
+        };
+        let Json(first) = notify().await.unwrap();
+        let notified: Vec<&str> = first
+            .notified
+            .iter()
+            .map(|t| t.contract_id.as_str())
+            .collect();
+        assert_eq!(notified, vec!["CVULN"]);
+        let Json(again) = notify().await.unwrap();
+        assert!(again.notified.is_empty());
+        assert_eq!(again.already_notified, 1);
+
+        assert_eq!(
+            status(acknowledge(None, signed.clone()).await),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(acknowledge(Some(&api_key), String::new()).await),
+            StatusCode::BAD_REQUEST
+        );
+        let stranger = SigningKey::from_bytes(&[9u8; 32]);
+        let forged = BASE64.encode(stranger.sign(&ack_message(patch_id, "CVULN")).to_bytes());
+        assert_eq!(
+            status(acknowledge(Some(&api_key), forged).await),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let Json(record) = acknowledge(Some(&api_key), signed.clone()).await.unwrap();
+        assert_eq!(record.acknowledged_by.as_deref(), Some("owner"));
+        assert_eq!(record.ack_signature.as_deref(), Some(signed.as_str()));
+
+        let Json(summary) =
+            get_patch_acknowledgements(State(state.clone()), Path(patch_id.to_string()))
+                .await
+                .unwrap();
+        assert_eq!((summary.notified, summary.acknowledged), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn receiver_confirms_delivery_with_the_key_notify_issued() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let state = state.with_patch_receipt_secret(Some("registry-secret".to_string()));
+        let owner = SigningKey::from_bytes(&[7u8; 32]);
+        let api_key = crate::api_keys::generate_api_key();
+        let patch_id = notified_fixture(&state, &owner, &api_key).await;
+
+        let Json(notified) = notify_patch(
+            State(state.clone()),
+            Path(patch_id.to_string()),
+            Extension(AdminOperator("ops".to_string())),
+        )
+        .await
+        .unwrap();
+        let issued = notified.notified[0].receipt_key.clone().unwrap();
+        let issued = hex::decode(issued).unwrap();
+
+        let body = serde_json::to_vec(&DeliveryReceiptRequest {
+            contract_id: "CVULN".to_string(),
+        })
+        .unwrap();
+        let confirm = |key: &[u8], api_key: Option<&str>| {
+            let mut headers = signed_receipt(key, &body, state.clock.unix_now());
+            if let Some(api_key) = api_key {
+                headers.insert(crate::api_keys::API_KEY_HEADER, api_key.parse().unwrap());
+            }
+            confirm_patch_delivery(
+                State(state.clone()),
+                Path(patch_id.to_string()),
+                headers,
+                Bytes::from(body.clone()),
+            )
+        };
+
+        let err = confirm(&issued, None).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+        let other = receipt_key(b"registry-secret", patch_id, "COTHER");
+        let err = confirm(&other, Some(&api_key)).await.unwrap_err();
+        assert_eq!(err.error(), "InvalidReceiptSignature");
+
+        let Json(record) = confirm(&issued, Some(&api_key)).await.unwrap();
+        assert_eq!(record.delivery_status, PatchDeliveryStatus::Confirmed);
+        assert!(record.confirmed_at.is_some());
+    }
+
+    #[test]
+    fn unnotified_contract_cannot_acknowledge() {
+        let err = ensure_notified(None, Uuid::new_v4(), CONTRACT_ID).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn acknowledgement_rate_handles_no_notifications() {
+        let patch_id = Uuid::new_v4();
+        assert_eq!(
+            summarize_acknowledgements(patch_id, 4, 3).acknowledgement_rate,
+            0.75
+        );
+        assert_eq!(
+            summarize_acknowledgements(patch_id, 0, 0).acknowledgement_rate,
+            0.0
+        );
+    }
+
+    fn patch(target: &str, severity: PatchSeverity) -> SecurityPatch {
+        SecurityPatch {
+            id: Uuid::new_v4(),
+            target_version: target.to_string(),
+            vulnerable_range: String::new(),
+            severity,
+            new_wasm_hash: "ff".repeat(32),
+            rollout_percentage: 100,
+            description: None,
+            publisher_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn contract_matches_two_of_three_patches() {
+        let by_range = patch("1.0.0..1.2.0", PatchSeverity::Medium);
+        let by_hash = patch("AB12", PatchSeverity::Critical);
+        let other_range = patch("2.0.0..2.1.0", PatchSeverity::High);
+        let applied = HashSet::from([by_range.id]);
+
+        let version = SemVer::parse("1.1.0").unwrap();
+        let matches = applicable_patches(
+            vec![by_range.clone(), other_range, by_hash.clone()],
+            Some(&version),
+            "ab12",
+            &applied,
+        );
+
+        let summary: Vec<_> = matches
+            .iter()
+            .map(|m| (m.patch.id, m.patch.severity, m.status))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (by_hash.id, PatchSeverity::Critical, PatchStatus::Available),
+                (by_range.id, PatchSeverity::Medium, PatchStatus::Applied),
+            ]
+        );
+    }
+
+    #[test]
+    fn equal_severity_lists_newest_first() {
+        let older = SecurityPatch {
+            created_at: Utc::now() - Duration::days(1),
+            ..patch("1.0.0", PatchSeverity::High)
+        };
+        let newer = patch("1.0.0", PatchSeverity::High);
+        let version = SemVer::parse("1.0.0").unwrap();
+
+        let matches = applicable_patches(
+            vec![older.clone(), newer.clone()],
+            Some(&version),
+            "",
+            &HashSet::new(),
+        );
+        let ids: Vec<_> = matches.iter().map(|m| m.patch.id).collect();
+        assert_eq!(ids, vec![newer.id, older.id]);
+    }
+
+    #[test]
+    fn version_targets_need_a_published_version() {
+        let target = PatchTarget::parse("1.0.0..1.2.0").unwrap();
+        assert!(!target.matches(None, "ab12"));
+        assert_eq!(PatchTarget::parse("2.0.0..1.0.0"), None);
+        assert_eq!(PatchTarget::parse("  "), None);
+    }
+
+    fn ranged(range: &str) -> SecurityPatch {
+        SecurityPatch {
+            vulnerable_range: range.to_string(),
+            ..patch("AB12", PatchSeverity::High)
+        }
+    }
+
+    #[test]
+    fn vulnerable_range_decides_version_applicability() {
+        let patch = ranged(">=1.0.0, <1.2.0");
+        let applies = |v: &str| patch_applies(&patch, SemVer::parse(v).as_ref(), "cd34");
+
+        // Inside
+        assert!(applies("1.1.3"));
+        // At the boundaries: the lower bound is inclusive, the upper is not
+        assert!(applies("1.0.0"));
+        assert!(!applies("1.2.0"));
+        // Outside
+        assert!(!applies("0.9.9"));
+        assert!(!applies("2.0.0"));
+        // The hash target still matches whatever the version
+        assert!(patch_applies(
+            &patch,
+            SemVer::parse("2.0.0").as_ref(),
+            "ab12"
+        ));
+    }
+
+    fn release(contract_id: &str, version: Option<&str>, wasm_hash: &str) -> ContractRelease {
+        ContractRelease {
+            id: Uuid::new_v4(),
+            contract_id: contract_id.to_string(),
+            version: version.map(str::to_string),
+            wasm_hash: wasm_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn notify_preview_targets_contracts_the_patch_applies_to() {
+        let mut by_range = patch("AB12", PatchSeverity::High);
+        by_range.vulnerable_range = ">=1.0.0, <1.2.0".to_string();
+        let contracts = vec![
+            release("C-in-range", Some("1.1.0"), "00"),
+            release("C-patched", Some("1.2.0"), "01"),
+            release("C-by-hash", Some("3.0.0"), "ab12"),
+            release("C-unreleased", None, "02"),
+        ];
+
+        let previewed: Vec<&str> = notification_targets(&by_range, &contracts)
+            .iter()
+            .map(|target| target.contract_id.as_str())
+            .collect();
+        let notified: Vec<&str> = contracts
+            .iter()
+            .filter(|contract| {
+                let semver = contract.version.as_deref().and_then(SemVer::parse);
+                !applicable_patches(
+                    vec![by_range.clone()],
+                    semver.as_ref(),
+                    &contract.wasm_hash,
+                    &HashSet::new(),
+                )
+                .is_empty()
+            })
+            .map(|contract| contract.contract_id.as_str())
+            .collect();
+        assert_eq!(previewed, vec!["C-in-range", "C-by-hash"]);
+        assert_eq!(previewed, notified);
+
+        let notice = render_patch_notice(&by_range, &contracts[0]);
+        assert_eq!(notice.patch_id, by_range.id);
+        assert_eq!(notice.contract_id, "C-in-range");
+        assert_eq!(notice.current_version.as_deref(), Some("1.1.0"));
+        assert_eq!(notice.new_wasm_hash, by_range.new_wasm_hash);
+    }
+
+    #[test]
+    fn severity_preferences_suppress_notices_below_the_threshold() {
+        let contracts = vec![
+            release("C-default", Some("1.0.0"), "ab12"),
+            release("C-high-only", Some("1.0.0"), "ab12"),
+            release("C-low-ok", Some("1.0.0"), "ab12"),
+        ];
+        let thresholds = HashMap::from([
+            (contracts[1].id, PatchSeverity::High),
+            (contracts[2].id, PatchSeverity::Low),
+        ]);
+        let medium = patch("ab12", PatchSeverity::Medium);
+
+        let (notified, suppressed) = apply_severity_preferences(
+            medium.severity,
+            notification_targets(&medium, &contracts),
+            &thresholds,
+        );
+        let notified: Vec<&str> = notified.iter().map(|t| t.contract_id.as_str()).collect();
+        assert_eq!(notified, vec!["C-default", "C-low-ok"]);
+        assert_eq!(suppressed, 1);
+
+        let (notified, suppressed) = apply_severity_preferences(
+            PatchSeverity::Critical,
+            contracts.iter().collect(),
+            &thresholds,
+        );
+        assert_eq!((notified.len(), suppressed), (3, 0));
+    }
+
+    #[test]
+    fn impact_includes_downstream_dependents_of_affected_contracts() {
+        let patch = patch("ab12", PatchSeverity::Critical);
+        let contracts = vec![
+            release("C-vulnerable", Some("1.0.0"), "ab12"),
+            release("C-router", Some("1.0.0"), "01"),
+            release("C-app", Some("1.0.0"), "02"),
+            release("C-unrelated", Some("1.0.0"), "03"),
+        ];
+        let [vulnerable, router, app, unrelated] = [0, 1, 2, 3].map(|i| contracts[i].id);
+        let edge = |source, target| shared::GraphEdge {
+            source,
+            target,
+            dependency_type: "calls".to_string(),
+        };
+        // app -> router -> vulnerable; app also depends on unrelated, which
+        // is upstream of it and so outside the blast radius
+        let edges = vec![
+            edge(router, vulnerable),
+            edge(app, router),
+            edge(app, unrelated),
+        ];
+
+        let roots: Vec<Uuid> = notification_targets(&patch, &contracts)
+            .iter()
+            .map(|target| target.id)
+            .collect();
+        assert_eq!(roots, vec![vulnerable]);
+
+        let walk = dependency::bounded_dependents(&roots, MAX_IMPACT_DEPTH, 100, &edges);
+        let names: HashMap<Uuid, (String, String)> = contracts
+            .iter()
+            .map(|c| (c.id, (c.contract_id.clone(), c.contract_id.clone())))
+            .collect();
+        let tree = impact_tree(&roots, &walk, &names);
+
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].contract_id, "C-vulnerable");
+        assert_eq!(tree[0].depth, 0);
+        let router_node = &tree[0].dependents[0];
+        assert_eq!(
+            (router_node.contract_id.as_str(), router_node.depth),
+            ("C-router", 1)
+        );
+        let app_node = &router_node.dependents[0];
+        assert_eq!(
+            (app_node.contract_id.as_str(), app_node.depth),
+            ("C-app", 2)
+        );
+        assert!(app_node.dependents.is_empty());
+        assert!(!walk.reached.iter().any(|r| r.id == unrelated));
+    }
+
+    #[test]
+    fn patches_without_a_range_use_their_version_target() {
+        let legacy = patch("1.0.0..1.2.0", PatchSeverity::Low);
+        assert!(patch_applies(&legacy, SemVer::parse("1.2.0").as_ref(), ""));
+        assert!(!patch_applies(&legacy, SemVer::parse("1.2.1").as_ref(), ""));
+    }
+
+    #[test]
+    fn publisher_patches_report_their_rollout_stage() {
+        let publisher = Some(Uuid::new_v4());
+        let at = |pct: i32, severity: PatchSeverity, age_days: i64| SecurityPatch {
+            rollout_percentage: pct,
+            publisher_id: publisher,
+            created_at: Utc::now() - Duration::days(age_days),
+            ..patch("1.0.0", severity)
+        };
+        let canary = at(5, PatchSeverity::Medium, 1);
+        let halfway = at(50, PatchSeverity::Critical, 3);
+        let finishing = at(100, PatchSeverity::Critical, 1);
+        let done = at(100, PatchSeverity::Low, 0);
+
+        let acknowledgements = HashMap::from([
+            (halfway.id, (10, 4)),
+            (finishing.id, (8, 8)),
+            (done.id, (3, 1)),
+        ]);
+        let applied = HashMap::from([(halfway.id, 5), (finishing.id, 6), (done.id, 3)]);
+        let summaries = publisher_patches(
+            vec![
+                done.clone(),
+                canary.clone(),
+                halfway.clone(),
+                finishing.clone(),
+            ],
+            &acknowledgements,
+            &applied,
+        );
+
+        let rows: Vec<_> = summaries
+            .iter()
+            .map(|s| (s.patch.id, s.rollout_stage, s.acknowledged, s.applied))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                // Critical first, newest first within it
+                (finishing.id, RolloutStage::Stage4, 8, 6),
+                (halfway.id, RolloutStage::Stage3, 4, 5),
+                (canary.id, RolloutStage::Stage1, 0, 0),
+                (done.id, RolloutStage::Complete, 1, 3),
+            ]
+        );
+        assert_eq!(summaries[1].notified, 10);
+        assert_eq!(rollout_stage(25, 0, 0), RolloutStage::Stage2);
+    }
+
+    #[test]
+    fn escalation_raises_severity_and_reorders_applicable_patches() {
+        assert!(check_escalation(PatchSeverity::Medium, PatchSeverity::Critical).is_ok());
+        assert!(check_escalation(PatchSeverity::Low, PatchSeverity::High).is_ok());
+
+        let high = patch("AB12", PatchSeverity::High);
+        let mut escalated = patch("1.0.0..1.2.0", PatchSeverity::Medium);
+        escalated.severity = PatchSeverity::Critical;
+
+        let version = SemVer::parse("1.1.0").unwrap();
+        let order: Vec<Uuid> = applicable_patches(
+            vec![high.clone(), escalated.clone()],
+            Some(&version),
+            "ab12",
+            &HashSet::new(),
+        )
+        .iter()
+        .map(|m| m.patch.id)
+        .collect();
+        assert_eq!(order, vec![escalated.id, high.id]);
+    }
+
+    #[test]
+    fn escalation_refuses_downgrades() {
+        let status = |from, to| {
+            check_escalation(from, to)
+                .unwrap_err()
+                .into_response()
+                .status()
+        };
+        assert_eq!(
+            status(PatchSeverity::Critical, PatchSeverity::Medium),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert!(check_escalation(PatchSeverity::High, PatchSeverity::High).is_ok());
+    }
+
+    #[tokio::test]
+    async fn escalation_reprioritizes_pending_notices_and_ignores_unchanged_severity() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let owner = SigningKey::from_bytes(&[7u8; 32]);
+        let api_key = crate::api_keys::generate_api_key();
+        let patch_id = notified_fixture(&state, &owner, &api_key).await;
+        notify_patch(
+            State(state.clone()),
+            Path(patch_id.to_string()),
+            Extension(AdminOperator("ops".to_string())),
+        )
+        .await
+        .unwrap();
+        let priority = || {
+            sqlx::query_scalar::<_, PatchSeverity>(
+                "SELECT priority FROM patch_notifications WHERE patch_id = $1",
+            )
+            .bind(patch_id)
+            .fetch_one(&state.db)
+        };
+        assert_eq!(priority().await.unwrap(), PatchSeverity::High);
+
+        let escalate = |severity| {
+            escalate_patch_severity(
+                State(state.clone()),
+                Path(patch_id.to_string()),
+                Extension(AdminOperator("ops".to_string())),
+                Ok(Json(EscalatePatchRequest {
+                    severity,
+                    reason: None,
+                })),
+            )
+        };
+        let Json(escalated) = escalate(PatchSeverity::Critical).await.unwrap();
+        assert_eq!(escalated.previous_severity, PatchSeverity::High);
+        assert!(escalated.audit.is_some());
+        assert_eq!(escalated.pending_notifications, 1);
+        assert_eq!(priority().await.unwrap(), PatchSeverity::Critical);
+
+        let Json(unchanged) = escalate(PatchSeverity::Critical).await.unwrap();
+        assert_eq!(unchanged.patch.severity, PatchSeverity::Critical);
+        assert!(unchanged.audit.is_none());
+        assert_eq!(unchanged.pending_notifications, 1);
+        let audits: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM security_patch_audit_log WHERE patch_id = $1")
+                .bind(patch_id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(audits, 1);
+    }
+
+    #[test]
+    fn critical_rollout_halts_where_a_low_one_would_continue() {
+        let engine = RolloutEngine::default();
+        // 5% failed: inside the Low gate, far outside the Critical one
+        let low = RolloutState::new(&patch("AB12", PatchSeverity::Low), 100, 95, 5);
+        assert!(low.admit(&engine).is_ok());
+        assert_eq!(
+            low.progress(&engine).approval_gate,
+            ApprovalGate::NotRequired
+        );
+
+        let critical = RolloutState::new(&patch("AB12", PatchSeverity::Critical), 100, 95, 5);
+        let err = critical.admit(&engine).unwrap_err();
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+        assert_eq!(err.error(), "RolloutHalted");
+        let progress = critical.progress(&engine);
+        assert_eq!(progress.approval_gate, ApprovalGate::Rejected);
+        assert_eq!(progress.stage, RolloutStage::Stage4);
+        assert!((progress.failure_rate - 0.05).abs() < f64::EPSILON);
+        assert!((progress.progress_percentage - 95.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn partial_rollout_admits_only_its_cohort() {
+        let engine = RolloutEngine::default();
+        let quarter = SecurityPatch {
+            rollout_percentage: 25,
+            ..patch("AB12", PatchSeverity::Medium)
+        };
+
+        let filling = RolloutState::new(&quarter, 8, 1, 0);
+        assert_eq!(filling.cohort_size, 2);
+        assert_eq!(filling.plan.stage, RolloutStage::Stage2);
+        assert!(filling.admit(&engine).is_ok());
+
+        let full = RolloutState::new(&quarter, 8, 2, 0);
+        assert_eq!(
+            full.admit(&engine).unwrap_err().error(),
+            "RolloutQuotaReached"
+        );
+
+        // A complete rollout keeps admitting contracts that became affected
+        let complete = RolloutState::new(&patch("AB12", PatchSeverity::Medium), 2, 2, 0);
+        assert_eq!(complete.plan.stage, RolloutStage::Complete);
+        assert!(complete.admit(&engine).is_ok());
+    }
+
+    #[test]
+    fn simulation_reports_the_remaining_stages_from_the_current_one() {
+        let quarter = SecurityPatch {
+            rollout_percentage: 25,
+            ..patch("AB12", PatchSeverity::Critical)
+        };
+        let rollout = RolloutState::new(&quarter, 200, 10, 0);
+        let simulation = RolloutEngine::default().simulate(&rollout.plan, 200);
+        let response = simulation_response(quarter.id, simulation);
+
+        let stages: Vec<_> = response.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                RolloutStage::Stage2,
+                RolloutStage::Stage3,
+                RolloutStage::Stage4
+            ]
+        );
+        // Stage 1 reached its 10 contracts before the simulation starts
+        let cohorts: u64 = response.stages.iter().map(|s| s.cohort_size).sum();
+        assert_eq!(cohorts, 190);
+        assert_eq!(response.stages[1].starts_after_secs, 24 * 3600);
+        assert_eq!(response.estimated_duration_secs, 120 * 3600);
+        assert!(response.gates.iter().all(|g| g.max_failure_rate == 0.01));
+    }
+
+    #[tokio::test]
+    async fn publisher_applies_patch_through_the_rollout_gate() {
+        use axum::{
+            body::Body,
+            http::Request,
+            routing::{get, post},
+            Router,
+        };
+        use tower::ServiceExt;
+
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let owner = SigningKey::from_bytes(&[7u8; 32]);
+        let api_key = crate::api_keys::generate_api_key();
+        let patch_id = notified_fixture(&state, &owner, &api_key).await;
+        let publisher: Uuid =
+            sqlx::query_scalar("SELECT publisher_id FROM contracts WHERE contract_id = 'CVULN'")
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        let second = crate::test_db::insert_contract(&state.db, publisher, "CVULN2").await;
+        crate::test_db::insert_version(&state.db, second, "1.1.0", "published").await;
+
+        let app = Router::new()
+            .route("/api/patches/:patch_id", get(get_security_patch))
+            .route("/api/patches/:patch_id/apply", post(apply_patch))
+            .route("/api/patches/:patch_id/audits", get(get_patch_applications))
+            .route("/api/patches/:patch_id/rollout", get(get_patch_rollout))
+            .with_state(state.clone());
+        let apply = |contract_id: &str, key: Option<&str>, error: Option<&str>| {
+            let mut request = Request::post(format!("/api/patches/{}/apply", patch_id))
+                .header("content-type", "application/json");
+            if let Some(key) = key {
+                request = request.header(crate::api_keys::API_KEY_HEADER, key);
+            }
+            let body = serde_json::json!({ "contract_id": contract_id, "error": error });
+            request.body(Body::from(body.to_string())).unwrap()
+        };
+        let read = |path: &str| {
+            Request::get(format!("/api/patches/{}{}", patch_id, path))
+                .body(Body::empty())
+                .unwrap()
+        };
+        async fn json<T: serde::de::DeserializeOwned>(response: axum::response::Response) -> T {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice(&bytes).unwrap()
+        }
+
+        let unauthenticated = app
+            .clone()
+            .oneshot(apply("CVULN", None, None))
+            .await
+            .unwrap();
+        assert_eq!(unauthenticated.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(apply("CVULN", Some(&api_key), Some("out of gas")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let failure: PatchApplication = json(response).await;
+        assert!(failure.failed);
+        assert_eq!(failure.error.as_deref(), Some("out of gas"));
+
+        // One failure in one attempt is over the High gate
+        let progress: RolloutProgress =
+            json(app.clone().oneshot(read("/rollout")).await.unwrap()).await;
+        assert_eq!((progress.applied, progress.failed), (0, 1));
+        assert_eq!(progress.approval_gate, ApprovalGate::Rejected);
+        let halted = app
+            .clone()
+            .oneshot(apply("CVULN2", Some(&api_key), None))
+            .await
+            .unwrap();
+        assert_eq!(halted.status(), StatusCode::CONFLICT);
+
+        // The retry succeeds and reopens the gate
+        let retried = app
+            .clone()
+            .oneshot(apply("CVULN", Some(&api_key), None))
+            .await
+            .unwrap();
+        assert_eq!(retried.status(), StatusCode::OK);
+        let resumed = app
+            .clone()
+            .oneshot(apply("CVULN2", Some(&api_key), None))
+            .await
+            .unwrap();
+        assert_eq!(resumed.status(), StatusCode::OK);
+        let again = app
+            .clone()
+            .oneshot(apply("CVULN", Some(&api_key), None))
+            .await
+            .unwrap();
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+
+        let audits: PatchApplicationsResponse =
+            json(app.clone().oneshot(read("/audits")).await.unwrap()).await;
+        assert_eq!((audits.total, audits.failed, audits.items.len()), (2, 0, 2));
+        let progress: RolloutProgress =
+            json(app.clone().oneshot(read("/rollout")).await.unwrap()).await;
+        assert_eq!(progress.stage, RolloutStage::Complete);
+        assert_eq!(progress.approval_gate, ApprovalGate::NotRequired);
+        let patch: SecurityPatch = json(app.oneshot(read("")).await.unwrap()).await;
+        assert_eq!(patch.id, patch_id);
+    }
+
+    #[tokio::test]
+    async fn patch_targets_selected_in_sql_match_patch_applies() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let pool = &state.db;
+        let publisher = crate::test_db::insert_publisher(pool).await;
+        // 1.10.0 outranks 1.9.0 only by semver, not as text
+        let semver = crate::test_db::insert_contract(pool, publisher, "CSEMVER").await;
+        crate::test_db::insert_version(pool, semver, "1.9.0", "published").await;
+        crate::test_db::insert_version(pool, semver, "1.10.0", "published").await;
+        let prerelease = crate::test_db::insert_contract(pool, publisher, "CPRE").await;
+        crate::test_db::insert_version(pool, prerelease, "1.2.0", "published").await;
+        crate::test_db::insert_version(pool, prerelease, "2.0.0-rc.1", "published").await;
+        // Build metadata is ignored, drafts are not releases
+        let build = crate::test_db::insert_contract(pool, publisher, "CBUILD").await;
+        crate::test_db::insert_version(pool, build, "1.1.0+ci.7", "published").await;
+        crate::test_db::insert_version(pool, build, "3.0.0", "draft").await;
+        let unversioned = crate::test_db::insert_contract(pool, publisher, "CUNVERSIONED").await;
+        sqlx::query("UPDATE contracts SET wasm_hash = 'ABCDEF0123' WHERE id = $1")
+            .bind(unversioned)
+            .execute(pool)
+            .await
+            .unwrap();
+        let unparsed = crate::test_db::insert_contract(pool, publisher, "CUNPARSED").await;
+        crate::test_db::insert_version(pool, unparsed, "v1", "published").await;
+
+        let contracts: Vec<(Uuid, String, String)> =
+            sqlx::query_as("SELECT id, contract_id, wasm_hash FROM contracts")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        let mut releases = Vec::new();
+        for (id, contract_id, wasm_hash) in contracts {
+            let versions: Vec<ContractVersion> = sqlx::query_as(
+                "SELECT * FROM contract_versions WHERE contract_id = $1 AND status = 'published'",
+            )
+            .bind(id)
+            .fetch_all(pool)
+            .await
+            .unwrap();
+            releases.push(ContractRelease::resolve(
+                id,
+                contract_id,
+                wasm_hash,
+                &versions,
+            ));
+        }
+        releases.sort_by(|a, b| a.contract_id.cmp(&b.contract_id));
+
+        let cases = [
+            (
+                "1.2.0..1.10.0",
+                ">=1.2.0, <=1.10.0",
+                &["CPRE", "CSEMVER"][..],
+            ),
+            ("abcdef0123", ">1.2.0", &["CSEMVER", "CUNVERSIONED"]),
+            ("1.0.0..1.9.9", "", &["CBUILD", "CPRE"]),
+            ("abcdef0123", "not a range", &["CUNVERSIONED"]),
+            ("", "", &[]),
+            ("1.0.0", "*", &["CBUILD", "CPRE", "CSEMVER"]),
+        ];
+        for (target_version, vulnerable_range, expected) in cases {
+            let patch_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO security_patches \
+                   (target_version, vulnerable_range, severity, new_wasm_hash) \
+                 VALUES ($1, $2, 'high', $3) RETURNING id",
+            )
+            .bind(target_version)
+            .bind(vulnerable_range)
+            .bind("ff".repeat(32))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            let patch = fetch_patch(&state, patch_id).await.unwrap();
+
+            let selected = load_targets(&state, &patch).await.unwrap();
+            let in_rust = notification_targets(&patch, &releases);
+            let ids: Vec<&str> = selected.iter().map(|t| t.contract_id.as_str()).collect();
+            assert_eq!(ids, expected, "{} / {}", target_version, vulnerable_range);
+            assert_eq!(
+                ids,
+                in_rust
+                    .iter()
+                    .map(|t| t.contract_id.as_str())
+                    .collect::<Vec<_>>()
+            );
+            for (sql, rust) in selected.iter().zip(in_rust) {
+                assert_eq!(
+                    (&sql.version, &sql.wasm_hash),
+                    (&rust.version, &rust.wasm_hash)
+                );
+            }
+            assert_eq!(
+                affected_population(&state, &patch).await.unwrap(),
+                expected.len() as u64
+            );
+        }
+    }
+}
@@ -11,47 +11,170 @@ use axum::{
     extract::{connect_info::ConnectInfo, MatchedPath, State},
     http::{
         header::{AUTHORIZATION, RETRY_AFTER},
-        HeaderName, HeaderValue, Method, Request, StatusCode,
+        HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode,
     },
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
+use sqlx::PgPool;
+
+use crate::auth::AuthManager;
 
 const DEFAULT_READ_LIMIT_PER_MINUTE: u32 = 100;
 const DEFAULT_WRITE_LIMIT_PER_MINUTE: u32 = 20;
+const DEFAULT_PARTNER_WRITE_LIMIT_PER_MINUTE: u32 = 200;
 const DEFAULT_AUTH_LIMIT_PER_MINUTE: u32 = 1_000;
 const DEFAULT_HEALTH_LIMIT_PER_MINUTE: u32 = 10_000;
 const DEFAULT_WINDOW_SECONDS: u64 = 60;
+const PUBLISHER_TIER_CACHE_TTL: Duration = Duration::from_secs(300);
 const ENDPOINT_LIMIT_ENV_PREFIX: &str = "RATE_LIMIT_ENDPOINT_";
 
 const HEADER_RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
 const HEADER_RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
 const HEADER_RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
 
+/// Rate-limit tier stored on `publishers.rate_limit_tier`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublisherTier {
+    Default,
+    VerifiedPartner,
+}
+
+impl PublisherTier {
+    fn from_db(raw: &str) -> Self {
+        match raw {
+            "verified_partner" => PublisherTier::VerifiedPartner,
+            _ => PublisherTier::Default,
+        }
+    }
+}
+
+/// Publisher resolved from a valid bearer token on the request
+struct PublisherIdentity {
+    address: String,
+    tier: PublisherTier,
+}
+
+struct CachedTier {
+    tier: PublisherTier,
+    fetched_at: Instant,
+}
+
 #[derive(Clone)]
 pub struct RateLimitState {
     config: Arc<RateLimitConfig>,
     buckets: Arc<Mutex<HashMap<BucketKey, BucketState>>>,
+    /// Validates publisher tokens; `None` disables publisher tiers
+    auth: Option<Arc<AuthManager>>,
+    db: Option<PgPool>,
+    tiers: Arc<Mutex<HashMap<String, CachedTier>>>,
 }
 
 impl RateLimitState {
     pub fn from_env() -> Self {
-        Self::new(RateLimitConfig::from_env())
+        // A tier is only as trustworthy as the key that signed the token, and
+        // `AuthManager::from_env` falls back to a well-known dev secret
+        let auth = match env::var("JWT_SECRET") {
+            Ok(secret) if !secret.trim().is_empty() => Some(AuthManager::new(secret)),
+            _ => {
+                tracing::warn!("JWT_SECRET is not set; publisher rate-limit tiers are disabled");
+                None
+            }
+        };
+        Self::new(RateLimitConfig::from_env(), auth)
     }
 
-    fn new(config: RateLimitConfig) -> Self {
+    fn new(config: RateLimitConfig, auth: Option<AuthManager>) -> Self {
         Self {
             config: Arc::new(config),
             buckets: Arc::new(Mutex::new(HashMap::new())),
+            auth: auth.map(Arc::new),
+            db: None,
+            tiers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    fn check_request<B>(&self, request: &Request<B>) -> RateLimitDecision {
-        let (limit, endpoint_key) = self.select_limit(request);
-        let ip = extract_client_ip(request);
-        let key = BucketKey { ip, endpoint_key };
+    /// Resolve publisher tiers from the `publishers` table
+    pub fn with_db(mut self, db: PgPool) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Identify the publisher behind a valid bearer token, if any
+    async fn identify_publisher(&self, headers: &HeaderMap) -> Option<PublisherIdentity> {
+        let auth = self.auth.as_ref()?;
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)?;
+        let address = auth.validate_jwt(token).ok()?.sub;
+        let tier = self.publisher_tier(&address).await;
+        Some(PublisherIdentity { address, tier })
+    }
+
+    async fn publisher_tier(&self, address: &str) -> PublisherTier {
+        {
+            let tiers = self.tiers.lock().expect("tier cache mutex poisoned");
+            if let Some(cached) = tiers.get(address) {
+                if cached.fetched_at.elapsed() < PUBLISHER_TIER_CACHE_TTL {
+                    return cached.tier;
+                }
+            }
+        }
+
+        let Some(db) = &self.db else {
+            return PublisherTier::Default;
+        };
+
+        let tier = match sqlx::query_scalar::<_, String>(
+            "SELECT rate_limit_tier FROM publishers WHERE stellar_address = $1",
+        )
+        .bind(address)
+        .fetch_optional(db)
+        .await
+        {
+            Ok(raw) => raw
+                .as_deref()
+                .map(PublisherTier::from_db)
+                .unwrap_or(PublisherTier::Default),
+            Err(err) => {
+                // Do not cache failures; the next request retries the lookup
+                tracing::warn!(error = ?err, "failed to look up publisher rate-limit tier");
+                return PublisherTier::Default;
+            }
+        };
+
+        self.tiers
+            .lock()
+            .expect("tier cache mutex poisoned")
+            .insert(
+                address.to_string(),
+                CachedTier {
+                    tier,
+                    fetched_at: Instant::now(),
+                },
+            );
+        tier
+    }
+
+    fn check_request<B>(
+        &self,
+        request: &Request<B>,
+        publisher: Option<&PublisherIdentity>,
+    ) -> RateLimitDecision {
+        let (limit, endpoint_key) = self.select_limit(request, publisher);
+        // Identified publishers get their own budget regardless of source IP
+        let client = match publisher {
+            Some(identity) => format!("publisher:{}", identity.address),
+            None => extract_client_ip(request),
+        };
+        let key = BucketKey {
+            client,
+            endpoint_key,
+        };
         let now = Instant::now();
 
         let mut buckets = self.buckets.lock().expect("rate limiter mutex poisoned");
@@ -92,13 +215,16 @@ impl RateLimitState {
         }
     }
 
-    fn select_limit<B>(&self, request: &Request<B>) -> (u32, String) {
+    /// Authenticated requests get `auth_limit` for reads and writes, and
+    /// verified partners at least `partner_write_limit` for writes. Without
+    /// publisher tiers any `Authorization` header counts as authenticated.
+    fn select_limit<B>(
+        &self,
+        request: &Request<B>,
+        publisher: Option<&PublisherIdentity>,
+    ) -> (u32, String) {
         let method = request.method();
-        let matched_path = request
-            .extensions()
-            .get::<MatchedPath>()
-            .map(|p| p.as_str())
-            .unwrap_or_else(|| request.uri().path());
+        let matched_path = matched_path(request).unwrap_or_else(|| request.uri().path());
         let endpoint_key = endpoint_key(method, matched_path);
 
         if let Some(limit) = self.config.endpoint_limits.get(&endpoint_key) {
@@ -109,11 +235,23 @@ impl RateLimitState {
             return (self.config.health_limit, endpoint_key);
         }
 
-        if request.headers().contains_key(AUTHORIZATION) {
+        let is_write = is_write_method(method);
+        if is_write
+            && publisher.map(|identity| identity.tier) == Some(PublisherTier::VerifiedPartner)
+        {
+            let limit = self.config.partner_write_limit.max(self.config.auth_limit);
+            return (limit, endpoint_key);
+        }
+
+        let authenticated = match self.auth {
+            Some(_) => publisher.is_some(),
+            None => request.headers().contains_key(AUTHORIZATION),
+        };
+        if authenticated {
             return (self.config.auth_limit, endpoint_key);
         }
 
-        if is_write_method(method) {
+        if is_write {
             return (self.config.write_limit, endpoint_key);
         }
 
@@ -124,6 +262,7 @@ impl RateLimitState {
 struct RateLimitConfig {
     read_limit: u32,
     write_limit: u32,
+    partner_write_limit: u32,
     auth_limit: u32,
     health_limit: u32,
     window: Duration,
@@ -137,6 +276,10 @@ impl RateLimitConfig {
             "RATE_LIMIT_WRITE_PER_MINUTE",
             DEFAULT_WRITE_LIMIT_PER_MINUTE,
         );
+        let partner_write_limit = env_u32(
+            "RATE_LIMIT_PARTNER_WRITE_PER_MINUTE",
+            DEFAULT_PARTNER_WRITE_LIMIT_PER_MINUTE,
+        );
         let auth_limit = env_u32("RATE_LIMIT_AUTH_PER_MINUTE", DEFAULT_AUTH_LIMIT_PER_MINUTE);
         let health_limit = env_u32(
             "RATE_LIMIT_HEALTH_PER_MINUTE",
@@ -165,6 +308,7 @@ impl RateLimitConfig {
         tracing::info!(
             read_limit,
             write_limit,
+            partner_write_limit,
             auth_limit,
             health_limit,
             window_seconds,
@@ -175,6 +319,7 @@ impl RateLimitConfig {
         Self {
             read_limit,
             write_limit,
+            partner_write_limit,
             auth_limit,
             health_limit,
             window: Duration::from_secs(window_seconds),
@@ -187,6 +332,7 @@ impl RateLimitConfig {
         Self {
             read_limit,
             write_limit,
+            partner_write_limit: write_limit * 10,
             auth_limit: DEFAULT_AUTH_LIMIT_PER_MINUTE,
             health_limit,
             window,
@@ -197,7 +343,7 @@ impl RateLimitConfig {
 
 #[derive(Hash, Eq, PartialEq)]
 struct BucketKey {
-    ip: String,
+    client: String,
     endpoint_key: String,
 }
 
@@ -218,7 +364,8 @@ pub async fn rate_limit_middleware(
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    let decision = rate_limiter.check_request(&request);
+    let publisher = rate_limiter.identify_publisher(request.headers()).await;
+    let decision = rate_limiter.check_request(&request, publisher.as_ref());
 
     if !decision.allowed {
         let mut response = (
@@ -265,29 +412,31 @@ fn attach_rate_limit_headers(response: &mut Response, decision: &RateLimitDecisi
 }
 
 fn extract_client_ip<B>(request: &Request<B>) -> String {
-    if let Some(ip) = request
-        .headers()
+    if let Some(ip) = client_ip_from_headers(request.headers()) {
+        return ip.to_string();
+    }
+
+    if let Some(connect_info) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        return connect_info.0.ip().to_string();
+    }
+
+    "unknown".to_string()
+}
+
+/// Resolve the client IP from proxy headers (`x-forwarded-for`, then `x-real-ip`).
+pub(crate) fn client_ip_from_headers(headers: &HeaderMap) -> Option<IpAddr> {
+    if let Some(ip) = headers
         .get("x-forwarded-for")
         .and_then(|value| value.to_str().ok())
         .and_then(parse_x_forwarded_for)
     {
-        return ip.to_string();
+        return Some(ip);
     }
 
-    if let Some(ip) = request
-        .headers()
+    headers
         .get("x-real-ip")
         .and_then(|value| value.to_str().ok())
         .and_then(parse_ip_addr)
-    {
-        return ip.to_string();
-    }
-
-    if let Some(connect_info) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
-        return connect_info.0.ip().to_string();
-    }
-
-    "unknown".to_string()
 }
 
 fn parse_x_forwarded_for(raw: &str) -> Option<IpAddr> {
@@ -307,6 +456,15 @@ fn is_write_method(method: &Method) -> bool {
     )
 }
 
+/// Route template the request matched (e.g. `/api/contracts/:id`); `None`
+/// for requests that fell through to the fallback
+pub(crate) fn matched_path<B>(request: &Request<B>) -> Option<&str> {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str())
+}
+
 fn endpoint_key(method: &Method, path: &str) -> String {
     let normalized_path = path
         .chars()
@@ -384,13 +542,14 @@ mod tests {
         health_limit: u32,
         window: Duration,
     ) -> Router<()> {
-        let limiter = RateLimitState::new(RateLimitConfig::for_tests(
-            read_limit,
-            write_limit,
-            health_limit,
-            window,
-        ));
+        let limiter = RateLimitState::new(
+            RateLimitConfig::for_tests(read_limit, write_limit, health_limit, window),
+            Some(AuthManager::new("test-secret".to_string())),
+        );
+        test_router(limiter)
+    }
 
+    fn test_router(limiter: RateLimitState) -> Router<()> {
         Router::new()
             .route("/health", get(|| async { "ok" }))
             .route("/read", get(|| async { "read" }))
@@ -604,4 +763,106 @@ mod tests {
 
         assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
     }
+
+    fn issue_token(auth: &mut AuthManager, seed: u8) -> (String, String) {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let sk = SigningKey::from_bytes(&[seed; 32]);
+        let address = hex::encode(sk.verifying_key().as_bytes());
+        let nonce = auth.create_challenge(&address);
+        let sig = hex::encode(sk.sign(nonce.as_bytes()).to_bytes());
+        let token = auth
+            .verify_and_issue_jwt(&address, &address, &sig)
+            .expect("jwt must be issued");
+        (address, token)
+    }
+
+    async fn writes_allowed(app: &Router<()>, token: &str) -> u32 {
+        let mut allowed = 0;
+        for _ in 0..50 {
+            let response = call(
+                app,
+                Request::builder()
+                    .uri("/write")
+                    .method("POST")
+                    .header("x-forwarded-for", "203.0.113.77")
+                    .header(AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                break;
+            }
+            allowed += 1;
+        }
+        allowed
+    }
+
+    #[tokio::test]
+    async fn partner_tier_gets_higher_write_budget() {
+        let mut auth = AuthManager::new("test-secret".to_string());
+        let (partner, partner_token) = issue_token(&mut auth, 3);
+        let (_, default_token) = issue_token(&mut auth, 4);
+
+        let app = test_router(tiered_limiter(Some(auth), &partner));
+
+        // Same source IP, but each publisher is limited on its own key
+        assert_eq!(writes_allowed(&app, &default_token).await, 5);
+        assert_eq!(writes_allowed(&app, &partner_token).await, 20);
+    }
+
+    /// Anonymous writes 2, authenticated 5, partner writes 20
+    fn tiered_limiter(auth: Option<AuthManager>, partner: &str) -> RateLimitState {
+        let config = RateLimitConfig {
+            auth_limit: 5,
+            ..RateLimitConfig::for_tests(100, 2, 10_000, Duration::from_secs(60))
+        };
+        let limiter = RateLimitState::new(config, auth);
+        limiter.tiers.lock().unwrap().insert(
+            partner.to_string(),
+            CachedTier {
+                tier: PublisherTier::VerifiedPartner,
+                fetched_at: Instant::now(),
+            },
+        );
+        limiter
+    }
+
+    #[tokio::test]
+    async fn tiers_are_disabled_without_a_jwt_secret() {
+        let mut auth = AuthManager::new("test-secret".to_string());
+        let (partner, partner_token) = issue_token(&mut auth, 5);
+        let app = test_router(tiered_limiter(None, &partner));
+
+        // The partner's token is not trusted, but still counts as authenticated
+        assert_eq!(writes_allowed(&app, &partner_token).await, 5);
+    }
+
+    #[tokio::test]
+    async fn invalid_token_falls_back_to_anonymous_limit() {
+        let app = test_app(100, 2, 10_000, Duration::from_secs(60));
+        assert_eq!(writes_allowed(&app, "not-a-jwt").await, 2);
+    }
+
+    #[test]
+    fn client_ip_prefers_forwarded_for_over_real_ip() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("10.0.0.2"));
+        assert_eq!(
+            client_ip_from_headers(&headers),
+            Some("10.0.0.2".parse().unwrap())
+        );
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("garbage, 203.0.113.7, 10.0.0.1"),
+        );
+        assert_eq!(
+            client_ip_from_headers(&headers),
+            Some("203.0.113.7".parse().unwrap())
+        );
+
+        assert_eq!(client_ip_from_headers(&HeaderMap::new()), None);
+    }
 }
@@ -0,0 +1,305 @@
+// HMAC-SHA256 request signing for trusted server-to-server writes.
+//
+// A signing client sends three headers:
+//
+//   X-Client-Id:  the client's configured id
+//   X-Timestamp:  unix seconds when the request was signed
+//   X-Signature:  hex HMAC-SHA256 over "{timestamp}.{raw body}" keyed with the
+//                 client's shared secret
+//
+// Requests outside the timestamp window are rejected so a captured request
+// cannot be replayed later. Signing is opt-in: with no clients configured the
+// middleware lets every request through.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::clock::{Clock, SystemClock};
+
+pub const CLIENT_ID_HEADER: &str = "x-client-id";
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Default accepted clock skew between signer and server
+const DEFAULT_MAX_SKEW_SECS: u64 = 300;
+/// Largest body buffered for verification
+const MAX_SIGNED_BODY_BYTES: usize = 10 * 1024 * 1024;
+const SHA256_BLOCK_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    MissingHeaders,
+    UnknownClient,
+    InvalidTimestamp,
+    StaleTimestamp,
+    InvalidSignature,
+}
+
+impl SignatureError {
+    fn reason(self) -> &'static str {
+        match self {
+            Self::MissingHeaders => "missing_signature_headers",
+            Self::UnknownClient => "unknown_client",
+            Self::InvalidTimestamp => "invalid_timestamp",
+            Self::StaleTimestamp => "stale_timestamp",
+            Self::InvalidSignature => "invalid_signature",
+        }
+    }
+}
+
+/// Shared secrets per client and the accepted timestamp window
+#[derive(Debug, Clone)]
+pub struct RequestSigning {
+    secrets: HashMap<String, Vec<u8>>,
+    max_skew_secs: u64,
+    clock: Arc<dyn Clock>,
+}
+
+impl RequestSigning {
+    pub fn new(secrets: HashMap<String, Vec<u8>>, max_skew_secs: u64) -> Self {
+        Self {
+            secrets,
+            max_skew_secs,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Check timestamps against `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// `REQUEST_SIGNING_SECRETS` as comma-separated `client_id:secret` pairs
+    /// and `REQUEST_SIGNING_MAX_SKEW_SECS` (default 300)
+    pub fn from_env() -> Self {
+        let secrets = std::env::var("REQUEST_SIGNING_SECRETS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| pair.split_once(':'))
+            .map(|(client, secret)| (client.trim().to_string(), secret.trim().as_bytes().to_vec()))
+            .filter(|(client, secret)| !client.is_empty() && !secret.is_empty())
+            .collect();
+        let max_skew_secs = std::env::var("REQUEST_SIGNING_MAX_SKEW_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_SKEW_SECS);
+        Self::new(secrets, max_skew_secs)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.secrets.is_empty()
+    }
+
+    /// Check the signature headers against `body`, as of `now` (unix seconds)
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8], now: u64) -> Result<(), SignatureError> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let (Some(client_id), Some(timestamp), Some(signature)) = (
+            header(CLIENT_ID_HEADER),
+            header(TIMESTAMP_HEADER),
+            header(SIGNATURE_HEADER),
+        ) else {
+            return Err(SignatureError::MissingHeaders);
+        };
+
+        let secret = self
+            .secrets
+            .get(client_id)
+            .ok_or(SignatureError::UnknownClient)?;
+        let signed_at: u64 = timestamp
+            .parse()
+            .map_err(|_| SignatureError::InvalidTimestamp)?;
+        if now.abs_diff(signed_at) > self.max_skew_secs {
+            return Err(SignatureError::StaleTimestamp);
+        }
+
+        let provided = hex::decode(signature.trim_start_matches("sha256="))
+            .map_err(|_| SignatureError::InvalidSignature)?;
+        let expected = hmac_sha256(secret, &signing_payload(timestamp, body));
+        if constant_time_eq(&provided, &expected) {
+            Ok(())
+        } else {
+            Err(SignatureError::InvalidSignature)
+        }
+    }
+}
+
+/// Bytes covered by the signature: `{timestamp}.{body}`
+pub fn signing_payload(timestamp: &str, body: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(timestamp.len() + 1 + body.len());
+    payload.extend_from_slice(timestamp.as_bytes());
+    payload.push(b'.');
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// HMAC-SHA256 (RFC 2104)
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Reject requests whose signature does not verify with 401. The body is
+/// buffered for verification and handed on to the handler unchanged.
+pub async fn verify_signed_request(
+    State(signing): State<Arc<RequestSigning>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !signing.is_enabled() {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return unauthorized(SignatureError::InvalidSignature),
+    };
+
+    if let Err(err) = signing.verify(&parts.headers, &bytes, signing.clock.unix_now()) {
+        tracing::warn!(path = %parts.uri.path(), reason = err.reason(), "rejected unsigned request");
+        return unauthorized(err);
+    }
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+fn unauthorized(err: SignatureError) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({
+            "error": "Unauthorized",
+            "message": err.reason(),
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use axum::{http::HeaderValue, middleware, routing::post, Router};
+    use chrono::{TimeZone, Utc};
+    use tower::ServiceExt;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn signing() -> RequestSigning {
+        RequestSigning::new(
+            HashMap::from([("indexer".to_string(), b"s3cret".to_vec())]),
+            DEFAULT_MAX_SKEW_SECS,
+        )
+    }
+
+    fn signed_headers(body: &[u8], timestamp: u64) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature = hex::encode(hmac_sha256(b"s3cret", &signing_payload(&timestamp, body)));
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_ID_HEADER, HeaderValue::from_static("indexer"));
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_str(&timestamp).unwrap());
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_str(&signature).unwrap());
+        headers
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231_test_case_2() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn valid_signature_is_accepted() {
+        let body = br#"{"interactions":[]}"#;
+        let headers = signed_headers(body, NOW - 10);
+        assert_eq!(signing().verify(&headers, body, NOW), Ok(()));
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let headers = signed_headers(br#"{"interactions":[]}"#, NOW);
+        assert_eq!(
+            signing().verify(&headers, br#"{"interactions":[{}]}"#, NOW),
+            Err(SignatureError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn replayed_request_outside_window_is_rejected() {
+        let body = b"{}";
+        let headers = signed_headers(body, NOW - DEFAULT_MAX_SKEW_SECS - 1);
+        assert_eq!(
+            signing().verify(&headers, body, NOW),
+            Err(SignatureError::StaleTimestamp)
+        );
+    }
+
+    #[test]
+    fn unknown_client_and_missing_headers_are_rejected() {
+        let body = b"{}";
+        let mut headers = signed_headers(body, NOW);
+        headers.insert(CLIENT_ID_HEADER, HeaderValue::from_static("stranger"));
+        assert_eq!(
+            signing().verify(&headers, body, NOW),
+            Err(SignatureError::UnknownClient)
+        );
+        assert_eq!(
+            signing().verify(&HeaderMap::new(), body, NOW),
+            Err(SignatureError::MissingHeaders)
+        );
+    }
+
+    #[tokio::test]
+    async fn middleware_checks_timestamps_against_the_clock() {
+        let clock = Arc::new(FixedClock::at(Utc.timestamp_opt(NOW as i64, 0).unwrap()));
+        let app = Router::new()
+            .route("/ingest", post(|| async { StatusCode::NO_CONTENT }))
+            .route_layer(middleware::from_fn_with_state(
+                Arc::new(signing().with_clock(clock.clone())),
+                verify_signed_request,
+            ));
+        let body = b"{}";
+        let send = |app: Router| async move {
+            let mut request = Request::post("/ingest")
+                .body(Body::from(&body[..]))
+                .unwrap();
+            *request.headers_mut() = signed_headers(body, NOW);
+            app.oneshot(request).await.unwrap().status()
+        };
+
+        assert_eq!(send(app.clone()).await, StatusCode::NO_CONTENT);
+        clock.advance(chrono::Duration::seconds(DEFAULT_MAX_SKEW_SECS as i64 + 1));
+        assert_eq!(send(app).await, StatusCode::UNAUTHORIZED);
+    }
+}
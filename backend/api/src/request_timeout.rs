@@ -0,0 +1,170 @@
+// Upper bound on how long any request may run.
+//
+// Handlers that outlive their limit are dropped and the client gets a 504.
+// Verification routes compile contracts, so they get a separate, longer limit
+// that always sits a little above the verifier's own build timeout: a build
+// that times out inside the verifier reports its own error rather than being
+// cut off here first.
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::ApiError;
+
+/// Routes held to `RequestTimeouts::verify` instead of the default
+const VERIFY_ROUTES: [&str; 2] = ["/api/contracts/verify", "/api/contracts/:id/verify/check"];
+
+/// How much longer than the verifier's build timeout verify routes may run
+pub const VERIFY_GRACE: Duration = Duration::from_secs(30);
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeouts {
+    pub default: Duration,
+    pub verify: Duration,
+}
+
+impl RequestTimeouts {
+    /// `REQUEST_TIMEOUT_SECS` for most routes and `VERIFY_REQUEST_TIMEOUT_SECS`
+    /// for verification. The verify limit is raised to `build_timeout` plus
+    /// `VERIFY_GRACE` when unset or configured below that.
+    pub fn from_env(build_timeout: Duration) -> Self {
+        let secs = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs)
+        };
+        Self::new(
+            secs("REQUEST_TIMEOUT_SECS").unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+            secs("VERIFY_REQUEST_TIMEOUT_SECS"),
+            build_timeout,
+        )
+    }
+
+    pub fn new(default: Duration, verify: Option<Duration>, build_timeout: Duration) -> Self {
+        let floor = build_timeout + VERIFY_GRACE;
+        let verify = match verify {
+            Some(verify) if verify < floor => {
+                tracing::warn!(
+                    configured = ?verify,
+                    using = ?floor,
+                    "verify request timeout must exceed the build timeout; raising it"
+                );
+                floor
+            }
+            Some(verify) => verify,
+            None => floor,
+        };
+        Self { default, verify }
+    }
+
+    /// Limit for a request that matched `path`
+    pub fn for_path(&self, path: Option<&str>) -> Duration {
+        match path {
+            Some(path) if VERIFY_ROUTES.contains(&path) => self.verify,
+            _ => self.default,
+        }
+    }
+}
+
+pub async fn enforce_request_timeout(
+    State(timeouts): State<RequestTimeouts>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = crate::rate_limit::matched_path(&req).map(str::to_string);
+    let limit = timeouts.for_path(path.as_deref());
+
+    match tokio::time::timeout(limit, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!(
+                path = path.as_deref().unwrap_or("unmatched"),
+                timeout = ?limit,
+                "request timed out"
+            );
+            ApiError::new(
+                StatusCode::GATEWAY_TIMEOUT,
+                "RequestTimeout",
+                format!("Request did not complete within {}s", limit.as_secs_f64()),
+            )
+            .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(timeouts: RequestTimeouts) -> Router {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            StatusCode::OK
+        };
+        Router::new()
+            .route("/slow", get(slow))
+            .route("/api/contracts/:id/verify/check", get(slow))
+            .layer(middleware::from_fn_with_state(
+                timeouts,
+                enforce_request_timeout,
+            ))
+    }
+
+    async fn status(app: Router, uri: &str) -> StatusCode {
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn slow_handler_gets_gateway_timeout() {
+        let timeouts = RequestTimeouts {
+            default: Duration::from_millis(20),
+            verify: Duration::from_secs(5),
+        };
+        assert_eq!(
+            status(app(timeouts), "/slow").await,
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        // Verify routes get the longer limit
+        assert_eq!(
+            status(app(timeouts), "/api/contracts/abc/verify/check").await,
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn verify_limit_stays_above_the_build_timeout() {
+        let build = Duration::from_secs(600);
+        let default = Duration::from_secs(30);
+
+        let unset = RequestTimeouts::new(default, None, build);
+        assert_eq!(unset.verify, build + VERIFY_GRACE);
+
+        let too_low = RequestTimeouts::new(default, Some(Duration::from_secs(60)), build);
+        assert_eq!(too_low.verify, build + VERIFY_GRACE);
+
+        let generous = RequestTimeouts::new(default, Some(Duration::from_secs(900)), build);
+        assert_eq!(generous.verify, Duration::from_secs(900));
+        assert_eq!(
+            generous.for_path(Some("/api/contracts/verify")),
+            generous.verify
+        );
+        assert_eq!(generous.for_path(Some("/api/contracts/:id")), default);
+        assert_eq!(generous.for_path(None), default);
+    }
+}
@@ -0,0 +1,598 @@
+// Bulk re-verification after a compiler upgrade
+//
+// An admin enqueues a job for a new soroban-sdk version; every currently
+// verified contract is rebuilt from its stored source and contracts that no
+// longer reproduce lose `is_verified` (with an audit entry). Progress lives in
+// `reverification_results`, so a job interrupted by a restart resumes from the
+// contracts that still have no outcome. Each replica resumes the job, and
+// contracts are claimed row by row so no two of them rebuild the same one. A
+// job that finishes with outcomes it could not record is marked `failed` so it
+// does not block the next one.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::{AuditActionType, RegistryError};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+use verifier::VerifierConfig;
+
+use crate::{
+    contract_history_handlers::log_contract_change,
+    error::{ApiError, ApiResult},
+    handlers::db_internal_error,
+    state::AppState,
+};
+
+const AUDIT_ACTOR: &str = "system:reverification";
+
+#[derive(Debug, Deserialize)]
+pub struct StartReverificationRequest {
+    /// New default `soroban-sdk` version to rebuild against
+    pub compiler_version: String,
+}
+
+/// Per-contract result of a re-verification build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReverifyOutcome {
+    /// Rebuilt artifact still matches the deployed hash
+    StillVerified,
+    /// Source no longer builds or no longer matches; `is_verified` cleared
+    Downgraded,
+    /// No stored source to rebuild from; left untouched
+    Skipped,
+    /// Infrastructure failure; left untouched
+    Errored,
+}
+
+impl ReverifyOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::StillVerified => "still_verified",
+            Self::Downgraded => "downgraded",
+            Self::Skipped => "skipped",
+            Self::Errored => "errored",
+        }
+    }
+}
+
+/// Classify a rebuild result. `None` means there was no source to rebuild.
+///
+/// Only failures attributable to the contract (mismatched bytecode, source or
+/// build params the new compiler rejects) downgrade it; anything else is an
+/// operational error and must not cost the publisher their verified badge.
+pub fn classify_outcome(result: Option<&Result<bool, RegistryError>>) -> ReverifyOutcome {
+    match result {
+        None => ReverifyOutcome::Skipped,
+        Some(Ok(true)) => ReverifyOutcome::StillVerified,
+        Some(Ok(false)) => ReverifyOutcome::Downgraded,
+        Some(Err(RegistryError::VerificationFailed(_) | RegistryError::InvalidInput(_))) => {
+            ReverifyOutcome::Downgraded
+        }
+        Some(Err(_)) => ReverifyOutcome::Errored,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReverificationStatus {
+    pub job_id: Uuid,
+    pub compiler_version: String,
+    pub status: String,
+    pub total: i64,
+    pub processed: i64,
+    pub still_verified: i64,
+    pub downgraded: i64,
+    pub skipped: i64,
+    pub errored: i64,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    id: Uuid,
+    compiler_version: String,
+    status: String,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// POST /api/admin/reverify
+pub async fn start_reverification(
+    State(state): State<AppState>,
+    payload: Result<Json<StartReverificationRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<ReverificationStatus>)> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    let compiler_version = req.compiler_version.trim().to_string();
+    verifier::validate_sdk_version(&compiler_version)
+        .map_err(|msg| ApiError::bad_request("InvalidCompilerVersion", msg))?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin reverification job", err))?;
+
+    let job_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO reverification_jobs (compiler_version) VALUES ($1) RETURNING id",
+    )
+    .bind(&compiler_version)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            "ReverificationInProgress",
+            "A re-verification job is already running",
+        ),
+        _ => db_internal_error("create reverification job", err),
+    })?;
+
+    sqlx::query(
+        "INSERT INTO reverification_results (job_id, contract_id) \
+         SELECT $1, id FROM contracts WHERE is_verified = true",
+    )
+    .bind(job_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("enqueue reverification", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit reverification job", err))?;
+
+    tracing::info!(job_id = %job_id, compiler_version = %compiler_version, "re-verification job enqueued");
+    spawn_job(state.clone(), job_id, compiler_version);
+
+    let status = fetch_status(&state, job_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(status)))
+}
+
+/// GET /api/admin/reverify/:job_id
+pub async fn get_reverification_status(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> ApiResult<Json<ReverificationStatus>> {
+    let job_id = Uuid::parse_str(&job_id).map_err(|_| {
+        ApiError::bad_request("InvalidJobId", format!("Invalid job ID: {}", job_id))
+    })?;
+    Ok(Json(fetch_status(&state, job_id).await?))
+}
+
+async fn fetch_status(state: &AppState, job_id: Uuid) -> ApiResult<ReverificationStatus> {
+    let job: JobRow = sqlx::query_as(
+        "SELECT id, compiler_version, status, created_at, completed_at \
+         FROM reverification_jobs WHERE id = $1",
+    )
+    .bind(job_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch reverification job", err))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "ReverificationJobNotFound",
+            format!("No re-verification job with ID: {}", job_id),
+        )
+    })?;
+
+    let counts: Vec<(Option<String>, i64)> = sqlx::query_as(
+        "SELECT outcome, COUNT(*) FROM reverification_results WHERE job_id = $1 GROUP BY outcome",
+    )
+    .bind(job_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count reverification results", err))?;
+
+    let count = |outcome: ReverifyOutcome| {
+        counts
+            .iter()
+            .filter(|(o, _)| o.as_deref() == Some(outcome.as_str()))
+            .map(|(_, n)| *n)
+            .sum::<i64>()
+    };
+    let total: i64 = counts.iter().map(|(_, n)| *n).sum();
+    let pending: i64 = counts
+        .iter()
+        .filter(|(o, _)| o.is_none())
+        .map(|(_, n)| *n)
+        .sum();
+
+    Ok(ReverificationStatus {
+        job_id: job.id,
+        compiler_version: job.compiler_version,
+        status: job.status,
+        total,
+        processed: total - pending,
+        still_verified: count(ReverifyOutcome::StillVerified),
+        downgraded: count(ReverifyOutcome::Downgraded),
+        skipped: count(ReverifyOutcome::Skipped),
+        errored: count(ReverifyOutcome::Errored),
+        created_at: job.created_at,
+        completed_at: job.completed_at,
+    })
+}
+
+/// Resume any job left running by a previous process
+pub fn spawn_resume_incomplete_jobs(state: AppState) {
+    tokio::spawn(async move {
+        let jobs: Result<Vec<(Uuid, String)>, sqlx::Error> = sqlx::query_as(
+            "SELECT id, compiler_version FROM reverification_jobs WHERE status = 'running'",
+        )
+        .fetch_all(&state.db)
+        .await;
+
+        match jobs {
+            Ok(jobs) => {
+                for (job_id, compiler_version) in jobs {
+                    tracing::info!(job_id = %job_id, "resuming re-verification job");
+                    spawn_job(state.clone(), job_id, compiler_version);
+                }
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to load incomplete re-verification jobs")
+            }
+        }
+    });
+}
+
+fn spawn_job(state: AppState, job_id: Uuid, compiler_version: String) {
+    // Identifies this process's claims; other replicas resuming the same job
+    // skip the rows it holds
+    let worker = Uuid::new_v4();
+    tokio::spawn(async move {
+        if let Err(err) = run_job(&state, job_id, worker, compiler_version).await {
+            tracing::error!(job_id = %job_id, error = ?err, "re-verification job stopped");
+            if let Err(err) = finish_job(&state, job_id, worker, JobEnd::Failed).await {
+                tracing::error!(job_id = %job_id, error = ?err, "failed to mark re-verification job failed");
+            }
+        }
+    });
+}
+
+async fn run_job(
+    state: &AppState,
+    job_id: Uuid,
+    worker: Uuid,
+    compiler_version: String,
+) -> Result<(), sqlx::Error> {
+    let config = Arc::new(VerifierConfig {
+        sdk_version: compiler_version,
+        ..VerifierConfig::from_env()
+    });
+
+    // Builds share the API's verification queue, so re-verification never
+    // runs more builds than the process allows in total
+    let mut tasks = JoinSet::new();
+    for _ in 0..config.max_concurrent_builds.max(1) {
+        let state = state.clone();
+        let config = config.clone();
+        tasks.spawn(async move {
+            loop {
+                let queue = state.verification_queue.clone();
+                let claimed = queue
+                    .run(Uuid::new_v4(), async {
+                        let contract_id =
+                            claim_next(&state, job_id, worker, config.build_timeout).await?;
+                        if let Some(contract_id) = contract_id {
+                            if let Err(err) =
+                                reverify_contract(&state, job_id, contract_id, &config).await
+                            {
+                                tracing::error!(
+                                    job_id = %job_id,
+                                    contract_id = %contract_id,
+                                    error = ?err,
+                                    "failed to record re-verification outcome"
+                                );
+                            }
+                        }
+                        Ok::<_, sqlx::Error>(contract_id.is_some())
+                    })
+                    .await?;
+                if !claimed {
+                    return Ok::<_, sqlx::Error>(());
+                }
+            }
+        });
+    }
+    while let Some(worker_result) = tasks.join_next().await {
+        worker_result.expect("re-verification worker panicked")?;
+    }
+
+    match finish_job(state, job_id, worker, JobEnd::Settled).await? {
+        Some(status) => {
+            tracing::info!(job_id = %job_id, status = %status, "re-verification job finished")
+        }
+        None => tracing::info!(
+            job_id = %job_id,
+            "re-verification contracts still building elsewhere; leaving the job to them"
+        ),
+    }
+    Ok(())
+}
+
+/// Claim the next contract of `job_id` nobody is rebuilding. Claims held
+/// longer than twice the build timeout belong to a process that died and are
+/// taken over.
+async fn claim_next(
+    state: &AppState,
+    job_id: Uuid,
+    worker: Uuid,
+    build_timeout: std::time::Duration,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(
+        "UPDATE reverification_results SET claimed_by = $2, claimed_at = NOW() \
+         WHERE (job_id, contract_id) = ( \
+             SELECT job_id, contract_id FROM reverification_results \
+             WHERE job_id = $1 AND outcome IS NULL \
+               AND (claimed_at IS NULL OR claimed_at < NOW() - make_interval(secs => $3)) \
+             ORDER BY contract_id LIMIT 1 \
+             FOR UPDATE SKIP LOCKED) \
+         RETURNING contract_id",
+    )
+    .bind(job_id)
+    .bind(worker)
+    .bind(build_timeout.as_secs_f64() * 2.0)
+    .fetch_optional(&state.db)
+    .await
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobEnd {
+    /// Every contract was processed; the job fails if any outcome is missing
+    Settled,
+    /// The job stopped before processing every contract
+    Failed,
+}
+
+/// Take the job out of `running`, freeing the single-running-job slot.
+/// Returns the status it ended in, or `None` when a settled job still has
+/// contracts claimed by another worker, which finishes it instead.
+async fn finish_job(
+    state: &AppState,
+    job_id: Uuid,
+    worker: Uuid,
+    end: JobEnd,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "UPDATE reverification_jobs SET completed_at = NOW(), status = CASE \
+             WHEN $2 OR EXISTS ( \
+                 SELECT 1 FROM reverification_results WHERE job_id = $1 AND outcome IS NULL) \
+             THEN 'failed' ELSE 'completed' END \
+         WHERE id = $1 AND status = 'running' AND ($2 OR NOT EXISTS ( \
+             SELECT 1 FROM reverification_results \
+             WHERE job_id = $1 AND outcome IS NULL AND claimed_by <> $3)) \
+         RETURNING status",
+    )
+    .bind(job_id)
+    .bind(end == JobEnd::Failed)
+    .bind(worker)
+    .fetch_optional(&state.db)
+    .await
+}
+
+async fn reverify_contract(
+    state: &AppState,
+    job_id: Uuid,
+    contract_id: Uuid,
+    config: &VerifierConfig,
+) -> Result<(), sqlx::Error> {
+    let source: Option<(String, serde_json::Value, String)> = sqlx::query_as(
+        "SELECT v.source_code, v.build_params, c.wasm_hash \
+         FROM verifications v JOIN contracts c ON c.id = v.contract_id \
+         WHERE v.contract_id = $1 AND v.status = 'verified' AND v.source_code IS NOT NULL \
+         ORDER BY v.verified_at DESC NULLS LAST LIMIT 1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let result = match &source {
+        Some((source_code, build_params, wasm_hash)) => {
+            let result = verifier::verify_build(source_code, build_params, wasm_hash, config).await;
+            Some(
+                result
+                    .map(|build| build.verified)
+                    .map_err(RegistryError::from),
+            )
+        }
+        None => None,
+    };
+    let outcome = classify_outcome(result.as_ref());
+    let error_message = match &result {
+        Some(Err(err)) => Some(err.to_string()),
+        Some(Ok(false)) => Some("rebuilt bytecode does not match deployed wasm hash".to_string()),
+        _ => None,
+    };
+
+    if outcome == ReverifyOutcome::Downgraded {
+        let downgraded = sqlx::query(
+            "UPDATE contracts SET is_verified = false, updated_at = NOW(), \
+                network_configs = ( \
+                    SELECT COALESCE(jsonb_object_agg(key, value || '{\"is_verified\": false}'), '{}') \
+                    FROM jsonb_each(network_configs)) \
+             WHERE id = $1 AND is_verified = true",
+        )
+        .bind(contract_id)
+        .execute(&state.db)
+        .await?;
+
+        // A resumed job may revisit a contract it already downgraded
+        if downgraded.rows_affected() > 0 {
+            log_contract_change(
+                &state.db,
+                contract_id,
+                AuditActionType::VerificationChanged,
+                Some(json!({ "is_verified": true })),
+                Some(json!({
+                    "is_verified": false,
+                    "reason": "reverification_failed",
+                    "compiler_version": config.sdk_version,
+                    "reverification_job_id": job_id,
+                })),
+                AUDIT_ACTOR,
+                None,
+            )
+            .await?;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE reverification_results SET outcome = $3, error_message = $4, processed_at = NOW() \
+         WHERE job_id = $1 AND contract_id = $2",
+    )
+    .bind(job_id)
+    .bind(contract_id)
+    .bind(outcome.as_str())
+    .bind(&error_message)
+    .execute(&state.db)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_rebuild_stays_verified() {
+        assert_eq!(
+            classify_outcome(Some(&Ok(true))),
+            ReverifyOutcome::StillVerified
+        );
+    }
+
+    #[test]
+    fn contract_side_failures_downgrade() {
+        assert_eq!(
+            classify_outcome(Some(&Ok(false))),
+            ReverifyOutcome::Downgraded
+        );
+        assert_eq!(
+            classify_outcome(Some(&Err(RegistryError::VerificationFailed(
+                "compilation failed".to_string()
+            )))),
+            ReverifyOutcome::Downgraded
+        );
+        assert_eq!(
+            classify_outcome(Some(&Err(RegistryError::InvalidInput(
+                "unknown profile".to_string()
+            )))),
+            ReverifyOutcome::Downgraded
+        );
+    }
+
+    #[test]
+    fn infrastructure_failures_do_not_downgrade() {
+        assert_eq!(
+            classify_outcome(Some(&Err(RegistryError::Internal(
+                "failed to run cargo".to_string()
+            )))),
+            ReverifyOutcome::Errored
+        );
+        assert_eq!(classify_outcome(None), ReverifyOutcome::Skipped);
+    }
+
+    #[tokio::test]
+    async fn workers_split_contracts_and_the_last_one_finishes_the_job() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let job: Uuid = sqlx::query_scalar(
+            "INSERT INTO reverification_jobs (compiler_version) VALUES ('22.0.0') RETURNING id",
+        )
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        for contract_id in ["CA", "CB"] {
+            let contract = crate::test_db::insert_contract(&state.db, publisher, contract_id).await;
+            sqlx::query("INSERT INTO reverification_results (job_id, contract_id) VALUES ($1, $2)")
+                .bind(job)
+                .bind(contract)
+                .execute(&state.db)
+                .await
+                .unwrap();
+        }
+        let record = |contract: Uuid| {
+            sqlx::query(
+                "UPDATE reverification_results SET outcome = 'skipped' \
+                 WHERE job_id = $1 AND contract_id = $2",
+            )
+            .bind(job)
+            .bind(contract)
+            .execute(&state.db)
+        };
+        let timeout = std::time::Duration::from_secs(600);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let a = claim_next(&state, job, first, timeout)
+            .await
+            .unwrap()
+            .unwrap();
+        let b = claim_next(&state, job, second, timeout)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(a, b);
+        assert_eq!(claim_next(&state, job, first, timeout).await.unwrap(), None);
+
+        // The second worker is still building, so the first leaves the job
+        record(a).await.unwrap();
+        assert_eq!(
+            finish_job(&state, job, first, JobEnd::Settled)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Once its claim outlives the lease, the second worker counts as dead
+        sqlx::query(
+            "UPDATE reverification_results SET claimed_at = NOW() - INTERVAL '1 hour' \
+             WHERE contract_id = $1",
+        )
+        .bind(b)
+        .execute(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(
+            claim_next(&state, job, first, timeout).await.unwrap(),
+            Some(b)
+        );
+        record(b).await.unwrap();
+        assert_eq!(
+            finish_job(&state, job, first, JobEnd::Settled)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("completed")
+        );
+    }
+
+    #[test]
+    fn outcome_strings_match_schema() {
+        for outcome in [
+            ReverifyOutcome::StillVerified,
+            ReverifyOutcome::Downgraded,
+            ReverifyOutcome::Skipped,
+            ReverifyOutcome::Errored,
+        ] {
+            assert_eq!(
+                serde_json::to_value(outcome).unwrap(),
+                json!(outcome.as_str())
+            );
+        }
+    }
+}
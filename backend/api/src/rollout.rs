@@ -0,0 +1,454 @@
+// Staged rollout gating for security patches.
+//
+// A patch rollout moves through four stages, each covering a larger share of
+// the affected contracts. Before a stage may advance, the failure rate of the
+// applications attempted in it is checked against a gate derived from the
+// patch's severity: Critical patches reach many contracts quickly, so they
+// tolerate fewer failures than Low ones before the rollout halts.
+//
+// `RolloutEngine::simulate` projects a plan against an affected population
+// without applying anything, so operators can check cohort sizes, gates and
+// the expected duration before a real rollout starts.
+
+use chrono::Duration;
+use shared::{PatchSeverity, RolloutStage, SecurityPatch};
+use uuid::Uuid;
+
+/// Share of affected contracts covered once each stage is done
+pub const STAGE_PERCENTAGES: [(RolloutStage, u8); 4] = [
+    (RolloutStage::Stage1, 5),
+    (RolloutStage::Stage2, 25),
+    (RolloutStage::Stage3, 50),
+    (RolloutStage::Stage4, 100),
+];
+
+/// Highest tolerated failure rate (0.0–1.0) per patch severity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FailureRateThresholds {
+    pub critical: f64,
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+}
+
+impl Default for FailureRateThresholds {
+    fn default() -> Self {
+        Self {
+            critical: 0.01,
+            high: 0.02,
+            medium: 0.05,
+            low: 0.10,
+        }
+    }
+}
+
+impl FailureRateThresholds {
+    /// `ROLLOUT_MAX_FAILURE_RATE_{CRITICAL,HIGH,MEDIUM,LOW}`, each a fraction
+    /// between 0 and 1; unset or invalid levels keep their default
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let level = |name: &str, default: f64| {
+            std::env::var(format!("ROLLOUT_MAX_FAILURE_RATE_{}", name))
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|rate| (0.0..=1.0).contains(rate))
+                .unwrap_or(default)
+        };
+        Self {
+            critical: level("CRITICAL", defaults.critical),
+            high: level("HIGH", defaults.high),
+            medium: level("MEDIUM", defaults.medium),
+            low: level("LOW", defaults.low),
+        }
+    }
+
+    pub fn for_severity(&self, severity: PatchSeverity) -> f64 {
+        match severity {
+            PatchSeverity::Critical => self.critical,
+            PatchSeverity::High => self.high,
+            PatchSeverity::Medium => self.medium,
+            PatchSeverity::Low => self.low,
+        }
+    }
+}
+
+/// How long each stage runs before its gate is evaluated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageDwellTimes {
+    pub stage1: Duration,
+    pub stage2: Duration,
+    pub stage3: Duration,
+    pub stage4: Duration,
+}
+
+impl Default for StageDwellTimes {
+    fn default() -> Self {
+        Self {
+            stage1: Duration::hours(24),
+            stage2: Duration::hours(24),
+            stage3: Duration::hours(48),
+            stage4: Duration::hours(48),
+        }
+    }
+}
+
+impl StageDwellTimes {
+    /// `ROLLOUT_DWELL_HOURS_STAGE{1,2,3,4}`, each a whole number of hours;
+    /// unset or invalid stages keep their default
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let stage = |n: u8, default: Duration| {
+            std::env::var(format!("ROLLOUT_DWELL_HOURS_STAGE{}", n))
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .map_or(default, |hours| Duration::hours(hours.into()))
+        };
+        Self {
+            stage1: stage(1, defaults.stage1),
+            stage2: stage(2, defaults.stage2),
+            stage3: stage(3, defaults.stage3),
+            stage4: stage(4, defaults.stage4),
+        }
+    }
+
+    pub fn for_stage(&self, stage: RolloutStage) -> Duration {
+        match stage {
+            RolloutStage::Stage1 => self.stage1,
+            RolloutStage::Stage2 => self.stage2,
+            RolloutStage::Stage3 => self.stage3,
+            RolloutStage::Stage4 => self.stage4,
+            RolloutStage::Complete => Duration::zero(),
+        }
+    }
+}
+
+/// Where a patch's rollout stands; the severity decides its gate
+#[derive(Debug, Clone, PartialEq)]
+pub struct RolloutPlan {
+    pub patch_id: Uuid,
+    pub severity: PatchSeverity,
+    pub stage: RolloutStage,
+}
+
+impl RolloutPlan {
+    pub fn for_patch(patch: &SecurityPatch) -> Self {
+        Self {
+            patch_id: patch.id,
+            severity: patch.severity,
+            stage: RolloutStage::Stage1,
+        }
+    }
+
+    /// Share of affected contracts the current stage covers
+    pub fn cohort_percentage(&self) -> u8 {
+        STAGE_PERCENTAGES
+            .iter()
+            .find(|(stage, _)| *stage == self.stage)
+            .map_or(100, |(_, pct)| *pct)
+    }
+}
+
+fn next_stage(stage: RolloutStage) -> RolloutStage {
+    match stage {
+        RolloutStage::Stage1 => RolloutStage::Stage2,
+        RolloutStage::Stage2 => RolloutStage::Stage3,
+        RolloutStage::Stage3 => RolloutStage::Stage4,
+        RolloutStage::Stage4 | RolloutStage::Complete => RolloutStage::Complete,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GateDecision {
+    /// The stage passed its gate; move on to this stage
+    Advance(RolloutStage),
+    /// The stage's cohort has not all been attempted yet
+    Hold,
+    /// Too many failures for the patch's severity; stop the rollout
+    Halt { failure_rate: f64, threshold: f64 },
+}
+
+/// One stage of a simulated rollout
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedStage {
+    pub stage: RolloutStage,
+    /// Contracts newly reached in this stage
+    pub cohort_size: u64,
+    /// Contracts reached once this stage is done
+    pub cumulative: u64,
+    /// Offset from the start of the simulation at which the stage begins
+    pub starts_after: Duration,
+    pub dwell: Duration,
+}
+
+/// Gate a simulated rollout must pass to leave `after`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedGate {
+    pub after: RolloutStage,
+    pub advances_to: RolloutStage,
+    pub max_failure_rate: f64,
+}
+
+/// Projection of a rollout; nothing is applied to produce it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RolloutSimulation {
+    pub population: u64,
+    /// Remaining stages in order, starting at the plan's current one
+    pub stages: Vec<SimulatedStage>,
+    pub gates: Vec<SimulatedGate>,
+    /// Time to completion if every gate passes on its first evaluation
+    pub estimated_duration: Duration,
+}
+
+/// Contracts reached once `stage` is done, out of `population`
+fn cumulative_reach(stage: RolloutStage, population: u64) -> u64 {
+    let pct = STAGE_PERCENTAGES
+        .iter()
+        .find(|(s, _)| *s == stage)
+        .map_or(100, |(_, pct)| *pct);
+    (population * u64::from(pct)).div_ceil(100)
+}
+
+fn previous_stage(stage: RolloutStage) -> Option<RolloutStage> {
+    match stage {
+        RolloutStage::Stage1 => None,
+        RolloutStage::Stage2 => Some(RolloutStage::Stage1),
+        RolloutStage::Stage3 => Some(RolloutStage::Stage2),
+        RolloutStage::Stage4 => Some(RolloutStage::Stage3),
+        RolloutStage::Complete => Some(RolloutStage::Stage4),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RolloutEngine {
+    thresholds: FailureRateThresholds,
+    dwell_times: StageDwellTimes,
+}
+
+impl RolloutEngine {
+    pub fn new(thresholds: FailureRateThresholds) -> Self {
+        Self {
+            thresholds,
+            dwell_times: StageDwellTimes::default(),
+        }
+    }
+
+    pub fn with_dwell_times(mut self, dwell_times: StageDwellTimes) -> Self {
+        self.dwell_times = dwell_times;
+        self
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(FailureRateThresholds::from_env()).with_dwell_times(StageDwellTimes::from_env())
+    }
+
+    /// Project `plan` over `population` affected contracts: the cohort each
+    /// remaining stage reaches, the gates between them, and how long the
+    /// rollout takes if none of them halts it
+    pub fn simulate(&self, plan: &RolloutPlan, population: u64) -> RolloutSimulation {
+        let mut stages = Vec::new();
+        let mut gates = Vec::new();
+        let mut elapsed = Duration::zero();
+        let mut reached =
+            previous_stage(plan.stage).map_or(0, |stage| cumulative_reach(stage, population));
+
+        let mut stage = plan.stage;
+        while stage != RolloutStage::Complete {
+            let cumulative = cumulative_reach(stage, population);
+            let dwell = self.dwell_times.for_stage(stage);
+            stages.push(SimulatedStage {
+                stage,
+                cohort_size: cumulative - reached,
+                cumulative,
+                starts_after: elapsed,
+                dwell,
+            });
+            let next = next_stage(stage);
+            gates.push(SimulatedGate {
+                after: stage,
+                advances_to: next,
+                max_failure_rate: self.threshold(plan),
+            });
+            elapsed += dwell;
+            reached = cumulative;
+            stage = next;
+        }
+
+        RolloutSimulation {
+            population,
+            stages,
+            gates,
+            estimated_duration: elapsed,
+        }
+    }
+
+    /// Failure-rate gate for `plan`'s patch
+    pub fn threshold(&self, plan: &RolloutPlan) -> f64 {
+        self.thresholds.for_severity(plan.severity)
+    }
+
+    /// Whether `plan` may leave its current stage, given the applications
+    /// attempted in it out of `cohort_size`
+    pub fn evaluate(
+        &self,
+        plan: &RolloutPlan,
+        applied: u64,
+        failed: u64,
+        cohort_size: u64,
+    ) -> GateDecision {
+        if plan.stage == RolloutStage::Complete {
+            return GateDecision::Advance(RolloutStage::Complete);
+        }
+
+        let attempted = applied + failed;
+        let threshold = self.threshold(plan);
+        let failure_rate = if attempted == 0 {
+            0.0
+        } else {
+            failed as f64 / attempted as f64
+        };
+        // Halt as soon as the gate is crossed, even mid-stage
+        if failure_rate > threshold {
+            return GateDecision::Halt {
+                failure_rate,
+                threshold,
+            };
+        }
+        if attempted < cohort_size {
+            return GateDecision::Hold;
+        }
+        GateDecision::Advance(next_stage(plan.stage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(severity: PatchSeverity) -> RolloutPlan {
+        RolloutPlan {
+            patch_id: Uuid::new_v4(),
+            severity,
+            stage: RolloutStage::Stage2,
+        }
+    }
+
+    #[test]
+    fn failure_rate_passing_a_low_gate_halts_a_critical_rollout() {
+        let engine = RolloutEngine::default();
+        // 3 of 60 failed: 5%
+        assert_eq!(
+            engine.evaluate(&plan(PatchSeverity::Low), 57, 3, 60),
+            GateDecision::Advance(RolloutStage::Stage3)
+        );
+        assert_eq!(
+            engine.evaluate(&plan(PatchSeverity::Critical), 57, 3, 60),
+            GateDecision::Halt {
+                failure_rate: 0.05,
+                threshold: 0.01,
+            }
+        );
+    }
+
+    #[test]
+    fn thresholds_are_configurable_per_severity() {
+        let engine = RolloutEngine::new(FailureRateThresholds {
+            critical: 0.10,
+            ..FailureRateThresholds::default()
+        });
+        assert_eq!(
+            engine.evaluate(&plan(PatchSeverity::Critical), 57, 3, 60),
+            GateDecision::Advance(RolloutStage::Stage3)
+        );
+    }
+
+    #[test]
+    fn incomplete_stage_holds_until_its_cohort_is_attempted() {
+        let engine = RolloutEngine::default();
+        let low = plan(PatchSeverity::Low);
+        assert_eq!(engine.evaluate(&low, 10, 0, 60), GateDecision::Hold);
+        assert_eq!(low.cohort_percentage(), 25);
+
+        let last = RolloutPlan {
+            stage: RolloutStage::Stage4,
+            ..low
+        };
+        assert_eq!(
+            engine.evaluate(&last, 100, 0, 100),
+            GateDecision::Advance(RolloutStage::Complete)
+        );
+    }
+
+    #[test]
+    fn simulated_cohorts_cover_the_population_in_stage_order() {
+        let engine = RolloutEngine::default();
+        let plan = RolloutPlan {
+            stage: RolloutStage::Stage1,
+            ..plan(PatchSeverity::High)
+        };
+
+        for population in [0, 1, 7, 100, 1_003] {
+            let sim = engine.simulate(&plan, population);
+            let total: u64 = sim.stages.iter().map(|s| s.cohort_size).sum();
+            assert_eq!(total, population);
+            assert_eq!(sim.stages.last().unwrap().cumulative, population);
+        }
+
+        let sim = engine.simulate(&plan, 200);
+        let order: Vec<RolloutStage> = sim.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            order,
+            vec![
+                RolloutStage::Stage1,
+                RolloutStage::Stage2,
+                RolloutStage::Stage3,
+                RolloutStage::Stage4,
+            ]
+        );
+        let cohorts: Vec<u64> = sim.stages.iter().map(|s| s.cohort_size).collect();
+        assert_eq!(cohorts, vec![10, 40, 50, 100]);
+        assert!(sim
+            .stages
+            .windows(2)
+            .all(|w| w[0].starts_after + w[0].dwell == w[1].starts_after));
+
+        assert_eq!(sim.gates.len(), 4);
+        assert_eq!(sim.gates[3].advances_to, RolloutStage::Complete);
+        assert!(sim.gates.iter().all(|g| g.max_failure_rate == 0.02));
+        assert_eq!(sim.estimated_duration, Duration::hours(144));
+    }
+
+    #[test]
+    fn simulation_resumes_from_the_plans_stage_with_configured_dwell() {
+        let engine = RolloutEngine::default().with_dwell_times(StageDwellTimes {
+            stage3: Duration::hours(6),
+            stage4: Duration::hours(2),
+            ..StageDwellTimes::default()
+        });
+        let sim = engine.simulate(
+            &RolloutPlan {
+                stage: RolloutStage::Stage3,
+                ..plan(PatchSeverity::Low)
+            },
+            100,
+        );
+        let cohorts: Vec<(RolloutStage, u64)> = sim
+            .stages
+            .iter()
+            .map(|s| (s.stage, s.cohort_size))
+            .collect();
+        assert_eq!(
+            cohorts,
+            vec![(RolloutStage::Stage3, 25), (RolloutStage::Stage4, 50)]
+        );
+        assert_eq!(sim.estimated_duration, Duration::hours(8));
+
+        let done = engine.simulate(
+            &RolloutPlan {
+                stage: RolloutStage::Complete,
+                ..plan(PatchSeverity::Low)
+            },
+            100,
+        );
+        assert!(done.stages.is_empty() && done.gates.is_empty());
+    }
+}
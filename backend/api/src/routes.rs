@@ -1,11 +1,21 @@
+use std::sync::Arc;
+
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
 
 use crate::{
-    breaking_changes, custom_metrics_handlers, deprecation_handlers, handlers, metrics_handler,
+    admin_auth::{self, AdminAuth},
+    anomaly, api_key_handlers, audit_log_export, blocklist_handlers, breaking_changes, changelog,
+    compatibility_dashboard, custom_metrics_handlers, deprecation_handlers, handlers,
+    interaction_aggregates, interaction_export, metrics_handler, migration_handlers,
+    notification_preferences, patch_handlers,
+    request_signing::{self, RequestSigning},
+    reverification,
     state::AppState,
+    verification_handlers,
 };
 
 pub fn observability_routes() -> Router<AppState> {
@@ -21,7 +31,20 @@ pub fn contract_routes() -> Router<AppState> {
             get(handlers::get_trending_contracts),
         )
         .route("/api/contracts/graph", get(handlers::get_contract_graph))
+        .route(
+            "/api/contracts/versions",
+            get(handlers::get_published_versions_batch),
+        )
+        .route("/api/tags", get(handlers::list_tags))
         .route("/api/contracts/:id", get(handlers::get_contract))
+        .route(
+            "/api/contracts/by-contract-id/:contract_id",
+            get(handlers::get_contract_registrations),
+        )
+        .route(
+            "/api/contracts/logical/:logical_id",
+            get(handlers::get_logical_contract),
+        )
         .route("/api/contracts/:id/abi", get(handlers::get_contract_abi))
         .route(
             "/api/contracts/:id/openapi.yaml",
@@ -35,26 +58,67 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/:id/versions",
             get(handlers::get_contract_versions).post(handlers::create_contract_version),
         )
+        .route(
+            "/api/contracts/:id/versions/validate",
+            post(handlers::validate_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/versions/import",
+            post(handlers::import_contract_versions),
+        )
+        .route(
+            "/api/contracts/:id/versions/latest",
+            get(handlers::get_latest_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/applicable-patches",
+            get(patch_handlers::get_applicable_patches),
+        )
+        .route(
+            "/api/contracts/:id/notification-preferences",
+            get(notification_preferences::get_notification_preferences)
+                .put(notification_preferences::update_notification_preferences),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/publish",
+            post(handlers::publish_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/changelog",
+            get(changelog::get_contract_changelog),
+        )
+        .route(
+            "/api/contracts/:id/changelog.atom",
+            get(changelog::get_contract_changelog_atom),
+        )
         .route(
             "/api/contracts/breaking-changes",
             get(breaking_changes::get_breaking_changes),
         )
         .route(
-            "/api/contracts/:id/versions",
-            get(handlers::get_contract_versions),
+            "/api/contracts/compare",
+            get(breaking_changes::compare_contracts),
         )
         .route(
             "/api/contracts/:id/interactions",
-            get(handlers::get_contract_interactions).post(handlers::post_contract_interaction),
+            get(handlers::get_contract_interactions),
         )
         .route(
-            "/api/contracts/:id/interactions/batch",
-            post(handlers::post_contract_interactions_batch),
+            "/api/contracts/:id/interactions/export",
+            get(interaction_export::export_contract_interactions),
+        )
+        .route(
+            "/api/contracts/:id/anomalies",
+            get(anomaly::get_contract_anomalies),
         )
         .route(
             "/api/contracts/:id/deprecation-info",
             get(deprecation_handlers::get_deprecation_info),
         )
+        .route(
+            "/api/contracts/:id/deprecated-usage",
+            get(deprecation_handlers::get_deprecated_usage),
+        )
         .route(
             "/api/contracts/:id/deprecate",
             post(deprecation_handlers::deprecate_contract),
@@ -67,6 +131,10 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/:id/analytics",
             get(handlers::get_contract_analytics),
         )
+        .route(
+            "/api/contracts/:id/events/stream",
+            get(handlers::stream_contract_events),
+        )
         .route(
             "/api/contracts/:id/trust-score",
             get(handlers::get_trust_score),
@@ -79,15 +147,35 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/:id/dependents",
             get(handlers::get_contract_dependents),
         )
-        .route("/api/contracts/:id/deprecation-info", get(deprecation_handlers::get_deprecation_info))
-        .route("/api/contracts/:id/deprecate", post(deprecation_handlers::deprecate_contract))
-        .route("/api/contracts/:id/state/:key", get(handlers::get_contract_state).post(handlers::update_contract_state))
-        .route("/api/contracts/:id/analytics", get(handlers::get_contract_analytics))
-        .route("/api/contracts/:id/trust-score", get(handlers::get_trust_score))
-        .route("/api/contracts/:id/dependencies", get(handlers::get_contract_dependencies))
-        .route("/api/contracts/:id/dependents", get(handlers::get_contract_dependents))
         .route("/api/contracts/:id/impact", get(handlers::get_impact_analysis))
-        .route("/api/contracts/verify", post(handlers::verify_contract))
+        .route(
+            "/api/contracts/verify",
+            post(verification_handlers::verify_contract),
+        )
+        .route(
+            "/api/verifications/toolchain",
+            get(verification_handlers::get_verifier_toolchain),
+        )
+        .route(
+            "/api/verifications/:id/status",
+            get(verification_handlers::get_verification_status),
+        )
+        .route(
+            "/api/verifications/:id/source",
+            get(verification_handlers::download_verified_source),
+        )
+        .route(
+            "/api/contracts/:id/verifications/compare",
+            get(verification_handlers::compare_verifications),
+        )
+        .route(
+            "/api/contracts/:id/verify/check",
+            post(verification_handlers::check_verification),
+        )
+        .route(
+            "/api/contracts/:id/verify/artifact",
+            post(verification_handlers::verify_artifact),
+        )
         .route(
             "/api/contracts/:id/performance",
             get(handlers::get_contract_performance),
@@ -105,6 +193,10 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/:id/metrics/catalog",
             get(custom_metrics_handlers::get_metric_catalog),
         )
+        .route(
+            "/api/compatibility/dashboard",
+            get(compatibility_dashboard::get_compatibility_dashboard),
+        )
         // .route(
         //     "/api/contracts/:id/compatibility",
         //     get(compatibility_handlers::get_contract_compatibility)
@@ -124,6 +216,85 @@ pub fn contract_routes() -> Router<AppState> {
     // to be integrated with the main AppState
 }
 
+/// Interaction ingestion, which server-to-server clients may be required to
+/// sign (see `request_signing`)
+pub fn signed_ingest_routes(signing: Arc<RequestSigning>) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/contracts/:id/interactions",
+            post(handlers::post_contract_interaction),
+        )
+        .route(
+            "/api/contracts/:id/interactions/batch",
+            post(handlers::post_contract_interactions_batch),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            signing,
+            request_signing::verify_signed_request,
+        ))
+}
+
+/// Security patch endpoints; publishing, escalating, sending or previewing
+/// notices for, tracing the impact of and simulating the rollout of a patch
+/// require an operator token (see `admin_auth`). Applying one requires an API key of the contract's
+/// publisher.
+pub fn patch_routes(admin: Arc<AdminAuth>) -> Router<AppState> {
+    Router::new()
+        .route("/api/patches", post(patch_handlers::create_security_patch))
+        .route(
+            "/api/patches/:patch_id/escalate",
+            post(patch_handlers::escalate_patch_severity),
+        )
+        .route(
+            "/api/patches/:patch_id/notify",
+            post(patch_handlers::notify_patch),
+        )
+        .route(
+            "/api/patches/:patch_id/notify/preview",
+            post(patch_handlers::preview_patch_notifications),
+        )
+        .route(
+            "/api/patches/:patch_id/impact",
+            get(patch_handlers::get_patch_impact),
+        )
+        .route(
+            "/api/patches/:patch_id/rollout/simulation",
+            get(patch_handlers::simulate_patch_rollout),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            admin,
+            admin_auth::require_admin,
+        ))
+        .route(
+            "/api/patches/:patch_id",
+            get(patch_handlers::get_security_patch),
+        )
+        .route(
+            "/api/patches/:patch_id/apply",
+            post(patch_handlers::apply_patch),
+        )
+        .route(
+            "/api/patches/:patch_id/audits",
+            get(patch_handlers::get_patch_applications),
+        )
+        .route(
+            "/api/patches/:patch_id/rollout",
+            get(patch_handlers::get_patch_rollout),
+        )
+        .route(
+            "/api/patches/:patch_id/acknowledge",
+            post(patch_handlers::acknowledge_patch),
+        )
+        .route(
+            "/api/patches/:patch_id/acknowledgements",
+            get(patch_handlers::get_patch_acknowledgements),
+        )
+        .route(
+            "/api/patches/:patch_id/delivery-receipt",
+            post(patch_handlers::confirm_patch_delivery),
+        )
+}
+
 pub fn publisher_routes() -> Router<AppState> {
     Router::new()
         .route("/api/publishers", post(handlers::create_publisher))
@@ -132,6 +303,10 @@ pub fn publisher_routes() -> Router<AppState> {
             "/api/publishers/:id/contracts",
             get(handlers::get_publisher_contracts),
         )
+        .route(
+            "/api/publishers/:id/patches",
+            get(patch_handlers::get_publisher_patches),
+        )
 }
 
 pub fn health_routes() -> Router<AppState> {
@@ -140,8 +315,79 @@ pub fn health_routes() -> Router<AppState> {
         .route("/api/stats", get(handlers::get_stats))
 }
 
+/// Operator endpoints; every route requires an operator token (see
+/// `admin_auth`).
+pub fn admin_routes(admin: Arc<AdminAuth>) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/admin/api-keys",
+            post(api_key_handlers::create_api_key),
+        )
+        .route(
+            "/api/admin/api-keys/:key_id",
+            delete(api_key_handlers::revoke_api_key),
+        )
+        .route(
+            "/api/contracts/:id/interactions",
+            delete(interaction_aggregates::erase_account_interactions),
+        )
+        .route(
+            "/api/admin/contracts/:id/aggregates/rebuild",
+            post(interaction_aggregates::rebuild_contract_aggregates),
+        )
+        .route(
+            "/api/admin/contracts/:id/versions/import",
+            post(handlers::admin_import_contract_versions),
+        )
+        .route(
+            "/api/admin/verifications/:id/source-url",
+            post(verification_handlers::create_source_url),
+        )
+        .route(
+            "/api/admin/reverify",
+            post(reverification::start_reverification),
+        )
+        .route(
+            "/api/admin/reverify/:job_id",
+            get(reverification::get_reverification_status),
+        )
+        .route(
+            "/api/admin/blocked-contracts",
+            get(blocklist_handlers::list_blocked_contracts)
+                .post(blocklist_handlers::block_contract),
+        )
+        .route(
+            "/api/admin/blocked-contracts/:contract_id",
+            delete(blocklist_handlers::unblock_contract),
+        )
+        .route(
+            "/api/admin/blocked-contracts/:contract_id/audit",
+            get(blocklist_handlers::get_blocklist_audit),
+        )
+        .route(
+            "/api/admin/audit-log/export",
+            get(audit_log_export::export_audit_log),
+        )
+        .route(
+            "/api/admin/audit-log/retention",
+            get(audit_log_export::get_retention_status),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            admin,
+            admin_auth::require_admin,
+        ))
+}
+
 pub fn migration_routes() -> Router<AppState> {
     Router::new()
+        .route(
+            "/api/migrations/scripts",
+            post(migration_handlers::register_migration),
+        )
+        .route(
+            "/api/migrations/scripts/validate",
+            post(migration_handlers::validate_migrations),
+        )
 }
 
 pub fn canary_routes() -> Router<AppState> {
@@ -1,4 +1,11 @@
+use crate::audit_retention::{AuditRetention, AuditRetentionConfig};
+use crate::blocklist::Blocklist;
 use crate::cache::{CacheConfig, CacheLayer};
+use crate::clock::{Clock, SystemClock};
+use crate::event_stream::EventHub;
+use crate::pagination::PaginationConfig;
+use crate::trending::TrendingConfig;
+use crate::verification_queue::BuildQueue;
 use prometheus::Registry;
 use sqlx::PgPool;
 use std::sync::atomic::AtomicBool;
@@ -13,6 +20,24 @@ pub struct AppState {
     pub cache: Arc<CacheLayer>,
     pub registry: Registry,
     pub is_shutting_down: Arc<AtomicBool>,
+    pub pagination: PaginationConfig,
+    pub trending: TrendingConfig,
+    /// Live analytics events for SSE subscribers
+    pub events: Arc<EventHub>,
+    /// Build slots for API-submitted source verifications
+    pub verification_queue: Arc<BuildQueue>,
+    /// Contract IDs refused at publish and interaction time
+    pub blocklist: Arc<Blocklist>,
+    /// Audit-log retention settings and job status
+    pub audit_retention: Arc<AuditRetention>,
+    /// Current time for health scoring, interaction defaults and trending
+    pub clock: Arc<dyn Clock>,
+    /// Whether the last verifier toolchain self-test passed; false until the
+    /// first one has run
+    pub verifier_ready: Arc<AtomicBool>,
+    /// Secret patch-notice receipt keys are derived from; receipts are
+    /// refused without it
+    pub patch_receipt_secret: Option<String>,
 }
 
 impl AppState {
@@ -24,6 +49,53 @@ impl AppState {
             cache: Arc::new(CacheLayer::new(config)),
             registry,
             is_shutting_down,
+            pagination: PaginationConfig::default(),
+            trending: TrendingConfig::default(),
+            events: Arc::new(EventHub::default()),
+            verification_queue: Arc::new(BuildQueue::default()),
+            blocklist: Arc::new(Blocklist::default()),
+            audit_retention: Arc::new(AuditRetention::default()),
+            clock: Arc::new(SystemClock),
+            verifier_ready: Arc::new(AtomicBool::new(false)),
+            patch_receipt_secret: None,
         }
     }
+
+    /// Use page-size limits loaded from configuration
+    pub fn with_pagination(mut self, pagination: PaginationConfig) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    /// Use the trending growth ratio loaded from configuration
+    pub fn with_trending(mut self, trending: TrendingConfig) -> Self {
+        self.trending = trending;
+        self
+    }
+
+    /// Allow up to `max_concurrent` verification builds at once
+    pub fn with_max_concurrent_builds(mut self, max_concurrent: usize) -> Self {
+        self.verification_queue = Arc::new(BuildQueue::new(max_concurrent));
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Derive patch-notice receipt keys from `secret`; blank disables receipts
+    pub fn with_patch_receipt_secret(mut self, secret: Option<String>) -> Self {
+        self.patch_receipt_secret = secret
+            .map(|secret| secret.trim().to_string())
+            .filter(|secret| !secret.is_empty());
+        self
+    }
+
+    /// Use the audit-log retention settings loaded from configuration
+    pub fn with_audit_retention(mut self, config: AuditRetentionConfig) -> Self {
+        self.audit_retention = Arc::new(AuditRetention::new(config));
+        self
+    }
 }
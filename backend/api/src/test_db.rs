@@ -0,0 +1,154 @@
+// Throwaway Postgres databases for tests that run real queries.
+//
+// Point `TEST_DATABASE_URL` at a server the tests may create databases on,
+// e.g. `postgres://postgres@localhost/postgres`. The migrations are applied
+// once to a template database named after their checksums, and every test
+// gets its own copy of it. Without the variable these tests skip themselves.
+
+use std::sync::{atomic::AtomicBool, Arc};
+
+use prometheus::Registry;
+use sha2::{Digest, Sha256};
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions},
+    Connection, Executor, PgConnection, PgPool,
+};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+static MIGRATOR: Migrator = sqlx::migrate!("../../database/migrations");
+
+/// Serializes template creation across test threads and processes
+const TEMPLATE_LOCK_KEY: i64 = 0x7265_6769_7374_7279;
+
+/// A fresh, fully migrated database, or `None` when `TEST_DATABASE_URL` is
+/// not set
+pub async fn pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set; skipping database test");
+        return None;
+    };
+    let server: PgConnectOptions = url.parse().expect("invalid TEST_DATABASE_URL");
+    let template = template_name();
+    let database = format!("registry_test_{}", Uuid::new_v4().simple());
+
+    let mut admin = PgConnection::connect_with(&server)
+        .await
+        .expect("connect to TEST_DATABASE_URL");
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(TEMPLATE_LOCK_KEY)
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)")
+            .bind(&template)
+            .fetch_one(&mut admin)
+            .await
+            .unwrap();
+    if !exists {
+        // Built under a scratch name so a failed run leaves no half-migrated
+        // template behind
+        let building = format!("{}_building", template);
+        admin
+            .execute(format!("DROP DATABASE IF EXISTS {}", building).as_str())
+            .await
+            .unwrap();
+        admin
+            .execute(format!("CREATE DATABASE {}", building).as_str())
+            .await
+            .unwrap();
+        let mut conn = PgConnection::connect_with(&server.clone().database(&building))
+            .await
+            .unwrap();
+        // Applied one by one rather than through `Migrator::run`, which
+        // refuses the two migrations sharing version 003
+        for migration in MIGRATOR.iter() {
+            sqlx::raw_sql(&migration.sql)
+                .execute(&mut conn)
+                .await
+                .unwrap_or_else(|err| panic!("migration {}: {}", migration.description, err));
+        }
+        conn.close().await.unwrap();
+        admin
+            .execute(format!("ALTER DATABASE {} RENAME TO {}", building, template).as_str())
+            .await
+            .unwrap();
+    }
+    admin
+        .execute(format!("CREATE DATABASE {} TEMPLATE {}", database, template).as_str())
+        .await
+        .unwrap();
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(TEMPLATE_LOCK_KEY)
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    admin.close().await.unwrap();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(server.database(&database))
+        .await
+        .unwrap();
+    Some(pool)
+}
+
+/// Application state over a fresh database
+pub async fn state() -> Option<AppState> {
+    let pool = pool().await?;
+    let state = AppState::new(pool, Registry::new(), Arc::new(AtomicBool::new(false)));
+    state.blocklist.reload(&state.db).await.unwrap();
+    Some(state)
+}
+
+fn template_name() -> String {
+    let mut hasher = Sha256::new();
+    for migration in MIGRATOR.iter() {
+        hasher.update(migration.version.to_be_bytes());
+        hasher.update(&migration.checksum);
+    }
+    format!(
+        "registry_test_template_{}",
+        &hex::encode(hasher.finalize())[..16]
+    )
+}
+
+pub async fn insert_publisher(pool: &PgPool) -> Uuid {
+    sqlx::query_scalar("INSERT INTO publishers (stellar_address) VALUES ($1) RETURNING id")
+        .bind(format!("G{}", Uuid::new_v4().simple()))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// A testnet contract with on-chain ID `contract_id`, returning its row ID
+pub async fn insert_contract(pool: &PgPool, publisher_id: Uuid, contract_id: &str) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO contracts (contract_id, wasm_hash, name, publisher_id, network) \
+         VALUES ($1, $2, $1, $3, 'testnet') RETURNING id",
+    )
+    .bind(contract_id)
+    .bind(format!("{:0>64}", contract_id.to_lowercase()))
+    .bind(publisher_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// A version of `contract` with `status` `published` or `draft`
+pub async fn insert_version(pool: &PgPool, contract: Uuid, version: &str, status: &str) -> Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO contract_versions (contract_id, version, wasm_hash, status) \
+         VALUES ($1, $2, $3, $4::version_status) RETURNING id",
+    )
+    .bind(contract)
+    .bind(version)
+    .bind(format!("{:0>64}", version.replace('.', "")))
+    .bind(status)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
@@ -0,0 +1,169 @@
+// What counts as "trending".
+//
+// A contract is trending when its interactions in the current window exceed
+// `growth_ratio` times those in the window just before it. The ratio is bound
+// into every query that classifies contracts, and `is_trending` applies the
+// same comparison in Rust, so the trending listing and the per-contract flag
+// cannot disagree. Operators tune it with `TRENDING_GROWTH_RATIO`.
+//
+// Window boundaries are computed from the application clock and bound into
+// the queries rather than taken from the database's NOW().
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Whether contract `$1` is trending, with the current window starting at
+/// `$2`, the previous one at `$3`, at growth ratio `$4`
+pub const CONTRACT_IS_TRENDING_SQL: &str = r#"
+    SELECT w.recent > w.previous * $4
+    FROM (
+        SELECT
+            COUNT(*) FILTER (WHERE ci.created_at >= $2) AS recent,
+            COUNT(*) FILTER (WHERE ci.created_at < $2) AS previous
+        FROM contract_interactions ci
+        WHERE ci.contract_id = $1
+          AND ci.created_at >= $3
+    ) w
+"#;
+
+/// Trending contracts, most active first: current window starting at `$1`,
+/// previous window at `$2`, `$3` growth ratio, `$4` limit
+pub const TRENDING_CONTRACTS_SQL: &str = r#"
+    SELECT c.id, c.contract_id, c.name, c.description, c.network, c.is_verified,
+           c.category, c.tags, c.created_at, c.popularity_score,
+           (SELECT COUNT(*) FROM contract_deployments cd WHERE cd.contract_id = c.id)
+               AS deployment_count,
+           w.recent AS interaction_count
+    FROM contracts c
+    JOIN LATERAL (
+        SELECT
+            COUNT(*) FILTER (WHERE ci.created_at >= $1) AS recent,
+            COUNT(*) FILTER (WHERE ci.created_at < $1) AS previous
+        FROM contract_interactions ci
+        WHERE ci.contract_id = c.id
+          AND ci.created_at >= $2
+    ) w ON true
+    WHERE w.recent > w.previous * $3
+    ORDER BY w.recent DESC, c.popularity_score DESC
+    LIMIT $4
+"#;
+
+/// Window length for a `timeframe` of "7d", "30d" or "90d" (default 7 days)
+pub fn timeframe_days(timeframe: Option<&str>) -> i32 {
+    match timeframe {
+        Some("30d") => 30,
+        Some("90d") => 90,
+        _ => 7,
+    }
+}
+
+/// The current trending window and the one just before it, both `days` long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrendingWindows {
+    pub current_start: DateTime<Utc>,
+    pub previous_start: DateTime<Utc>,
+}
+
+impl TrendingWindows {
+    /// Windows whose current one ends at `now`
+    pub fn ending_at(now: DateTime<Utc>, days: i32) -> Self {
+        let length = Duration::days(i64::from(days));
+        Self {
+            current_start: now - length,
+            previous_start: now - length * 2,
+        }
+    }
+}
+
+/// The same comparison the trending queries make
+pub fn is_trending(recent: i64, previous: i64, growth_ratio: f64) -> bool {
+    recent as f64 > previous as f64 * growth_ratio
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrendingConfig {
+    /// Minimum growth of the current window over the previous one
+    pub growth_ratio: f64,
+}
+
+impl Default for TrendingConfig {
+    fn default() -> Self {
+        Self { growth_ratio: 1.5 }
+    }
+}
+
+impl TrendingConfig {
+    /// Load `TRENDING_GROWTH_RATIO`, which must be a number above 1.0
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let Some(raw) = lookup("TRENDING_GROWTH_RATIO") else {
+            return Ok(Self::default());
+        };
+        let growth_ratio: f64 = raw.trim().parse().map_err(|_| {
+            format!(
+                "TRENDING_GROWTH_RATIO must be a number (got '{}')",
+                raw.trim()
+            )
+        })?;
+        if !growth_ratio.is_finite() || growth_ratio <= 1.0 {
+            return Err(format!(
+                "TRENDING_GROWTH_RATIO must be greater than 1.0 (got {})",
+                growth_ratio
+            ));
+        }
+        Ok(Self { growth_ratio })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(value: Option<&str>) -> Result<TrendingConfig, String> {
+        TrendingConfig::from_lookup(|_| value.map(str::to_string))
+    }
+
+    #[test]
+    fn contracts_either_side_of_the_threshold_are_classified_correctly() {
+        let config = load(Some("2.0")).unwrap();
+        // 100 interactions last window: 201 is just above 2x, 199 just below
+        assert!(is_trending(201, 100, config.growth_ratio));
+        assert!(!is_trending(199, 100, config.growth_ratio));
+        // Exactly at the ratio is not growth beyond it
+        assert!(!is_trending(200, 100, config.growth_ratio));
+
+        let default = TrendingConfig::default();
+        assert!(is_trending(151, 100, default.growth_ratio));
+        assert!(!is_trending(149, 100, default.growth_ratio));
+    }
+
+    #[test]
+    fn ratio_must_exceed_one() {
+        assert_eq!(load(None).unwrap(), TrendingConfig::default());
+        assert!(load(Some("1.0")).is_err());
+        assert!(load(Some("0.5")).is_err());
+        assert!(load(Some("fast")).is_err());
+        assert!(load(Some("inf")).is_err());
+    }
+
+    #[test]
+    fn windows_split_at_exact_day_boundaries() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2026, 3, 8, 0, 0, 0).unwrap();
+        let windows = TrendingWindows::ending_at(now, 7);
+        assert_eq!(
+            windows.current_start,
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            windows.previous_start,
+            Utc.with_ymd_and_hms(2026, 2, 22, 0, 0, 0).unwrap()
+        );
+
+        let month = TrendingWindows::ending_at(now, timeframe_days(Some("30d")));
+        assert_eq!(month.current_start, now - Duration::days(30));
+        assert_eq!(month.previous_start, now - Duration::days(60));
+    }
+}
@@ -6,7 +6,7 @@
 use axum::{
     async_trait,
     extract::{FromRequest, Request},
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     Json,
 };
 use chrono::{SecondsFormat, Utc};
@@ -14,6 +14,9 @@ use serde::{de::DeserializeOwned, Serialize};
 use uuid::Uuid;
 
 /// A field-level validation error
+///
+/// `field` is a path into the request body (e.g. `name` or
+/// `dependencies[0].name`) so clients can highlight the offending input.
 #[derive(Debug, Clone, Serialize)]
 pub struct FieldError {
     pub field: String,
@@ -41,7 +44,13 @@ pub struct ValidationErrorResponse {
 }
 
 impl ValidationErrorResponse {
+    /// Build a response for field-level failures (422 Unprocessable Entity)
     pub fn new(errors: Vec<FieldError>) -> Self {
+        Self::with_status(errors, StatusCode::UNPROCESSABLE_ENTITY)
+    }
+
+    /// Build a response carrying an explicit status code
+    pub fn with_status(errors: Vec<FieldError>, status: StatusCode) -> Self {
         let error_summary = if errors.len() == 1 {
             format!("Validation failed for field '{}'", errors[0].field)
         } else {
@@ -52,7 +61,7 @@ impl ValidationErrorResponse {
             error: "ValidationError".to_string(),
             message: error_summary,
             errors,
-            code: 400,
+            code: status.as_u16(),
             timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
             correlation_id: Uuid::new_v4().to_string(),
         }
@@ -60,27 +69,49 @@ impl ValidationErrorResponse {
 }
 
 /// Validation error that converts to an HTTP response
+///
+/// Field-level failures are reported as 422 Unprocessable Entity; a body
+/// that cannot be parsed at all is reported as 400 Bad Request.
 #[derive(Debug)]
 pub struct ValidationError {
+    pub status: StatusCode,
     pub errors: Vec<FieldError>,
 }
 
 impl ValidationError {
     pub fn new(errors: Vec<FieldError>) -> Self {
-        Self { errors }
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            errors,
+        }
     }
 
     pub fn single(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(vec![FieldError::new(field, message)])
+    }
+
+    /// The request body could not be parsed into the target type
+    pub fn malformed_body(message: impl Into<String>) -> Self {
         Self {
-            errors: vec![FieldError::new(field, message)],
+            status: StatusCode::BAD_REQUEST,
+            errors: vec![FieldError::new("body", message)],
         }
     }
 }
 
 impl axum::response::IntoResponse for ValidationError {
     fn into_response(self) -> axum::response::Response {
-        let response = ValidationErrorResponse::new(self.errors);
-        (StatusCode::BAD_REQUEST, Json(response)).into_response()
+        let status = self.status;
+        let payload = ValidationErrorResponse::with_status(self.errors, status);
+        let correlation_id = payload.correlation_id.clone();
+
+        let mut response = (status, Json(payload)).into_response();
+        if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+            response
+                .headers_mut()
+                .insert(header::HeaderName::from_static("x-correlation-id"), value);
+        }
+        response
     }
 }
 
@@ -102,7 +133,8 @@ pub trait Validatable: Sized {
 /// 1. Parse JSON from the request body
 /// 2. Sanitize all string fields (trim, strip HTML, normalize)
 /// 3. Validate fields against defined rules
-/// 4. Return detailed 400 errors for validation failures
+/// 4. Return detailed 422 errors for validation failures (400 if the body
+///    is not valid JSON for `T`)
 ///
 /// # Example
 ///
@@ -145,7 +177,7 @@ where
                 }
                 _ => "Invalid JSON payload".to_string(),
             };
-            ValidationError::single("body", message)
+            ValidationError::malformed_body(message)
         })?;
 
         // Step 2: Sanitize the data
@@ -276,7 +308,7 @@ mod tests {
         let response = ValidationErrorResponse::new(errors);
 
         assert_eq!(response.error, "ValidationError");
-        assert_eq!(response.code, 400);
+        assert_eq!(response.code, 422);
         assert_eq!(response.errors.len(), 2);
         assert!(response.message.contains("2 fields"));
     }
@@ -288,4 +320,75 @@ mod tests {
 
         assert!(response.message.contains("field 'name'"));
     }
+
+    async fn post_publish(body: &str) -> (StatusCode, serde_json::Value) {
+        use axum::{body::Body, routing::post, Router};
+        use shared::models::PublishRequest;
+        use tower::ServiceExt;
+
+        async fn handler(ValidatedJson(_req): ValidatedJson<PublishRequest>) -> StatusCode {
+            StatusCode::CREATED
+        }
+
+        let app = Router::new().route("/api/contracts", post(handler));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/contracts")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+        (status, json)
+    }
+
+    #[tokio::test]
+    async fn test_invalid_publish_request_returns_field_paths() {
+        let body = serde_json::json!({
+            "contract_id": "not-a-contract",
+            "name": "My Contract",
+            "network": "testnet",
+            "tags": [],
+            "publisher_address": "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H",
+            "dependencies": [
+                { "name": "", "version_constraint": "^1.0.0" }
+            ]
+        });
+
+        let (status, json) = post_publish(&body.to_string()).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["error"], "ValidationError");
+        assert_eq!(json["code"], 422);
+
+        let errors = json["errors"].as_array().unwrap();
+        let fields: Vec<&str> = errors
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert!(fields.contains(&"contract_id"));
+        assert!(fields.contains(&"dependencies[0].name"));
+        assert!(errors.iter().all(|e| e["message"].is_string()));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_publish_body_returns_bad_request() {
+        let (status, json) = post_publish("{ not json").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["errors"][0]["field"], "body");
+    }
 }
@@ -5,11 +5,13 @@
 //!
 //! # Overview
 //!
-//! The validation system consists of three main components:
+//! The validation system consists of five main components:
 //!
 //! 1. **Extractors** - Custom Axum extractors like `ValidatedJson<T>`
 //! 2. **Validators** - Reusable validation functions for common patterns
 //! 3. **Sanitizers** - Functions to clean and normalize input data
+//! 4. **Policies** - Which markup each free-text field may keep
+//! 5. **Taxonomy** - Optional curated tag list with synonym normalization
 //!
 //! # Usage
 //!
@@ -41,7 +43,10 @@
 //!
 //! ## Validation Error Response
 //!
-//! When validation fails, a 400 Bad Request is returned:
+//! When validation fails, a 422 Unprocessable Entity is returned with one
+//! entry per offending field (nested paths such as `dependencies[0].name`
+//! are preserved). A body that is not valid JSON yields a 400 with a single
+//! `body` error.
 //!
 //! ```json
 //! {
@@ -51,27 +56,31 @@
 //!     {"field": "contract_id", "message": "must be a valid Stellar contract ID"},
 //!     {"field": "name", "message": "must be at least 1 character"}
 //!   ],
-//!   "code": 400,
+//!   "code": 422,
 //!   "timestamp": "2026-02-20T10:30:00Z",
 //!   "correlation_id": "uuid-here"
 //! }
 //! ```
 
 pub mod extractors;
+pub mod policy;
 pub mod requests;
 pub mod sanitizers;
+pub mod taxonomy;
 pub mod validators;
 
 // Re-export commonly used items
 pub use extractors::{FieldError, Validatable, ValidatedJson, ValidationBuilder, ValidationError};
+pub use policy::SanitizePolicy;
 pub use sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description,
-    sanitize_description_optional, sanitize_name, sanitize_tags, sanitize_url_optional, strip_html,
-    trim, trim_optional,
+    sanitize_description_optional, sanitize_name, sanitize_tags, sanitize_text,
+    sanitize_text_optional, sanitize_url_optional, strip_html, trim, trim_optional,
 };
 pub use validators::{
-    validate_contract_id, validate_length, validate_network_config_versions, validate_no_html,
-    validate_no_xss, validate_required, validate_semver, validate_source_code_size,
-    validate_stellar_address, validate_stellar_address_optional, validate_tags, validate_url,
-    validate_url_optional,
+    validate_callback_url, validate_contract_id, validate_json_size, validate_length,
+    validate_network_config_versions, validate_no_html, validate_no_xss, validate_required,
+    validate_semver, validate_semver_range, validate_signature_algorithm_optional,
+    validate_source_code_size, validate_stellar_address, validate_stellar_address_optional,
+    validate_strkey_checksum, validate_tags, validate_url, validate_url_optional, JsonLimits,
 };
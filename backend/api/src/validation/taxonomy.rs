@@ -0,0 +1,248 @@
+//! Curated tag taxonomy
+//!
+//! Tags are free-form by default. With `TAG_TAXONOMY_MODE=normalize`, known
+//! spellings of a curated tag ("DeFi", "de-fi", "decentralized-finance") are
+//! rewritten to its canonical form; `strict` additionally rejects tags the
+//! taxonomy does not know. The built-in taxonomy can be replaced with a JSON
+//! object of `{ "canonical": ["synonym", ...] }` named by `TAG_TAXONOMY_FILE`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+lazy_static! {
+    static ref TAG_TAXONOMY: TagTaxonomy = TagTaxonomy::from_env();
+}
+
+/// The taxonomy configured for this process
+pub fn current() -> &'static TagTaxonomy {
+    &TAG_TAXONOMY
+}
+
+/// How submitted tags are checked against the taxonomy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMode {
+    /// Tags are kept as submitted
+    #[default]
+    FreeForm,
+    /// Known synonyms become their canonical tag; unknown tags are kept
+    Normalize,
+    /// As `Normalize`, and tags outside the taxonomy are rejected
+    Strict,
+}
+
+impl std::str::FromStr for TagMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "free_form" | "free-form" | "off" => Ok(Self::FreeForm),
+            "normalize" => Ok(Self::Normalize),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!(
+                "unknown tag taxonomy mode '{}'; expected free_form, normalize or strict",
+                other
+            )),
+        }
+    }
+}
+
+const DEFAULT_TAXONOMY: &[(&str, &[&str])] = &[
+    ("defi", &["decentralized-finance", "decentralised-finance"]),
+    ("dex", &["decentralized-exchange", "exchange", "amm"]),
+    ("lending", &["loans", "borrowing"]),
+    ("stablecoin", &["stable-coin", "stable"]),
+    ("token", &["tokens", "fungible-token", "sep-41"]),
+    ("nft", &["nfts", "non-fungible-token", "collectible"]),
+    ("dao", &["governance", "voting"]),
+    ("oracle", &["oracles", "price-feed"]),
+    ("bridge", &["cross-chain", "interoperability"]),
+    ("payments", &["payment", "remittance"]),
+    ("gaming", &["game", "games"]),
+    ("identity", &["did", "kyc"]),
+    ("multisig", &["multi-sig", "multisignature"]),
+    ("escrow", &[]),
+    ("staking", &["stake", "yield"]),
+];
+
+/// Spelling-insensitive lookup key: lowercase letters and digits only, so
+/// "De-Fi", "de fi" and "defi" collide
+fn key(tag: &str) -> String {
+    tag.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// One curated tag, as listed by GET /api/tags
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaxonomyTag {
+    pub tag: String,
+    pub synonyms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TagTaxonomy {
+    pub mode: TagMode,
+    tags: BTreeMap<String, Vec<String>>,
+    /// Lookup key of every canonical tag and synonym -> canonical tag
+    canonical: HashMap<String, String>,
+}
+
+impl TagTaxonomy {
+    pub fn new(mode: TagMode, tags: BTreeMap<String, Vec<String>>) -> Self {
+        let mut canonical = HashMap::new();
+        for (tag, synonyms) in &tags {
+            for spelling in std::iter::once(tag).chain(synonyms) {
+                canonical.insert(key(spelling), tag.clone());
+            }
+        }
+        Self {
+            mode,
+            tags,
+            canonical,
+        }
+    }
+
+    /// The built-in taxonomy under `mode`
+    pub fn builtin(mode: TagMode) -> Self {
+        Self::new(
+            mode,
+            DEFAULT_TAXONOMY
+                .iter()
+                .map(|(tag, synonyms)| {
+                    (
+                        tag.to_string(),
+                        synonyms.iter().map(|s| s.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// `TAG_TAXONOMY_MODE` and `TAG_TAXONOMY_FILE`; an invalid mode or an
+    /// unreadable file falls back to free-form tags or the built-in list
+    pub fn from_env() -> Self {
+        let mode = std::env::var("TAG_TAXONOMY_MODE")
+            .ok()
+            .and_then(|v| match v.parse() {
+                Ok(mode) => Some(mode),
+                Err(err) => {
+                    tracing::warn!("ignoring TAG_TAXONOMY_MODE: {}", err);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let Ok(path) = std::env::var("TAG_TAXONOMY_FILE") else {
+            return Self::builtin(mode);
+        };
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()));
+        match loaded {
+            Ok(tags) => Self::new(mode, tags),
+            Err(err) => {
+                tracing::warn!(path = %path, "ignoring TAG_TAXONOMY_FILE: {}", err);
+                Self::builtin(mode)
+            }
+        }
+    }
+
+    /// Canonical form of `tag`, if the taxonomy knows it under any spelling
+    pub fn canonical(&self, tag: &str) -> Option<&str> {
+        self.canonical.get(&key(tag)).map(String::as_str)
+    }
+
+    /// `tags` with known synonyms replaced by their canonical tag and the
+    /// duplicates that leaves removed; unchanged in free-form mode
+    pub fn normalize(&self, tags: Vec<String>) -> Vec<String> {
+        if self.mode == TagMode::FreeForm {
+            return tags;
+        }
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = self.canonical(&tag).map_or(tag, str::to_string);
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+
+    /// In strict mode, reject any tag outside the taxonomy
+    pub fn check(&self, tags: &[String]) -> Result<(), String> {
+        if self.mode != TagMode::Strict {
+            return Ok(());
+        }
+        match tags.iter().find(|tag| self.canonical(tag).is_none()) {
+            Some(unknown) => Err(format!(
+                "tag '{}' is not in the tag taxonomy; see GET /api/tags",
+                unknown.trim()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Every curated tag with its synonyms, alphabetically
+    pub fn list(&self) -> Vec<TaxonomyTag> {
+        self.tags
+            .iter()
+            .map(|(tag, synonyms)| TaxonomyTag {
+                tag: tag.clone(),
+                synonyms: synonyms.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn synonyms_normalize_to_their_canonical_tag() {
+        let taxonomy = TagTaxonomy::builtin(TagMode::Normalize);
+        assert_eq!(
+            taxonomy.normalize(tags(&[
+                "DeFi",
+                "de-fi",
+                "Decentralized Finance",
+                "NFTs",
+                "custom"
+            ])),
+            tags(&["defi", "nft", "custom"])
+        );
+        assert!(taxonomy.check(&tags(&["custom"])).is_ok());
+
+        // Free-form mode leaves tags alone
+        let free = TagTaxonomy::builtin(TagMode::FreeForm);
+        assert_eq!(
+            free.normalize(tags(&["DeFi", "de-fi"])),
+            tags(&["DeFi", "de-fi"])
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_tags() {
+        let taxonomy = TagTaxonomy::builtin(TagMode::Strict);
+        let normalized = taxonomy.normalize(tags(&["De-Fi", "oracle"]));
+        assert!(taxonomy.check(&normalized).is_ok());
+
+        let err = taxonomy.check(&tags(&["defi", "memecoin"])).unwrap_err();
+        assert!(err.contains("memecoin"));
+    }
+
+    #[test]
+    fn parses_modes() {
+        assert_eq!("Strict".parse(), Ok(TagMode::Strict));
+        assert_eq!("free-form".parse(), Ok(TagMode::FreeForm));
+        assert!("loose".parse::<TagMode>().is_err());
+        assert_eq!(TagMode::default(), TagMode::FreeForm);
+    }
+}
@@ -6,12 +6,14 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use super::taxonomy::{self, TagTaxonomy};
+
 lazy_static! {
     /// Stellar contract ID pattern: 56 characters starting with 'C'
     static ref CONTRACT_ID_REGEX: Regex = Regex::new(r"^C[A-Z0-9]{55}$").unwrap();
 
-    /// Stellar address pattern: 56 characters starting with 'G'
-    static ref STELLAR_ADDRESS_REGEX: Regex = Regex::new(r"^G[A-Z0-9]{55}$").unwrap();
+    /// Stellar address pattern: 56 base32 characters starting with 'G'
+    static ref STELLAR_ADDRESS_REGEX: Regex = Regex::new(r"^G[A-Z2-7]{55}$").unwrap();
 
     /// Semver pattern: major.minor.patch with optional pre-release
     static ref SEMVER_REGEX: Regex = Regex::new(
@@ -23,6 +25,14 @@ lazy_static! {
         r"^https?://[^\s/$.?#].[^\s]*$"
     ).unwrap();
 
+    /// Git remote for a source reference: https URL or scp-style SSH
+    static ref GIT_URL_REGEX: Regex = Regex::new(
+        r"^(https://[^\s/@]+/[^\s]+|git@[^\s:/]+:[^\s]+)$"
+    ).unwrap();
+
+    /// Full git object id: 40 hex chars (SHA-1) or 64 (SHA-256)
+    static ref GIT_COMMIT_REGEX: Regex = Regex::new(r"^([0-9a-f]{40}|[0-9a-f]{64})$").unwrap();
+
     /// HTML tag detection pattern
     static ref HTML_TAG_REGEX: Regex = Regex::new(r"<[^>]+>").unwrap();
 
@@ -32,6 +42,67 @@ lazy_static! {
     ).unwrap();
 }
 
+/// Strkey version byte of an ed25519 account ID (`G...`)
+const STRKEY_ACCOUNT_ID: u8 = 6 << 3;
+
+/// Whether `c` belongs to the RFC 4648 base32 alphabet strkeys are written
+/// in, ignoring case
+pub fn is_strkey_char(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), 'A'..='Z' | '2'..='7')
+}
+
+/// Check that a canonical (uppercase) strkey carries `version` and a valid
+/// CRC16-XModem checksum over its version byte and 32-byte payload
+pub fn validate_strkey_checksum(strkey: &str, version: u8) -> Result<(), String> {
+    let decoded = decode_base32(strkey).ok_or("must be base32 encoded")?;
+    let [ref body @ .., lo, hi] = decoded[..] else {
+        return Err("is too short to carry a checksum".to_string());
+    };
+    if body.len() != 33 || body[0] != version {
+        return Err("has the wrong strkey length or version".to_string());
+    }
+    if crc16_xmodem(body) != u16::from_le_bytes([lo, hi]) {
+        return Err("has an invalid checksum".to_string());
+    }
+    Ok(())
+}
+
+fn decode_base32(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in value.bytes() {
+        let digit = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u32::from(digit);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // Leftover bits are padding and must be zero
+    (buffer == 0).then_some(bytes)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
 /// Validate that a string is not empty after trimming
 pub fn validate_required(value: &str, field_name: &str) -> Result<(), String> {
     if value.trim().is_empty() {
@@ -91,7 +162,8 @@ pub fn validate_contract_id(contract_id: &str) -> Result<(), String> {
 }
 
 /// Validate Stellar address format
-/// Must be 56 characters starting with 'G'
+/// Must be a 56-character account strkey starting with 'G' whose checksum
+/// matches. Expects input already run through `normalize_stellar_address`.
 pub fn validate_stellar_address(address: &str) -> Result<(), String> {
     let trimmed = address.trim();
 
@@ -105,7 +177,8 @@ pub fn validate_stellar_address(address: &str) -> Result<(), String> {
         );
     }
 
-    Ok(())
+    validate_strkey_checksum(trimmed, STRKEY_ACCOUNT_ID)
+        .map_err(|err| format!("stellar address {}", err))
 }
 
 /// Validate optional Stellar address (only validates if Some)
@@ -154,6 +227,31 @@ pub fn validate_url_optional(url: &Option<String>) -> Result<(), String> {
     }
 }
 
+/// Validate a webhook callback URL: https, or plain http to a loopback host
+/// for local development
+pub fn validate_callback_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| "must be a valid URL".to_string())?;
+    let Some(host) = parsed.host_str() else {
+        return Err("must include a host".to_string());
+    };
+    match parsed.scheme() {
+        "https" => Ok(()),
+        "http" if matches!(host, "localhost" | "127.0.0.1" | "[::1]") => Ok(()),
+        _ => Err("must use https:// (http:// is only accepted for localhost)".to_string()),
+    }
+}
+
+/// Validate a git source reference: a clonable remote and a full commit hash
+pub fn validate_git_reference(git_url: &str, commit: &str) -> Result<(), String> {
+    if !GIT_URL_REGEX.is_match(git_url.trim()) {
+        return Err("git_url must be an https:// or git@host:path repository URL".to_string());
+    }
+    if !GIT_COMMIT_REGEX.is_match(commit.trim()) {
+        return Err("commit must be a full lowercase hex commit hash".to_string());
+    }
+    Ok(())
+}
+
 /// Validate that a string contains no HTML tags
 pub fn validate_no_html(value: &str) -> Result<(), String> {
     if HTML_TAG_REGEX.is_match(value) {
@@ -170,11 +268,21 @@ pub fn validate_no_xss(value: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Validate a list of tags
+/// Validate a list of tags; in strict taxonomy mode every tag must be curated
 pub fn validate_tags(
     tags: &[String],
     max_tags: usize,
     max_tag_length: usize,
+) -> Result<(), String> {
+    validate_tags_with(tags, max_tags, max_tag_length, taxonomy::current())
+}
+
+/// [`validate_tags`] against an explicit taxonomy
+pub fn validate_tags_with(
+    tags: &[String],
+    max_tags: usize,
+    max_tag_length: usize,
+    taxonomy: &TagTaxonomy,
 ) -> Result<(), String> {
     if tags.len() > max_tags {
         return Err(format!("at most {} tags are allowed", max_tags));
@@ -196,7 +304,7 @@ pub fn validate_tags(
         }
     }
 
-    Ok(())
+    taxonomy.check(tags)
 }
 
 /// Validate source code size
@@ -213,6 +321,24 @@ pub fn validate_source_code_size(source: &str, max_bytes: usize) -> Result<(), S
     Ok(())
 }
 
+/// Signature algorithms accepted on contract versions. Only Ed25519 is
+/// verified today; `secp256k1` joins this list once the handler supports it.
+pub const SUPPORTED_SIGNATURE_ALGORITHMS: &[&str] = &["ed25519"];
+
+/// Validate an optional signature algorithm (`None` means unsigned).
+///
+/// Expects the value to already be lowercased by the sanitizer.
+pub fn validate_signature_algorithm_optional(algorithm: &Option<String>) -> Result<(), String> {
+    match algorithm {
+        Some(algo) if !SUPPORTED_SIGNATURE_ALGORITHMS.contains(&algo.as_str()) => Err(format!(
+            "unsupported signature algorithm '{}'; expected one of: {}",
+            algo,
+            SUPPORTED_SIGNATURE_ALGORITHMS.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Validate JSON value is not deeply nested (prevent DoS)
 pub fn validate_json_depth(value: &serde_json::Value, max_depth: usize) -> Result<(), String> {
     fn check_depth(v: &serde_json::Value, current: usize, max: usize) -> Result<(), String> {
@@ -238,6 +364,65 @@ pub fn validate_json_depth(value: &serde_json::Value, max_depth: usize) -> Resul
     check_depth(value, 0, max_depth)
 }
 
+/// Size bounds for an untrusted JSON value
+#[derive(Debug, Clone, Copy)]
+pub struct JsonLimits {
+    /// Maximum nesting depth (the root is depth 0)
+    pub max_depth: usize,
+    /// Maximum number of values in the whole document, containers included
+    pub max_nodes: usize,
+    /// Maximum number of elements in any single array
+    pub max_array_len: usize,
+}
+
+/// Validate JSON depth, total node count and array length (prevent DoS).
+///
+/// `validate_json_depth` alone lets a flat-but-huge document through; this
+/// also bounds breadth. Traversal is iterative so hostile nesting cannot
+/// overflow the stack before the depth check trips.
+pub fn validate_json_size(value: &serde_json::Value, limits: JsonLimits) -> Result<(), String> {
+    let mut stack = vec![(value, 0usize)];
+    let mut nodes = 1usize;
+
+    while let Some((v, depth)) = stack.pop() {
+        if depth > limits.max_depth {
+            return Err(format!(
+                "JSON exceeds maximum nesting depth of {}",
+                limits.max_depth
+            ));
+        }
+        let children: Box<dyn Iterator<Item = &serde_json::Value>> = match v {
+            serde_json::Value::Array(arr) => {
+                if arr.len() > limits.max_array_len {
+                    return Err(format!(
+                        "JSON array exceeds maximum length of {}",
+                        limits.max_array_len
+                    ));
+                }
+                Box::new(arr.iter())
+            }
+            serde_json::Value::Object(obj) => Box::new(obj.values()),
+            _ => continue,
+        };
+        for child in children {
+            nodes += 1;
+            if nodes > limits.max_nodes {
+                return Err(format!(
+                    "JSON exceeds maximum of {} nodes",
+                    limits.max_nodes
+                ));
+            }
+            stack.push((child, depth + 1));
+        }
+    }
+    Ok(())
+}
+
+/// Validate a semver range such as `>=1.0.0, <1.2.0`, `^1.4.0` or `1.0.0..1.2.0`
+pub fn validate_semver_range(range: &str) -> Result<(), String> {
+    shared::VersionRange::parse(range).map(|_| ())
+}
+
 /// Validate per-network config version range (Issue #43).
 /// Ensures min_version and max_version are valid semver and min <= max when both present.
 pub fn validate_network_config_versions(
@@ -269,6 +454,48 @@ pub fn validate_network_config_versions(
 mod tests {
     use super::*;
 
+    const LIMITS: JsonLimits = JsonLimits {
+        max_depth: 10,
+        max_nodes: 1_000,
+        max_array_len: 100,
+    };
+
+    #[test]
+    fn test_validate_git_reference() {
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        assert!(validate_git_reference("https://github.com/org/token", commit).is_ok());
+        assert!(validate_git_reference("git@github.com:org/token.git", commit).is_ok());
+        assert!(validate_git_reference("file:///etc/passwd", commit).is_err());
+        assert!(validate_git_reference("https://github.com/org/token", "0123abc").is_err());
+        assert!(validate_git_reference("https://github.com/org/token", "main").is_err());
+    }
+
+    #[test]
+    fn test_validate_json_size_rejects_deep_nesting() {
+        let mut deep = serde_json::json!(1);
+        for _ in 0..20 {
+            deep = serde_json::json!({ "a": deep });
+        }
+        let err = validate_json_size(&deep, LIMITS).unwrap_err();
+        assert!(err.contains("nesting depth"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_json_size_rejects_shallow_but_enormous() {
+        let wide: serde_json::Map<String, serde_json::Value> = (0..5_000)
+            .map(|i| (format!("k{}", i), serde_json::json!(i)))
+            .collect();
+        let err = validate_json_size(&serde_json::Value::Object(wide), LIMITS).unwrap_err();
+        assert!(err.contains("1000 nodes"), "{}", err);
+
+        let long = serde_json::json!({ "items": vec![0; 101] });
+        let err = validate_json_size(&long, LIMITS).unwrap_err();
+        assert!(err.contains("array exceeds maximum length"), "{}", err);
+
+        let ok = serde_json::json!({ "items": vec![0; 100], "nested": { "a": [1, 2] } });
+        assert!(validate_json_size(&ok, LIMITS).is_ok());
+    }
+
     #[test]
     fn test_validate_contract_id() {
         // Valid contract ID
@@ -286,15 +513,34 @@ mod tests {
         assert!(validate_contract_id("").is_err());
     }
 
+    #[test]
+    fn test_validate_signature_algorithm_optional() {
+        assert!(validate_signature_algorithm_optional(&None).is_ok());
+        assert!(validate_signature_algorithm_optional(&Some("ed25519".to_string())).is_ok());
+
+        let err = validate_signature_algorithm_optional(&Some("rsa".to_string())).unwrap_err();
+        assert!(err.contains("'rsa'"), "{}", err);
+        assert!(validate_signature_algorithm_optional(&Some("secp256k1".to_string())).is_err());
+    }
+
     #[test]
     fn test_validate_stellar_address() {
         // Valid address
-        let valid = "GDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+        let valid = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
         assert!(validate_stellar_address(valid).is_ok());
 
         // Invalid: starts with C
         let invalid_c = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
         assert!(validate_stellar_address(invalid_c).is_err());
+
+        // Invalid: well-formed but the checksum does not match
+        let bad_checksum = "GDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+        let err = validate_stellar_address(bad_checksum).unwrap_err();
+        assert!(err.contains("checksum"), "{}", err);
+
+        // Invalid: '0' and '1' are not base32
+        let not_base32 = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX01";
+        assert!(validate_stellar_address(not_base32).is_err());
     }
 
     #[test]
@@ -343,4 +589,17 @@ mod tests {
         assert!(validate_semver("2.0.0-rc.1+build.123").is_ok());
         assert!(validate_semver("not-a-version").is_err());
     }
+
+    #[test]
+    fn test_validate_semver_range() {
+        assert!(validate_semver_range(">=1.0.0, <1.2.0").is_ok());
+        assert!(validate_semver_range(">= 1.0.0 < 1.2.0").is_ok());
+        assert!(validate_semver_range("^1.4.0").is_ok());
+        assert!(validate_semver_range("1.0.0..1.2.0").is_ok());
+        assert!(validate_semver_range("*").is_ok());
+        assert!(validate_semver_range("").is_err());
+        assert!(validate_semver_range(">=1.x").is_err());
+        assert!(validate_semver_range(">=1.2.0, <1.2.0").is_err());
+        assert!(validate_semver_range("2.0.0..1.0.0").is_err());
+    }
 }
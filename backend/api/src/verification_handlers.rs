@@ -0,0 +1,2015 @@
+use axum::{
+    extract::{rejection::JsonRejection, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use shared::{
+    AnalyticsEventType, CompareVerificationsQuery, Network, NetworkConfig, SignedSourceUrl,
+    SourceDownloadQuery, SourceReference, SourceUrlParams, VerificationCallbackPayload,
+    VerificationComparison, VerificationInputDiff, VerificationMethod, VerificationReasonCode,
+    VerificationStatus, VerificationStatusResponse, VerifyArtifactRequest, VerifyCheckRequest,
+    VerifyCheckResponse, VerifyQueryParams, VerifyRequest,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::{
+    admin_auth::AdminOperator,
+    analytics,
+    api_keys::ContractWriteKey,
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, fetch_contract_identity},
+    metrics,
+    request_signing::{constant_time_eq, hmac_sha256},
+    state::AppState,
+    validation::validators::{validate_callback_url, validate_git_reference},
+    verification_queue::BuildQueue,
+    verification_webhook::{self, WebhookConfig},
+};
+
+/// How long a synchronous POST /api/contracts/verify waits for its build
+/// before answering with the still-pending verification
+const SYNC_VERIFY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What to do when a verification's `compiler_version` differs from the SDK
+/// version the contract was first verified with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SdkMismatchPolicy {
+    /// Accept the submission and report the mismatch in `warnings`
+    #[default]
+    Warn,
+    /// Refuse the submission
+    Reject,
+}
+
+impl SdkMismatchPolicy {
+    /// `VERIFY_SDK_MISMATCH`: `warn` (default) or `reject`
+    pub fn from_env() -> Self {
+        match std::env::var("VERIFY_SDK_MISMATCH") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("reject") => Self::Reject,
+            _ => Self::Warn,
+        }
+    }
+}
+
+/// The mismatch message when `supplied` differs from the contract's
+/// `recorded` SDK version; a leading `v` is ignored on either side
+fn sdk_mismatch(recorded: Option<&str>, supplied: &str) -> Option<String> {
+    let normalize = |version: &str| version.trim().trim_start_matches('v').to_string();
+    let recorded = recorded.map(normalize).filter(|v| !v.is_empty())?;
+    if recorded == normalize(supplied) {
+        return None;
+    }
+    Some(format!(
+        "compiler_version '{}' differs from soroban-sdk {} this contract was first verified with; \
+         the build may not reproduce the deployed artifact",
+        supplied.trim(),
+        recorded
+    ))
+}
+
+/// Warnings to return for `mismatch` under `policy`, or the rejection
+fn apply_sdk_policy(policy: SdkMismatchPolicy, mismatch: Option<String>) -> ApiResult<Vec<String>> {
+    match (policy, mismatch) {
+        (_, None) => Ok(Vec::new()),
+        (SdkMismatchPolicy::Warn, Some(message)) => Ok(vec![message]),
+        (SdkMismatchPolicy::Reject, Some(message)) => {
+            Err(ApiError::unprocessable("SdkVersionMismatch", message))
+        }
+    }
+}
+
+async fn check_sdk_version(
+    state: &AppState,
+    contract_uuid: Uuid,
+    compiler_version: &str,
+) -> ApiResult<Vec<String>> {
+    let recorded: Option<String> =
+        sqlx::query_scalar("SELECT verified_sdk_version FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get verified sdk version", err))?;
+    apply_sdk_policy(
+        SdkMismatchPolicy::from_env(),
+        sdk_mismatch(recorded.as_deref(), compiler_version),
+    )
+}
+
+/// A previously verified artifact matching the submitted source hash
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct CachedVerification {
+    id: Uuid,
+    verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct VerificationRow {
+    id: Uuid,
+    contract_id: Uuid,
+    status: VerificationStatus,
+    method: VerificationMethod,
+    reason_code: Option<VerificationReasonCode>,
+    error_message: Option<String>,
+    verified_at: Option<DateTime<Utc>>,
+    source_git_url: Option<String>,
+    source_commit: Option<String>,
+}
+
+/// POST /api/contracts/verify[?async=true]
+///
+/// Queues a build of the submitted source. By default waits for the outcome;
+/// with `async=true` returns the pending verification immediately so the
+/// client can poll GET /api/verifications/:id/status.
+pub async fn verify_contract(
+    State(state): State<AppState>,
+    Query(params): Query<VerifyQueryParams>,
+    payload: Result<Json<VerifyRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<VerificationStatusResponse>)> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+
+    if req.source_code.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingSourceCode",
+            "source_code is required",
+        ));
+    }
+
+    let source_reference = check_source_reference(&req)?;
+    let callback_url = check_callback_url(&req)?;
+
+    let (contract_uuid, _) = fetch_contract_identity(&state, &req.contract_id).await?;
+    let network = fetch_verification_network(&state, contract_uuid, req.network.as_ref()).await?;
+    let warnings = check_sdk_version(&state, contract_uuid, &req.compiler_version).await?;
+    let source_hash =
+        verifier::source_hash(&req.source_code, &req.compiler_version, &req.build_params);
+
+    let verification_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO verifications \
+            (contract_id, status, method, source_code, build_params, compiler_version, source_hash, \
+             from_cache, source_git_url, source_commit, network, callback_url, claimed_at) \
+         VALUES ($1, 'pending', $10, $2, $3, $4, $5, FALSE, $6, $7, $8, $9, NOW()) \
+         RETURNING id",
+    )
+    .bind(contract_uuid)
+    .bind(&req.source_code)
+    .bind(&req.build_params)
+    .bind(&req.compiler_version)
+    .bind(&source_hash)
+    .bind(source_reference.as_ref().map(|r| &r.git_url))
+    .bind(source_reference.as_ref().map(|r| &r.commit))
+    .bind(&network)
+    .bind(&callback_url)
+    .bind(if verifier::is_precompiled(&req.source_code) {
+        VerificationMethod::Artifact
+    } else {
+        VerificationMethod::Build
+    })
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("record verification", err))?;
+
+    let build = spawn_verification_build(
+        state.clone(),
+        verification_id,
+        contract_uuid,
+        req.source_code,
+        req.build_params,
+        callback_url,
+    );
+
+    if !params.run_async
+        && tokio::time::timeout(SYNC_VERIFY_TIMEOUT, build)
+            .await
+            .is_err()
+    {
+        tracing::warn!(
+            verification_id = %verification_id,
+            "verification still building after sync timeout; returning pending"
+        );
+    }
+
+    let row = fetch_verification(&state, verification_id).await?;
+    let mut response = status_response(row, &state.verification_queue);
+    response.warnings = warnings;
+    let code = if response.status == VerificationStatus::Pending {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+    Ok((code, Json(response)))
+}
+
+/// The submission's callback URL, refused when it is not https or when no
+/// webhook secret is configured to sign the callback with
+fn check_callback_url(req: &VerifyRequest) -> ApiResult<Option<String>> {
+    let Some(url) = req.callback_url.as_deref().map(str::trim) else {
+        return Ok(None);
+    };
+    validate_callback_url(url).map_err(|msg| {
+        ApiError::bad_request("InvalidCallbackUrl", format!("callback_url {}", msg))
+    })?;
+    if WebhookConfig::from_env().secret.is_none() {
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "VerificationCallbacksDisabled",
+            "Verification callbacks are not configured on this registry",
+        ));
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// Normalized provenance for a precompiled submission. Source builds carry
+/// their own source, so a reference is refused for them.
+fn check_source_reference(req: &VerifyRequest) -> ApiResult<Option<SourceReference>> {
+    let Some(reference) = &req.source_reference else {
+        return Ok(None);
+    };
+    if !verifier::is_precompiled(&req.source_code) {
+        return Err(ApiError::bad_request(
+            "InvalidSourceReference",
+            "source_reference is only accepted with wasm_base64: artifacts",
+        ));
+    }
+    normalize_source_reference(reference).map(Some)
+}
+
+fn normalize_source_reference(reference: &SourceReference) -> ApiResult<SourceReference> {
+    let reference = SourceReference {
+        git_url: reference.git_url.trim().to_string(),
+        commit: reference.commit.trim().to_ascii_lowercase(),
+    };
+    validate_git_reference(&reference.git_url, &reference.commit)
+        .map_err(|e| ApiError::bad_request("InvalidSourceReference", e))?;
+    Ok(reference)
+}
+
+/// The network a verification applies to: the requested one, which must be
+/// configured for the contract, or else the contract's own
+async fn fetch_verification_network(
+    state: &AppState,
+    contract_uuid: Uuid,
+    requested: Option<&Network>,
+) -> ApiResult<Network> {
+    let (own, configs): (Network, Option<Value>) =
+        sqlx::query_as("SELECT network, network_configs FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract networks", err))?;
+    resolve_network(&own, configs.as_ref(), requested)
+}
+
+fn resolve_network(
+    own: &Network,
+    configs: Option<&Value>,
+    requested: Option<&Network>,
+) -> ApiResult<Network> {
+    let Some(requested) = requested.filter(|net| *net != own) else {
+        return Ok(own.clone());
+    };
+    let configured = configs
+        .and_then(|v| serde_json::from_value::<HashMap<String, NetworkConfig>>(v.clone()).ok())
+        .is_some_and(|m| m.contains_key(&requested.to_string()));
+    if !configured {
+        return Err(ApiError::bad_request(
+            "UnknownNetwork",
+            format!("Contract is not configured for network {}", requested),
+        ));
+    }
+    Ok(requested.clone())
+}
+
+/// `network_configs` with `network` flagged verified; other networks keep
+/// their state. The contract's own network may predate its config entry.
+fn mark_network_verified(configs: Option<Value>, network: &Network, contract_id: &str) -> Value {
+    let mut configs = match configs {
+        Some(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let entry = configs.entry(network.to_string()).or_insert_with(|| {
+        serde_json::json!({
+            "contract_id": contract_id,
+            "min_version": null,
+            "max_version": null
+        })
+    });
+    if let Some(entry) = entry.as_object_mut() {
+        entry.insert("is_verified".to_string(), Value::Bool(true));
+    }
+    Value::Object(configs)
+}
+
+/// The `wasm_base64:` source an artifact submission is verified and stored
+/// as; the prefix is optional in the request
+fn artifact_source(wasm_base64: &str) -> String {
+    let artifact = wasm_base64.trim();
+    if verifier::is_precompiled(artifact) {
+        artifact.to_string()
+    } else {
+        format!("{}{}", verifier::WASM_BASE64_PREFIX, artifact)
+    }
+}
+
+/// Hash an artifact against the deployed bytecode. Undecodable or oversized
+/// artifacts are the caller's mistake rather than a verification outcome.
+async fn settle_artifact(
+    source: &str,
+    deployed_hash: &str,
+    config: &verifier::VerifierConfig,
+) -> ApiResult<BuildOutcome> {
+    let result = verifier::verify_build(source, &Value::Null, deployed_hash, config).await;
+    match &result {
+        Err(verifier::BuildError::InvalidInput(message))
+        | Err(verifier::BuildError::Artifact(message)) => {
+            Err(ApiError::bad_request("InvalidArtifact", message.clone()))
+        }
+        _ => Ok(settle_build(&result)),
+    }
+}
+
+/// POST /api/contracts/:id/verify/artifact
+///
+/// Verifies a WASM built elsewhere, typically in CI, by hashing it against the
+/// deployed bytecode. Nothing is compiled, so the result is recorded straight
+/// away instead of going through the build queue. Requires an API key of the
+/// contract's publisher (`X-API-Key`).
+///
+/// A match only shows the bytes are the deployed ones, not that any source
+/// produces them, so the contract is not marked verified.
+pub async fn verify_artifact(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    _key: ContractWriteKey,
+    payload: Result<Json<VerifyArtifactRequest>, JsonRejection>,
+) -> ApiResult<Json<VerificationStatusResponse>> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+    if req.wasm_base64.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingArtifact",
+            "wasm_base64 is required",
+        ));
+    }
+    let source_reference = req
+        .source_reference
+        .as_ref()
+        .map(normalize_source_reference)
+        .transpose()?;
+    let compiler_version = req
+        .compiler_version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+    let network = fetch_verification_network(&state, contract_uuid, req.network.as_ref()).await?;
+    let deployed_hash: String = sqlx::query_scalar("SELECT wasm_hash FROM contracts WHERE id = $1")
+        .bind(contract_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("get contract wasm hash", err))?;
+
+    let started = std::time::Instant::now();
+    let source = artifact_source(&req.wasm_base64);
+    let build_params = Value::Null;
+    let config = verifier::VerifierConfig::from_env();
+    let outcome = settle_artifact(&source, &deployed_hash, &config).await?;
+    metrics::VERIFICATION_LATENCY
+        .with_label_values(&[outcome.label])
+        .observe(started.elapsed().as_secs_f64());
+
+    let source_hash =
+        verifier::source_hash(&source, compiler_version.unwrap_or_default(), &build_params);
+    let verification_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO verifications \
+            (contract_id, status, method, source_code, build_params, compiler_version, \
+             source_hash, from_cache, source_git_url, source_commit, reason_code, \
+             error_message, built_wasm_hash, network, verified_at) \
+         VALUES ($1, $2, 'artifact', $3, $4, $5, $6, FALSE, $7, $8, $9, $10, $11, $12, \
+             CASE WHEN $2 = 'verified'::verification_status THEN NOW() ELSE NULL END) \
+         RETURNING id",
+    )
+    .bind(contract_uuid)
+    .bind(&outcome.status)
+    .bind(&source)
+    .bind(&build_params)
+    .bind(compiler_version)
+    .bind(&source_hash)
+    .bind(source_reference.as_ref().map(|r| &r.git_url))
+    .bind(source_reference.as_ref().map(|r| &r.commit))
+    .bind(outcome.reason_code)
+    .bind(&outcome.error_message)
+    .bind(&outcome.built_wasm_hash)
+    .bind(&network)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("record artifact verification", err))?;
+
+    if outcome.status == VerificationStatus::Verified {
+        metrics::VERIFICATION_SUCCESS.inc();
+    } else {
+        metrics::VERIFICATION_FAILURE.inc();
+    }
+    tracing::info!(
+        verification_id = %verification_id,
+        contract_id = %contract_uuid,
+        reason_code = ?outcome.reason_code,
+        "artifact verification recorded"
+    );
+
+    let row = fetch_verification(&state, verification_id).await?;
+    Ok(Json(status_response(row, &state.verification_queue)))
+}
+
+/// Cache slot for the toolchain self-test; probing spawns a process per tool
+const TOOLCHAIN_CACHE_NAMESPACE: &str = "verifier";
+const TOOLCHAIN_CACHE_KEY: &str = "toolchain";
+const TOOLCHAIN_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Result of `verifier::self_test`, reused for `TOOLCHAIN_CACHE_TTL`
+pub async fn verifier_health(state: &AppState) -> verifier::VerifierHealth {
+    if let (Some(cached), true) = state
+        .cache
+        .get(TOOLCHAIN_CACHE_NAMESPACE, TOOLCHAIN_CACHE_KEY)
+        .await
+    {
+        if let Ok(health) = serde_json::from_str(&cached) {
+            return health;
+        }
+    }
+    probe_toolchain(state).await
+}
+
+/// Run `verifier::self_test` now, caching the result and recording whether
+/// the toolchain is ready for `/health`
+async fn probe_toolchain(state: &AppState) -> verifier::VerifierHealth {
+    let health = verifier::self_test(&verifier::VerifierConfig::from_env()).await;
+    state
+        .verifier_ready
+        .store(health.ready, std::sync::atomic::Ordering::Relaxed);
+    if !health.ready {
+        tracing::warn!(tools = ?health.tools, "verifier toolchain is incomplete");
+    }
+    if let Ok(serialized) = serde_json::to_string(&health) {
+        state
+            .cache
+            .put(
+                TOOLCHAIN_CACHE_NAMESPACE,
+                TOOLCHAIN_CACHE_KEY,
+                serialized,
+                Some(TOOLCHAIN_CACHE_TTL),
+            )
+            .await;
+    }
+    health
+}
+
+/// Probe the toolchain now and every `TOOLCHAIN_CACHE_TTL`, so `/health`
+/// never waits on a self-test
+pub fn spawn_toolchain_probe(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TOOLCHAIN_CACHE_TTL);
+        loop {
+            interval.tick().await;
+            probe_toolchain(&state).await;
+        }
+    });
+}
+
+/// GET /api/verifications/toolchain
+///
+/// 503 while a required build tool is missing, so operators can gate traffic
+/// on it before sending verification requests.
+pub async fn get_verifier_toolchain(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<verifier::VerifierHealth>) {
+    let health = verifier_health(&state).await;
+    let status = if health.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(health))
+}
+
+/// GET /api/verifications/:id/status
+pub async fn get_verification_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<VerificationStatusResponse>> {
+    let verification_id = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidVerificationId",
+            format!("Invalid verification ID format: {}", id),
+        )
+    })?;
+
+    let row = fetch_verification(&state, verification_id).await?;
+    Ok(Json(status_response(row, &state.verification_queue)))
+}
+
+async fn fetch_verification(state: &AppState, verification_id: Uuid) -> ApiResult<VerificationRow> {
+    sqlx::query_as(
+        "SELECT id, contract_id, status, method, reason_code, error_message, verified_at, \
+                source_git_url, source_commit \
+         FROM verifications WHERE id = $1",
+    )
+    .bind(verification_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get verification", err))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "VerificationNotFound",
+            format!("No verification found with ID: {}", verification_id),
+        )
+    })
+}
+
+/// Reported on a matching artifact, which leaves `is_verified` untouched
+const BYTECODE_ONLY_WARNING: &str =
+    "bytecode-only match: no source was compiled, so the contract is not marked verified";
+
+/// Queue details are only reported while the verification is pending
+fn status_response(row: VerificationRow, queue: &BuildQueue) -> VerificationStatusResponse {
+    let position = match row.status {
+        VerificationStatus::Pending => queue.position(row.id),
+        _ => None,
+    };
+    let warnings = if row.method == VerificationMethod::Artifact
+        && row.status == VerificationStatus::Verified
+    {
+        vec![BYTECODE_ONLY_WARNING.to_string()]
+    } else {
+        Vec::new()
+    };
+    VerificationStatusResponse {
+        verification_id: row.id,
+        contract_id: row.contract_id,
+        status: row.status,
+        method: row.method,
+        queue_position: position.map(|p| p.position),
+        queue_depth: position.map(|p| p.depth),
+        reason_code: row.reason_code,
+        error_message: row.error_message,
+        verified_at: row.verified_at,
+        source_reference: row
+            .source_git_url
+            .zip(row.source_commit)
+            .map(|(git_url, commit)| SourceReference { git_url, commit }),
+        warnings,
+    }
+}
+
+/// The inputs and output of one verification run
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct VerificationRun {
+    id: Uuid,
+    compiler_version: Option<String>,
+    build_params: Option<Value>,
+    effective_build_params: Option<Value>,
+    resolved_dependencies: Option<Value>,
+    built_wasm_hash: Option<String>,
+}
+
+/// GET /api/contracts/:id/verifications/compare?a=<id>&b=<id>
+///
+/// Reports whether two verification runs of the contract compiled to the same
+/// artifact, and which build inputs differ when they did not.
+pub async fn compare_verifications(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<CompareVerificationsQuery>,
+) -> ApiResult<Json<VerificationComparison>> {
+    let (contract_uuid, _) = fetch_contract_identity(&state, &id).await?;
+
+    let mut runs = Vec::with_capacity(2);
+    for verification_id in [query.a, query.b] {
+        let run: VerificationRun = sqlx::query_as(
+            "SELECT id, compiler_version, build_params, effective_build_params, \
+                    resolved_dependencies, built_wasm_hash \
+             FROM verifications WHERE id = $1 AND contract_id = $2",
+        )
+        .bind(verification_id)
+        .bind(contract_uuid)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|err| db_internal_error("get verification for comparison", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "VerificationNotFound",
+                format!(
+                    "No verification {} found for contract {}",
+                    verification_id, id
+                ),
+            )
+        })?;
+        runs.push(run);
+    }
+
+    Ok(Json(compare_runs(&runs[0], &runs[1])))
+}
+
+fn compare_runs(a: &VerificationRun, b: &VerificationRun) -> VerificationComparison {
+    let reproducible = match (&a.built_wasm_hash, &b.built_wasm_hash) {
+        (Some(x), Some(y)) => x.eq_ignore_ascii_case(y),
+        _ => false,
+    };
+
+    let mut differences = Vec::new();
+    if !reproducible {
+        let compiler = |run: &VerificationRun| run.compiler_version.clone().map(Value::String);
+        diff_inputs(
+            "compiler_version",
+            compiler(a),
+            compiler(b),
+            &mut differences,
+        );
+        diff_object_inputs(
+            "resolved_dependencies",
+            a.resolved_dependencies.as_ref(),
+            b.resolved_dependencies.as_ref(),
+            &mut differences,
+        );
+        diff_object_inputs(
+            "build_params",
+            a.build_params.as_ref(),
+            b.build_params.as_ref(),
+            &mut differences,
+        );
+        diff_object_inputs(
+            "effective_build_params",
+            a.effective_build_params.as_ref(),
+            b.effective_build_params.as_ref(),
+            &mut differences,
+        );
+    }
+
+    VerificationComparison {
+        a: a.id,
+        b: b.id,
+        reproducible,
+        built_wasm_hash_a: a.built_wasm_hash.clone(),
+        built_wasm_hash_b: b.built_wasm_hash.clone(),
+        build_params_a: a.build_params.clone(),
+        build_params_b: b.build_params.clone(),
+        effective_build_params_a: a.effective_build_params.clone(),
+        effective_build_params_b: b.effective_build_params.clone(),
+        differences,
+    }
+}
+
+fn diff_inputs(
+    field: &str,
+    a: Option<Value>,
+    b: Option<Value>,
+    differences: &mut Vec<VerificationInputDiff>,
+) {
+    if a != b {
+        differences.push(VerificationInputDiff {
+            field: field.to_string(),
+            a,
+            b,
+        });
+    }
+}
+
+/// Compare two JSON objects key by key, reporting each differing key as
+/// `prefix.key`; anything other than two objects is compared whole
+fn diff_object_inputs(
+    prefix: &str,
+    a: Option<&Value>,
+    b: Option<&Value>,
+    differences: &mut Vec<VerificationInputDiff>,
+) {
+    match (a, b) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                diff_inputs(
+                    &format!("{}.{}", prefix, key),
+                    a.get(key).cloned(),
+                    b.get(key).cloned(),
+                    differences,
+                );
+            }
+        }
+        _ => diff_inputs(prefix, a.cloned(), b.cloned(), differences),
+    }
+}
+
+/// POST /api/contracts/:id/verify/check
+///
+/// Returns the cached result immediately when the source hashes to an artifact
+/// already verified for this contract; otherwise records a pending verification
+/// and builds it in the background.
+pub async fn check_verification(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Result<Json<VerifyCheckRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<VerifyCheckResponse>)> {
+    let Json(req) = payload.map_err(|err| {
+        ApiError::bad_request(
+            "InvalidRequest",
+            format!("Invalid JSON payload: {}", err.body_text()),
+        )
+    })?;
+
+    if req.source_code.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingSourceCode",
+            "source_code is required",
+        ));
+    }
+
+    let (contract_uuid, contract_id) = fetch_contract_identity(&state, &id).await?;
+    let warnings = check_sdk_version(&state, contract_uuid, &req.compiler_version).await?;
+    let source_hash =
+        verifier::source_hash(&req.source_code, &req.compiler_version, &req.build_params);
+
+    let cached: Option<CachedVerification> = sqlx::query_as(
+        "SELECT id, verified_at FROM verifications \
+         WHERE contract_id = $1 AND source_hash = $2 AND status = 'verified' \
+         ORDER BY verified_at DESC NULLS LAST LIMIT 1",
+    )
+    .bind(contract_uuid)
+    .bind(&source_hash)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("lookup cached verification", err))?;
+
+    let (status, from_cache) = match cached {
+        Some(_) => (VerificationStatus::Verified, true),
+        None => (VerificationStatus::Pending, false),
+    };
+
+    // Each check is recorded so cache hits and misses are observable
+    let verification_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO verifications \
+            (contract_id, status, source_code, build_params, compiler_version, verified_at, source_hash, from_cache, reason_code, claimed_at) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW()) \
+         RETURNING id",
+    )
+    .bind(contract_uuid)
+    .bind(&status)
+    .bind(if from_cache { None } else { Some(&req.source_code) })
+    .bind(&req.build_params)
+    .bind(&req.compiler_version)
+    .bind(cached.as_ref().map(|_| Utc::now()))
+    .bind(&source_hash)
+    .bind(from_cache)
+    .bind(cached.as_ref().map(|_| VerificationReasonCode::HashMatch))
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("record verification check", err))?;
+
+    if cached.is_some() {
+        metrics::CACHE_HITS.inc();
+        tracing::info!(
+            contract_id = %contract_id,
+            source_hash = %source_hash,
+            "verification served from compile cache"
+        );
+    } else {
+        metrics::CACHE_MISSES.inc();
+        spawn_verification_build(
+            state.clone(),
+            verification_id,
+            contract_uuid,
+            req.source_code,
+            req.build_params,
+            None,
+        );
+    }
+
+    let (code, Json(mut response)) = build_check_response(verification_id, source_hash, cached);
+    response.warnings = warnings;
+    Ok((code, Json(response)))
+}
+
+fn build_check_response(
+    verification_id: Uuid,
+    source_hash: String,
+    cached: Option<CachedVerification>,
+) -> (StatusCode, Json<VerifyCheckResponse>) {
+    match cached {
+        Some(hit) => (
+            StatusCode::OK,
+            Json(VerifyCheckResponse {
+                verification_id,
+                status: VerificationStatus::Verified,
+                from_cache: true,
+                source_hash,
+                cached_verification_id: Some(hit.id),
+                verified_at: hit.verified_at,
+                warnings: Vec::new(),
+            }),
+        ),
+        None => (
+            StatusCode::ACCEPTED,
+            Json(VerifyCheckResponse {
+                verification_id,
+                status: VerificationStatus::Pending,
+                from_cache: false,
+                source_hash,
+                cached_verification_id: None,
+                verified_at: None,
+                warnings: Vec::new(),
+            }),
+        ),
+    }
+}
+
+/// Build and compare the submitted source off the request path, then settle
+/// the pending verification row. Builds wait their turn in the state's
+/// build queue.
+fn spawn_verification_build(
+    state: AppState,
+    verification_id: Uuid,
+    contract_uuid: Uuid,
+    source_code: String,
+    build_params: Value,
+    callback_url: Option<String>,
+) -> JoinHandle<()> {
+    metrics::VERIFICATION_QUEUE_DEPTH.inc();
+    tokio::spawn(async move {
+        let queue = state.verification_queue.clone();
+        queue
+            .run(
+                verification_id,
+                run_verification_build(
+                    &state,
+                    verification_id,
+                    contract_uuid,
+                    &source_code,
+                    &build_params,
+                    callback_url,
+                ),
+            )
+            .await;
+        metrics::VERIFICATION_QUEUE_DEPTH.dec();
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct OrphanedBuild {
+    id: Uuid,
+    contract_id: Uuid,
+    source_code: String,
+    build_params: Value,
+    callback_url: Option<String>,
+}
+
+/// Claim pending verifications whose build is not running anywhere: those
+/// never claimed and those whose claim outlived `lease` because the process
+/// holding it stopped. Replicas starting together each get distinct rows.
+async fn claim_orphaned_builds(
+    state: &AppState,
+    lease: Duration,
+) -> Result<Vec<OrphanedBuild>, sqlx::Error> {
+    sqlx::query_as(
+        "UPDATE verifications SET claimed_at = NOW() \
+         WHERE id IN ( \
+             SELECT id FROM verifications \
+             WHERE status = 'pending' AND source_code IS NOT NULL \
+               AND (claimed_at IS NULL OR claimed_at < NOW() - make_interval(secs => $1)) \
+             FOR UPDATE SKIP LOCKED) \
+         RETURNING id, contract_id, source_code, build_params, callback_url",
+    )
+    .bind(lease.as_secs_f64())
+    .fetch_all(&state.db)
+    .await
+}
+
+/// Requeue verifications left pending by a previous process. A claim is
+/// considered abandoned after twice the build timeout.
+pub fn spawn_resume_pending_builds(state: AppState) {
+    tokio::spawn(async move {
+        let lease = verifier::VerifierConfig::from_env().build_timeout * 2;
+        match claim_orphaned_builds(&state, lease).await {
+            Ok(builds) => {
+                for build in builds {
+                    tracing::info!(verification_id = %build.id, "resuming pending verification build");
+                    spawn_verification_build(
+                        state.clone(),
+                        build.id,
+                        build.contract_id,
+                        build.source_code,
+                        build.build_params,
+                        build.callback_url,
+                    );
+                }
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to load pending verification builds")
+            }
+        }
+    });
+}
+
+async fn run_verification_build(
+    state: &AppState,
+    verification_id: Uuid,
+    contract_uuid: Uuid,
+    source_code: &str,
+    build_params: &Value,
+    callback_url: Option<String>,
+) {
+    let started = std::time::Instant::now();
+
+    // Renew the claim now the build has a slot, so a replica starting up
+    // does not take over a build that is running
+    if let Err(err) = sqlx::query("UPDATE verifications SET claimed_at = NOW() WHERE id = $1")
+        .bind(verification_id)
+        .execute(&state.db)
+        .await
+    {
+        tracing::warn!(verification_id = %verification_id, error = ?err, "failed to renew verification claim");
+    }
+
+    let wasm_hash: Result<String, sqlx::Error> =
+        sqlx::query_scalar("SELECT wasm_hash FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await;
+
+    let expected_wasm_hash = wasm_hash.as_ref().ok().cloned();
+    let outcome = match wasm_hash {
+        Ok(hash) => {
+            let config = verifier::VerifierConfig::from_env();
+            let result = verifier::verify_build(source_code, build_params, &hash, &config).await;
+            settle_build(&result)
+        }
+        Err(err) => BuildOutcome {
+            status: VerificationStatus::Failed,
+            reason_code: VerificationReasonCode::InternalError,
+            error_message: Some(format!("failed to load contract wasm hash: {}", err)),
+            built_wasm_hash: None,
+            effective_build_params: None,
+            resolved_dependencies: None,
+            label: "error",
+        },
+    };
+    let status = outcome.status.clone();
+
+    if status == VerificationStatus::Verified {
+        metrics::VERIFICATION_SUCCESS.inc();
+    } else {
+        metrics::VERIFICATION_FAILURE.inc();
+    }
+    metrics::VERIFICATION_LATENCY
+        .with_label_values(&[outcome.label])
+        .observe(started.elapsed().as_secs_f64());
+
+    let result = sqlx::query(
+        "UPDATE verifications SET status = $2, error_message = $3, reason_code = $4, \
+            built_wasm_hash = $5, effective_build_params = $6, resolved_dependencies = $7, \
+            verified_at = CASE WHEN $2 = 'verified'::verification_status THEN NOW() ELSE NULL END \
+         WHERE id = $1",
+    )
+    .bind(verification_id)
+    .bind(&status)
+    .bind(&outcome.error_message)
+    .bind(outcome.reason_code)
+    .bind(&outcome.built_wasm_hash)
+    .bind(&outcome.effective_build_params)
+    .bind(&outcome.resolved_dependencies)
+    .execute(&state.db)
+    .await;
+
+    // A `wasm_base64:` submission was only hashed, like an artifact upload
+    match result {
+        Ok(_)
+            if status == VerificationStatus::Verified && !verifier::is_precompiled(source_code) =>
+        {
+            record_verified(state, verification_id, contract_uuid).await
+        }
+        Ok(_) => {}
+        Err(err) => {
+            tracing::error!(
+                verification_id = %verification_id,
+                error = ?err,
+                "failed to record verification outcome"
+            );
+            return;
+        }
+    }
+
+    if let Some(url) = callback_url {
+        let payload =
+            callback_payload(verification_id, contract_uuid, expected_wasm_hash, &outcome);
+        verification_webhook::spawn_delivery(state.clock.clone(), url, payload);
+    }
+}
+
+/// Longest tail of the build error output sent in a callback
+const CALLBACK_LOG_EXCERPT_BYTES: usize = 4096;
+
+fn callback_payload(
+    verification_id: Uuid,
+    contract_uuid: Uuid,
+    expected_wasm_hash: Option<String>,
+    outcome: &BuildOutcome,
+) -> VerificationCallbackPayload {
+    VerificationCallbackPayload {
+        verification_id,
+        contract_id: contract_uuid,
+        status: outcome.status.clone(),
+        reason_code: outcome.reason_code,
+        expected_wasm_hash,
+        built_wasm_hash: outcome.built_wasm_hash.clone(),
+        log_excerpt: outcome.error_message.as_deref().map(log_excerpt),
+        completed_at: Utc::now(),
+    }
+}
+
+/// The last `CALLBACK_LOG_EXCERPT_BYTES` of `log`, where compiler errors end
+fn log_excerpt(log: &str) -> String {
+    let mut start = log.len().saturating_sub(CALLBACK_LOG_EXCERPT_BYTES);
+    while !log.is_char_boundary(start) {
+        start += 1;
+    }
+    log[start..].to_string()
+}
+
+/// Follow-up for a verification that just settled as verified
+async fn record_verified(state: &AppState, verification_id: Uuid, contract_uuid: Uuid) {
+    if let Err(err) = record_verified_network(state, verification_id).await {
+        tracing::error!(
+            verification_id = %verification_id,
+            error = ?err,
+            "failed to record verified network"
+        );
+    }
+
+    // The first successful verification fixes the contract's SDK version
+    if let Err(err) = sqlx::query(
+        "UPDATE contracts c SET verified_sdk_version = v.compiler_version \
+         FROM verifications v \
+         WHERE v.id = $1 AND c.id = v.contract_id AND c.verified_sdk_version IS NULL \
+           AND v.compiler_version IS NOT NULL",
+    )
+    .bind(verification_id)
+    .execute(&state.db)
+    .await
+    {
+        tracing::error!(
+            verification_id = %verification_id,
+            error = ?err,
+            "failed to record verified sdk version"
+        );
+    }
+
+    analytics::spawn_record_event(
+        state,
+        AnalyticsEventType::ContractVerified,
+        contract_uuid,
+        None,
+        None,
+        Some(serde_json::json!({ "verification_id": verification_id })),
+    );
+}
+
+/// Mark the verification's network verified in `network_configs`. The
+/// top-level `is_verified` means verified on any network, so it is set too.
+async fn record_verified_network(state: &AppState, verification_id: Uuid) -> sqlx::Result<()> {
+    let mut tx = state.db.begin().await?;
+    let (contract_uuid, network, contract_id, configs): (Uuid, Network, String, Option<Value>) =
+        sqlx::query_as(
+            "SELECT c.id, COALESCE(v.network, c.network), c.contract_id, c.network_configs \
+             FROM verifications v JOIN contracts c ON c.id = v.contract_id \
+             WHERE v.id = $1 \
+             FOR UPDATE OF c",
+        )
+        .bind(verification_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE contracts SET network_configs = $2, is_verified = true, updated_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(contract_uuid)
+    .bind(mark_network_verified(configs, &network, &contract_id))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// How a finished build settles its verification row
+#[derive(Debug, PartialEq)]
+struct BuildOutcome {
+    status: VerificationStatus,
+    reason_code: VerificationReasonCode,
+    error_message: Option<String>,
+    built_wasm_hash: Option<String>,
+    /// `verifier::EffectiveBuildParams` of the build, when one ran
+    effective_build_params: Option<Value>,
+    /// Crate versions from the build's lockfile, when one ran
+    resolved_dependencies: Option<Value>,
+    /// `VERIFICATION_LATENCY` label
+    label: &'static str,
+}
+
+fn settle_build(
+    result: &Result<verifier::VerificationResult, verifier::BuildError>,
+) -> BuildOutcome {
+    let reason_code = verifier::reason_code(result);
+    match result {
+        Ok(build) if build.verified => BuildOutcome {
+            status: VerificationStatus::Verified,
+            reason_code,
+            error_message: None,
+            built_wasm_hash: Some(build.built_wasm_hash.clone()),
+            effective_build_params: effective_params(build),
+            resolved_dependencies: resolved_dependencies(build),
+            label: "verified",
+        },
+        Ok(build) => BuildOutcome {
+            status: VerificationStatus::Failed,
+            reason_code,
+            error_message: Some("compiled bytecode does not match deployed wasm hash".to_string()),
+            built_wasm_hash: Some(build.built_wasm_hash.clone()),
+            effective_build_params: effective_params(build),
+            resolved_dependencies: resolved_dependencies(build),
+            label: "failed",
+        },
+        Err(err) => BuildOutcome {
+            status: VerificationStatus::Failed,
+            reason_code,
+            error_message: Some(err.to_string()),
+            built_wasm_hash: None,
+            effective_build_params: None,
+            resolved_dependencies: None,
+            label: "error",
+        },
+    }
+}
+
+fn resolved_dependencies(build: &verifier::VerificationResult) -> Option<Value> {
+    build
+        .resolved_dependencies
+        .as_ref()
+        .and_then(|resolved| serde_json::to_value(resolved).ok())
+}
+
+fn effective_params(build: &verifier::VerificationResult) -> Option<Value> {
+    build
+        .effective_params
+        .as_ref()
+        .and_then(|params| serde_json::to_value(params).ok())
+}
+
+/// Source download links are valid for this long unless the caller asks for less
+const SOURCE_URL_DEFAULT_TTL_SECS: u64 = 300;
+const SOURCE_URL_MAX_TTL_SECS: u64 = 3600;
+
+/// Key for source download tokens, from `SOURCE_URL_SECRET`
+fn source_url_secret() -> ApiResult<String> {
+    std::env::var("SOURCE_URL_SECRET")
+        .ok()
+        .filter(|secret| !secret.trim().is_empty())
+        .ok_or_else(|| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SourceDownloadsDisabled",
+                "Source downloads are not configured on this registry",
+            )
+        })
+}
+
+/// Hex HMAC-SHA256 over `source:{verification_id}:{expires}`
+fn source_token(secret: &[u8], verification_id: Uuid, expires: u64) -> String {
+    hex::encode(hmac_sha256(
+        secret,
+        format!("source:{}:{}", verification_id, expires).as_bytes(),
+    ))
+}
+
+/// Accept `token` for `verification_id` only if it was issued with this
+/// secret and `expires` has not passed as of `now` (unix seconds)
+fn check_source_token(
+    secret: &[u8],
+    verification_id: Uuid,
+    query: &SourceDownloadQuery,
+    now: u64,
+) -> ApiResult<()> {
+    let provided = hex::decode(&query.token).unwrap_or_default();
+    let expected =
+        hex::decode(source_token(secret, verification_id, query.expires)).unwrap_or_default();
+    if !constant_time_eq(&provided, &expected) {
+        return Err(ApiError::forbidden(
+            "InvalidSourceToken",
+            "Source download token is not valid for this verification",
+        ));
+    }
+    if now > query.expires {
+        return Err(ApiError::new(
+            StatusCode::GONE,
+            "SourceUrlExpired",
+            "Source download link has expired; request a new one",
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/admin/verifications/:id/source-url
+///
+/// Issues a short-lived signed link to the source a verification was built
+/// from. Operator only: holding the link is what authorizes the download.
+pub async fn create_source_url(
+    State(state): State<AppState>,
+    Extension(AdminOperator(operator)): Extension<AdminOperator>,
+    Path(id): Path<String>,
+    Query(params): Query<SourceUrlParams>,
+) -> ApiResult<Json<SignedSourceUrl>> {
+    let verification_id = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidVerificationId",
+            format!("Invalid verification ID format: {}", id),
+        )
+    })?;
+    let secret = source_url_secret()?;
+    fetch_verification(&state, verification_id).await?;
+
+    let ttl = params
+        .ttl_secs
+        .unwrap_or(SOURCE_URL_DEFAULT_TTL_SECS)
+        .clamp(1, SOURCE_URL_MAX_TTL_SECS);
+    let expires = state.clock.unix_now() + ttl;
+    let token = source_token(secret.trim().as_bytes(), verification_id, expires);
+    tracing::info!(
+        verification_id = %verification_id,
+        operator = %operator,
+        ttl_secs = ttl,
+        "issued source download link"
+    );
+
+    Ok(Json(SignedSourceUrl {
+        verification_id,
+        url: format!(
+            "/api/verifications/{}/source?expires={}&token={}",
+            verification_id, expires, token
+        ),
+        expires_at: DateTime::from_timestamp(expires as i64, 0).unwrap_or_else(Utc::now),
+    }))
+}
+
+/// GET /api/verifications/:id/source?expires=&token=
+///
+/// Downloads the exact source a successful verification was built from.
+pub async fn download_verified_source(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<SourceDownloadQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let verification_id = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidVerificationId",
+            format!("Invalid verification ID format: {}", id),
+        )
+    })?;
+    let secret = source_url_secret()?;
+    check_source_token(
+        secret.trim().as_bytes(),
+        verification_id,
+        &query,
+        state.clock.unix_now(),
+    )?;
+
+    let row: Option<(VerificationStatus, Option<String>)> =
+        sqlx::query_as("SELECT status, source_code FROM verifications WHERE id = $1")
+            .bind(verification_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get verification source", err))?;
+    let (status, source) = row.ok_or_else(|| {
+        ApiError::not_found(
+            "VerificationNotFound",
+            format!("No verification found with ID: {}", verification_id),
+        )
+    })?;
+    if status != VerificationStatus::Verified {
+        return Err(ApiError::conflict(
+            "VerificationNotVerified",
+            format!("Verification {} did not succeed", verification_id),
+        ));
+    }
+    // Cache hits reuse an earlier build and store no source of their own
+    let source = source.ok_or_else(|| {
+        ApiError::not_found(
+            "SourceNotArchived",
+            format!("Verification {} has no stored source", verification_id),
+        )
+    })?;
+
+    let extension = if verifier::is_precompiled(&source) {
+        "wasm.b64"
+    } else {
+        "rs"
+    };
+    tracing::info!(verification_id = %verification_id, "verified source downloaded");
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"verification-{}.{}\"",
+                    verification_id, extension
+                ),
+            ),
+        ],
+        source,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{FixedClock, SystemClock};
+    use chrono::TimeZone;
+
+    const SOURCE_SECRET: &[u8] = b"source-secret";
+    const NOW: u64 = 1_760_000_000;
+
+    fn source_query(verification_id: Uuid, expires: u64) -> SourceDownloadQuery {
+        SourceDownloadQuery {
+            expires,
+            token: source_token(SOURCE_SECRET, verification_id, expires),
+        }
+    }
+
+    #[test]
+    fn valid_source_token_is_accepted() {
+        let id = Uuid::new_v4();
+        let query = source_query(id, NOW + SOURCE_URL_DEFAULT_TTL_SECS);
+        assert!(check_source_token(SOURCE_SECRET, id, &query, NOW).is_ok());
+    }
+
+    #[test]
+    fn expired_source_token_is_rejected() {
+        let id = Uuid::new_v4();
+        let query = source_query(id, NOW - 1);
+        let err = check_source_token(SOURCE_SECRET, id, &query, NOW).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::GONE);
+    }
+
+    #[test]
+    fn tampered_source_token_is_rejected() {
+        let id = Uuid::new_v4();
+        // Pushing the expiry out invalidates the signature
+        let mut extended = source_query(id, NOW + 60);
+        extended.expires += 3600;
+        let err = check_source_token(SOURCE_SECRET, id, &extended, NOW).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+
+        // A token for one verification doesn't open another
+        let other = source_query(Uuid::new_v4(), NOW + 60);
+        assert!(check_source_token(SOURCE_SECRET, id, &other, NOW).is_err());
+        assert!(check_source_token(b"other-secret", id, &source_query(id, NOW + 60), NOW).is_err());
+    }
+
+    #[test]
+    fn cache_hit_returns_verified_result_immediately() {
+        let previous = Uuid::new_v4();
+        let verified_at = Utc::now();
+        let (status, Json(body)) = build_check_response(
+            Uuid::new_v4(),
+            "abc".to_string(),
+            Some(CachedVerification {
+                id: previous,
+                verified_at: Some(verified_at),
+            }),
+        );
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.from_cache);
+        assert_eq!(body.status, VerificationStatus::Verified);
+        assert_eq!(body.cached_verification_id, Some(previous));
+        assert_eq!(body.verified_at, Some(verified_at));
+    }
+
+    #[test]
+    fn cache_miss_returns_pending() {
+        let id = Uuid::new_v4();
+        let (status, Json(body)) = build_check_response(id, "abc".to_string(), None);
+
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(!body.from_cache);
+        assert_eq!(body.status, VerificationStatus::Pending);
+        assert_eq!(body.verification_id, id);
+        assert!(body.cached_verification_id.is_none());
+
+        let json = serde_json::to_value(&body).unwrap();
+        assert!(json.get("cached_verification_id").is_none());
+        assert_eq!(json["from_cache"], false);
+    }
+
+    #[test]
+    fn settled_builds_keep_message_and_reason_code() {
+        // The caller submitted no build params; the stored ones carry the defaults
+        let effective = verifier::EffectiveBuildParams::resolve(
+            &serde_json::json!({}),
+            &verifier::VerifierConfig::default(),
+        )
+        .unwrap();
+        let mismatch = settle_build(&Ok(verifier::VerificationResult {
+            verified: false,
+            built_wasm_hash: "abc".to_string(),
+            toolchain: verifier::BuildCommand::default(),
+            target: Some("wasm32-unknown-unknown".to_string()),
+            effective_params: Some(effective),
+            resolved_dependencies: Some(std::collections::BTreeMap::from([(
+                "soroban-sdk".to_string(),
+                "21.7.6".to_string(),
+            )])),
+            timings: Default::default(),
+        }));
+        assert_eq!(mismatch.status, VerificationStatus::Failed);
+        assert_eq!(mismatch.reason_code, VerificationReasonCode::HashMismatch);
+        assert_eq!(mismatch.built_wasm_hash.as_deref(), Some("abc"));
+        let stored = mismatch.effective_build_params.unwrap();
+        assert_eq!(stored["profile"], "release");
+        assert_eq!(stored["optimize"], true);
+        assert_eq!(stored["features"], serde_json::json!([]));
+        assert_eq!(
+            mismatch.resolved_dependencies,
+            Some(serde_json::json!({ "soroban-sdk": "21.7.6" }))
+        );
+        assert!(mismatch.error_message.is_some());
+
+        let timed_out = settle_build(&Err(verifier::BuildError::Timeout(Duration::from_secs(
+            600,
+        ))));
+        assert_eq!(timed_out.reason_code, VerificationReasonCode::Timeout);
+        assert_eq!(
+            timed_out.error_message.as_deref(),
+            Some("build timed out after 600s")
+        );
+
+        let response = serde_json::to_value(VerificationStatusResponse {
+            verification_id: Uuid::nil(),
+            contract_id: Uuid::nil(),
+            status: timed_out.status,
+            method: VerificationMethod::Build,
+            queue_position: None,
+            queue_depth: None,
+            reason_code: Some(timed_out.reason_code),
+            error_message: timed_out.error_message,
+            verified_at: None,
+            source_reference: None,
+            warnings: Vec::new(),
+        })
+        .unwrap();
+        assert_eq!(response["reason_code"], "TIMEOUT");
+        assert!(response.get("warnings").is_none());
+    }
+
+    #[test]
+    fn sdk_mismatch_is_reported_in_the_result() {
+        assert_eq!(sdk_mismatch(None, "21.0.0"), None);
+        assert_eq!(sdk_mismatch(Some("21.0.0"), "v21.0.0"), None);
+
+        let mismatch = sdk_mismatch(Some("21.0.0"), "22.0.1");
+        let warnings = apply_sdk_policy(SdkMismatchPolicy::Warn, mismatch.clone()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'22.0.1'"), "{}", warnings[0]);
+        assert!(
+            warnings[0].contains("soroban-sdk 21.0.0"),
+            "{}",
+            warnings[0]
+        );
+
+        let (_, Json(mut response)) = build_check_response(Uuid::new_v4(), "abc".to_string(), None);
+        response.warnings = warnings;
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["warnings"][0], response.warnings[0]);
+
+        let err = apply_sdk_policy(SdkMismatchPolicy::Reject, mismatch).unwrap_err();
+        assert_eq!(
+            axum::response::IntoResponse::into_response(err).status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    fn run(sdk_version: &str, built_wasm_hash: &str) -> VerificationRun {
+        VerificationRun {
+            id: Uuid::new_v4(),
+            compiler_version: Some("1.79.0".to_string()),
+            build_params: Some(serde_json::json!({ "profile": "release" })),
+            effective_build_params: Some(serde_json::json!({
+                "profile": "release",
+                "features": [],
+                "optimize": true,
+            })),
+            resolved_dependencies: Some(serde_json::json!({
+                "soroban-sdk": sdk_version,
+                "stellar-xdr": "21.0.0",
+            })),
+            built_wasm_hash: Some(built_wasm_hash.to_string()),
+        }
+    }
+
+    #[test]
+    fn comparison_pinpoints_sdk_version_change() {
+        let before = run("21.0.0", "aa11");
+        let after = run("21.1.0", "bb22");
+
+        let report = compare_runs(&before, &after);
+        assert!(!report.reproducible);
+        assert_eq!(
+            report.differences,
+            vec![VerificationInputDiff {
+                field: "resolved_dependencies.soroban-sdk".to_string(),
+                a: Some(serde_json::json!("21.0.0")),
+                b: Some(serde_json::json!("21.1.0")),
+            }]
+        );
+        assert_eq!(report.build_params_a, before.build_params);
+        assert_eq!(
+            report.effective_build_params_b.unwrap()["profile"],
+            "release"
+        );
+    }
+
+    #[test]
+    fn identical_hashes_are_reproducible() {
+        let report = compare_runs(&run("21.0.0", "AA11"), &run("21.0.0", "aa11"));
+        assert!(report.reproducible);
+        assert!(report.differences.is_empty());
+
+        // A run without a recorded artifact cannot prove reproducibility
+        let mut unbuilt = run("21.0.0", "aa11");
+        unbuilt.built_wasm_hash = None;
+        assert!(!compare_runs(&run("21.0.0", "aa11"), &unbuilt).reproducible);
+    }
+
+    fn pending_row(id: Uuid) -> VerificationRow {
+        VerificationRow {
+            id,
+            contract_id: Uuid::new_v4(),
+            status: VerificationStatus::Pending,
+            method: VerificationMethod::Build,
+            reason_code: None,
+            error_message: None,
+            verified_at: None,
+            source_git_url: None,
+            source_commit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn async_submit_can_be_polled_until_settled() {
+        let queue = std::sync::Arc::new(BuildQueue::new(1));
+        let (busy, submitted) = (Uuid::new_v4(), Uuid::new_v4());
+        let (release, hold) = tokio::sync::oneshot::channel::<()>();
+
+        // Another build holds the only slot
+        let blocker = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.run(busy, async { hold.await.ok() }).await }
+        });
+        tokio::task::yield_now().await;
+
+        // Async submit returns straight away; the build waits in line
+        let build = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.run(submitted, async {}).await }
+        });
+        tokio::task::yield_now().await;
+
+        let polled = status_response(pending_row(submitted), &queue);
+        assert_eq!(polled.status, VerificationStatus::Pending);
+        assert_eq!(polled.queue_position, Some(1));
+        assert_eq!(polled.queue_depth, Some(1));
+
+        release.send(()).unwrap();
+        blocker.await.unwrap();
+        build.await.unwrap();
+
+        let settled = status_response(
+            VerificationRow {
+                status: VerificationStatus::Verified,
+                verified_at: Some(Utc::now()),
+                ..pending_row(submitted)
+            },
+            &queue,
+        );
+        assert_eq!(settled.queue_position, None);
+        let json = serde_json::to_value(&settled).unwrap();
+        assert!(json.get("queue_position").is_none());
+        assert!(json.get("queue_depth").is_none());
+    }
+
+    fn precompiled_request(reference: Option<SourceReference>) -> VerifyRequest {
+        VerifyRequest {
+            contract_id: "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC".to_string(),
+            source_code: format!("{}AGFzbQEAAAA=", verifier::WASM_BASE64_PREFIX),
+            build_params: serde_json::json!({}),
+            compiler_version: "21.0.0".to_string(),
+            source_reference: reference,
+            network: None,
+            callback_url: None,
+        }
+    }
+
+    #[test]
+    fn precompiled_verification_keeps_its_source_reference() {
+        let submitted = SourceReference {
+            git_url: " https://github.com/org/token ".to_string(),
+            commit: "0123456789ABCDEF0123456789ABCDEF01234567".to_string(),
+        };
+        let reference = check_source_reference(&precompiled_request(Some(submitted)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reference.git_url, "https://github.com/org/token");
+        assert_eq!(reference.commit, "0123456789abcdef0123456789abcdef01234567");
+
+        // The stored columns come back on the verification record
+        let queue = BuildQueue::new(1);
+        let response = status_response(
+            VerificationRow {
+                status: VerificationStatus::Verified,
+                source_git_url: Some(reference.git_url.clone()),
+                source_commit: Some(reference.commit.clone()),
+                ..pending_row(Uuid::new_v4())
+            },
+            &queue,
+        );
+        assert_eq!(response.source_reference, Some(reference));
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["source_reference"]["git_url"],
+            "https://github.com/org/token"
+        );
+    }
+
+    #[test]
+    fn source_reference_is_validated_and_precompiled_only() {
+        let bad_commit = SourceReference {
+            git_url: "https://github.com/org/token".to_string(),
+            commit: "main".to_string(),
+        };
+        assert!(check_source_reference(&precompiled_request(Some(bad_commit.clone()))).is_err());
+
+        let valid = SourceReference {
+            commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            ..bad_commit
+        };
+        let source_build = VerifyRequest {
+            source_code: "fn main() {}".to_string(),
+            ..precompiled_request(Some(valid))
+        };
+        assert!(check_source_reference(&source_build).is_err());
+        assert!(check_source_reference(&precompiled_request(None))
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn matching_artifact_is_verified() {
+        use base64::Engine;
+        use sha2::{Digest, Sha256};
+
+        let wasm = b"\0asm\x01\0\0\0";
+        let encoded = base64::engine::general_purpose::STANDARD.encode(wasm);
+        let deployed = hex::encode(Sha256::digest(wasm));
+        let config = verifier::VerifierConfig::default();
+
+        // The prefix is optional in the request
+        let source = artifact_source(&encoded);
+        assert_eq!(
+            source,
+            artifact_source(&format!(" wasm_base64:{} ", encoded))
+        );
+
+        let outcome = settle_artifact(&source, &deployed, &config).await.unwrap();
+        assert_eq!(outcome.status, VerificationStatus::Verified);
+        assert_eq!(outcome.reason_code, VerificationReasonCode::HashMatch);
+        assert_eq!(outcome.built_wasm_hash, Some(deployed));
+    }
+
+    #[tokio::test]
+    async fn mismatching_artifact_fails() {
+        use axum::response::IntoResponse;
+
+        let config = verifier::VerifierConfig::default();
+        let source = artifact_source("AGFzbQEAAAA=");
+        let outcome = settle_artifact(&source, &"0".repeat(64), &config)
+            .await
+            .unwrap();
+        assert_eq!(outcome.status, VerificationStatus::Failed);
+        assert_eq!(outcome.reason_code, VerificationReasonCode::HashMismatch);
+        assert!(outcome.built_wasm_hash.is_some());
+
+        let err = settle_artifact(&artifact_source("not base64!"), &"0".repeat(64), &config)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn matching_artifact_leaves_the_contract_unverified() {
+        use axum::{body::Body, http::Request, routing::post, Router};
+        use base64::Engine;
+        use sha2::{Digest, Sha256};
+        use tower::ServiceExt;
+
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let wasm = b"\0asm\x01\0\0\0";
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let contract = crate::test_db::insert_contract(&state.db, publisher, "CARTIFACT").await;
+        sqlx::query("UPDATE contracts SET wasm_hash = $2 WHERE id = $1")
+            .bind(contract)
+            .bind(hex::encode(Sha256::digest(wasm)))
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let api_key = crate::api_keys::generate_api_key();
+        sqlx::query("INSERT INTO api_keys (publisher_id, name, key_hash) VALUES ($1, 'ci', $2)")
+            .bind(publisher)
+            .bind(crate::api_keys::hash_api_key(&api_key))
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        let app = Router::new()
+            .route("/api/contracts/:id/verify/artifact", post(verify_artifact))
+            .with_state(state.clone());
+        let body = serde_json::json!({
+            "wasm_base64": base64::engine::general_purpose::STANDARD.encode(wasm)
+        })
+        .to_string();
+        let submit = |key: Option<&str>| {
+            let mut request = Request::post("/api/contracts/CARTIFACT/verify/artifact")
+                .header("content-type", "application/json");
+            if let Some(key) = key {
+                request = request.header(crate::api_keys::API_KEY_HEADER, key);
+            }
+            request.body(Body::from(body.clone())).unwrap()
+        };
+
+        let unauthenticated = app.clone().oneshot(submit(None)).await.unwrap();
+        assert_eq!(unauthenticated.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.oneshot(submit(Some(&api_key))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let response: VerificationStatusResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(response.status, VerificationStatus::Verified);
+        assert_eq!(response.method, VerificationMethod::Artifact);
+        assert_eq!(response.warnings, vec![BYTECODE_ONLY_WARNING.to_string()]);
+
+        let is_verified: bool =
+            sqlx::query_scalar("SELECT is_verified FROM contracts WHERE id = $1")
+                .bind(contract)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        assert!(!is_verified);
+    }
+
+    #[test]
+    fn verifying_on_testnet_leaves_mainnet_unverified() {
+        let configs = serde_json::json!({
+            "mainnet": { "contract_id": "CMAIN", "is_verified": false, "min_version": null, "max_version": null },
+            "testnet": { "contract_id": "CTEST", "is_verified": false, "min_version": null, "max_version": null }
+        });
+        let network =
+            resolve_network(&Network::Mainnet, Some(&configs), Some(&Network::Testnet)).unwrap();
+        assert_eq!(network, Network::Testnet);
+
+        let marked = mark_network_verified(Some(configs), &network, "CMAIN");
+        let marked: HashMap<String, NetworkConfig> = serde_json::from_value(marked).unwrap();
+        assert!(marked["testnet"].is_verified);
+        assert_eq!(marked["testnet"].contract_id, "CTEST");
+        assert!(!marked["mainnet"].is_verified);
+    }
+
+    #[test]
+    fn verification_network_defaults_to_the_contracts_own() {
+        assert_eq!(
+            resolve_network(&Network::Testnet, None, None).unwrap(),
+            Network::Testnet
+        );
+        // Unconfigured networks are refused rather than silently added
+        assert!(resolve_network(&Network::Testnet, None, Some(&Network::Mainnet)).is_err());
+
+        // Rows registered before network_configs gain an entry for their network
+        let marked = mark_network_verified(None, &Network::Testnet, "CTEST");
+        assert_eq!(marked["testnet"]["contract_id"], "CTEST");
+        assert_eq!(marked["testnet"]["is_verified"], true);
+    }
+
+    /// Callbacks a local receiver got, as (headers, body); the first
+    /// `failures` deliveries are answered with a 503
+    async fn callback_receiver(
+        failures: usize,
+    ) -> (
+        String,
+        std::sync::Arc<std::sync::Mutex<Vec<(axum::http::HeaderMap, axum::body::Bytes)>>>,
+    ) {
+        use std::sync::{Arc, Mutex};
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post({
+                let received = received.clone();
+                move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                    let received = received.clone();
+                    async move {
+                        let mut received = received.lock().unwrap();
+                        received.push((headers, body));
+                        if received.len() <= failures {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        } else {
+                            StatusCode::NO_CONTENT
+                        }
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn callback_carries_the_settled_outcome() {
+        let config = WebhookConfig {
+            secret: Some("hook-secret".to_string()),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+        };
+        let clock = FixedClock::at(Utc.timestamp_opt(1_700_000_000, 0).unwrap());
+        let verification_id = Uuid::new_v4();
+        let contract_uuid = Uuid::new_v4();
+
+        let verified = settle_build(&Ok(verifier::VerificationResult {
+            verified: true,
+            built_wasm_hash: "abc".to_string(),
+            toolchain: verifier::BuildCommand::default(),
+            target: None,
+            effective_params: None,
+            resolved_dependencies: None,
+            timings: Default::default(),
+        }));
+        let failed = settle_build(&Err(verifier::BuildError::Compile(format!(
+            "{}error[E0425]: cannot find value `x` in this scope",
+            "warning: unused import\n".repeat(500)
+        ))));
+
+        // The verified callback is only acknowledged on its second attempt
+        for (outcome, failures) in [(verified, 1), (failed, 0)] {
+            let (url, received) = callback_receiver(failures).await;
+            let payload = callback_payload(
+                verification_id,
+                contract_uuid,
+                Some("abc".to_string()),
+                &outcome,
+            );
+            let attempt = verification_webhook::deliver(&config, &clock, &url, &payload)
+                .await
+                .unwrap();
+            assert_eq!(attempt as usize, failures + 1);
+
+            let received = received.lock().unwrap();
+            let (headers, body) = received.last().unwrap();
+            let timestamp = headers[crate::request_signing::TIMESTAMP_HEADER]
+                .to_str()
+                .unwrap();
+            assert_eq!(timestamp, "1700000000");
+            let signature = headers[crate::request_signing::SIGNATURE_HEADER]
+                .to_str()
+                .unwrap();
+            assert_eq!(
+                signature,
+                hex::encode(hmac_sha256(
+                    b"hook-secret",
+                    &crate::request_signing::signing_payload(timestamp, body)
+                ))
+            );
+
+            let delivered: VerificationCallbackPayload = serde_json::from_slice(body).unwrap();
+            assert_eq!(delivered, payload);
+            assert_eq!(delivered.status, outcome.status);
+            assert_eq!(delivered.reason_code, outcome.reason_code);
+        }
+
+        let (url, received) = callback_receiver(0).await;
+        let payload = callback_payload(
+            verification_id,
+            contract_uuid,
+            None,
+            &settle_build(&Err(verifier::BuildError::Compile("x".repeat(10_000)))),
+        );
+        verification_webhook::deliver(&config, &clock, &url, &payload)
+            .await
+            .unwrap();
+        let delivered: VerificationCallbackPayload =
+            serde_json::from_slice(&received.lock().unwrap()[0].1).unwrap();
+        assert_eq!(delivered.status, VerificationStatus::Failed);
+        assert_eq!(delivered.reason_code, VerificationReasonCode::CompileError);
+        assert!(delivered.built_wasm_hash.is_none());
+        assert_eq!(
+            delivered.log_excerpt.unwrap().len(),
+            CALLBACK_LOG_EXCERPT_BYTES
+        );
+    }
+
+    #[tokio::test]
+    async fn callback_gives_up_after_its_attempts() {
+        let config = WebhookConfig {
+            secret: None,
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+        };
+        let (url, received) = callback_receiver(usize::MAX).await;
+        let outcome = settle_build(&Err(verifier::BuildError::Timeout(Duration::from_secs(1))));
+        let payload = callback_payload(Uuid::new_v4(), Uuid::new_v4(), None, &outcome);
+
+        let err = verification_webhook::deliver(&config, &SystemClock, &url, &payload)
+            .await
+            .unwrap_err();
+        assert!(err.contains("503"), "{}", err);
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn startup_takes_over_only_abandoned_pending_builds() {
+        let Some(state) = crate::test_db::state().await else {
+            return;
+        };
+        let publisher = crate::test_db::insert_publisher(&state.db).await;
+        let contract = crate::test_db::insert_contract(&state.db, publisher, "CRESUME").await;
+        let pending = |claimed_at: &'static str| {
+            let db = state.db.clone();
+            async move {
+                sqlx::query_scalar::<_, Uuid>(&format!(
+                    "INSERT INTO verifications \
+                        (contract_id, status, source_code, build_params, callback_url, claimed_at) \
+                     VALUES ($1, 'pending', 'src', '{{}}', 'https://ci.example/hook', {}) \
+                     RETURNING id",
+                    claimed_at
+                ))
+                .bind(contract)
+                .fetch_one(&db)
+                .await
+                .unwrap()
+            }
+        };
+        let building = pending("NOW()").await;
+        let abandoned = pending("NOW() - INTERVAL '1 hour'").await;
+        let never_claimed = pending("NULL").await;
+        let lease = Duration::from_secs(600);
+
+        let mut claimed: Vec<Uuid> = claim_orphaned_builds(&state, lease)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|build| {
+                assert_eq!(
+                    build.callback_url.as_deref(),
+                    Some("https://ci.example/hook")
+                );
+                build.id
+            })
+            .collect();
+        claimed.sort();
+        let mut expected = vec![abandoned, never_claimed];
+        expected.sort();
+        assert_eq!(claimed, expected);
+        assert!(!claimed.contains(&building));
+
+        // Another replica starting right after finds nothing left to take
+        assert!(claim_orphaned_builds(&state, lease)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
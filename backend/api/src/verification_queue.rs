@@ -0,0 +1,132 @@
+// Queue for source-verification builds submitted through the API.
+//
+// Builds are CPU and disk heavy, so at most `max_concurrent` run at once and
+// the rest wait in submission order. The queue also remembers who is waiting
+// so GET /api/verifications/:id/status can report a position to clients.
+
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+/// Where a verification currently sits in the build queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QueuePosition {
+    /// 1-based place in line; 0 once the build is running
+    pub position: usize,
+    /// Builds waiting for a slot, including this one
+    pub depth: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    waiting: VecDeque<Uuid>,
+    running: HashSet<Uuid>,
+}
+
+pub struct BuildQueue {
+    permits: Semaphore,
+    state: Mutex<QueueState>,
+}
+
+impl Default for BuildQueue {
+    fn default() -> Self {
+        Self::new(verifier::DEFAULT_MAX_CONCURRENT_BUILDS)
+    }
+}
+
+impl BuildQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent.max(1)),
+            state: Mutex::new(QueueState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for a build slot, then run `build` for `verification_id`
+    pub async fn run<F: Future>(&self, verification_id: Uuid, build: F) -> F::Output {
+        self.state().waiting.push_back(verification_id);
+        let permit = self.permits.acquire().await;
+        {
+            let mut state = self.state();
+            state.waiting.retain(|id| *id != verification_id);
+            state.running.insert(verification_id);
+        }
+
+        let output = build.await;
+
+        self.state().running.remove(&verification_id);
+        drop(permit);
+        output
+    }
+
+    /// Queue position of a verification, or `None` when this process is not
+    /// holding it (finished, or submitted before a restart)
+    pub fn position(&self, verification_id: Uuid) -> Option<QueuePosition> {
+        let state = self.state();
+        let depth = state.waiting.len();
+        if state.running.contains(&verification_id) {
+            return Some(QueuePosition { position: 0, depth });
+        }
+        state
+            .waiting
+            .iter()
+            .position(|id| *id == verification_id)
+            .map(|index| QueuePosition {
+                position: index + 1,
+                depth,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn waiting_builds_report_their_place_in_line() {
+        let queue = Arc::new(BuildQueue::new(1));
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let (release, hold) = oneshot::channel::<()>();
+
+        let running = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.run(first, async { hold.await.ok() }).await }
+        });
+        tokio::task::yield_now().await;
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.run(second, async { "built" }).await }
+        });
+        tokio::task::yield_now().await;
+
+        assert_eq!(
+            queue.position(first),
+            Some(QueuePosition {
+                position: 0,
+                depth: 1
+            })
+        );
+        assert_eq!(
+            queue.position(second),
+            Some(QueuePosition {
+                position: 1,
+                depth: 1
+            })
+        );
+
+        release.send(()).unwrap();
+        running.await.unwrap();
+        assert_eq!(waiting.await.unwrap(), "built");
+        assert_eq!(queue.position(first), None);
+        assert_eq!(queue.position(second), None);
+    }
+}
@@ -0,0 +1,155 @@
+// Completion callbacks for verifications submitted with a `callback_url`.
+//
+// When the build settles, the outcome is POSTed to the callback as a
+// `VerificationCallbackPayload`, signed the same way as inbound signed
+// requests:
+//
+//   X-Timestamp:  unix seconds when the callback was signed
+//   X-Signature:  hex HMAC-SHA256 over "{timestamp}.{raw body}" keyed with
+//                 `VERIFICATION_WEBHOOK_SECRET`
+//
+// A receiver that does not answer 2xx is retried with exponential backoff up
+// to `VERIFICATION_WEBHOOK_MAX_ATTEMPTS` times. Callbacks are refused at
+// submission when no secret is configured, since receivers could not tell a
+// genuine callback from a forged one.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use shared::VerificationCallbackPayload;
+use tokio::task::JoinHandle;
+
+use crate::clock::Clock;
+use crate::request_signing::{hmac_sha256, signing_payload, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub secret: Option<String>,
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each one after
+    pub initial_backoff: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// `VERIFICATION_WEBHOOK_SECRET`, `VERIFICATION_WEBHOOK_MAX_ATTEMPTS` and
+    /// `VERIFICATION_WEBHOOK_BACKOFF_MS`; invalid values keep the defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            secret: std::env::var("VERIFICATION_WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.trim().is_empty()),
+            max_attempts: std::env::var("VERIFICATION_WEBHOOK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &u32| n > 0)
+                .unwrap_or(defaults.max_attempts),
+            initial_backoff: std::env::var("VERIFICATION_WEBHOOK_BACKOFF_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.initial_backoff),
+        }
+    }
+}
+
+/// Wait after failed attempt `attempt` (1-based)
+fn backoff(initial: Duration, attempt: u32) -> Duration {
+    initial
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// POST `payload` to `url` until it is acknowledged with a 2xx or the
+/// attempts run out, signing each attempt as of `clock`. Returns the attempt
+/// that succeeded.
+pub async fn deliver(
+    config: &WebhookConfig,
+    clock: &dyn Clock,
+    url: &str,
+    payload: &VerificationCallbackPayload,
+) -> Result<u32, String> {
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|err| err.to_string())?;
+    let body = serde_json::to_vec(payload).map_err(|err| err.to_string())?;
+
+    let mut last_error = String::new();
+    for attempt in 1..=config.max_attempts {
+        let timestamp = clock.unix_now().to_string();
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, &timestamp);
+        if let Some(secret) = &config.secret {
+            let signature = hmac_sha256(secret.as_bytes(), &signing_payload(&timestamp, &body));
+            request = request.header(SIGNATURE_HEADER, hex::encode(signature));
+        }
+
+        match request.body(body.clone()).send().await {
+            Ok(response) if response.status().is_success() => return Ok(attempt),
+            Ok(response) => last_error = format!("receiver answered {}", response.status()),
+            Err(err) => last_error = err.to_string(),
+        }
+        tracing::warn!(
+            verification_id = %payload.verification_id,
+            attempt,
+            error = %last_error,
+            "verification callback delivery failed"
+        );
+        if attempt < config.max_attempts {
+            tokio::time::sleep(backoff(config.initial_backoff, attempt)).await;
+        }
+    }
+    Err(last_error)
+}
+
+/// Deliver the callback off the build path; failures are only logged
+pub fn spawn_delivery(
+    clock: Arc<dyn Clock>,
+    url: String,
+    payload: VerificationCallbackPayload,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let config = WebhookConfig::from_env();
+        if let Err(err) = deliver(&config, clock.as_ref(), &url, &payload).await {
+            tracing::error!(
+                verification_id = %payload.verification_id,
+                attempts = config.max_attempts,
+                error = %err,
+                "giving up on verification callback"
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let initial = Duration::from_secs(2);
+        assert_eq!(backoff(initial, 1), Duration::from_secs(2));
+        assert_eq!(backoff(initial, 2), Duration::from_secs(4));
+        assert_eq!(backoff(initial, 4), Duration::from_secs(16));
+        assert_eq!(backoff(initial, 30), MAX_BACKOFF);
+    }
+}
@@ -181,7 +181,7 @@ fn test_csv_row_with_no_stellar_version() {
     let stellar_version: Option<&str> = None;
     let csv_row = format!(
         "1.0.0,GDEF,my-contract,1.0.0,{},false",
-        stellar_version.unwrap_or("")
+        stellar_version.map(str::to_string).unwrap_or_default()
     );
     assert_eq!(csv_row, "1.0.0,GDEF,my-contract,1.0.0,,false");
 }
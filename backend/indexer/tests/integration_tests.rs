@@ -1,11 +1,11 @@
-/// Integration tests for the indexer service
-/// These tests validate core functionality without requiring a real database
+// Integration tests for the indexer service
+// These tests validate core functionality without requiring a real database
 
 #[cfg(test)]
 mod tests {
     use indexer::backoff::ExponentialBackoff;
     use indexer::detector::detect_contract_deployments;
-    use indexer::rpc::{ContractDeployment, Operation};
+    use indexer::rpc::Operation;
     use indexer::state::IndexerState;
     use serde_json::json;
     use shared::Network;
@@ -46,6 +46,76 @@ pub struct ContractGetResponse {
     pub network_config: Option<NetworkConfig>,
 }
 
+/// One registry row for an on-chain contract ID (one per network)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ContractRegistration {
+    pub id: Uuid,
+    pub network: Network,
+    pub name: String,
+    pub publisher_id: Uuid,
+    pub is_verified: bool,
+    /// Status of the most recent verification attempt, if any
+    pub verification_status: Option<VerificationStatus>,
+    pub logical_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Registrations sharing a logical contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrationGroup {
+    pub logical_id: Uuid,
+    pub networks: Vec<Network>,
+    pub registration_ids: Vec<Uuid>,
+}
+
+/// Response for GET /api/contracts/by-contract-id/:contract_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractRegistrationsResponse {
+    pub contract_id: String,
+    pub registrations: Vec<ContractRegistration>,
+    pub groups: Vec<RegistrationGroup>,
+}
+
+/// One registration in a logical contract group
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LogicalContractMember {
+    pub id: Uuid,
+    pub contract_id: String,
+    pub network: Network,
+    pub name: String,
+    pub publisher_id: Uuid,
+    pub is_verified: bool,
+    /// Status of the most recent verification attempt, if any
+    pub verification_status: Option<VerificationStatus>,
+    /// Highest published version by semver, if any
+    #[sqlx(default)]
+    pub latest_version: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Latest published version of a logical contract on one network
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkVersion {
+    pub network: Network,
+    pub version: String,
+}
+
+/// Roll-up of a logical contract across its networks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalContractSummary {
+    pub verified_on_any_network: bool,
+    pub verified_networks: Vec<Network>,
+    pub latest_versions: Vec<NetworkVersion>,
+}
+
+/// Response for GET /api/contracts/logical/:logical_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalContractResponse {
+    pub logical_id: Uuid,
+    pub contracts: Vec<LogicalContractMember>,
+    pub summary: LogicalContractSummary,
+}
+
 /// Per-network config: address, verified status, min/max version (Issue #43)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
@@ -58,7 +128,7 @@ pub struct NetworkConfig {
 }
 
 /// Network where the contract is deployed
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "network_type", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum Network {
@@ -110,6 +180,44 @@ pub struct ContractVersion {
     /// Signature algorithm identifier (e.g. "ed25519")
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub signature_algorithm: Option<String>,
+    /// Drafts are hidden from listings and the changelog until published
+    #[serde(default)]
+    pub status: VersionStatus,
+}
+
+/// Query params for GET /api/contracts/versions
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchVersionsQuery {
+    /// Comma-separated contract row IDs
+    pub ids: String,
+}
+
+/// Query params for GET /api/contracts/:id/versions/latest
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LatestVersionQuery {
+    /// Consider prerelease versions such as `2.0.0-beta.1`
+    #[serde(default)]
+    pub include_prerelease: bool,
+}
+
+/// Response for GET /api/contracts/:id/versions/latest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatestVersionResponse {
+    #[serde(flatten)]
+    pub version: ContractVersion,
+    /// Where to fetch this version's ABI, when one was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi_url: Option<String>,
+}
+
+/// Publication state of a contract version
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "version_status", rename_all = "lowercase")]
+pub enum VersionStatus {
+    Draft,
+    #[default]
+    Published,
 }
 
 /// Verification status and details
@@ -124,10 +232,16 @@ pub struct Verification {
     pub verified_at: Option<DateTime<Utc>>,
     pub error_message: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Compile-cache key over source, compiler version and build params
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    /// True when this row reused a previously verified artifact
+    #[serde(default)]
+    pub from_cache: bool,
 }
 
 /// Verification status enum
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "verification_status", rename_all = "lowercase")]
 pub enum VerificationStatus {
     Pending,
@@ -135,6 +249,43 @@ pub enum VerificationStatus {
     Failed,
 }
 
+/// Stable reason a verification settled the way it did, for clients to branch
+/// on instead of parsing `error_message`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(
+    type_name = "verification_reason_code",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum VerificationReasonCode {
+    /// Rebuilt artifact hashes to the deployed wasm hash
+    HashMatch,
+    /// Build succeeded but the artifact differs from the deployed bytecode
+    HashMismatch,
+    /// The source did not compile, or produced no usable artifact
+    CompileError,
+    /// The build exceeded the verifier's time limit
+    Timeout,
+    /// The configured build toolchain is not installed on the verifier
+    ToolchainMissing,
+    /// Build params or compiler version were rejected before building
+    InvalidInput,
+    /// Any other verifier failure
+    InternalError,
+}
+
+/// How a verification reached its result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "verification_method", rename_all = "lowercase")]
+pub enum VerificationMethod {
+    /// The submitted source was compiled and the artifact hashed
+    #[default]
+    Build,
+    /// A prebuilt WASM artifact was hashed without compiling
+    Artifact,
+}
+
 /// Contract maturity level - indicates stability and production readiness
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MaturityLevel {
@@ -167,7 +318,7 @@ pub struct ContractStats {
 }
 
 /// GraphNode (minimal contract info for graph rendering)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct GraphNode {
     pub id: Uuid,
     pub contract_id: String,
@@ -179,7 +330,7 @@ pub struct GraphNode {
 }
 
 /// Graph edge (dependency relationship)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct GraphEdge {
     pub source: Uuid,
     pub target: Uuid,
@@ -226,6 +377,46 @@ pub struct CreateContractVersionRequest {
     pub publisher_key: Option<String>,
     #[serde(default)]
     pub signature_algorithm: Option<String>,
+    /// Stage the version as a draft; promote it later via `.../publish`
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// One historical version in POST /api/contracts/:id/versions/import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedVersion {
+    pub version: String,
+    pub wasm_hash: String,
+    pub abi: serde_json::Value,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub commit_hash: Option<String>,
+    #[serde(default)]
+    pub release_notes: Option<String>,
+    /// When the version was originally released; defaults to import time
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Request body for POST /api/contracts/:id/versions/import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportVersionsRequest {
+    /// Oldest release first
+    pub versions: Vec<ImportedVersion>,
+    /// Skip the breaking-change check, for trusted migrations; accepted only
+    /// on the admin import route
+    #[serde(default)]
+    pub skip_checks: bool,
+}
+
+/// Response for POST /api/contracts/:id/versions/import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportVersionsResponse {
+    pub contract_id: Uuid,
+    /// Inserted versions, in import order
+    pub imported: Vec<ContractVersion>,
+    pub checks_skipped: bool,
 }
 
 // ────────────────────────────────────────────────────────────────────────────
@@ -253,6 +444,26 @@ pub struct DeprecationInfo {
     pub dependents_notified: i64,
 }
 
+/// Post-sunset usage of one deprecated method
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeprecatedMethodUsage {
+    pub method: String,
+    pub sunset_at: DateTime<Utc>,
+    pub replacement_method: Option<String>,
+    /// Calls recorded after the sunset date
+    pub calls_after_sunset: i64,
+    pub last_called_at: Option<DateTime<Utc>>,
+}
+
+/// Response for GET /api/contracts/:id/deprecated-usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecatedUsageResponse {
+    pub contract_id: String,
+    pub total_calls_after_sunset: i64,
+    /// Sunset methods, most-called first
+    pub methods: Vec<DeprecatedMethodUsage>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeprecateContractRequest {
     pub retirement_at: DateTime<Utc>,
@@ -309,6 +520,403 @@ pub struct MigrationScript {
     pub created_at: DateTime<Utc>,
 }
 
+/// Severity of a security patch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "patch_severity", rename_all = "lowercase")]
+pub enum PatchSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl PatchSeverity {
+    /// Ordering key, higher is more severe
+    pub fn rank(self) -> u8 {
+        match self {
+            Self::Critical => 3,
+            Self::High => 2,
+            Self::Medium => 1,
+            Self::Low => 0,
+        }
+    }
+}
+
+/// A security patch published to the registry. `target_version` is an
+/// inclusive version range (`1.0.0..1.2.0`), a single version, or the
+/// vulnerable WASM hash.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SecurityPatch {
+    pub id: Uuid,
+    pub target_version: String,
+    /// Semver range of vulnerable versions (see `VersionRange`); empty for
+    /// patches that only target a WASM hash
+    pub vulnerable_range: String,
+    pub severity: PatchSeverity,
+    pub new_wasm_hash: String,
+    pub rollout_percentage: i32,
+    pub description: Option<String>,
+    /// Publisher that issued the patch, if recorded
+    pub publisher_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for POST /api/patches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSecurityPatchRequest {
+    pub target_version: String,
+    /// Semver range of vulnerable versions, e.g. `>=1.0.0, <1.2.0`
+    pub vulnerable_range: String,
+    pub severity: PatchSeverity,
+    pub new_wasm_hash: String,
+    #[serde(default = "default_rollout_percentage")]
+    pub rollout_percentage: i32,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub publisher_id: Option<Uuid>,
+}
+
+fn default_rollout_percentage() -> i32 {
+    100
+}
+
+/// Whether a patch relevant to a contract has been applied to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchStatus {
+    /// Recorded in `patch_audits` for this contract
+    Applied,
+    /// Relevant but not yet applied
+    Available,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicablePatch {
+    #[serde(flatten)]
+    pub patch: SecurityPatch,
+    pub status: PatchStatus,
+}
+
+/// Whether the receiver of a patch notice has confirmed processing it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "patch_delivery_status", rename_all = "lowercase")]
+pub enum PatchDeliveryStatus {
+    /// Sent to the receiver
+    #[default]
+    Delivered,
+    /// The receiver posted a signed delivery receipt
+    Confirmed,
+}
+
+/// A security patch notice delivered to one contract
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationRecord {
+    pub id: Uuid,
+    pub patch_id: Uuid,
+    pub contract_id: Uuid,
+    pub notified_at: DateTime<Utc>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub acknowledged_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack_signature: Option<String>,
+    #[serde(default)]
+    pub delivery_status: PatchDeliveryStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed_at: Option<DateTime<Utc>>,
+    /// Severity the notice is handled at; raised with the patch while the
+    /// notice is unacknowledged
+    pub priority: PatchSeverity,
+}
+
+/// Request body for POST /api/patches/:patch_id/delivery-receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReceiptRequest {
+    pub contract_id: String,
+}
+
+/// Request body for POST /api/patches/:patch_id/acknowledge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcknowledgePatchRequest {
+    pub contract_id: String,
+    /// Who is acknowledging, recorded with the acknowledgement
+    pub acknowledged_by: String,
+    /// Base64 Ed25519 signature over `ack:{patch_id}:{contract_id}` by an
+    /// active signing key of the contract's publisher
+    pub signature: String,
+}
+
+/// A contract's notification preferences; a contract without stored
+/// preferences is notified of everything
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ContractNotificationPreferences {
+    /// Lowest patch severity notified; `None` notifies every severity
+    #[serde(default)]
+    pub min_severity: Option<PatchSeverity>,
+    /// Webhook event types delivered (e.g. `contract.verified`); `None`
+    /// delivers every event type
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+impl ContractNotificationPreferences {
+    /// Whether a patch notice of `severity` meets the threshold
+    pub fn allows_severity(&self, severity: PatchSeverity) -> bool {
+        self.min_severity
+            .is_none_or(|min| severity.rank() >= min.rank())
+    }
+}
+
+/// Acknowledgement progress of a patch, for acknowledgement-gated rollouts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchAcknowledgementSummary {
+    pub patch_id: Uuid,
+    pub notified: i64,
+    pub acknowledged: i64,
+    /// `acknowledged / notified`, 0.0 when nobody was notified
+    pub acknowledgement_rate: f64,
+}
+
+/// A patch in GET /api/publishers/:id/patches, with how far its rollout got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublisherPatch {
+    #[serde(flatten)]
+    pub patch: SecurityPatch,
+    pub rollout_stage: RolloutStage,
+    pub notified: i64,
+    pub acknowledged: i64,
+    /// Contracts with the patch recorded in `patch_audits`
+    pub applied: i64,
+}
+
+/// Request body for POST /api/patches/:patch_id/apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPatchRequest {
+    pub contract_id: String,
+    /// Why applying the patch failed; set to report a failed application
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A patch applied to, or attempted on, one contract (`patch_audits`)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PatchApplication {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub patch_id: Uuid,
+    pub applied_at: DateTime<Utc>,
+    pub failed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for GET /api/patches/:patch_id/audits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchApplicationsResponse {
+    /// Contracts the patch was applied to; failed attempts are not counted
+    pub total: i64,
+    pub failed: i64,
+    /// Newest first
+    pub items: Vec<PatchApplication>,
+}
+
+/// Whether a patch rollout may take on more contracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalGate {
+    /// The rollout advances on its failure-rate gate alone
+    NotRequired,
+    /// The failure-rate gate halted the rollout; new contracts are refused
+    /// until retries bring the rate back under it
+    Rejected,
+}
+
+/// Response for GET /api/patches/:patch_id/rollout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RolloutProgress {
+    pub patch_id: Uuid,
+    pub stage: RolloutStage,
+    /// Contracts the patch's `rollout_percentage` admits
+    pub cohort_size: u64,
+    pub applied: u64,
+    pub failed: u64,
+    /// Fraction of attempted applications that failed (0.0–1.0)
+    pub failure_rate: f64,
+    pub approval_gate: ApprovalGate,
+    /// Affected contracts the patch was applied to (0–100)
+    pub progress_percentage: f64,
+}
+
+/// One stage of a simulated patch rollout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RolloutSimulationStage {
+    pub stage: RolloutStage,
+    /// Contracts newly reached in this stage
+    pub cohort_size: u64,
+    /// Contracts reached once this stage is done
+    pub cumulative: u64,
+    /// Seconds from the start of the simulation until the stage begins
+    pub starts_after_secs: i64,
+    pub dwell_secs: i64,
+}
+
+/// Failure-rate gate a simulated rollout must pass to leave `after`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RolloutSimulationGate {
+    pub after: RolloutStage,
+    pub advances_to: RolloutStage,
+    pub max_failure_rate: f64,
+}
+
+/// Response for GET /api/patches/:patch_id/rollout/simulation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RolloutSimulationResponse {
+    pub patch_id: Uuid,
+    /// Contracts the patch applies to
+    pub population: u64,
+    /// Remaining stages in order, starting at the rollout's current one
+    pub stages: Vec<RolloutSimulationStage>,
+    pub gates: Vec<RolloutSimulationGate>,
+    /// Seconds to completion if every gate passes on its first evaluation
+    pub estimated_duration_secs: i64,
+}
+
+/// Response for GET /api/contracts/:id/applicable-patches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicablePatchesResponse {
+    pub contract_id: Uuid,
+    /// Latest published release the patches were evaluated against
+    pub current_version: Option<String>,
+    pub wasm_hash: String,
+    /// Most severe first
+    pub patches: Vec<ApplicablePatch>,
+}
+
+/// Notice a patch notification delivers to one affected contract
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchNotice {
+    pub patch_id: Uuid,
+    pub contract_id: String,
+    pub severity: PatchSeverity,
+    /// Release the patch was matched against; `None` when matched on the
+    /// registered WASM hash
+    pub current_version: Option<String>,
+    pub wasm_hash: String,
+    pub new_wasm_hash: String,
+    pub vulnerable_range: String,
+    pub description: Option<String>,
+}
+
+/// A contract a patch notification would go to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchNoticeTarget {
+    pub contract_id: String,
+    pub current_version: Option<String>,
+    pub wasm_hash: String,
+    /// Hex HMAC key the receiver signs its delivery receipt with; issued
+    /// only when the notice is recorded, and only if receipts are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt_key: Option<String>,
+}
+
+/// Response for POST /api/patches/:patch_id/notify/preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchNotifyPreview {
+    pub patch_id: Uuid,
+    pub target_count: usize,
+    pub targets: Vec<PatchNoticeTarget>,
+    /// Affected contracts whose notification preferences exclude the
+    /// patch's severity; not counted in `targets`
+    #[serde(default)]
+    pub suppressed_count: usize,
+    /// Notice the first target would receive; `None` when nothing is affected
+    pub sample: Option<PatchNotice>,
+}
+
+/// Response for POST /api/patches/:patch_id/notify
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchNotifyResult {
+    pub patch_id: Uuid,
+    /// Contracts sent a notice by this request
+    pub notified: Vec<PatchNoticeTarget>,
+    /// Targets that already had a notice for the patch
+    pub already_notified: usize,
+    /// Affected contracts whose notification preferences exclude the
+    /// patch's severity
+    pub suppressed_count: usize,
+}
+
+/// A contract in a patch's blast radius and the contracts depending on it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchImpactNode {
+    pub id: Uuid,
+    pub contract_id: String,
+    pub name: String,
+    /// Hops from the nearest contract the patch targets; 0 for targets
+    pub depth: u32,
+    /// Dependents first reached through this contract
+    pub dependents: Vec<PatchImpactNode>,
+}
+
+/// Response for GET /api/patches/:patch_id/impact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchImpactResponse {
+    pub patch_id: Uuid,
+    pub severity: PatchSeverity,
+    /// Contracts the patch applies to directly
+    pub affected_count: usize,
+    /// Contracts depending on an affected contract, directly or transitively
+    pub downstream_count: usize,
+    /// The traversal bound was reached; more downstream contracts may exist
+    pub truncated: bool,
+    /// One tree per directly affected contract
+    pub affected: Vec<PatchImpactNode>,
+}
+
+/// Change recorded in a security patch's audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "patch_audit_action", rename_all = "snake_case")]
+pub enum PatchAuditAction {
+    SeverityEscalated,
+}
+
+/// Entry in `security_patch_audit_log`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PatchAuditEntry {
+    pub id: Uuid,
+    pub patch_id: Uuid,
+    pub action: PatchAuditAction,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: Option<serde_json::Value>,
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for POST /api/patches/:patch_id/escalate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalatePatchRequest {
+    /// Must not be less severe than the patch's current severity; the
+    /// current severity is accepted and changes nothing
+    pub severity: PatchSeverity,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Response for POST /api/patches/:patch_id/escalate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchEscalation {
+    pub patch: SecurityPatch,
+    pub previous_severity: PatchSeverity,
+    /// None when the patch already had the requested severity
+    pub audit: Option<PatchAuditEntry>,
+    /// Notified contracts that have not acknowledged the patch yet; their
+    /// notices now carry the escalated severity
+    pub pending_notifications: i64,
+}
+
 /// Recursive dependency tree node for API response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyTreeNode {
@@ -323,11 +931,188 @@ pub struct DependencyTreeNode {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyRequest {
     pub contract_id: String,
+    /// Contract source, or `wasm_base64:<base64>` to verify a precompiled
+    /// artifact without building
+    pub source_code: String,
+    pub build_params: serde_json::Value,
+    pub compiler_version: String,
+    /// Where a `wasm_base64:` artifact was built from; recorded, not built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_reference: Option<SourceReference>,
+    /// Network the verification applies to; defaults to the contract's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+    /// Where to POST a signed [`VerificationCallbackPayload`] once the build
+    /// settles, verified or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+}
+
+/// Request body for POST /api/contracts/:id/verify/artifact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyArtifactRequest {
+    /// The built WASM, base64-encoded; a `wasm_base64:` prefix is optional
+    pub wasm_base64: String,
+    /// SDK version the artifact was built with, when known
+    #[serde(default)]
+    pub compiler_version: Option<String>,
+    /// Where the artifact was built from; recorded, not built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_reference: Option<SourceReference>,
+    /// Network the verification applies to; defaults to the contract's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+}
+
+/// Git source a precompiled artifact was built from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceReference {
+    pub git_url: String,
+    pub commit: String,
+}
+
+/// Request body for POST /api/contracts/:id/verify/check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyCheckRequest {
     pub source_code: String,
+    #[serde(default)]
     pub build_params: serde_json::Value,
     pub compiler_version: String,
 }
 
+/// Response for POST /api/contracts/:id/verify/check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyCheckResponse {
+    /// The verification row recorded for this check
+    pub verification_id: Uuid,
+    pub status: VerificationStatus,
+    pub from_cache: bool,
+    pub source_hash: String,
+    /// The earlier verification whose result was reused, on a cache hit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_verification_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
+    /// Problems with the submission that did not stop it, e.g. a
+    /// `compiler_version` differing from the one first verified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Query params for GET /api/contracts/:id/verifications/compare
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompareVerificationsQuery {
+    pub a: Uuid,
+    pub b: Uuid,
+}
+
+/// Query params for POST /api/admin/verifications/:id/source-url
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SourceUrlParams {
+    /// How long the link stays valid; capped by the server
+    pub ttl_secs: Option<u64>,
+}
+
+/// A short-lived link to download the source a verification was built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedSourceUrl {
+    pub verification_id: Uuid,
+    /// Path and query to GET, e.g. `/api/verifications/<id>/source?expires=..&token=..`
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query params for GET /api/verifications/:id/source
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceDownloadQuery {
+    /// Unix seconds after which the token is no longer accepted
+    pub expires: u64,
+    /// Hex HMAC-SHA256 over the verification ID and `expires`
+    pub token: String,
+}
+
+/// One input that differs between two verification runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationInputDiff {
+    /// e.g. `compiler_version`, `build_params.profile`,
+    /// `resolved_dependencies.soroban-sdk`
+    pub field: String,
+    pub a: Option<serde_json::Value>,
+    pub b: Option<serde_json::Value>,
+}
+
+/// Response for GET /api/contracts/:id/verifications/compare
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationComparison {
+    pub a: Uuid,
+    pub b: Uuid,
+    /// Both runs compiled to the same artifact hash
+    pub reproducible: bool,
+    pub built_wasm_hash_a: Option<String>,
+    pub built_wasm_hash_b: Option<String>,
+    /// `build_params` as each caller submitted them
+    pub build_params_a: Option<serde_json::Value>,
+    pub build_params_b: Option<serde_json::Value>,
+    /// Build settings each run actually used, defaults applied; absent for
+    /// runs that have not been built
+    pub effective_build_params_a: Option<serde_json::Value>,
+    pub effective_build_params_b: Option<serde_json::Value>,
+    /// Inputs that differ between the runs; empty when reproducible
+    pub differences: Vec<VerificationInputDiff>,
+}
+
+/// Query params for POST /api/contracts/verify
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VerifyQueryParams {
+    /// Return the verification ID immediately instead of waiting for the build
+    #[serde(rename = "async", default)]
+    pub run_async: bool,
+}
+
+/// Response for GET /api/verifications/:id/status and POST /api/contracts/verify
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationStatusResponse {
+    pub verification_id: Uuid,
+    pub contract_id: Uuid,
+    pub status: VerificationStatus,
+    #[serde(default)]
+    pub method: VerificationMethod,
+    /// 1-based place in the build queue while pending; 0 once building
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// Builds waiting for a slot while this one is pending
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<VerificationReasonCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
+    /// Provenance attached to a precompiled verification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_reference: Option<SourceReference>,
+    /// Problems with the submission that did not stop it, e.g. a
+    /// `compiler_version` differing from the one first verified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Body POSTed to a verification's `callback_url` when its build settles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationCallbackPayload {
+    pub verification_id: Uuid,
+    pub contract_id: Uuid,
+    pub status: VerificationStatus,
+    pub reason_code: VerificationReasonCode,
+    /// The contract's deployed wasm hash the build was compared against
+    pub expected_wasm_hash: Option<String>,
+    pub built_wasm_hash: Option<String>,
+    /// Tail of the build error output, for failed builds
+    pub log_excerpt: Option<String>,
+    pub completed_at: DateTime<Utc>,
+}
+
 /// Sorting options for contracts
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -348,6 +1133,15 @@ pub enum SortOrder {
     Desc,
 }
 
+/// How a tag filter combines multiple tags
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TagsMatch {
+    #[default]
+    All,
+    Any,
+}
+
 /// Search/filter parameters for contracts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractSearchParams {
@@ -357,11 +1151,17 @@ pub struct ContractSearchParams {
     pub networks: Option<Vec<Network>>,
     pub verified_only: Option<bool>,
     pub category: Option<String>,
-    pub tags: Option<Vec<String>>,
+    /// Comma-separated tags, e.g. ?tags=token,defi
+    pub tags: Option<String>,
+    /// Whether contracts must carry all of `tags` (default) or any of them
+    pub tags_match: Option<TagsMatch>,
     pub maturity: Option<MaturityLevel>,
     pub page: Option<i64>,
     #[serde(alias = "page_size")]
     pub limit: Option<i64>,
+    /// Keyset cursor from a previous page's `next_cursor`/`prev_cursor`;
+    /// takes precedence over `page`
+    pub cursor: Option<String>,
     pub sort_by: Option<SortBy>,
     pub sort_order: Option<SortOrder>,
 }
@@ -397,6 +1197,12 @@ pub struct PaginatedResponse<T> {
     pub page: i64,
     #[serde(rename = "pages")]
     pub total_pages: i64,
+    /// Cursor for the following page, when listing by creation time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Cursor for the preceding page, when listing by creation time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
 }
 
 impl<T> PaginatedResponse<T> {
@@ -411,8 +1217,16 @@ impl<T> PaginatedResponse<T> {
             total,
             page,
             total_pages,
+            next_cursor: None,
+            prev_cursor: None,
         }
     }
+
+    pub fn with_cursors(mut self, next: Option<String>, prev: Option<String>) -> Self {
+        self.next_cursor = next;
+        self.prev_cursor = prev;
+        self
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -448,8 +1262,8 @@ pub struct ContractInteractionResponse {
 /// Query params for GET /api/contracts/:id/interactions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InteractionsQueryParams {
-    #[serde(default = "default_interactions_limit")]
-    pub limit: i64,
+    /// Page size; defaults and caps come from the API's pagination config
+    pub limit: Option<i64>,
     #[serde(default)]
     pub offset: i64,
     pub account: Option<String>,
@@ -458,10 +1272,6 @@ pub struct InteractionsQueryParams {
     pub to_timestamp: Option<String>,
 }
 
-fn default_interactions_limit() -> i64 {
-    50
-}
-
 /// Request body for POST /api/contracts/:id/interactions (single)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateInteractionRequest {
@@ -486,6 +1296,9 @@ pub struct InteractionsListResponse {
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
+    /// Whether the contract's interactions are growing past the trending
+    /// threshold over the last 7 days
+    pub is_trending: bool,
 }
 
 /// Migration status
@@ -585,7 +1398,8 @@ pub enum CanaryStatus {
     Failed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "rollout_stage", rename_all = "snake_case")]
 pub enum RolloutStage {
     Stage1,
@@ -966,6 +1780,9 @@ pub enum AnalyticsEventType {
     ContractVerified,
     ContractDeployed,
     VersionCreated,
+    InteractionRecorded,
+    DeprecatedMethodCalled,
+    InteractionAnomaly,
 }
 
 impl std::fmt::Display for AnalyticsEventType {
@@ -975,6 +1792,9 @@ impl std::fmt::Display for AnalyticsEventType {
             Self::ContractVerified => write!(f, "contract_verified"),
             Self::ContractDeployed => write!(f, "contract_deployed"),
             Self::VersionCreated => write!(f, "version_created"),
+            Self::InteractionRecorded => write!(f, "interaction_recorded"),
+            Self::DeprecatedMethodCalled => write!(f, "deprecated_method_called"),
+            Self::InteractionAnomaly => write!(f, "interaction_anomaly"),
         }
     }
 }
@@ -1010,6 +1830,22 @@ pub struct DailyAggregate {
     pub updated_at: DateTime<Utc>,
 }
 
+/// An hour in which a contract's interactions spiked above its trailing
+/// daily average
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InteractionAnomaly {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    /// Start of the flagged hour
+    pub window_start: DateTime<Utc>,
+    pub observed_count: i64,
+    /// Expected interactions per hour, from the trailing daily aggregates
+    pub baseline_hourly: f64,
+    /// Spike multiple that was configured when the anomaly was flagged
+    pub multiplier: f64,
+    pub detected_at: DateTime<Utc>,
+}
+
 // ────────────────────────────────────────────────────────────────────────────
 // Analytics API response DTOs
 // ────────────────────────────────────────────────────────────────────────────
@@ -1148,6 +1984,9 @@ pub struct ContractAuditLog {
     pub previous_hash: Option<String>,
     pub hash: Option<String>,
     pub signature: Option<String>,
+    /// Client IP address that made the change, when known
+    #[serde(default)]
+    pub ip_address: Option<String>,
 }
 
 /// Full contract state captured at each audited change in `contract_snapshots`.
@@ -1169,6 +2008,24 @@ pub struct FieldChange {
     pub to: serde_json::Value,
 }
 
+/// One value a metadata field held, from the audit entry that set it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldValueChange {
+    pub value: serde_json::Value,
+    pub effective_at: DateTime<Utc>,
+    pub changed_by: String,
+    pub action_type: AuditActionType,
+}
+
+/// Response for GET /api/contracts/:id/metadata-history?field=
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataHistory {
+    pub contract_id: Uuid,
+    pub field: String,
+    /// Oldest first
+    pub values: Vec<FieldValueChange>,
+}
+
 /// Response for GET /api/contracts/:id/versions/:v1/diff/:v2
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionDiff {
@@ -1191,29 +2048,66 @@ pub struct RollbackRequest {
 }
 
 // Multisig deployment types
+
+/// Lifecycle of a multisig deployment proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "proposal_status", rename_all = "lowercase")]
+pub enum ProposalStatus {
+    Pending,
+    Approved,
+    Executed,
+    Expired,
+    Rejected,
+    Cancelled,
+}
+
+impl std::fmt::Display for ProposalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ProposalStatus::Pending => "pending",
+            ProposalStatus::Approved => "approved",
+            ProposalStatus::Executed => "executed",
+            ProposalStatus::Expired => "expired",
+            ProposalStatus::Rejected => "rejected",
+            ProposalStatus::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// Who may sign deployment proposals and how many signatures approve one
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct MultisigPolicy {
     pub id: Uuid,
     pub name: String,
+    /// Signatures required to approve (M of N)
     pub threshold: i32,
-    pub required_signatures: i32,
     pub signer_addresses: Vec<String>,
+    /// How long a proposal under this policy stays open
     pub expiry_seconds: i32,
     pub created_by: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DeployProposal {
     pub id: Uuid,
     pub contract_name: String,
-    pub contract_id: Uuid,
+    /// On-chain contract ID being deployed
+    pub contract_id: String,
     pub wasm_hash: String,
-    pub network: String,
+    pub network: Network,
     pub description: Option<String>,
     pub policy_id: Uuid,
-    pub status: String,
+    pub status: ProposalStatus,
     pub expires_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
     pub proposer: String,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancelled_by: Option<String>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -1221,9 +2115,11 @@ pub struct ProposalSignature {
     pub id: Uuid,
     pub proposal_id: Uuid,
     pub signer_address: String,
+    pub signature_data: Option<String>,
+    pub signed_at: DateTime<Utc>,
 }
 
-/// Paginated response for audit log
+/// A proposal with its policy and the signatures collected so far
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposalWithSignatures {
     pub proposal: DeployProposal,
@@ -1232,6 +2128,37 @@ pub struct ProposalWithSignatures {
     pub signatures_needed: i32,
 }
 
+/// Request body for POST /api/multisig/policies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub threshold: i32,
+    pub signer_addresses: Vec<String>,
+    /// Defaults to one day
+    pub expiry_seconds: Option<i32>,
+    pub created_by: String,
+}
+
+/// Request body for POST /api/contracts/deploy-proposal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProposalRequest {
+    pub contract_name: String,
+    pub contract_id: String,
+    pub wasm_hash: String,
+    pub network: Network,
+    pub description: Option<String>,
+    pub policy_id: Uuid,
+    pub proposer: String,
+}
+
+/// Request body for POST /api/contracts/:id/sign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignProposalRequest {
+    pub signer_address: String,
+    pub signature_data: Option<String>,
+}
+
+/// Paginated response for audit log
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLogPage {
     pub items: Vec<ContractAuditLog>,
@@ -1638,6 +2565,19 @@ pub struct ContractHealth {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Body of `GET /health`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServiceHealth {
+    /// `ok`, `degraded` (database unreachable) or `shutting_down`
+    pub status: String,
+    pub version: String,
+    pub timestamp: DateTime<Utc>,
+    pub uptime_secs: u64,
+    /// Whether the verification toolchain is complete; absent while shutting down
+    #[serde(default)]
+    pub verifier_ready: Option<bool>,
+}
+
 // Backup and disaster recovery types
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ContractBackup {
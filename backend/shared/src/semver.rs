@@ -93,3 +93,170 @@ impl VersionConstraint {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// A range of versions, as comparators that must all hold: `>=1.0.0, <1.2.0`,
+/// `^1.4.0`, `~2.3.1`, `=1.0.0`, a bare version, or `*` for every version.
+/// `1.0.0..1.2.0` is accepted as an inclusive range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    raw: String,
+    bounds: Vec<(RangeOp, SemVer)>,
+}
+
+impl VersionRange {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let raw = s.trim();
+        if raw.is_empty() {
+            return Err("version range is empty".to_string());
+        }
+        let version = |v: &str| {
+            SemVer::parse(v.trim())
+                .ok_or_else(|| format!("'{}' is not a valid version (e.g. 1.0.0)", v.trim()))
+        };
+
+        let mut bounds = Vec::new();
+        if let Some((min, max)) = raw.split_once("..") {
+            bounds.push((RangeOp::Gte, version(min)?));
+            bounds.push((RangeOp::Lte, version(max)?));
+        } else if raw != "*" {
+            // Join operators written apart from their version (">= 1.0.0")
+            let mut tokens: Vec<String> = Vec::new();
+            for token in raw.split(|c: char| c == ',' || c.is_whitespace()) {
+                if token.is_empty() {
+                    continue;
+                }
+                match tokens.last_mut() {
+                    Some(last) if last.chars().all(|c| "<>=^~".contains(c)) => last.push_str(token),
+                    _ => tokens.push(token.to_string()),
+                }
+            }
+            for token in &tokens {
+                push_comparator(&mut bounds, token, version)?;
+            }
+        }
+
+        let range = Self {
+            raw: raw.to_string(),
+            bounds,
+        };
+        if range.is_empty_set() {
+            return Err(format!("version range '{}' matches no version", raw));
+        }
+        Ok(range)
+    }
+
+    pub fn contains(&self, version: &SemVer) -> bool {
+        self.bounds.iter().all(|(op, bound)| match op {
+            RangeOp::Gt => version > bound,
+            RangeOp::Gte => version >= bound,
+            RangeOp::Lt => version < bound,
+            RangeOp::Lte => version <= bound,
+        })
+    }
+
+    /// Tightest lower bound and whether it is inclusive; every bound is
+    /// implied by this one and `upper_bound`
+    pub fn lower_bound(&self) -> Option<(&SemVer, bool)> {
+        self.bounds
+            .iter()
+            .filter(|(op, _)| matches!(op, RangeOp::Gt | RangeOp::Gte))
+            .max_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then((a.0 == RangeOp::Gt).cmp(&(b.0 == RangeOp::Gt)))
+            })
+            .map(|(op, bound)| (bound, *op == RangeOp::Gte))
+    }
+
+    /// Tightest upper bound and whether it is inclusive
+    pub fn upper_bound(&self) -> Option<(&SemVer, bool)> {
+        self.bounds
+            .iter()
+            .filter(|(op, _)| matches!(op, RangeOp::Lt | RangeOp::Lte))
+            .min_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then((a.0 == RangeOp::Lte).cmp(&(b.0 == RangeOp::Lte)))
+            })
+            .map(|(op, bound)| (bound, *op == RangeOp::Lte))
+    }
+
+    /// Whether the tightest lower bound sits above the tightest upper bound
+    fn is_empty_set(&self) -> bool {
+        match (self.lower_bound(), self.upper_bound()) {
+            (Some((lo, lo_inclusive)), Some((hi, hi_inclusive))) => {
+                lo > hi || (lo == hi && !(lo_inclusive && hi_inclusive))
+            }
+            _ => false,
+        }
+    }
+}
+
+fn push_comparator(
+    bounds: &mut Vec<(RangeOp, SemVer)>,
+    token: &str,
+    version: impl Fn(&str) -> Result<SemVer, String>,
+) -> Result<(), String> {
+    let ops = [
+        (">=", RangeOp::Gte),
+        ("<=", RangeOp::Lte),
+        (">", RangeOp::Gt),
+        ("<", RangeOp::Lt),
+    ];
+    if let Some((rest, op)) = ops
+        .iter()
+        .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (rest, *op)))
+    {
+        bounds.push((op, version(rest)?));
+        return Ok(());
+    }
+
+    if let Some(rest) = token.strip_prefix('^') {
+        let v = version(rest)?;
+        // Same upper bounds as `VersionConstraint::Caret`
+        let upper = match (v.major, v.minor) {
+            (0, 0) => SemVer {
+                patch: v.patch + 1,
+                ..v.clone()
+            },
+            (0, minor) => SemVer {
+                major: 0,
+                minor: minor + 1,
+                patch: 0,
+            },
+            (major, _) => SemVer {
+                major: major + 1,
+                minor: 0,
+                patch: 0,
+            },
+        };
+        bounds.push((RangeOp::Gte, v));
+        bounds.push((RangeOp::Lt, upper));
+    } else if let Some(rest) = token.strip_prefix('~') {
+        let v = version(rest)?;
+        let upper = SemVer {
+            major: v.major,
+            minor: v.minor + 1,
+            patch: 0,
+        };
+        bounds.push((RangeOp::Gte, v));
+        bounds.push((RangeOp::Lt, upper));
+    } else {
+        let v = version(token.strip_prefix('=').unwrap_or(token))?;
+        bounds.push((RangeOp::Gte, v.clone()));
+        bounds.push((RangeOp::Lte, v));
+    }
+    Ok(())
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
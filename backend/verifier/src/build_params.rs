@@ -0,0 +1,182 @@
+// Validation and application of user-supplied build parameters
+//
+// `build_params` arrive as free-form JSON; only `features` and `profile` are
+// forwarded to cargo, and both are checked here before they reach the command
+// line.
+
+use serde_json::Value;
+use shared::RegistryError;
+
+/// Maximum number of cargo features a build may enable
+pub const MAX_FEATURES: usize = 32;
+/// Maximum length of a single feature name
+pub const MAX_FEATURE_LENGTH: usize = 64;
+/// Cargo profiles that are always accepted
+pub const BUILTIN_PROFILES: [&str; 2] = ["release", "dev"];
+
+/// Check a single feature name: ASCII alphanumerics, `-` and `_` only
+pub fn validate_feature(feature: &str) -> Result<(), String> {
+    if feature.is_empty() {
+        return Err("feature names must not be empty".to_string());
+    }
+    if feature.len() > MAX_FEATURE_LENGTH {
+        let prefix: String = feature.chars().take(16).collect();
+        return Err(format!(
+            "feature '{}...' exceeds {} characters",
+            prefix, MAX_FEATURE_LENGTH
+        ));
+    }
+    if !feature
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "feature '{}' may only contain letters, digits, '-' and '_'",
+            feature
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the `features` entry of `build_params`, if present
+pub fn validate_features(build_params: &Value) -> Result<(), String> {
+    let features = match build_params.get("features") {
+        None | Some(Value::Null) => return Ok(()),
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err("features must be an array of strings".to_string()),
+    };
+
+    if features.len() > MAX_FEATURES {
+        return Err(format!("at most {} features are allowed", MAX_FEATURES));
+    }
+
+    for item in features {
+        let name = item
+            .as_str()
+            .ok_or_else(|| "features must be an array of strings".to_string())?;
+        validate_feature(name)?;
+    }
+    Ok(())
+}
+
+/// Validate the `profile` entry of `build_params` against the built-in
+/// profiles plus any configured custom ones
+pub fn validate_profile(build_params: &Value, custom_profiles: &[String]) -> Result<(), String> {
+    let profile = match build_params.get("profile") {
+        None | Some(Value::Null) => return Ok(()),
+        Some(Value::String(p)) => p.as_str(),
+        Some(_) => return Err("profile must be a string".to_string()),
+    };
+
+    let known = BUILTIN_PROFILES.contains(&profile) || custom_profiles.iter().any(|p| p == profile);
+    if !known {
+        let mut allowed: Vec<&str> = BUILTIN_PROFILES.to_vec();
+        allowed.extend(custom_profiles.iter().map(String::as_str));
+        return Err(format!(
+            "unknown profile '{}'; expected one of: {}",
+            profile,
+            allowed.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Validate `build_params` and translate them into cargo arguments.
+///
+/// Returns the arguments to append after `cargo build` and the profile whose
+/// output directory will hold the artifact.
+pub fn apply_build_params(
+    build_params: &Value,
+    custom_profiles: &[String],
+) -> Result<(Vec<String>, String), RegistryError> {
+    validate_features(build_params).map_err(RegistryError::InvalidInput)?;
+    validate_profile(build_params, custom_profiles).map_err(RegistryError::InvalidInput)?;
+
+    let mut args = Vec::new();
+
+    let profile = build_params
+        .get("profile")
+        .and_then(Value::as_str)
+        .unwrap_or("release")
+        .to_string();
+    args.push("--profile".to_string());
+    args.push(profile.clone());
+
+    let features = requested_features(build_params);
+    if !features.is_empty() {
+        args.push("--features".to_string());
+        args.push(features.join(","));
+    }
+
+    Ok((args, profile))
+}
+
+/// Features named in `build_params`, in the order given
+pub fn requested_features(build_params: &Value) -> Vec<&str> {
+    build_params
+        .get("features")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Directory under `target/<triple>/` that cargo writes a profile's output to
+pub fn profile_output_dir(profile: &str) -> &str {
+    match profile {
+        "dev" => "debug",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_valid_feature_set() {
+        let params =
+            json!({ "features": ["testutils", "alloc_v2", "no-std"], "profile": "release" });
+        let (args, profile) = apply_build_params(&params, &[]).unwrap();
+
+        assert_eq!(profile, "release");
+        assert_eq!(
+            args,
+            vec![
+                "--profile",
+                "release",
+                "--features",
+                "testutils,alloc_v2,no-std"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_features() {
+        for bad in [
+            json!({ "features": ["ok", "evil; rm -rf /"] }),
+            json!({ "features": ["$(whoami)"] }),
+            json!({ "features": ["a".repeat(MAX_FEATURE_LENGTH + 1)] }),
+            json!({ "features": (0..=MAX_FEATURES).map(|i| format!("f{}", i)).collect::<Vec<_>>() }),
+            json!({ "features": "testutils" }),
+        ] {
+            assert!(validate_features(&bad).is_err(), "accepted {}", bad);
+            assert!(matches!(
+                apply_build_params(&bad, &[]),
+                Err(RegistryError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn profile_must_be_known_or_configured() {
+        assert!(validate_profile(&json!({ "profile": "dev" }), &[]).is_ok());
+        assert!(validate_profile(&json!({ "profile": "release-lto" }), &[]).is_err());
+        assert!(validate_profile(
+            &json!({ "profile": "release-lto" }),
+            &["release-lto".to_string()]
+        )
+        .is_ok());
+        assert_eq!(profile_output_dir("dev"), "debug");
+    }
+}
@@ -0,0 +1,59 @@
+// Structured failures from building a contract for verification.
+//
+// Callers that only need the registry-wide error categories can convert with
+// `RegistryError::from`; callers reporting to clients use `reason_code`.
+
+use shared::{RegistryError, VerificationReasonCode};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    /// Build params or the configured SDK version were rejected
+    #[error("invalid build input: {0}")]
+    InvalidInput(String),
+    /// The build toolchain binary could not be started
+    #[error("build toolchain not found: {0}")]
+    ToolchainMissing(String),
+    /// The compiler ran and failed
+    #[error("compilation failed: {0}")]
+    Compile(String),
+    /// The compiler ran past the configured build timeout
+    #[error("build timed out after {}s", .0.as_secs())]
+    Timeout(Duration),
+    /// The build produced no usable artifact (missing or too large)
+    #[error("{0}")]
+    Artifact(String),
+    /// Workspace or filesystem failure on the verifier itself
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl BuildError {
+    pub fn reason_code(&self) -> VerificationReasonCode {
+        match self {
+            Self::InvalidInput(_) => VerificationReasonCode::InvalidInput,
+            Self::ToolchainMissing(_) => VerificationReasonCode::ToolchainMissing,
+            Self::Compile(_) | Self::Artifact(_) => VerificationReasonCode::CompileError,
+            Self::Timeout(_) => VerificationReasonCode::Timeout,
+            Self::Internal(_) => VerificationReasonCode::InternalError,
+        }
+    }
+}
+
+/// Failures caused by the contract's source map to `VerificationFailed`;
+/// timeouts and missing toolchains are the verifier's problem, not the
+/// publisher's, and map to `Internal`.
+impl From<BuildError> for RegistryError {
+    fn from(err: BuildError) -> Self {
+        match err {
+            BuildError::InvalidInput(msg) => RegistryError::InvalidInput(msg),
+            BuildError::Compile(_) | BuildError::Artifact(_) => {
+                RegistryError::VerificationFailed(err.to_string())
+            }
+            BuildError::ToolchainMissing(_) | BuildError::Timeout(_) | BuildError::Internal(_) => {
+                RegistryError::Internal(err.to_string())
+            }
+        }
+    }
+}
@@ -2,7 +2,48 @@
 // Compiles source code and compares with on-chain bytecode
 
 use anyhow::Result;
-use shared::RegistryError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use shared::{RegistryError, VerificationReasonCode};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tracing::Instrument;
+
+pub mod build_params;
+pub mod error;
+pub mod self_test;
+pub mod toolchain;
+pub mod workspace;
+
+pub use build_params::apply_build_params;
+pub use error::BuildError;
+pub use self_test::{self_test, ToolStatus, VerifierHealth};
+pub use toolchain::BuildCommand;
+pub use workspace::{BuildOutput, BuildWorkspace, LocalWorkspaces, WorkspaceProvider};
+
+/// Compute the compile-cache key for a verification input.
+///
+/// Two submissions with the same source, compiler version and build params
+/// produce the same artifact, so a previously verified result for the same
+/// hash can be reused without rebuilding.
+pub fn source_hash(
+    source_code: &str,
+    compiler_version: &str,
+    build_params: &serde_json::Value,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(source_code.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(compiler_version.trim().as_bytes());
+    hasher.update([0u8]);
+    // serde_json::Value keeps object keys sorted, so this is canonical
+    hasher.update(build_params.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
 
 /// Verify that source code matches deployed contract bytecode
 pub async fn verify_contract(
@@ -24,27 +65,984 @@ pub async fn verify_contract(
     Ok(false)
 }
 
+/// Default cap on the size of a compiled WASM artifact (2 MiB). Soroban
+/// contracts are far smaller than this in practice.
+pub const DEFAULT_MAX_ARTIFACT_BYTES: u64 = 2 * 1024 * 1024;
+
+/// `soroban-sdk` version used when no override is configured
+pub const SOROBAN_SDK_VERSION: &str = "21";
+/// Default number of contract builds allowed to run at once
+pub const DEFAULT_MAX_CONCURRENT_BUILDS: usize = 2;
+/// Default wall-clock limit on a single contract build
+pub const DEFAULT_BUILD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Runtime settings for the verification engine
+#[derive(Debug, Clone)]
+pub struct VerifierConfig {
+    /// Largest WASM artifact the verifier will load into memory
+    pub max_artifact_bytes: u64,
+    /// Cargo profiles accepted in `build_params.profile` besides `release`/`dev`
+    pub custom_profiles: Vec<String>,
+    /// `soroban-sdk` version contracts are built against
+    pub sdk_version: String,
+    /// Upper bound on concurrent builds for batch work such as re-verification
+    pub max_concurrent_builds: usize,
+    /// Toolchain used to compile contracts
+    pub build_command: BuildCommand,
+    /// Soroban CLI binary run when `build_command` is `SorobanCli`
+    pub soroban_cli: String,
+    /// Builds running longer than this are killed and reported as timed out
+    pub build_timeout: Duration,
+    /// Rust target cargo builds compile for; one of `ALLOWED_WASM_TARGETS`
+    pub wasm_target: String,
+    /// `RUSTFLAGS` passed to the build, each drawn from `ALLOWED_RUSTFLAGS`
+    pub rustflags: Vec<String>,
+}
+
+impl Default for VerifierConfig {
+    fn default() -> Self {
+        Self {
+            max_artifact_bytes: DEFAULT_MAX_ARTIFACT_BYTES,
+            custom_profiles: Vec::new(),
+            sdk_version: SOROBAN_SDK_VERSION.to_string(),
+            max_concurrent_builds: DEFAULT_MAX_CONCURRENT_BUILDS,
+            build_command: BuildCommand::default(),
+            soroban_cli: toolchain::DEFAULT_SOROBAN_CLI.to_string(),
+            build_timeout: DEFAULT_BUILD_TIMEOUT,
+            wasm_target: toolchain::WASM_TARGET.to_string(),
+            rustflags: Vec::new(),
+        }
+    }
+}
+
+impl VerifierConfig {
+    /// Read overrides from the environment (`VERIFIER_MAX_ARTIFACT_BYTES`,
+    /// `VERIFIER_CUSTOM_PROFILES` as a comma-separated list,
+    /// `VERIFIER_SOROBAN_SDK_VERSION`, `VERIFIER_MAX_CONCURRENT_BUILDS`,
+    /// `VERIFIER_BUILD_COMMAND` as `cargo` or `soroban-cli`, `VERIFIER_SOROBAN_CLI`,
+    /// `VERIFIER_BUILD_TIMEOUT_SECS`, `VERIFIER_WASM_TARGET`, `VERIFIER_RUSTFLAGS`
+    /// as whitespace-separated flags). A disallowed target or flag is ignored
+    /// with a warning.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_artifact_bytes: std::env::var("VERIFIER_MAX_ARTIFACT_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(defaults.max_artifact_bytes),
+            custom_profiles: std::env::var("VERIFIER_CUSTOM_PROFILES")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or(defaults.custom_profiles),
+            sdk_version: std::env::var("VERIFIER_SOROBAN_SDK_VERSION")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or(defaults.sdk_version),
+            max_concurrent_builds: std::env::var("VERIFIER_MAX_CONCURRENT_BUILDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(defaults.max_concurrent_builds),
+            build_command: std::env::var("VERIFIER_BUILD_COMMAND")
+                .ok()
+                .and_then(|v| match v.parse() {
+                    Ok(command) => Some(command),
+                    Err(err) => {
+                        tracing::warn!("ignoring VERIFIER_BUILD_COMMAND: {}", err);
+                        None
+                    }
+                })
+                .unwrap_or(defaults.build_command),
+            soroban_cli: std::env::var("VERIFIER_SOROBAN_CLI")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or(defaults.soroban_cli),
+            build_timeout: std::env::var("VERIFIER_BUILD_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.build_timeout),
+            wasm_target: std::env::var("VERIFIER_WASM_TARGET")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .and_then(|target| match toolchain::validate_wasm_target(&target) {
+                    Ok(()) => Some(target),
+                    Err(err) => {
+                        tracing::warn!("ignoring VERIFIER_WASM_TARGET: {}", err);
+                        None
+                    }
+                })
+                .unwrap_or(defaults.wasm_target),
+            rustflags: std::env::var("VERIFIER_RUSTFLAGS")
+                .ok()
+                .map(|v| v.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+                .and_then(|flags| match toolchain::validate_rustflags(&flags) {
+                    Ok(()) => Some(flags),
+                    Err(err) => {
+                        tracing::warn!("ignoring VERIFIER_RUSTFLAGS: {}", err);
+                        None
+                    }
+                })
+                .unwrap_or(defaults.rustflags),
+        }
+    }
+}
+
 /// Compile Rust source code to WASM
-pub async fn compile_contract(_source_code: &str) -> Result<Vec<u8>, RegistryError> {
-    // TODO: Implement compilation
-    // - Set up temporary build environment
-    // - Write source to temp directory
-    // - Run cargo build with soroban target
-    // - Return compiled WASM bytes
+pub async fn compile_contract(
+    source_code: &str,
+    build_params: &serde_json::Value,
+) -> Result<Vec<u8>, BuildError> {
+    compile_contract_with_config(source_code, build_params, &VerifierConfig::from_env()).await
+}
+
+/// Milliseconds spent in each verification stage, keyed by stage name
+pub type StageTimings = BTreeMap<&'static str, u64>;
+
+/// Writing the throwaway crate (manifest and source) to a temp workspace
+pub const STAGE_BOOTSTRAP: &str = "bootstrap";
+/// Running the toolchain and loading the artifact it produced
+pub const STAGE_BUILD: &str = "build";
+/// Hashing the artifact for comparison with the deployed bytecode
+pub const STAGE_HASH: &str = "hash";
+
+/// Run `stage` inside a tracing span and record how long it took
+async fn timed_stage<F: Future>(
+    timings: &mut StageTimings,
+    stage: &'static str,
+    fut: F,
+) -> F::Output {
+    let started = Instant::now();
+    let output = fut
+        .instrument(tracing::info_span!("verification_stage", stage))
+        .await;
+    record_stage(timings, stage, started);
+    output
+}
+
+fn record_stage(timings: &mut StageTimings, stage: &'static str, started: Instant) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    tracing::debug!(stage, elapsed_ms, "verification stage finished");
+    timings.insert(stage, elapsed_ms);
+}
+
+/// Compile Rust source code to WASM using an explicit configuration
+pub async fn compile_contract_with_config(
+    source_code: &str,
+    build_params: &serde_json::Value,
+    config: &VerifierConfig,
+) -> Result<Vec<u8>, BuildError> {
+    compile_timed(
+        &LocalWorkspaces,
+        source_code,
+        build_params,
+        config,
+        &mut StageTimings::new(),
+    )
+    .await
+    .map(|(wasm, _)| wasm)
+}
+
+fn build_params_error(err: RegistryError) -> BuildError {
+    match err {
+        RegistryError::InvalidInput(msg) => BuildError::InvalidInput(msg),
+        other => BuildError::Internal(other.to_string()),
+    }
+}
+
+async fn compile_timed<P: WorkspaceProvider>(
+    workspaces: &P,
+    source_code: &str,
+    build_params: &serde_json::Value,
+    config: &VerifierConfig,
+    timings: &mut StageTimings,
+) -> Result<(Vec<u8>, Option<ResolvedDependencies>), BuildError> {
+    let (build_args, _) =
+        apply_build_params(build_params, &config.custom_profiles).map_err(build_params_error)?;
+    validate_sdk_version(&config.sdk_version).map_err(BuildError::InvalidInput)?;
+    toolchain::validate_wasm_target(&config.wasm_target).map_err(BuildError::InvalidInput)?;
+    toolchain::validate_rustflags(&config.rustflags).map_err(BuildError::InvalidInput)?;
+
+    let workspace = timed_stage(
+        timings,
+        STAGE_BOOTSTRAP,
+        bootstrap(workspaces, source_code, config),
+    )
+    .await?;
+
+    let invocation = toolchain::build_invocation(config, workspace.root(), &build_args);
+    let build = async {
+        let output = tokio::time::timeout(config.build_timeout, workspace.run(&invocation))
+            .await
+            .map_err(|_| BuildError::Timeout(config.build_timeout))??;
+        if !output.success {
+            return Err(BuildError::Compile(output.stderr));
+        }
+
+        let wasm = workspace
+            .read_artifact(&invocation.artifact, config.max_artifact_bytes)
+            .await?;
+        // A build without a readable lockfile still verifies; it just has
+        // nothing to compare dependencies on
+        let lockfile = workspace
+            .read_artifact(
+                &workspace.root().join("Cargo.lock"),
+                config.max_artifact_bytes,
+            )
+            .await
+            .ok();
+        let resolved = lockfile.map(|lock| resolved_dependencies(&String::from_utf8_lossy(&lock)));
+        Ok((wasm, resolved))
+    };
+    timed_stage(timings, STAGE_BUILD, build).await
+}
+
+/// Lay out a single-crate workspace for `source_code`, which is released
+/// when the returned workspace drops
+async fn bootstrap<P: WorkspaceProvider>(
+    workspaces: &P,
+    source_code: &str,
+    config: &VerifierConfig,
+) -> Result<P::Workspace, BuildError> {
+    let workspace = workspaces.create().await?;
+
+    workspace
+        .write_file(
+            Path::new("Cargo.toml"),
+            contract_manifest(config).as_bytes(),
+        )
+        .await?;
+    workspace
+        .write_file(Path::new("src/lib.rs"), source_code.as_bytes())
+        .await?;
+    Ok(workspace)
+}
+
+/// Cargo manifest for the contract crate: the `testutils` feature contracts
+/// commonly gate on, the release profile a deployable contract is built
+/// with, and each configured custom profile on top of it
+fn contract_manifest(config: &VerifierConfig) -> String {
+    let mut manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+         [lib]\ncrate-type = [\"cdylib\"]\n\n\
+         [features]\ntestutils = [\"soroban-sdk/testutils\"]\n\n\
+         [dependencies]\nsoroban-sdk = \"{}\"\n\n\
+         [profile.release]\nopt-level = \"z\"\noverflow-checks = true\ndebug = 0\n\
+         strip = \"symbols\"\ndebug-assertions = false\npanic = \"abort\"\n\
+         codegen-units = 1\nlto = true\n",
+        toolchain::CONTRACT_CRATE_NAME,
+        config.sdk_version
+    );
+    for profile in &config.custom_profiles {
+        let builtin = build_params::BUILTIN_PROFILES.contains(&profile.as_str());
+        if !builtin && build_params::validate_feature(profile).is_ok() {
+            manifest.push_str(&format!(
+                "\n[profile.{}]\ninherits = \"release\"\n",
+                profile
+            ));
+        }
+    }
+    manifest
+}
+
+/// Check an `soroban-sdk` version requirement before it is written into a
+/// build manifest: digits and dots only, e.g. `21` or `21.7.1`.
+pub fn validate_sdk_version(version: &str) -> Result<(), String> {
+    let valid = !version.is_empty()
+        && version.len() <= 32
+        && version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid soroban-sdk version '{}'", version))
+    }
+}
+
+/// The build settings a verification actually ran with: the submitted
+/// `build_params` with defaults filled in, plus the verifier configuration
+/// that shaped the build. Re-running with these reproduces the build even if
+/// the defaults change later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectiveBuildParams {
+    pub profile: String,
+    pub features: Vec<String>,
+    /// Whether the profile builds with optimizations (all but `dev` do)
+    pub optimize: bool,
+    pub timeout_secs: u64,
+    pub toolchain: BuildCommand,
+    pub sdk_version: String,
+}
+
+impl EffectiveBuildParams {
+    /// Validate `build_params` and apply the same defaults the build will
+    pub fn resolve(
+        build_params: &serde_json::Value,
+        config: &VerifierConfig,
+    ) -> Result<Self, BuildError> {
+        let (_, profile) = apply_build_params(build_params, &config.custom_profiles)
+            .map_err(build_params_error)?;
+        Ok(Self {
+            features: build_params::requested_features(build_params)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            optimize: profile != "dev",
+            profile,
+            timeout_secs: config.build_timeout.as_secs(),
+            toolchain: config.build_command,
+            sdk_version: config.sdk_version.clone(),
+        })
+    }
+}
+
+/// Crate name to the version(s) the build's lockfile resolved it to
+pub type ResolvedDependencies = BTreeMap<String, String>;
+
+/// Every `[[package]]` in a `Cargo.lock`. A crate locked at several versions
+/// maps to all of them, comma separated in lockfile order.
+pub fn resolved_dependencies(lockfile: &str) -> ResolvedDependencies {
+    let mut resolved = ResolvedDependencies::new();
+    let mut name: Option<String> = None;
+    let mut version: Option<String> = None;
+    let mut flush = |name: &mut Option<String>, version: &mut Option<String>| {
+        if let (Some(name), Some(version)) = (name.take(), version.take()) {
+            resolved
+                .entry(name)
+                .and_modify(|versions: &mut String| {
+                    versions.push_str(", ");
+                    versions.push_str(&version);
+                })
+                .or_insert(version);
+        }
+    };
+    for line in lockfile.lines().map(str::trim) {
+        if line == "[[package]]" {
+            flush(&mut name, &mut version);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "name" => name = Some(value),
+            "version" => version = Some(value),
+            _ => {}
+        }
+    }
+    flush(&mut name, &mut version);
+    resolved
+}
+
+/// Outcome of rebuilding a contract and comparing it to deployed bytecode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerificationResult {
+    /// Whether the rebuilt artifact hashes to the deployed wasm hash
+    pub verified: bool,
+    /// SHA-256 of the rebuilt artifact
+    pub built_wasm_hash: String,
+    /// Toolchain that produced the artifact
+    pub toolchain: BuildCommand,
+    /// Rust target cargo compiled for; `None` for precompiled artifacts and
+    /// soroban CLI builds, which pick their own
+    pub target: Option<String>,
+    /// Settings the build ran with; `None` for precompiled artifacts
+    pub effective_params: Option<EffectiveBuildParams>,
+    /// Crate versions from the build's `Cargo.lock`; `None` for precompiled
+    /// artifacts and builds that left no lockfile
+    pub resolved_dependencies: Option<ResolvedDependencies>,
+    /// Time spent per stage (`bootstrap`, `build`, `hash`), in milliseconds
+    pub timings: StageTimings,
+}
+
+impl VerificationResult {
+    pub fn reason_code(&self) -> VerificationReasonCode {
+        if self.verified {
+            VerificationReasonCode::HashMatch
+        } else {
+            VerificationReasonCode::HashMismatch
+        }
+    }
+}
+
+/// Reason code for any verification build outcome
+pub fn reason_code(result: &Result<VerificationResult, BuildError>) -> VerificationReasonCode {
+    match result {
+        Ok(build) => build.reason_code(),
+        Err(err) => err.reason_code(),
+    }
+}
+
+/// Marks `source_code` as a base64 precompiled artifact rather than source
+pub const WASM_BASE64_PREFIX: &str = "wasm_base64:";
+
+/// Whether `source_code` is a `wasm_base64:` precompiled artifact
+pub fn is_precompiled(source_code: &str) -> bool {
+    source_code.trim_start().starts_with(WASM_BASE64_PREFIX)
+}
+
+/// Decode a `wasm_base64:` artifact, subject to the same size cap as built
+/// artifacts
+pub fn decode_precompiled(source_code: &str, max_bytes: u64) -> Result<Vec<u8>, BuildError> {
+    let encoded = source_code
+        .trim()
+        .strip_prefix(WASM_BASE64_PREFIX)
+        .ok_or_else(|| BuildError::InvalidInput("not a wasm_base64 artifact".to_string()))?;
+    let wasm = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| BuildError::InvalidInput(format!("invalid wasm_base64 artifact: {}", e)))?;
+    if wasm.len() as u64 > max_bytes {
+        return Err(artifact_too_large());
+    }
+    Ok(wasm)
+}
+
+/// Build `source_code` with `config` and check the artifact hashes to
+/// `deployed_wasm_hash`. A `wasm_base64:` artifact is hashed as submitted,
+/// without building.
+pub async fn verify_build(
+    source_code: &str,
+    build_params: &serde_json::Value,
+    deployed_wasm_hash: &str,
+    config: &VerifierConfig,
+) -> Result<VerificationResult, BuildError> {
+    verify_build_in(
+        &LocalWorkspaces,
+        source_code,
+        build_params,
+        deployed_wasm_hash,
+        config,
+    )
+    .await
+}
+
+/// `verify_build` with builds run in workspaces from `workspaces`
+pub async fn verify_build_in<P: WorkspaceProvider>(
+    workspaces: &P,
+    source_code: &str,
+    build_params: &serde_json::Value,
+    deployed_wasm_hash: &str,
+    config: &VerifierConfig,
+) -> Result<VerificationResult, BuildError> {
+    let mut timings = StageTimings::new();
+    let (wasm, effective_params, resolved_dependencies) = if is_precompiled(source_code) {
+        (
+            decode_precompiled(source_code, config.max_artifact_bytes)?,
+            None,
+            None,
+        )
+    } else {
+        let effective = EffectiveBuildParams::resolve(build_params, config)?;
+        let (wasm, resolved) =
+            compile_timed(workspaces, source_code, build_params, config, &mut timings).await?;
+        (wasm, Some(effective), resolved)
+    };
+
+    let started = Instant::now();
+    let built_wasm_hash = tracing::info_span!("verification_stage", stage = STAGE_HASH)
+        .in_scope(|| hex::encode(Sha256::digest(&wasm)));
+    record_stage(&mut timings, STAGE_HASH, started);
+
+    tracing::info!(
+        toolchain = ?config.build_command,
+        bootstrap_ms = timings.get(STAGE_BOOTSTRAP),
+        build_ms = timings.get(STAGE_BUILD),
+        hash_ms = timings.get(STAGE_HASH),
+        "verification build finished"
+    );
+    let target = match (&effective_params, config.build_command) {
+        (Some(_), BuildCommand::Cargo) => Some(config.wasm_target.clone()),
+        _ => None,
+    };
+    Ok(VerificationResult {
+        verified: built_wasm_hash.eq_ignore_ascii_case(deployed_wasm_hash.trim()),
+        built_wasm_hash,
+        toolchain: config.build_command,
+        target,
+        effective_params,
+        resolved_dependencies,
+        timings,
+    })
+}
 
-    Err(RegistryError::Internal(
-        "Compilation not yet implemented".to_string(),
-    ))
+/// Load a compiled artifact, refusing anything larger than `max_bytes`.
+///
+/// The size is checked from file metadata before reading, and the read itself
+/// is bounded in case the file grows in between.
+pub async fn read_artifact(path: &Path, max_bytes: u64) -> Result<Vec<u8>, BuildError> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| BuildError::Artifact(format!("compiled artifact not found: {}", e)))?;
+    if metadata.len() > max_bytes {
+        return Err(artifact_too_large());
+    }
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| BuildError::Internal(format!("failed to open artifact: {}", e)))?;
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    file.take(max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|e| BuildError::Internal(format!("failed to read artifact: {}", e)))?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(artifact_too_large());
+    }
+
+    Ok(bytes)
+}
+
+fn artifact_too_large() -> BuildError {
+    BuildError::Artifact("artifact too large".to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn sdk_version_must_be_numeric() {
+        assert!(validate_sdk_version("21").is_ok());
+        assert!(validate_sdk_version("21.7.1").is_ok());
+        assert!(validate_sdk_version("").is_err());
+        assert!(validate_sdk_version("21\"\n[patch]").is_err());
+        assert!(validate_sdk_version("21..1").is_err());
+    }
+
+    #[test]
+    fn manifest_declares_testutils_and_the_release_profile() {
+        let config = VerifierConfig {
+            custom_profiles: vec!["release-with-logs".to_string(), "x]\n[y".to_string()],
+            ..VerifierConfig::default()
+        };
+        let manifest = contract_manifest(&config);
+
+        assert!(manifest.contains("[features]\ntestutils = [\"soroban-sdk/testutils\"]\n"));
+        let release = manifest
+            .split("[profile.release]\n")
+            .nth(1)
+            .expect("release profile");
+        for setting in [
+            "opt-level = \"z\"",
+            "overflow-checks = true",
+            "panic = \"abort\"",
+            "lto = true",
+            "codegen-units = 1",
+        ] {
+            assert!(release.lines().any(|line| line == setting), "{}", setting);
+        }
+        // Custom profiles build on release; names that are not plain keys are left out
+        assert!(manifest.contains("[profile.release-with-logs]\ninherits = \"release\"\n"));
+        assert!(!manifest.contains("[y"));
+    }
+
+    /// Stand-in for the soroban CLI: writes a fixed artifact into `--out-dir`
+    #[cfg(unix)]
+    fn fake_soroban_cli(dir: &Path) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("fake-stellar");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             while [ $# -gt 0 ]; do\n\
+               if [ \"$1\" = \"--out-dir\" ]; then mkdir -p \"$2\"; \
+               printf wasm > \"$2/verified_contract.wasm\"; fi\n\
+               shift\n\
+             done\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn soroban_cli_build_reports_toolchain() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VerifierConfig {
+            build_command: BuildCommand::SorobanCli,
+            soroban_cli: fake_soroban_cli(dir.path()).to_string_lossy().into_owned(),
+            ..VerifierConfig::default()
+        };
+        let expected_hash = hex::encode(Sha256::digest(b"wasm"));
+
+        let result = verify_build("", &serde_json::json!({}), &expected_hash, &config)
+            .await
+            .unwrap();
+        assert!(result.verified);
+        assert_eq!(result.built_wasm_hash, expected_hash);
+        // The fake CLI leaves no lockfile behind
+        assert_eq!(result.resolved_dependencies, None);
+        assert_eq!(result.toolchain, BuildCommand::SorobanCli);
+        assert_eq!(result.target, None);
+        let effective = result.effective_params.unwrap();
+        assert_eq!(effective.profile, "release");
+        assert_eq!(effective.toolchain, BuildCommand::SorobanCli);
+        assert_eq!(effective.timeout_secs, DEFAULT_BUILD_TIMEOUT.as_secs());
+        assert_eq!(
+            result.timings.keys().copied().collect::<Vec<_>>(),
+            vec![STAGE_BOOTSTRAP, STAGE_BUILD, STAGE_HASH]
+        );
+
+        let mismatch = verify_build("", &serde_json::json!({}), "deadbeef", &config)
+            .await
+            .unwrap();
+        assert!(!mismatch.verified);
+    }
+
+    /// Stand-in for a soroban CLI running `body` as its script
+    #[cfg(unix)]
+    fn scripted_soroban_cli(dir: &Path, body: &str) -> VerifierConfig {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("scripted-stellar");
+        std::fs::write(&script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        VerifierConfig {
+            build_command: BuildCommand::SorobanCli,
+            soroban_cli: script.to_string_lossy().into_owned(),
+            ..VerifierConfig::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn build_outcomes_map_to_reason_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = serde_json::json!({});
+        let good = VerifierConfig {
+            build_command: BuildCommand::SorobanCli,
+            soroban_cli: fake_soroban_cli(dir.path()).to_string_lossy().into_owned(),
+            ..VerifierConfig::default()
+        };
+        let expected_hash = hex::encode(Sha256::digest(b"wasm"));
+
+        let matched = verify_build("", &params, &expected_hash, &good).await;
+        assert_eq!(reason_code(&matched), VerificationReasonCode::HashMatch);
+        let mismatched = verify_build("", &params, "deadbeef", &good).await;
+        assert_eq!(
+            reason_code(&mismatched),
+            VerificationReasonCode::HashMismatch
+        );
+
+        let failing = scripted_soroban_cli(dir.path(), "echo 'error[E0425]' >&2; exit 101");
+        let compile_error = verify_build("", &params, &expected_hash, &failing).await;
+        assert_eq!(
+            reason_code(&compile_error),
+            VerificationReasonCode::CompileError
+        );
+
+        let hanging = VerifierConfig {
+            build_timeout: Duration::from_millis(100),
+            ..scripted_soroban_cli(dir.path(), "sleep 5")
+        };
+        let timed_out = verify_build("", &params, &expected_hash, &hanging).await;
+        assert_eq!(reason_code(&timed_out), VerificationReasonCode::Timeout);
+
+        let missing = VerifierConfig {
+            soroban_cli: dir
+                .path()
+                .join("not-installed")
+                .to_string_lossy()
+                .into_owned(),
+            ..good.clone()
+        };
+        let no_toolchain = verify_build("", &params, &expected_hash, &missing).await;
+        assert_eq!(
+            reason_code(&no_toolchain),
+            VerificationReasonCode::ToolchainMissing
+        );
+
+        let bad_sdk = VerifierConfig {
+            sdk_version: "21; rm".to_string(),
+            ..good.clone()
+        };
+        let invalid = verify_build("", &params, &expected_hash, &bad_sdk).await;
+        assert_eq!(reason_code(&invalid), VerificationReasonCode::InvalidInput);
+
+        // Refused before anything runs, whatever the toolchain
+        let bad_target = VerifierConfig {
+            wasm_target: "x86_64-unknown-linux-gnu".to_string(),
+            ..good
+        };
+        let refused = verify_build("", &params, &expected_hash, &bad_target).await;
+        assert!(matches!(
+            refused,
+            Err(BuildError::InvalidInput(ref msg)) if msg.contains("x86_64-unknown-linux-gnu")
+        ));
+    }
+
+    #[test]
+    fn build_errors_keep_registry_error_categories() {
+        assert!(matches!(
+            RegistryError::from(BuildError::Compile("boom".to_string())),
+            RegistryError::VerificationFailed(msg) if msg == "compilation failed: boom"
+        ));
+        assert!(matches!(
+            RegistryError::from(BuildError::Timeout(Duration::from_secs(600))),
+            RegistryError::Internal(_)
+        ));
+    }
+
+    #[test]
+    fn lockfile_crates_map_to_every_version_they_resolved_to() {
+        let lockfile = r#"
+version = 3
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+
+[[package]]
+name = "soroban-sdk"
+version = "21.7.6"
+dependencies = [
+ "syn 2.0.87",
+]
+
+[[package]]
+name = "syn"
+version = "2.0.87"
+"#;
+        let resolved = resolved_dependencies(lockfile);
+        assert_eq!(
+            resolved,
+            BTreeMap::from([
+                ("soroban-sdk".to_string(), "21.7.6".to_string()),
+                ("syn".to_string(), "1.0.109, 2.0.87".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn effective_params_fill_in_omitted_fields() {
+        let config = VerifierConfig {
+            build_timeout: Duration::from_secs(300),
+            sdk_version: "22".to_string(),
+            ..VerifierConfig::default()
+        };
+
+        let omitted = EffectiveBuildParams::resolve(&serde_json::json!({}), &config).unwrap();
+        assert_eq!(
+            omitted,
+            EffectiveBuildParams {
+                profile: "release".to_string(),
+                features: Vec::new(),
+                optimize: true,
+                timeout_secs: 300,
+                toolchain: BuildCommand::Cargo,
+                sdk_version: "22".to_string(),
+            }
+        );
+
+        let explicit = EffectiveBuildParams::resolve(
+            &serde_json::json!({ "profile": "dev", "features": ["testutils"] }),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(explicit.profile, "dev");
+        assert!(!explicit.optimize);
+        assert_eq!(explicit.features, ["testutils"]);
+    }
+
+    #[tokio::test]
+    async fn precompiled_artifacts_are_hashed_without_building() {
+        let wasm = b"\0asm\x01\0\0\0";
+        let source = format!("{}{}", WASM_BASE64_PREFIX, BASE64.encode(wasm));
+        let deployed = hex::encode(Sha256::digest(wasm));
+        // A build toolchain that cannot run proves nothing was built
+        let config = VerifierConfig {
+            soroban_cli: "/nonexistent/stellar".to_string(),
+            build_command: BuildCommand::SorobanCli,
+            ..VerifierConfig::default()
+        };
+
+        let result = verify_build(&source, &serde_json::json!({}), &deployed, &config)
+            .await
+            .unwrap();
+        assert!(result.verified);
+        assert_eq!(result.effective_params, None);
+        assert_eq!(
+            result.timings.keys().copied().collect::<Vec<_>>(),
+            [STAGE_HASH]
+        );
+
+        let bad = format!("{}not base64!", WASM_BASE64_PREFIX);
+        let err = verify_build(&bad, &serde_json::json!({}), &deployed, &config)
+            .await
+            .unwrap_err();
+        assert_eq!(err.reason_code(), VerificationReasonCode::InvalidInput);
+    }
+
+    const MOCK_LOCKFILE: &[u8] = b"version = 3\n\n\
+        [[package]]\nname = \"contract\"\nversion = \"0.0.0\"\n\n\
+        [[package]]\nname = \"soroban-sdk\"\nversion = \"21.7.6\"\n\
+        source = \"registry+https://github.com/rust-lang/crates.io-index\"\n";
+
+    /// Workspace that keeps files in memory and "builds" by placing a fixed
+    /// artifact where the invocation expects it
+    struct MockWorkspaces {
+        artifact: Vec<u8>,
+        files: std::sync::Arc<std::sync::Mutex<BTreeMap<std::path::PathBuf, Vec<u8>>>>,
+    }
+
+    struct MockWorkspace {
+        root: std::path::PathBuf,
+        artifact: Vec<u8>,
+        files: std::sync::Arc<std::sync::Mutex<BTreeMap<std::path::PathBuf, Vec<u8>>>>,
+    }
+
+    impl WorkspaceProvider for MockWorkspaces {
+        type Workspace = MockWorkspace;
+
+        async fn create(&self) -> Result<MockWorkspace, BuildError> {
+            Ok(MockWorkspace {
+                root: std::path::PathBuf::from("/mock"),
+                artifact: self.artifact.clone(),
+                files: self.files.clone(),
+            })
+        }
+    }
+
+    impl BuildWorkspace for MockWorkspace {
+        fn root(&self) -> &Path {
+            &self.root
+        }
+
+        async fn write_file(&self, relative: &Path, contents: &[u8]) -> Result<(), BuildError> {
+            let mut files = self.files.lock().unwrap();
+            files.insert(self.root.join(relative), contents.to_vec());
+            Ok(())
+        }
+
+        async fn run(
+            &self,
+            invocation: &toolchain::BuildInvocation,
+        ) -> Result<BuildOutput, BuildError> {
+            let mut files = self.files.lock().unwrap();
+            files.insert(invocation.artifact.clone(), self.artifact.clone());
+            files.insert(self.root.join("Cargo.lock"), MOCK_LOCKFILE.to_vec());
+            Ok(BuildOutput {
+                success: true,
+                stderr: String::new(),
+            })
+        }
+
+        async fn read_artifact(&self, path: &Path, max_bytes: u64) -> Result<Vec<u8>, BuildError> {
+            let files = self.files.lock().unwrap();
+            let bytes = files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| BuildError::Artifact("compiled artifact not found".to_string()))?;
+            if bytes.len() as u64 > max_bytes {
+                return Err(artifact_too_large());
+            }
+            Ok(bytes)
+        }
+    }
+
+    #[tokio::test]
+    async fn builds_run_in_the_supplied_workspace() {
+        let workspaces = MockWorkspaces {
+            artifact: b"mock wasm".to_vec(),
+            files: Default::default(),
+        };
+        let expected_hash = hex::encode(Sha256::digest(b"mock wasm"));
+        let config = VerifierConfig::default();
+
+        let result = verify_build_in(
+            &workspaces,
+            "pub fn hello() {}",
+            &serde_json::json!({}),
+            &expected_hash,
+            &config,
+        )
+        .await
+        .unwrap();
+        assert!(result.verified);
+        assert_eq!(result.built_wasm_hash, expected_hash);
+        assert_eq!(result.target.as_deref(), Some(toolchain::WASM_TARGET));
+        let resolved = result.resolved_dependencies.as_ref().unwrap();
+        assert_eq!(
+            resolved.get("soroban-sdk").map(String::as_str),
+            Some("21.7.6")
+        );
+        assert_eq!(
+            result.timings.keys().copied().collect::<Vec<_>>(),
+            vec![STAGE_BOOTSTRAP, STAGE_BUILD, STAGE_HASH]
+        );
+
+        {
+            let files = workspaces.files.lock().unwrap();
+            assert_eq!(
+                files.get(Path::new("/mock/src/lib.rs")).map(Vec::as_slice),
+                Some(&b"pub fn hello() {}"[..])
+            );
+            let manifest = String::from_utf8_lossy(&files[Path::new("/mock/Cargo.toml")]);
+            assert!(manifest.contains(toolchain::CONTRACT_CRATE_NAME));
+        }
+
+        let limited = VerifierConfig {
+            max_artifact_bytes: 4,
+            ..config
+        };
+        let err = verify_build_in(
+            &workspaces,
+            "",
+            &serde_json::json!({}),
+            &expected_hash,
+            &limited,
+        )
+        .await;
+        assert!(matches!(err, Err(BuildError::Artifact(_))));
+    }
+
     #[tokio::test]
     async fn test_verify_contract() {
         // Placeholder test
         let result = verify_contract("", "test_hash").await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_read_artifact_rejects_oversized_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("huge.wasm");
+        std::fs::write(&path, vec![0u8; 4096]).unwrap();
+
+        match read_artifact(&path, 1024).await {
+            Err(BuildError::Artifact(msg)) => assert_eq!(msg, "artifact too large"),
+            other => panic!("expected artifact too large, got {:?}", other),
+        }
+
+        let bytes = read_artifact(&path, 4096).await.unwrap();
+        assert_eq!(bytes.len(), 4096);
+    }
+
+    #[test]
+    fn test_source_hash_is_stable() {
+        let params = serde_json::json!({ "optimize": true, "features": ["a"] });
+        let a = source_hash("fn main() {}", "21.0.0", &params);
+        let b = source_hash("fn main() {}", " 21.0.0 ", &params);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+
+        let changed = source_hash("fn main() { }", "21.0.0", &params);
+        assert_ne!(a, changed);
+        let other_compiler = source_hash("fn main() {}", "22.0.0", &params);
+        assert_ne!(a, other_compiler);
+    }
 }
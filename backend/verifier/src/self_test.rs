@@ -0,0 +1,139 @@
+// Toolchain self-test
+//
+// Checks that every program a build needs is installed before verification
+// requests are accepted: cargo, the wasm32 target, the optimizer and, when
+// builds go through it, the soroban CLI. Each check runs `<tool> --version`
+// (or lists installed rustup targets) and records what it printed.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::toolchain::BuildCommand;
+use crate::VerifierConfig;
+
+/// How long a single version probe may run before the tool counts as broken
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Optimizer run over release artifacts
+pub const OPTIMIZER: &str = "wasm-opt";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStatus {
+    pub name: String,
+    /// Builds with the current configuration cannot run without it
+    pub required: bool,
+    pub available: bool,
+    /// First line the tool printed, e.g. `cargo 1.79.0 (ffa9cf99a 2024-06-03)`
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierHealth {
+    /// Every required tool is available
+    pub ready: bool,
+    pub build_command: BuildCommand,
+    pub tools: Vec<ToolStatus>,
+}
+
+/// Run `program args` and return its first line of output
+async fn probe(program: &str, args: &[&str]) -> Result<String, String> {
+    let command = tokio::process::Command::new(program)
+        .args(args)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(PROBE_TIMEOUT, command)
+        .await
+        .map_err(|_| format!("{} did not respond within {:?}", program, PROBE_TIMEOUT))?
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("{} is not installed", program),
+            _ => format!("failed to run {}: {}", program, e),
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn tool_status(name: &str, required: bool, result: Result<String, String>) -> ToolStatus {
+    match result {
+        Ok(output) => ToolStatus {
+            name: name.to_string(),
+            required,
+            available: true,
+            version: output.lines().next().map(str::to_string),
+            error: None,
+        },
+        Err(error) => ToolStatus {
+            name: name.to_string(),
+            required,
+            available: false,
+            version: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Check the toolchain `config` builds with
+pub async fn self_test(config: &VerifierConfig) -> VerifierHealth {
+    let target = probe("rustup", &["target", "list", "--installed"])
+        .await
+        .and_then(|installed| {
+            if installed
+                .lines()
+                .any(|line| line.trim() == config.wasm_target)
+            {
+                Ok(config.wasm_target.clone())
+            } else {
+                Err(format!("{} target is not installed", config.wasm_target))
+            }
+        });
+
+    let tools = vec![
+        tool_status("cargo", true, probe("cargo", &["--version"]).await),
+        tool_status(&config.wasm_target, true, target),
+        tool_status(OPTIMIZER, false, probe(OPTIMIZER, &["--version"]).await),
+        tool_status(
+            &config.soroban_cli,
+            config.build_command == BuildCommand::SorobanCli,
+            probe(&config.soroban_cli, &["--version"]).await,
+        ),
+    ];
+
+    VerifierHealth {
+        ready: tools.iter().all(|t| t.available || !t.required),
+        build_command: config.build_command,
+        tools,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_tool_is_reported_unavailable() {
+        let config = VerifierConfig {
+            build_command: BuildCommand::SorobanCli,
+            soroban_cli: "/nonexistent/bin/stellar".to_string(),
+            ..VerifierConfig::default()
+        };
+        let health = self_test(&config).await;
+
+        let cli = health
+            .tools
+            .iter()
+            .find(|t| t.name == "/nonexistent/bin/stellar")
+            .unwrap();
+        assert!(cli.required);
+        assert!(!cli.available);
+        assert_eq!(cli.version, None);
+        assert!(cli.error.as_deref().unwrap().contains("not installed"));
+        assert!(!health.ready);
+    }
+}
@@ -0,0 +1,262 @@
+// Build toolchain selection
+//
+// Contracts can be built either with plain `cargo build` or through the
+// soroban toolchain (`stellar contract build` / `soroban contract build`).
+// This module decides which program to run and where its artifact lands;
+// the build workspace (see `workspace`) does the actual spawning.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::build_params::profile_output_dir;
+use crate::VerifierConfig;
+
+/// Rust target contract builds are compiled for unless configured otherwise
+pub const WASM_TARGET: &str = "wasm32-unknown-unknown";
+/// Targets a build may be configured for
+pub const ALLOWED_WASM_TARGETS: &[&str] = &[WASM_TARGET, "wasm32v1-none"];
+/// Codegen options `RUSTFLAGS` may set. Options that hand the compiler
+/// another program to run, such as `-C linker`, are deliberately absent.
+pub const ALLOWED_RUSTFLAGS: &[&str] = &[
+    "-Ctarget-cpu=",
+    "-Ctarget-feature=",
+    "-Copt-level=",
+    "-Cdebuginfo=",
+    "-Cstrip=",
+];
+/// Soroban CLI binary used when no override is configured
+pub const DEFAULT_SOROBAN_CLI: &str = "stellar";
+/// Crate name written into the generated build manifest
+pub const CONTRACT_CRATE_NAME: &str = "verified_contract";
+
+/// Which toolchain compiles contracts for verification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildCommand {
+    /// `cargo build --target wasm32-unknown-unknown`
+    #[default]
+    Cargo,
+    /// `<soroban cli> contract build`, the path used to produce deployed bytecode
+    SorobanCli,
+}
+
+impl FromStr for BuildCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cargo" => Ok(Self::Cargo),
+            "soroban-cli" | "soroban_cli" | "soroban" | "stellar" => Ok(Self::SorobanCli),
+            other => Err(format!(
+                "unknown build command '{}'; expected 'cargo' or 'soroban-cli'",
+                other
+            )),
+        }
+    }
+}
+
+/// Check a configured build target against `ALLOWED_WASM_TARGETS`
+pub fn validate_wasm_target(target: &str) -> Result<(), String> {
+    if ALLOWED_WASM_TARGETS.contains(&target) {
+        Ok(())
+    } else {
+        Err(format!(
+            "build target '{}' is not allowed; expected one of: {}",
+            target,
+            ALLOWED_WASM_TARGETS.join(", ")
+        ))
+    }
+}
+
+/// Check configured `RUSTFLAGS`: each flag is one `-C<option>=<value>` token
+/// for an option in `ALLOWED_RUSTFLAGS`, with a plain value
+pub fn validate_rustflags(flags: &[String]) -> Result<(), String> {
+    for flag in flags {
+        let value = ALLOWED_RUSTFLAGS
+            .iter()
+            .find_map(|prefix| flag.strip_prefix(prefix))
+            .ok_or_else(|| format!("RUSTFLAGS option '{}' is not allowed", flag))?;
+        let plain = !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | ',' | '.' | '_'));
+        if !plain {
+            return Err(format!("invalid value in RUSTFLAGS option '{}'", flag));
+        }
+    }
+    Ok(())
+}
+
+/// A fully resolved build: the program to run, its arguments and environment,
+/// and the path the artifact is expected at once it succeeds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInvocation {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub artifact: PathBuf,
+}
+
+/// Resolve the build `config` describes for a contract crate at `root`.
+///
+/// `build_args` are the validated `--profile`/`--features` arguments from
+/// `apply_build_params`; both toolchains accept them unchanged, and the
+/// artifact is looked for under the profile they name.
+pub fn build_invocation(
+    config: &VerifierConfig,
+    root: &Path,
+    build_args: &[String],
+) -> BuildInvocation {
+    let profile = build_args
+        .iter()
+        .position(|arg| arg == "--profile")
+        .and_then(|i| build_args.get(i + 1))
+        .map_or("release", String::as_str);
+    let artifact_name = format!("{}.wasm", CONTRACT_CRATE_NAME);
+    let env = if config.rustflags.is_empty() {
+        Vec::new()
+    } else {
+        vec![("RUSTFLAGS".to_string(), config.rustflags.join(" "))]
+    };
+    match config.build_command {
+        BuildCommand::Cargo => {
+            let mut args = vec![
+                "build".to_string(),
+                "--target".to_string(),
+                config.wasm_target.clone(),
+            ];
+            args.extend_from_slice(build_args);
+            BuildInvocation {
+                program: "cargo".to_string(),
+                args,
+                env,
+                artifact: root
+                    .join("target")
+                    .join(&config.wasm_target)
+                    .join(profile_output_dir(profile))
+                    .join(artifact_name),
+            }
+        }
+        BuildCommand::SorobanCli => {
+            // An explicit --out-dir keeps the artifact location independent of
+            // the target triple the CLI version defaults to
+            let out_dir = root.join("out");
+            let mut args = vec!["contract".to_string(), "build".to_string()];
+            args.extend_from_slice(build_args);
+            args.push("--out-dir".to_string());
+            args.push(out_dir.to_string_lossy().into_owned());
+            BuildInvocation {
+                program: config.soroban_cli.clone(),
+                args,
+                env,
+                artifact: out_dir.join(artifact_name),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_args(profile: &str) -> Vec<String> {
+        vec!["--profile".to_string(), profile.to_string()]
+    }
+
+    #[test]
+    fn parses_build_command_names() {
+        assert_eq!("cargo".parse(), Ok(BuildCommand::Cargo));
+        assert_eq!(" Soroban-CLI ".parse(), Ok(BuildCommand::SorobanCli));
+        assert_eq!("stellar".parse(), Ok(BuildCommand::SorobanCli));
+        assert!("make".parse::<BuildCommand>().is_err());
+        assert_eq!(BuildCommand::default(), BuildCommand::Cargo);
+    }
+
+    #[test]
+    fn cargo_invocation_reads_from_target_dir() {
+        let root = Path::new("/build");
+        let inv = build_invocation(&VerifierConfig::default(), root, &profile_args("release"));
+
+        assert_eq!(inv.program, "cargo");
+        assert_eq!(
+            inv.args,
+            vec!["build", "--target", WASM_TARGET, "--profile", "release"]
+        );
+        assert!(inv.env.is_empty());
+        assert_eq!(
+            inv.artifact,
+            root.join("target/wasm32-unknown-unknown/release/verified_contract.wasm")
+        );
+
+        // cargo writes the dev profile's output to `debug`
+        let dev = build_invocation(&VerifierConfig::default(), root, &profile_args("dev"));
+        assert_eq!(dev.args[3..], ["--profile", "dev"]);
+        assert_eq!(
+            dev.artifact,
+            root.join("target/wasm32-unknown-unknown/debug/verified_contract.wasm")
+        );
+    }
+
+    #[test]
+    fn configured_target_and_rustflags_reach_the_build() {
+        let config = VerifierConfig {
+            wasm_target: "wasm32v1-none".to_string(),
+            rustflags: vec!["-Ctarget-cpu=mvp".to_string(), "-Copt-level=z".to_string()],
+            ..VerifierConfig::default()
+        };
+        let root = Path::new("/build");
+        let inv = build_invocation(&config, root, &profile_args("release"));
+
+        assert_eq!(inv.args[..3], ["build", "--target", "wasm32v1-none"]);
+        assert_eq!(
+            inv.env,
+            vec![(
+                "RUSTFLAGS".to_string(),
+                "-Ctarget-cpu=mvp -Copt-level=z".to_string()
+            )]
+        );
+        assert_eq!(
+            inv.artifact,
+            root.join("target/wasm32v1-none/release/verified_contract.wasm")
+        );
+    }
+
+    #[test]
+    fn disallowed_targets_and_rustflags_are_rejected() {
+        assert!(validate_wasm_target(WASM_TARGET).is_ok());
+        assert!(validate_wasm_target("wasm32v1-none").is_ok());
+        assert!(validate_wasm_target("x86_64-unknown-linux-gnu").is_err());
+        assert!(validate_wasm_target("wasm32-unknown-unknown --config x").is_err());
+
+        assert!(validate_rustflags(&["-Ctarget-feature=+sign-ext".to_string()]).is_ok());
+        assert!(validate_rustflags(&["-Clinker=/tmp/evil".to_string()]).is_err());
+        assert!(validate_rustflags(&["-Copt-level=z;rm".to_string()]).is_err());
+        assert!(validate_rustflags(&["-Copt-level=".to_string()]).is_err());
+    }
+
+    #[test]
+    fn soroban_cli_invocation_uses_explicit_out_dir() {
+        let root = Path::new("/build");
+        let config = VerifierConfig {
+            build_command: BuildCommand::SorobanCli,
+            soroban_cli: "/opt/bin/soroban".to_string(),
+            ..VerifierConfig::default()
+        };
+        let inv = build_invocation(&config, root, &profile_args("release"));
+
+        assert_eq!(inv.program, "/opt/bin/soroban");
+        assert_eq!(
+            inv.args,
+            vec![
+                "contract",
+                "build",
+                "--profile",
+                "release",
+                "--out-dir",
+                "/build/out"
+            ]
+        );
+        assert_eq!(inv.artifact, root.join("out/verified_contract.wasm"));
+    }
+}
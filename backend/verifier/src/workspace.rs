@@ -0,0 +1,122 @@
+// Build workspaces
+//
+// A verification build needs somewhere to lay out the throwaway crate, run
+// the toolchain and pick up the artifact. `LocalWorkspaces` does all of that
+// in a temp dir on this host; container or remote builders implement the same
+// two traits and are passed to `verify_build_in` instead.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use crate::read_artifact;
+use crate::toolchain::BuildInvocation;
+use crate::BuildError;
+
+/// What a finished toolchain run reports back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOutput {
+    pub success: bool,
+    pub stderr: String,
+}
+
+/// One build's workspace. Dropping it releases whatever backs it.
+pub trait BuildWorkspace: Send + Sync {
+    /// Root the build invocation is resolved against
+    fn root(&self) -> &Path;
+
+    /// Write `contents` to `relative` under the root, creating parent dirs
+    fn write_file(
+        &self,
+        relative: &Path,
+        contents: &[u8],
+    ) -> impl Future<Output = Result<(), BuildError>> + Send;
+
+    /// Run `invocation` from the root. Dropping the future must stop the
+    /// build, which is how the build timeout is enforced.
+    fn run(
+        &self,
+        invocation: &BuildInvocation,
+    ) -> impl Future<Output = Result<BuildOutput, BuildError>> + Send;
+
+    /// Load the artifact at `path`, refusing anything over `max_bytes`
+    fn read_artifact(
+        &self,
+        path: &Path,
+        max_bytes: u64,
+    ) -> impl Future<Output = Result<Vec<u8>, BuildError>> + Send;
+}
+
+/// Creates a fresh workspace for each build
+pub trait WorkspaceProvider: Send + Sync {
+    type Workspace: BuildWorkspace;
+
+    fn create(&self) -> impl Future<Output = Result<Self::Workspace, BuildError>> + Send;
+}
+
+/// Builds in a temp dir on this host, removed when the workspace drops
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalWorkspaces;
+
+/// A temp dir created by `LocalWorkspaces`
+#[derive(Debug)]
+pub struct LocalWorkspace {
+    dir: tempfile::TempDir,
+}
+
+impl WorkspaceProvider for LocalWorkspaces {
+    type Workspace = LocalWorkspace;
+
+    async fn create(&self) -> Result<LocalWorkspace, BuildError> {
+        let dir = tempfile::tempdir()
+            .map_err(|e| BuildError::Internal(format!("failed to create build dir: {}", e)))?;
+        Ok(LocalWorkspace { dir })
+    }
+}
+
+impl LocalWorkspace {
+    fn path(&self, relative: &Path) -> PathBuf {
+        self.dir.path().join(relative)
+    }
+}
+
+impl BuildWorkspace for LocalWorkspace {
+    fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    async fn write_file(&self, relative: &Path, contents: &[u8]) -> Result<(), BuildError> {
+        let path = self.path(relative);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| BuildError::Internal(format!("failed to prepare build dir: {}", e)))?;
+        }
+        tokio::fs::write(&path, contents).await.map_err(|e| {
+            BuildError::Internal(format!("failed to write {}: {}", relative.display(), e))
+        })
+    }
+
+    async fn run(&self, invocation: &BuildInvocation) -> Result<BuildOutput, BuildError> {
+        let output = tokio::process::Command::new(&invocation.program)
+            .args(&invocation.args)
+            .envs(invocation.env.iter().map(|(k, v)| (k, v)))
+            .current_dir(self.dir.path())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    BuildError::ToolchainMissing(invocation.program.clone())
+                }
+                _ => BuildError::Internal(format!("failed to run {}: {}", invocation.program, e)),
+            })?;
+        Ok(BuildOutput {
+            success: output.status.success(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+
+    async fn read_artifact(&self, path: &Path, max_bytes: u64) -> Result<Vec<u8>, BuildError> {
+        read_artifact(path, max_bytes).await
+    }
+}
@@ -18,7 +18,10 @@ pub enum Network {
 
 use std::path::Path;
 
-use crate::patch::{PatchManager, Severity};
+use crate::patch::{
+    ApprovalGate, NewPatch, PatchManager, RegistryContractLookup, RolloutProgress, Severity,
+    VersionManager,
+};
 use crate::test_framework;
 
 pub fn generate_flame_graph_file(
@@ -31,15 +34,24 @@ pub fn generate_flame_graph_file(
 pub fn profile(
     contract_path: &str,
     method: Option<&str>,
+    iterations: u32,
     output: Option<&str>,
+    format: Option<&str>,
     flamegraph: Option<&str>,
     compare: Option<&str>,
     show_recommendations: bool,
+    thresholds: &profiler::RecommendationThresholds,
 ) -> Result<()> {
+    let format = match (format, output) {
+        (Some(_), None) => anyhow::bail!("--format only applies to --output; pass an output path"),
+        (Some(format), Some(_)) => format.parse::<profiler::ProfileFormat>()?,
+        (None, _) => profiler::ProfileFormat::default(),
+    };
+
     println!("\n{}", "Profiling contract execution...".bold().cyan());
     println!("{}", "=".repeat(80).cyan());
 
-    let profile_data = profiler::profile_contract(contract_path, method)
+    let profile_data = profiler::profile_contract(contract_path, method, iterations)
         .with_context(|| format!("Failed to profile contract: {}", contract_path))?;
 
     if let Some(method_name) = method {
@@ -64,11 +76,25 @@ pub fn profile(
         profile_data.functions.len()
     );
 
+    println!(
+        "\n  {:<32} {:>8} {:>12} {:>12}",
+        "Method".bold(),
+        "Calls".bold(),
+        "Total (ms)".bold(),
+        "Avg (ms)".bold()
+    );
+    for func in profiler::functions_by_time(&profile_data) {
+        println!(
+            "  {:<32} {:>8} {:>12.3} {:>12.3}",
+            func.name,
+            func.call_count,
+            func.total_time.as_secs_f64() * 1000.0,
+            func.avg_time.as_secs_f64() * 1000.0
+        );
+    }
+
     if let Some(output_path) = output {
-        let profile_json =
-            serde_json::to_string_pretty(&profile_data).context("Failed to serialize profile data")?;
-        fs::write(output_path, profile_json)
-            .with_context(|| format!("Failed to write profile output: {}", output_path))?;
+        profiler::write_profile(&profile_data, format, Path::new(output_path))?;
         println!("{} Profile output written to {}", "✓".green(), output_path);
     }
 
@@ -105,7 +131,7 @@ pub fn profile(
     }
 
     if show_recommendations {
-        let recommendations = profiler::generate_recommendations(&profile_data);
+        let recommendations = profiler::recommend(&profile_data, thresholds);
         println!("\n{}", "Recommendations:".bold().magenta());
         for recommendation in recommendations {
             println!("  - {}", recommendation);
@@ -560,6 +586,96 @@ fn extract_migration_id(migration: &serde_json::Value) -> Result<String> {
 
     Ok(migration_id.to_string())
 }
+pub async fn health(api_url: &str, format: &str, no_color: bool) -> Result<()> {
+    let as_json = match format {
+        "text" => false,
+        "json" => true,
+        other => anyhow::bail!("Unknown format '{}'; expected 'text' or 'json'", other),
+    };
+    if no_color {
+        colored::control::set_override(false);
+    }
+
+    // A degraded or draining API answers 503 with the same body, which is
+    // still reported rather than treated as a request failure
+    let response = reqwest::Client::new()
+        .get(format!("{}/health", api_url))
+        .send()
+        .await
+        .context("Failed to fetch registry health")?;
+    let raw: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to read registry health")?;
+    let health: shared::ServiceHealth =
+        serde_json::from_value(raw.clone()).context("Unexpected registry health response")?;
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&raw)?);
+    } else {
+        print_health(&health);
+    }
+
+    // Non-zero exit lets CI gate on an unhealthy registry
+    if !is_healthy(&health) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The API is serving and verification builds can run
+fn is_healthy(health: &shared::ServiceHealth) -> bool {
+    health.status == "ok" && health.verifier_ready != Some(false)
+}
+
+fn print_health(health: &shared::ServiceHealth) {
+    let status = match health.status.as_str() {
+        "ok" => "OK".green().bold(),
+        "degraded" => "DEGRADED".yellow().bold(),
+        other => other.to_uppercase().red().bold(),
+    };
+    let verifier = match health.verifier_ready {
+        Some(true) => "ready".green(),
+        Some(false) => "toolchain incomplete".yellow(),
+        None => "unknown".bright_black(),
+    };
+
+    println!("\n{} {}", "Registry Health:".bold(), health.version);
+    println!("  {} {}", "Status:".bold(), status);
+    println!("  {} {}", "Verifier:".bold(), verifier);
+    println!("  {} {}s", "Uptime:".bold(), health.uptime_secs);
+    println!(
+        "  {} {}",
+        "Checked:".bold(),
+        health.timestamp.format("%Y-%m-%d %H:%M UTC")
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod health_tests {
+    use super::*;
+
+    // Bodies as `GET /health` serves them
+    const OK: &str = r#"{"status":"ok","version":"0.1.0","timestamp":"2026-03-01T12:00:00+00:00","uptime_secs":42,"verifier_ready":true}"#;
+    const NO_TOOLCHAIN: &str = r#"{"status":"ok","version":"0.1.0","timestamp":"2026-03-01T12:00:00+00:00","uptime_secs":42,"verifier_ready":false}"#;
+    const SHUTTING_DOWN: &str = r#"{"status":"shutting_down","version":"0.1.0","timestamp":"2026-03-01T12:00:00+00:00","uptime_secs":42}"#;
+
+    fn parse(body: &str) -> shared::ServiceHealth {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn healthy_only_when_serving_with_a_ready_verifier() {
+        assert!(is_healthy(&parse(OK)));
+        assert!(!is_healthy(&parse(NO_TOOLCHAIN)));
+
+        let draining = parse(SHUTTING_DOWN);
+        assert_eq!(draining.verifier_ready, None);
+        assert!(!is_healthy(&draining));
+    }
+}
+
 pub async fn breaking_changes(api_url: &str, old_id: &str, new_id: &str, json: bool) -> Result<()> {
     let client = reqwest::Client::new();
     let url = format!(
@@ -837,6 +953,7 @@ pub async fn export(
     id: &str,
     output: &str,
     contract_dir: &str,
+    verify: bool,
 ) -> Result<()> {
     let source = std::path::Path::new(contract_dir);
     anyhow::ensure!(
@@ -851,10 +968,18 @@ pub async fn export(
         "contract",
         "testnet",
     )?;
+    if verify {
+        crate::import::verify_archive(std::path::Path::new(output))
+            .context("export round-trip check failed")?;
+    }
     println!("{}", "✓ Export complete!".green().bold());
     println!("  {}: {}", "Output".bold(), output);
     println!("  {}: {}", "Contract".bold(), id.bright_black());
-    println!("  {}: {}\n", "Name".bold(), "contract");
+    println!("  {}: {}", "Name".bold(), "contract");
+    if verify {
+        println!("  {}: {}", "Round-trip".bold(), "verified".green());
+    }
+    println!();
     Ok(())
 }
 
@@ -921,18 +1046,34 @@ fn severity_colored(sev: &Severity) -> colored::ColoredString {
 
 pub async fn patch_create(
     api_url: &str,
+    admin_token: &str,
     version: &str,
+    range: &str,
     hash: &str,
     severity: Severity,
     rollout: u8,
 ) -> Result<()> {
     println!("\n{}", "Creating security patch...".bold().cyan());
 
-    let patch = PatchManager::create(api_url, version, hash, severity, rollout).await?;
+    let lookup = RegistryContractLookup::new(api_url);
+    let (patch, distribution) = PatchManager::create_and_target(
+        api_url,
+        admin_token,
+        &lookup,
+        &NewPatch {
+            version,
+            vulnerable_range: range,
+            hash,
+            severity,
+            rollout,
+        },
+    )
+    .await?;
 
     println!("{}", "✓ Patch created!".green().bold());
     println!("  {}: {}", "ID".bold(), patch.id);
     println!("  {}: {}", "Target Version".bold(), patch.target_version);
+    println!("  {}: {}", "Vulnerable Range".bold(), patch.vulnerable_range);
     println!(
         "  {}: {}",
         "Severity".bold(),
@@ -943,7 +1084,21 @@ pub async fn patch_create(
         "New WASM Hash".bold(),
         patch.new_wasm_hash.bright_black()
     );
-    println!("  {}: {}%\n", "Rollout".bold(), patch.rollout_percentage);
+    println!("  {}: {}%", "Rollout".bold(), patch.rollout_percentage);
+    println!(
+        "  {}: {}",
+        "Affected Contracts".bold(),
+        distribution.targets().len()
+    );
+
+    let preferences = distribution.load_preferences(api_url).await?;
+    let recipients = distribution.recipients(&preferences);
+    println!(
+        "  {}: {} ({} suppressed by notification preferences)\n",
+        "Notified".bold(),
+        recipients.len(),
+        distribution.targets().len() - recipients.len()
+    );
 
     if matches!(patch.severity, Severity::Critical | Severity::High) {
         println!(
@@ -1031,6 +1186,66 @@ pub async fn trust_score(api_url: &str, contract_id: &str, network: Network) ->
     Ok(())
 }
 
+pub async fn patch_escalate(
+    api_url: &str,
+    admin_token: &str,
+    patch_id: &str,
+    severity: Severity,
+    versions: &VersionManager,
+) -> Result<()> {
+    println!("\n{}", "Escalating security patch...".bold().cyan());
+
+    let lookup = RegistryContractLookup::new(api_url);
+    let escalation = PatchManager::escalate(
+        api_url,
+        admin_token,
+        &lookup,
+        versions,
+        patch_id,
+        severity,
+    )
+    .await?;
+    let record = &escalation.record;
+
+    if record.previous_severity == record.patch.severity {
+        println!("{}", "✓ Patch already at this severity".green().bold());
+    } else {
+        println!("{}", "✓ Patch escalated!".green().bold());
+    }
+    println!("  {}: {}", "ID".bold(), record.patch.id);
+    println!(
+        "  {}: {} → {}",
+        "Severity".bold(),
+        severity_colored(&record.previous_severity),
+        severity_colored(&record.patch.severity)
+    );
+    if escalation.bump != escalation.previous_bump {
+        println!(
+            "  {}: {:?} → {:?}",
+            "Release Bump".bold(),
+            escalation.previous_bump,
+            escalation.bump
+        );
+    }
+    println!(
+        "  {}: {}",
+        "Pending Notifications".bold(),
+        record.pending_notifications
+    );
+
+    let distribution = &escalation.distribution;
+    let preferences = distribution.load_preferences(api_url).await?;
+    let recipients = distribution.recipients(&preferences);
+    println!(
+        "  {}: {} of {} affected contracts\n",
+        "Notified at New Severity".bold(),
+        recipients.len(),
+        distribution.targets().len()
+    );
+
+    Ok(())
+}
+
 pub async fn patch_notify(api_url: &str, patch_id: &str) -> Result<()> {
     println!("\n{}", "Identifying vulnerable contracts...".bold().cyan());
 
@@ -1042,6 +1257,13 @@ pub async fn patch_notify(api_url: &str, patch_id: &str) -> Result<()> {
         severity_colored(&patch.severity),
         patch.target_version.bold()
     );
+    if !patch.vulnerable_range.is_empty() {
+        println!(
+            "  {}: {}",
+            "Vulnerable versions".bold(),
+            patch.vulnerable_range
+        );
+    }
     println!("{}", "=".repeat(80).cyan());
 
     if contracts.is_empty() {
@@ -1068,12 +1290,53 @@ pub async fn patch_notify(api_url: &str, patch_id: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn patch_apply(api_url: &str, contract_id: &str, patch_id: &str) -> Result<()> {
+pub async fn patch_apply(
+    api_url: &str,
+    api_key: Option<&str>,
+    contract_id: &str,
+    patch_id: &str,
+    dry_run: bool,
+    error: Option<&str>,
+) -> Result<()> {
+    if dry_run {
+        println!("\n{}", "Checking patch preconditions...".bold().cyan());
+        let checks = PatchManager::preconditions(api_url, contract_id, patch_id).await?;
+        let version = checks
+            .current_version
+            .as_ref()
+            .map_or("unknown".to_string(), |v| v.to_string());
+        println!("{}", "✓ Patch can be applied (dry run)".green().bold());
+        println!("  {}: {}", "Current version".bold(), version);
+        println!("  {}: {}", "Applicable range".bold(), checks.range);
+        if !checks.patch.vulnerable_range.is_empty() {
+            println!(
+                "  {}: {}",
+                "Vulnerable range".bold(),
+                checks.patch.vulnerable_range
+            );
+        }
+        println!(
+            "  {}: {}/{} at {}%\n",
+            "Rollout".bold(),
+            checks.applied,
+            checks.total,
+            checks.patch.rollout_percentage
+        );
+        return Ok(());
+    }
+
+    let Some(api_key) = api_key else {
+        anyhow::bail!("an API key is required to apply a patch (--api-key)");
+    };
     println!("\n{}", "Applying security patch...".bold().cyan());
 
-    let audit = PatchManager::apply(api_url, contract_id, patch_id).await?;
+    let audit = PatchManager::apply(api_url, api_key, contract_id, patch_id, error).await?;
 
-    println!("{}", "✓ Patch applied successfully!".green().bold());
+    if audit.failed {
+        println!("{}", "✓ Patch failure recorded".yellow().bold());
+    } else {
+        println!("{}", "✓ Patch applied successfully!".green().bold());
+    }
     println!("  {}: {}", "Contract".bold(), audit.contract_id);
     println!("  {}: {}", "Patch".bold(), audit.patch_id);
     println!("  {}: {}\n", "Applied At".bold(), audit.applied_at);
@@ -1081,6 +1344,75 @@ pub async fn patch_apply(api_url: &str, contract_id: &str, patch_id: &str) -> Re
     Ok(())
 }
 
+pub async fn patch_rollout_status(
+    api_url: &str,
+    patch_id: &str,
+    json: bool,
+    watch: bool,
+    interval_secs: u64,
+) -> Result<()> {
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+
+    loop {
+        let progress = PatchManager::rollout_status(api_url, patch_id).await?;
+
+        if json {
+            // One document per poll so --watch output can be streamed
+            println!("{}", serde_json::to_string(&progress)?);
+        } else {
+            if watch {
+                // Clear the screen and home the cursor for a live view
+                print!("\x1B[2J\x1B[H");
+            }
+            print_rollout_progress(&progress);
+        }
+
+        if !watch || progress.is_settled() {
+            return Ok(());
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn print_rollout_progress(progress: &RolloutProgress) {
+    println!("\n{}", "Patch Rollout Status".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!("  {}: {}", "Patch".bold(), progress.patch_id);
+    println!("  {}: {}", "Stage".bold(), progress.stage);
+    println!(
+        "  {}: {:.1}%",
+        "Progress".bold(),
+        progress.progress_percentage
+    );
+    println!(
+        "  {}: {} ({} applied, {} failed)",
+        "Cohort".bold(),
+        progress.cohort_size,
+        progress.applied,
+        progress.failed
+    );
+
+    let failure = format!("{:.1}%", progress.failure_rate * 100.0);
+    let failure = if progress.failed > 0 {
+        failure.red()
+    } else {
+        failure.green()
+    };
+    println!("  {}: {}", "Failure Rate".bold(), failure);
+
+    let gate = progress.approval_gate.to_string();
+    let gate = match progress.approval_gate {
+        ApprovalGate::Pending => gate.yellow().bold(),
+        ApprovalGate::Rejected => gate.red().bold(),
+        _ => gate.normal(),
+    };
+    println!("  {}: {}\n", "Approval Gate".bold(), gate);
+}
+
 pub async fn deps_list(api_url: &str, contract_id: &str) -> Result<()> {
     let client = reqwest::Client::new();
     let url = format!("{}/api/contracts/{}/dependencies", api_url, contract_id);
@@ -1163,6 +1495,9 @@ pub async fn run_tests(
     test_file: &str,
     contract_path: Option<&str>,
     junit_output: Option<&str>,
+    report_dir: Option<&str>,
+    retries: u32,
+    parallel: usize,
     show_coverage: bool,
     verbose: bool,
 ) -> Result<()> {
@@ -1172,92 +1507,132 @@ pub async fn run_tests(
     }
 
     let contract_dir = contract_path.unwrap_or(".");
-    let mut runner = test_framework::TestRunner::new(contract_dir)?;
 
     println!("\n{}", "Running Integration Tests...".bold().cyan());
     println!("{}", "=".repeat(80).cyan());
 
-    let scenario = test_framework::load_test_scenario(test_path)?;
+    let scenarios = test_framework::load_test_scenarios(test_path)?;
 
     if verbose {
-        println!("\n{}: {}", "Scenario".bold(), scenario.name);
-        if let Some(desc) = &scenario.description {
-            println!("{}: {}", "Description".bold(), desc);
+        for scenario in &scenarios {
+            println!("\n{}: {}", "Scenario".bold(), scenario.name);
+            if let Some(desc) = &scenario.description {
+                println!("{}: {}", "Description".bold(), desc);
+            }
+            println!("{}: {}", "Steps".bold(), scenario.steps.len());
+        }
+        if parallel > 1 {
+            println!("{}: {}", "Parallel".bold(), parallel);
         }
-        println!("{}: {}", "Steps".bold(), scenario.steps.len());
     }
 
     let start_time = std::time::Instant::now();
-    let result = runner.run_scenario(scenario).await?;
+    let run = test_framework::run_suite(contract_dir, scenarios, parallel, retries).await?;
     let total_time = start_time.elapsed();
 
     println!("\n{}", "Test Results:".bold().green());
     println!("{}", "=".repeat(80).cyan());
 
-    let status_icon = if result.passed { "✓" } else { "✗" };
-
-    println!(
-        "\n{} {} {} ({:.2}ms)",
-        status_icon,
-        "Scenario:".bold(),
-        result.scenario.bold(),
-        result.duration.as_secs_f64() * 1000.0
-    );
-
-    if !result.passed {
-        if let Some(ref err) = result.error {
-            println!("{} {}", "Error:".bold().red(), err);
-        }
-    }
-
-    println!("\n{}", "Step Results:".bold());
-    for (i, step) in result.steps.iter().enumerate() {
-        let step_icon = if step.passed { "✓" } else { "✗" };
+    for result in &run.results {
+        let status_icon = if result.passed { "✓" } else { "✗" };
 
         println!(
-            "  {}. {} {} ({:.2}ms)",
-            i + 1,
-            step_icon,
-            step.step_name.bold(),
-            step.duration.as_secs_f64() * 1000.0
+            "\n{} {} {} ({:.2}ms)",
+            status_icon,
+            "Scenario:".bold(),
+            result.scenario.bold(),
+            result.duration.as_secs_f64() * 1000.0
         );
 
-        if verbose {
+        if !result.passed {
+            if let Some(ref err) = result.error {
+                println!("{} {}", "Error:".bold().red(), err);
+            }
+        }
+        if result.attempts > 1 {
             println!(
-                "     Assertions: {}/{} passed",
-                step.assertions_passed,
-                step.assertions_passed + step.assertions_failed
+                "{} {} after {} attempt(s)",
+                "↻".yellow(),
+                if result.passed { "Passed" } else { "Still failing" },
+                result.attempts
             );
         }
 
-        if let Some(ref err) = step.error {
-            println!("     {}", err.red());
+        println!("\n{}", "Step Results:".bold());
+        for (i, step) in result.steps.iter().enumerate() {
+            let step_icon = if step.passed { "✓" } else { "✗" };
+
+            println!(
+                "  {}. {} {} ({:.2}ms)",
+                i + 1,
+                step_icon,
+                step.step_name.bold(),
+                step.duration.as_secs_f64() * 1000.0
+            );
+
+            if verbose {
+                println!(
+                    "     Assertions: {}/{} passed",
+                    step.assertions_passed,
+                    step.assertions_passed + step.assertions_failed
+                );
+            }
+
+            if let Some(ref err) = step.error {
+                println!("     {}", err.red());
+            }
         }
     }
 
+    let failures = run.results.iter().filter(|r| !r.passed).count();
+    if run.results.len() > 1 {
+        println!(
+            "\n{} {}/{} scenarios passed",
+            "Summary:".bold(),
+            run.results.len() - failures,
+            run.results.len()
+        );
+    }
+
     if show_coverage {
         println!("\n{}", "Coverage Report:".bold().magenta());
-        println!("  Contracts Tested: {}", result.coverage.contracts_tested);
+        println!("  Contracts Tested: {}", run.coverage.contracts_tested);
         println!(
             "  Methods Tested: {}/{}",
-            result.coverage.methods_tested, result.coverage.total_methods
+            run.coverage.methods_tested, run.coverage.total_methods
         );
-        println!("  Coverage: {:.2}%", result.coverage.coverage_percent);
+        println!("  Coverage: {:.2}%", run.coverage.coverage_percent);
 
-        if result.coverage.coverage_percent < 80.0 {
+        if run.coverage.coverage_percent < 80.0 {
             println!("  {} Low coverage detected!", "⚠".yellow());
         }
     }
 
     if let Some(junit_path) = junit_output {
-        test_framework::generate_junit_xml(&[result], Path::new(junit_path))?;
+        test_framework::generate_junit_xml(&run.results, Path::new(junit_path))?;
         println!(
             "\n{} JUnit XML report exported to: {}",
             "✓".green(),
             junit_path
         );
-        test_framework::generate_junit_xml(&[result.clone()], Path::new(junit_path))?;
-        println!("\n{} JUnit XML report exported to: {}", "✓".green(), junit_path);
+    }
+
+    // Written before the failure check so CI still gets the artifact
+    if let Some(dir) = report_dir {
+        let index = test_framework::write_combined_report(
+            Path::new(dir),
+            &run.results,
+            &run.coverage,
+            &run.files,
+        )?;
+        println!(
+            "\n{} Combined report written to: {} ({}, {}, {})",
+            "✓".green(),
+            dir,
+            test_framework::REPORT_INDEX_FILE,
+            index.junit,
+            index.coverage
+        );
     }
 
     if total_time.as_secs() > 5 {
@@ -1271,7 +1646,7 @@ pub async fn run_tests(
     println!("\n{}", "=".repeat(80).cyan());
     println!();
 
-    if !result.passed {
+    if failures > 0 {
         anyhow::bail!("Tests failed");
     }
 
@@ -1686,10 +2061,13 @@ mod tests_network {
         profile(
             &contract_path,
             None,
+            1,
             Some(&json_output_str),
+            None,
             Some(&flame_output_str),
             None,
             true,
+            &profiler::RecommendationThresholds::default(),
         )
         .expect("expected profiling to succeed");
 
@@ -1714,10 +2092,13 @@ mod tests_network {
         profile(
             &contract_path,
             None,
+            1,
+            None,
             None,
             None,
             Some(&baseline_path_str),
             false,
+            &profiler::RecommendationThresholds::default(),
         )
         .expect("expected profiling with baseline comparison to succeed");
     }
@@ -1732,10 +2113,13 @@ mod tests_network {
         let err = profile(
             &contract_path,
             None,
+            1,
+            None,
             None,
             None,
             Some(&missing_baseline_str),
             false,
+            &profiler::RecommendationThresholds::default(),
         )
         .expect_err("expected missing baseline to fail");
 
@@ -1753,10 +2137,13 @@ mod tests_network {
         let err = profile(
             &contract_path,
             Some("does_not_exist"),
+            1,
+            None,
             None,
             None,
             None,
             false,
+            &profiler::RecommendationThresholds::default(),
         )
         .expect_err("expected unknown method to fail");
 
@@ -2,12 +2,15 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 
+use crate::patch::{Severity, VersionBump, VersionBumpPolicy};
+
 const DEFAULT_API_BASE: &str = "http://localhost:3001";
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const CONFIG_DIR_NAME: &str = ".soroban-registry";
@@ -54,6 +57,161 @@ impl FromStr for Network {
 #[derive(Debug, Clone, Deserialize, Default)]
 struct ConfigFile {
     defaults: Option<DefaultsSection>,
+    /// `[networks.<name>]` sections, overriding the built-in endpoints of
+    /// mainnet/testnet/futurenet or defining new networks
+    #[serde(default)]
+    networks: BTreeMap<String, NetworkSection>,
+    /// `[version_bumps]` section remapping patch severities to version
+    /// bumps, e.g. `high = "major"`; unnamed severities keep the default
+    #[serde(default)]
+    version_bumps: BTreeMap<String, VersionBump>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct NetworkSection {
+    rpc_url: Option<String>,
+    horizon_url: Option<String>,
+    /// Registry network a custom network's contracts are listed under;
+    /// defaults to testnet
+    kind: Option<Network>,
+}
+
+/// Endpoints used to talk to a network directly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkEndpoints {
+    /// Name given in the config or with `--network`
+    pub name: String,
+    pub network: Network,
+    pub rpc_url: String,
+    pub horizon_url: Option<String>,
+}
+
+/// Public RPC and Horizon endpoints used when the config doesn't override them
+fn default_endpoints(network: Network) -> (&'static str, &'static str) {
+    match network {
+        Network::Mainnet | Network::Auto => (
+            "https://soroban-rpc.mainnet.stellar.gateway.fm",
+            "https://horizon.stellar.org",
+        ),
+        Network::Testnet => (
+            "https://soroban-testnet.stellar.org",
+            "https://horizon-testnet.stellar.org",
+        ),
+        Network::Futurenet => (
+            "https://rpc-futurenet.stellar.org",
+            "https://horizon-futurenet.stellar.org",
+        ),
+    }
+}
+
+fn validate_endpoint(network: &str, key: &str, value: &str) -> Result<()> {
+    let url = reqwest::Url::parse(value)
+        .with_context(|| format!("networks.{}.{} is not a valid URL: {}", network, key, value))?;
+    if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+        anyhow::bail!(
+            "networks.{}.{} must be an http(s) URL with a host: {}",
+            network,
+            key,
+            value
+        );
+    }
+    Ok(())
+}
+
+impl ConfigFile {
+    /// Network names are case-insensitive, as they are with `--network`
+    fn normalize_network_names(&mut self) -> Result<()> {
+        let mut networks = BTreeMap::new();
+        for (name, section) in std::mem::take(&mut self.networks) {
+            if networks.insert(name.to_lowercase(), section).is_some() {
+                anyhow::bail!(
+                    "networks.{} is defined more than once (names are case-insensitive)",
+                    name
+                );
+            }
+        }
+        self.networks = networks;
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (name, section) in &self.networks {
+            if let Some(url) = &section.rpc_url {
+                validate_endpoint(name, "rpc_url", url)?;
+            }
+            if let Some(url) = &section.horizon_url {
+                validate_endpoint(name, "horizon_url", url)?;
+            }
+            if section.rpc_url.is_none() && name.parse::<Network>().is_err() {
+                anyhow::bail!(
+                    "networks.{} is not a built-in network and needs an rpc_url",
+                    name
+                );
+            }
+        }
+        for severity in self.version_bumps.keys() {
+            severity
+                .parse::<Severity>()
+                .with_context(|| format!("version_bumps.{} is not a severity", severity))?;
+        }
+        Ok(())
+    }
+
+    /// The default policy remapped by `[version_bumps]`, then by `overrides`
+    fn version_bump_policy(&self, overrides: Option<&str>) -> Result<VersionBumpPolicy> {
+        let from_file: HashMap<Severity, VersionBump> = self
+            .version_bumps
+            .iter()
+            .map(|(severity, bump)| Ok((severity.parse()?, *bump)))
+            .collect::<Result<_>>()?;
+        let from_cli = match overrides {
+            Some(spec) => VersionBumpPolicy::parse_overrides(spec)?,
+            None => HashMap::new(),
+        };
+        Ok(VersionBumpPolicy::default()
+            .with_overrides(&from_file)
+            .with_overrides(&from_cli))
+    }
+
+    /// Registry network `name` refers to: a built-in one, or the `kind` of
+    /// a custom network section
+    fn network(&self, name: &str) -> Result<Network> {
+        match name.parse::<Network>() {
+            Ok(network) => Ok(network),
+            Err(err) => match self.networks.get(&name.to_lowercase()) {
+                Some(section) => Ok(section.kind.unwrap_or(Network::Testnet)),
+                None => Err(err),
+            },
+        }
+    }
+
+    fn endpoints(&self, name: &str) -> Result<NetworkEndpoints> {
+        let name = name.to_lowercase();
+        let network = self.network(&name)?;
+        let section = self.networks.get(&name).cloned().unwrap_or_default();
+        let (rpc_url, horizon_url) = match name.parse::<Network>() {
+            Ok(builtin) => {
+                let (rpc, horizon) = default_endpoints(builtin);
+                (
+                    section.rpc_url.unwrap_or_else(|| rpc.to_string()),
+                    Some(section.horizon_url.unwrap_or_else(|| horizon.to_string())),
+                )
+            }
+            Err(_) => (
+                section
+                    .rpc_url
+                    .with_context(|| format!("networks.{} needs an rpc_url", name))?,
+                section.horizon_url,
+            ),
+        };
+
+        Ok(NetworkEndpoints {
+            name,
+            network,
+            rpc_url,
+            horizon_url,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -68,27 +226,42 @@ pub struct RuntimeConfig {
     pub network: Network,
     pub api_base: String,
     pub timeout: u64,
+    pub endpoints: NetworkEndpoints,
 }
 
 pub fn resolve_network(cli_network: Option<String>) -> Result<Network> {
-    let config = load_defaults_section()?;
-    match cli_network.or(config.network) {
-        Some(value) => value.parse::<Network>(),
+    let config = load_config()?;
+    let defaults = config.defaults.clone().unwrap_or_default();
+    match cli_network.or(defaults.network) {
+        Some(value) => config.network(&value),
         None => Ok(Network::Testnet),
     }
 }
 
+/// RPC/Horizon endpoints of the network selected with `--network` or the
+/// config default. Commands that talk to a network directly go through this.
+pub fn resolve_endpoints(cli_network: Option<String>) -> Result<NetworkEndpoints> {
+    let config = load_config()?;
+    let defaults = config.defaults.clone().unwrap_or_default();
+    let name = cli_network
+        .or(defaults.network)
+        .unwrap_or_else(|| Network::Testnet.to_string());
+    config.endpoints(&name)
+}
+
 pub fn resolve_runtime_config(
     cli_network: Option<String>,
     cli_api_base: Option<String>,
     cli_timeout: Option<u64>,
 ) -> Result<RuntimeConfig> {
-    let config = load_defaults_section()?;
+    let file = load_config()?;
+    let config = file.defaults.clone().unwrap_or_default();
 
-    let network = match cli_network.or(config.network) {
-        Some(value) => value.parse::<Network>()?,
-        None => Network::Testnet,
-    };
+    let network_name = cli_network
+        .or(config.network)
+        .unwrap_or_else(|| Network::Testnet.to_string());
+    let network = file.network(&network_name)?;
+    let endpoints = file.endpoints(&network_name)?;
 
     let api_base = cli_api_base
         .or(config.api_base)
@@ -102,13 +275,22 @@ pub fn resolve_runtime_config(
         network,
         api_base,
         timeout,
+        endpoints,
     })
 }
 
+/// Severity to version bump mapping for patch releases: the built-in one,
+/// remapped by the config file's `[version_bumps]` section and then by
+/// `overrides` (`--version-bumps` / `SOROBAN_REGISTRY_VERSION_BUMPS`)
+pub fn resolve_version_bump_policy(overrides: Option<&str>) -> Result<VersionBumpPolicy> {
+    load_config()?.version_bump_policy(overrides)
+}
+
 pub fn show_config() -> Result<()> {
     migrate_legacy_config()?;
     let path = config_file_path().context("Could not determine home directory")?;
-    let defaults = load_defaults_section()?;
+    let config = load_config()?;
+    let defaults = config.defaults.clone().unwrap_or_default();
 
     println!("Config file: {}", path.display());
     println!(
@@ -126,6 +308,25 @@ pub fn show_config() -> Result<()> {
         defaults.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS)
     );
 
+    let mut names: Vec<String> = [Network::Mainnet, Network::Testnet, Network::Futurenet]
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+    names.extend(
+        config
+            .networks
+            .keys()
+            .filter(|name| name.parse::<Network>().is_err())
+            .cloned(),
+    );
+    for name in names {
+        let endpoints = config.endpoints(&name)?;
+        println!("networks.{}.rpc_url = {}", name, endpoints.rpc_url);
+        if let Some(horizon) = endpoints.horizon_url {
+            println!("networks.{}.horizon_url = {}", name, horizon);
+        }
+    }
+
     Ok(())
 }
 
@@ -148,24 +349,31 @@ pub fn edit_config() -> Result<()> {
 }
 
 fn load_defaults_section() -> Result<DefaultsSection> {
+    Ok(load_config()?.defaults.unwrap_or_default())
+}
+
+fn load_config() -> Result<ConfigFile> {
     migrate_legacy_config()?;
     let path = match config_file_path() {
         Some(p) => p,
-        None => return Ok(DefaultsSection::default()),
+        None => return Ok(ConfigFile::default()),
     };
 
     if !path.exists() {
-        return Ok(DefaultsSection::default());
+        return Ok(ConfigFile::default());
     }
 
-    let config = load_config_file(&path)?;
-    Ok(config.defaults.unwrap_or_default())
+    load_config_file(&path)
 }
 
 fn load_config_file(path: &Path) -> Result<ConfigFile> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file at {:?}", path))?;
-    toml::from_str(&content).with_context(|| "Failed to parse config file")
+    let mut config: ConfigFile =
+        toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+    config.normalize_network_names()?;
+    config.validate()?;
+    Ok(config)
 }
 
 fn ensure_config_file_exists(path: &Path) -> Result<()> {
@@ -182,6 +390,15 @@ fn ensure_config_file_exists(path: &Path) -> Result<()> {
 network = "testnet"
 api_base = "http://localhost:3001"
 timeout = 30
+
+# Override a network's endpoints, or add your own and select it with --network:
+# [networks.local]
+# rpc_url = "http://localhost:8000/soroban/rpc"
+# kind = "testnet"
+
+# Remap the version bump a patch of each severity calls for:
+# [version_bumps]
+# high = "major"
 "#;
     fs::write(path, default_content)
         .with_context(|| format!("Failed to write default config to {:?}", path))?;
@@ -274,13 +491,125 @@ timeout = 55
         assert_eq!(defaults.timeout, Some(55));
     }
 
+    #[test]
+    fn test_version_bumps_section_and_overrides_remap_the_policy() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"[version_bumps]
+high = "major"
+medium = "minor"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config_file(&config_path).unwrap();
+        let policy = config.version_bump_policy(None).unwrap();
+        assert_eq!(policy.bump_for(Severity::High), VersionBump::Major);
+        assert_eq!(policy.bump_for(Severity::Medium), VersionBump::Minor);
+        assert_eq!(policy.bump_for(Severity::Low), VersionBump::Patch);
+
+        // The flag or environment variable wins over the file
+        let policy = config.version_bump_policy(Some("medium=patch")).unwrap();
+        assert_eq!(policy.bump_for(Severity::High), VersionBump::Major);
+        assert_eq!(policy.bump_for(Severity::Medium), VersionBump::Patch);
+
+        fs::write(&config_path, "[version_bumps]\nurgent = \"major\"\n").unwrap();
+        assert!(load_config_file(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_custom_network_endpoint_overrides_default() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"[networks.futurenet]
+rpc_url = "https://rpc.example.com"
+
+[networks.local]
+rpc_url = "http://localhost:8000/soroban/rpc"
+kind = "futurenet"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config_file(&config_path).unwrap();
+
+        let futurenet = config.endpoints("futurenet").unwrap();
+        assert_eq!(futurenet.rpc_url, "https://rpc.example.com");
+        assert_eq!(
+            futurenet.horizon_url.as_deref(),
+            Some(default_endpoints(Network::Futurenet).1)
+        );
+        assert_eq!(
+            config.endpoints("testnet").unwrap().rpc_url,
+            default_endpoints(Network::Testnet).0
+        );
+
+        let local = config.endpoints("local").unwrap();
+        assert_eq!(local.rpc_url, "http://localhost:8000/soroban/rpc");
+        assert_eq!(local.network, Network::Futurenet);
+        assert!(config.endpoints("unknown").is_err());
+    }
+
+    #[test]
+    fn test_network_names_are_case_insensitive() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"[networks.Local]
+rpc_url = "http://localhost:8000/soroban/rpc"
+
+[networks.TESTNET]
+rpc_url = "https://rpc.example.com"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config_file(&config_path).unwrap();
+        assert_eq!(
+            config.endpoints("local").unwrap().rpc_url,
+            "http://localhost:8000/soroban/rpc"
+        );
+        assert_eq!(
+            config.endpoints("Testnet").unwrap().rpc_url,
+            "https://rpc.example.com"
+        );
+
+        fs::write(
+            &config_path,
+            r#"[networks.local]
+rpc_url = "http://a.example"
+
+[networks.LOCAL]
+rpc_url = "http://b.example"
+"#,
+        )
+        .unwrap();
+        assert!(load_config_file(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_malformed_network_endpoint_is_rejected_on_load() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "[networks.testnet]\nrpc_url = \"not a url\"\n",
+        )
+        .unwrap();
+
+        let err = load_config_file(&config_path).unwrap_err();
+        assert!(err.to_string().contains("networks.testnet.rpc_url"));
+    }
+
     #[test]
     fn test_config_file_path_for_base() {
         let dir = tempdir().unwrap();
-        let expected = dir
-            .path()
-            .join(CONFIG_DIR_NAME)
-            .join(CONFIG_FILE_NAME);
+        let expected = dir.path().join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME);
         assert_eq!(config_file_path_for(dir.path()), expected);
     }
 
@@ -1,27 +1,29 @@
 use std::fs::{self, File};
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::Utc;
 
 use crate::io_utils::{compute_sha256_streaming, extract_tar_gz};
 use crate::manifest::{AuditEntry, ExportManifest};
 
-pub fn extract_and_verify(archive_path: &Path, output_dir: &Path) -> Result<ExportManifest> {
-    let tmp_dir = tempfile::tempdir().context("failed to create temp dir")?;
+/// Unpack the outer archive into `dir` and check the inner archive against
+/// the manifest's hash
+fn open_archive(archive_path: &Path, dir: &Path) -> Result<(ExportManifest, PathBuf)> {
+    extract_tar_gz(archive_path, dir)?;
 
-    extract_tar_gz(archive_path, tmp_dir.path())?;
-
-    let manifest_path = tmp_dir.path().join("manifest.json");
-    let inner_path = tmp_dir.path().join("contract.tar.gz");
+    let manifest_path = dir.join("manifest.json");
+    let inner_path = dir.join("contract.tar.gz");
 
     if !manifest_path.exists() || !inner_path.exists() {
         bail!("invalid archive: missing manifest.json or contract.tar.gz");
     }
 
-    let mut manifest: ExportManifest =
-        serde_json::from_reader(BufReader::new(File::open(&manifest_path)?))?;
+    let raw: serde_json::Value =
+        serde_json::from_reader(BufReader::new(File::open(&manifest_path)?))
+            .context("invalid archive: manifest.json is not valid JSON")?;
+    let manifest = ExportManifest::from_json(raw)?;
 
     let computed_hash = compute_sha256_streaming(&inner_path)?;
     if computed_hash != manifest.sha256 {
@@ -32,6 +34,13 @@ pub fn extract_and_verify(archive_path: &Path, output_dir: &Path) -> Result<Expo
         );
     }
 
+    Ok((manifest, inner_path))
+}
+
+pub fn extract_and_verify(archive_path: &Path, output_dir: &Path) -> Result<ExportManifest> {
+    let tmp_dir = tempfile::tempdir().context("failed to create temp dir")?;
+    let (mut manifest, inner_path) = open_archive(archive_path, tmp_dir.path())?;
+
     manifest.audit_trail.push(AuditEntry {
         action: "import_verified".into(),
         timestamp: Utc::now(),
@@ -49,3 +58,97 @@ pub fn extract_and_verify(archive_path: &Path, output_dir: &Path) -> Result<Expo
 
     Ok(manifest)
 }
+
+/// Whether `path` names a contract ABI (`abi.json` or `*.abi.json`)
+fn is_abi_file(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name == "abi.json" || file_name.ends_with(".abi.json")
+}
+
+/// Re-read an exported archive the way `import` would, without touching
+/// `output_dir` or the network. Every file the manifest lists must be in the
+/// contract archive with the recorded size, and ABI files must parse as JSON.
+pub fn verify_archive(archive_path: &Path) -> Result<ExportManifest> {
+    let tmp_dir = tempfile::tempdir().context("failed to create temp dir")?;
+    let outer_dir = tmp_dir.path().join("archive");
+    let contract_dir = tmp_dir.path().join("contract");
+
+    let (manifest, inner_path) = open_archive(archive_path, &outer_dir)?;
+    if manifest.contents.is_empty() {
+        bail!("invalid archive: manifest lists no contract files");
+    }
+
+    fs::create_dir_all(&contract_dir)?;
+    extract_tar_gz(&inner_path, &contract_dir)
+        .context("invalid archive: contract.tar.gz cannot be extracted")?;
+
+    for entry in &manifest.contents {
+        let path = contract_dir.join(&entry.path);
+        let metadata = fs::metadata(&path).map_err(|_| {
+            anyhow!(
+                "invalid archive: {} is listed in the manifest but missing from contract.tar.gz",
+                entry.path
+            )
+        })?;
+        if metadata.len() != entry.size {
+            bail!(
+                "invalid archive: {} is {} bytes, manifest records {}",
+                entry.path,
+                metadata.len(),
+                entry.size
+            );
+        }
+        if is_abi_file(&entry.path) {
+            serde_json::from_reader::<_, serde_json::Value>(BufReader::new(File::open(&path)?))
+                .with_context(|| {
+                    format!("invalid archive: ABI {} is not valid JSON", entry.path)
+                })?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_contract(dir: &Path) {
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"token\"\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), "#![no_std]\n").unwrap();
+        fs::write(
+            dir.join("abi.json"),
+            r#"{"functions":[{"name":"transfer"}]}"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn exported_fixture_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("token");
+        fixture_contract(&source);
+        let archive = tmp.path().join("token.tar.gz");
+
+        crate::export::create_archive(&source, &archive, "CTOKEN", "token", "testnet").unwrap();
+
+        let manifest = verify_archive(&archive).unwrap();
+        assert_eq!(manifest.contract_id, "CTOKEN");
+        assert_eq!(manifest.contents.len(), 3);
+    }
+
+    #[test]
+    fn broken_abi_fails_the_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("token");
+        fixture_contract(&source);
+        fs::write(source.join("abi.json"), "{\"functions\": [").unwrap();
+        let archive = tmp.path().join("token.tar.gz");
+
+        crate::export::create_archive(&source, &archive, "CTOKEN", "token", "testnet").unwrap();
+
+        let err = verify_archive(&archive).unwrap_err();
+        assert!(format!("{:#}", err).contains("ABI abi.json is not valid JSON"));
+    }
+}
@@ -16,9 +16,11 @@ mod io_utils;
 mod manifest;
 mod migration;
 mod multisig;
+mod notifications;
 mod package_signing;
 mod patch;
 mod profiler;
+mod release_notes;
 mod sla;
 mod test_framework;
 mod webhook;
@@ -26,7 +28,7 @@ mod wizard;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use patch::Severity;
+use patch::{Severity, VersionManager};
 
 /// Soroban Registry CLI — discover, publish, verify, and deploy Soroban contracts
 #[derive(Debug, Parser)]
@@ -40,7 +42,8 @@ pub struct Cli {
     )]
     pub api_url: String,
 
-    /// Stellar network to use (mainnet | testnet | futurenet)
+    /// Stellar network to use (mainnet | testnet | futurenet, or a network
+    /// defined under [networks.<name>] in the config file)
     #[arg(long, global = true)]
     pub network: Option<String>,
 
@@ -125,6 +128,16 @@ pub enum Commands {
         json: bool,
     },
 
+    /// Show the registry API's health and whether verification can run
+    Health {
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Disable colored status output
+        #[arg(long)]
+        no_color: bool,
+    },
+
     /// Detect breaking changes between contract versions
     BreakingChanges {
         /// Old contract identifier (UUID or contract_id@version)
@@ -136,6 +149,24 @@ pub enum Commands {
         json: bool,
     },
 
+    /// Release notes between two versions of a contract
+    ReleaseNotes {
+        /// Contract ID
+        contract_id: String,
+        /// Version to compare from
+        #[arg(long)]
+        from: String,
+        /// Version to compare to
+        #[arg(long)]
+        to: String,
+        /// Print only the function-level API changes
+        #[arg(long)]
+        diff_only: bool,
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
     /// Contract state migration assistant
     Migrate {
         #[command(subcommand)]
@@ -167,6 +198,11 @@ pub enum Commands {
         /// Path to contract source directory
         #[arg(long, default_value = ".")]
         contract_dir: String,
+
+        /// Re-read the archive after writing it and fail if it would not
+        /// import cleanly
+        #[arg(long)]
+        verify: bool,
     },
 
     /// Import a contract from an archive
@@ -262,10 +298,18 @@ pub enum Commands {
         #[arg(long)]
         method: Option<String>,
 
-        /// Output JSON file
+        /// Rounds of invocations to aggregate per method
+        #[arg(long, default_value_t = 1)]
+        iterations: u32,
+
+        /// Output file for the collected metrics
         #[arg(long)]
         output: Option<String>,
 
+        /// Format of --output: json (default), csv or pprof
+        #[arg(long)]
+        format: Option<String>,
+
         /// Generate flame graph
         #[arg(long)]
         flamegraph: Option<String>,
@@ -277,11 +321,25 @@ pub enum Commands {
         /// Show recommendations
         #[arg(long, default_value = "true")]
         recommendations: bool,
+
+        /// Recommend changes to methods taking more than this percentage of
+        /// the total runtime
+        #[arg(long, default_value_t = 10.0)]
+        hot_threshold: f64,
+
+        /// Recommend changes to methods averaging more than this many
+        /// milliseconds per call
+        #[arg(long, default_value_t = 1.0)]
+        slow_threshold_ms: f64,
+
+        /// Recommend caching for methods called more than this many times
+        #[arg(long, default_value_t = 1000)]
+        max_calls: u64,
     },
 
     /// Run integration tests
     Test {
-        /// Path to test file (YAML or JSON)
+        /// Path to test file (YAML or JSON), or a directory of them
         test_file: String,
 
         /// Path to contract directory or file
@@ -292,6 +350,21 @@ pub enum Commands {
         #[arg(long)]
         junit: Option<String>,
 
+        /// Directory for a combined report: JUnit XML, per-file coverage
+        /// and an index.json referencing both
+        #[arg(long)]
+        report: Option<String>,
+
+        /// Re-run a failing scenario up to this many times; it passes if any
+        /// attempt does
+        #[arg(long, default_value_t = 0)]
+        retries: u32,
+
+        /// Run up to this many scenarios at once; results are still reported
+        /// in file order
+        #[arg(long, default_value_t = 1)]
+        parallel: usize,
+
         /// Show coverage report
         #[arg(long, default_value = "true")]
         coverage: bool,
@@ -554,6 +627,15 @@ pub enum MultisigCommands {
     /// Execute an approved deployment proposal
     Execute { proposal_id: String },
 
+    /// Cancel a proposal that has not been executed (proposer or policy admin)
+    Cancel {
+        proposal_id: String,
+        #[arg(long)]
+        requested_by: String,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
     /// Show full info for a proposal (signatures, policy, status)
     Info { proposal_id: String },
 
@@ -561,6 +643,9 @@ pub enum MultisigCommands {
     ListProposals {
         #[arg(long)]
         status: Option<String>,
+        /// Only proposals that have collected enough signatures to execute
+        #[arg(long)]
+        ready_only: bool,
         #[arg(long, default_value = "20")]
         limit: usize,
     },
@@ -594,24 +679,69 @@ pub enum PatchCommands {
     Create {
         #[arg(long)]
         version: String,
+        /// Semver range of vulnerable versions, e.g. ">=1.0.0, <1.2.0"
+        #[arg(long)]
+        range: String,
         #[arg(long)]
         hash: String,
         #[arg(long)]
         severity: String,
         #[arg(long, default_value = "100")]
         rollout: u8,
+        /// Operator token for the registry's admin endpoints
+        #[arg(long, env = "SOROBAN_REGISTRY_ADMIN_TOKEN", hide_env_values = true)]
+        admin_token: String,
     },
     /// Notify subscribers about a patch
     Notify {
         #[arg(long)]
         patch_id: String,
     },
+    /// Raise a patch's severity (it can never be lowered)
+    Escalate {
+        #[arg(long)]
+        patch_id: String,
+        #[arg(long)]
+        severity: String,
+        /// Operator token for the registry's admin endpoints; the operator it
+        /// belongs to is recorded in the patch's audit trail
+        #[arg(long, env = "SOROBAN_REGISTRY_ADMIN_TOKEN", hide_env_values = true)]
+        admin_token: String,
+        /// Remap severities to version bumps, e.g. "high=major"; applied over
+        /// the config file's [version_bumps] section
+        #[arg(long, env = "SOROBAN_REGISTRY_VERSION_BUMPS")]
+        version_bumps: Option<String>,
+    },
     /// Apply a patch to a specific contract
     Apply {
         #[arg(long)]
         contract_id: String,
         #[arg(long)]
         patch_id: String,
+        /// Check preconditions (version range, rollout quota) without applying
+        #[arg(long)]
+        dry_run: bool,
+        /// API key of the contract's publisher; not needed for --dry-run
+        #[arg(long, env = "SOROBAN_REGISTRY_API_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+        /// Report that applying the patch failed, with this reason; failures
+        /// count against the rollout's failure-rate gate
+        #[arg(long)]
+        error: Option<String>,
+    },
+    /// Show staged rollout progress for a patch
+    RolloutStatus {
+        #[arg(long)]
+        patch_id: String,
+        /// Output as machine-readable JSON
+        #[arg(long)]
+        json: bool,
+        /// Keep polling and refresh until the rollout settles
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between polls in --watch mode
+        #[arg(long, default_value = "5")]
+        interval: u64,
     },
     /// Manage contract dependencies
     Deps {
@@ -717,6 +847,25 @@ pub enum WebhookCommands {
         delivery_id: String,
     },
 
+    /// Deliver a contract event to a webhook, honouring the contract's
+    /// notification preferences
+    Dispatch {
+        /// Webhook ID to deliver to
+        webhook_id: String,
+
+        /// Contract the event is about
+        #[arg(long)]
+        contract_id: String,
+
+        /// Event type, e.g. contract.verified
+        #[arg(long)]
+        event: String,
+
+        /// JSON payload to deliver
+        #[arg(long, default_value = "{}")]
+        payload: String,
+    },
+
     /// Verify a webhook payload signature locally
     VerifySig {
         /// HMAC secret key used for signing
@@ -792,6 +941,12 @@ async fn main() -> Result<()> {
     log::debug!("API URL: {}", cli.api_url);
 
     // ── Resolve network ───────────────────────────────────────────────────────
+    let endpoints = config::resolve_endpoints(cli.network.clone())?;
+    log::debug!(
+        "RPC endpoint ({}): {}",
+        endpoints.name,
+        endpoints.rpc_url
+    );
     let cfg_network = config::resolve_network(cli.network)?;
     let mut net_str = cfg_network.to_string();
     if net_str == "auto" { net_str = "mainnet".to_string(); }
@@ -869,10 +1024,31 @@ async fn main() -> Result<()> {
             log::debug!("Command: list | limit={}", limit);
             commands::list(&cli.api_url, limit, network, json).await?;
         }
+        Commands::Health { format, no_color } => {
+            log::debug!("Command: health | format={}", format);
+            commands::health(&cli.api_url, &format, no_color).await?;
+        }
         Commands::BreakingChanges { old_id, new_id, json } => {
             log::debug!("Command: breaking-changes | old={} new={}", old_id, new_id);
             commands::breaking_changes(&cli.api_url, &old_id, &new_id, json).await?;
         }
+        Commands::ReleaseNotes {
+            contract_id,
+            from,
+            to,
+            diff_only,
+            format,
+        } => {
+            log::debug!(
+                "Command: release-notes | contract_id={} from={} to={} diff_only={}",
+                contract_id,
+                from,
+                to,
+                diff_only
+            );
+            release_notes::release_notes(&cli.api_url, &contract_id, &from, &to, diff_only, &format)
+                .await?;
+        }
         Commands::UpgradeAnalyze { old, new, json } => {
             log::debug!("Command: upgrade analyze | old={} new={}", old, new);
             commands::upgrade_analyze(&cli.api_url, &old, &new, json).await?;
@@ -921,9 +1097,15 @@ async fn main() -> Result<()> {
             id,
             output,
             contract_dir,
+            verify,
         } => {
-            log::debug!("Command: export | id={} output={}", id, output);
-            commands::export(&cli.api_url, &id, &output, &contract_dir).await?;
+            log::debug!(
+                "Command: export | id={} output={} verify={}",
+                id,
+                output,
+                verify
+            );
+            commands::export(&cli.api_url, &id, &output, &contract_dir, verify).await?;
         }
         Commands::Import {
             archive,
@@ -992,32 +1174,88 @@ async fn main() -> Result<()> {
         Commands::Patch { action } => match action {
             PatchCommands::Create {
                 version,
+                range,
                 hash,
                 severity,
                 rollout,
+                admin_token,
             } => {
                 let sev = severity.parse::<Severity>()?;
                 log::debug!(
-                    "Command: patch create | version={} rollout={}",
+                    "Command: patch create | version={} range={} rollout={}",
                     version,
+                    range,
                     rollout
                 );
-                commands::patch_create(&cli.api_url, &version, &hash, sev, rollout).await?;
+                commands::patch_create(
+                    &cli.api_url,
+                    &admin_token,
+                    &version,
+                    &range,
+                    &hash,
+                    sev,
+                    rollout,
+                )
+                .await?;
             }
             PatchCommands::Notify { patch_id } => {
                 log::debug!("Command: patch notify | patch_id={}", patch_id);
                 commands::patch_notify(&cli.api_url, &patch_id).await?;
             }
+            PatchCommands::Escalate {
+                patch_id,
+                severity,
+                admin_token,
+                version_bumps,
+            } => {
+                let sev = severity.parse::<Severity>()?;
+                log::debug!(
+                    "Command: patch escalate | patch_id={} severity={}",
+                    patch_id,
+                    sev
+                );
+                let versions = VersionManager::new(config::resolve_version_bump_policy(
+                    version_bumps.as_deref(),
+                )?);
+                commands::patch_escalate(&cli.api_url, &admin_token, &patch_id, sev, &versions)
+                    .await?;
+            }
             PatchCommands::Apply {
                 contract_id,
                 patch_id,
+                dry_run,
+                api_key,
+                error,
             } => {
                 log::debug!(
-                    "Command: patch apply | contract_id={} patch_id={}",
+                    "Command: patch apply | contract_id={} patch_id={} dry_run={}",
                     contract_id,
-                    patch_id
+                    patch_id,
+                    dry_run
                 );
-                commands::patch_apply(&cli.api_url, &contract_id, &patch_id).await?;
+                commands::patch_apply(
+                    &cli.api_url,
+                    api_key.as_deref(),
+                    &contract_id,
+                    &patch_id,
+                    dry_run,
+                    error.as_deref(),
+                )
+                .await?;
+            }
+            PatchCommands::RolloutStatus {
+                patch_id,
+                json,
+                watch,
+                interval,
+            } => {
+                log::debug!(
+                    "Command: patch rollout-status | patch_id={} watch={}",
+                    patch_id,
+                    watch
+                );
+                commands::patch_rollout_status(&cli.api_url, &patch_id, json, watch, interval)
+                    .await?;
             }
             PatchCommands::Deps { command } => match command {
                 DepsCommands::List { contract_id } => {
@@ -1068,13 +1306,15 @@ async fn main() -> Result<()> {
                 );
                 multisig::create_proposal(
                     &cli.api_url,
-                    &contract_name,
-                    &contract_id,
-                    &wasm_hash,
-                    &net_str,
-                    &policy_id,
-                    &proposer,
-                    description.as_deref(),
+                    &multisig::NewProposal {
+                        contract_name: &contract_name,
+                        contract_id: &contract_id,
+                        wasm_hash: &wasm_hash,
+                        network: &net_str,
+                        policy_id: &policy_id,
+                        proposer: &proposer,
+                        description: description.as_deref(),
+                    },
                 )
                 .await?;
             }
@@ -1096,17 +1336,37 @@ async fn main() -> Result<()> {
                 log::debug!("Command: multisig execute | proposal_id={}", proposal_id);
                 multisig::execute_proposal(&cli.api_url, &proposal_id).await?;
             }
+            MultisigCommands::Cancel {
+                proposal_id,
+                requested_by,
+                reason,
+            } => {
+                log::debug!("Command: multisig cancel | proposal_id={}", proposal_id);
+                multisig::cancel_proposal(
+                    &cli.api_url,
+                    &proposal_id,
+                    &requested_by,
+                    reason.as_deref(),
+                )
+                .await?;
+            }
             MultisigCommands::Info { proposal_id } => {
                 log::debug!("Command: multisig info | proposal_id={}", proposal_id);
                 multisig::proposal_info(&cli.api_url, &proposal_id).await?;
             }
-            MultisigCommands::ListProposals { status, limit } => {
+            MultisigCommands::ListProposals {
+                status,
+                ready_only,
+                limit,
+            } => {
                 log::debug!(
-                    "Command: multisig list-proposals | status={:?} limit={}",
+                    "Command: multisig list-proposals | status={:?} ready_only={} limit={}",
                     status,
+                    ready_only,
                     limit
                 );
-                multisig::list_proposals(&cli.api_url, status.as_deref(), limit).await?;
+                multisig::list_proposals(&cli.api_url, status.as_deref(), ready_only, limit)
+                    .await?;
             }
         },
         Commands::Fuzz {
@@ -1132,16 +1392,23 @@ async fn main() -> Result<()> {
         Commands::Profile {
             contract_path,
             method,
+            iterations,
             output,
+            format,
             flamegraph,
             compare,
             recommendations,
+            hot_threshold,
+            slow_threshold_ms,
+            max_calls,
         } => {
             log::debug!(
-                "Command: profile | contract_path={} method={:?} output={:?} flamegraph={:?} compare={:?} recommendations={}",
+                "Command: profile | contract_path={} method={:?} iterations={} output={:?} format={:?} flamegraph={:?} compare={:?} recommendations={}",
                 contract_path,
                 method,
+                iterations,
                 output,
+                format,
                 flamegraph,
                 compare,
                 recommendations
@@ -1149,16 +1416,26 @@ async fn main() -> Result<()> {
             commands::profile(
                 &contract_path,
                 method.as_deref(),
+                iterations,
                 output.as_deref(),
+                format.as_deref(),
                 flamegraph.as_deref(),
                 compare.as_deref(),
                 recommendations,
+                &profiler::RecommendationThresholds::from_limits(
+                    hot_threshold,
+                    slow_threshold_ms,
+                    max_calls,
+                )?,
             )?;
         }
         Commands::Test {
             test_file,
             contract_path,
             junit,
+            report,
+            retries,
+            parallel,
             coverage,
             verbose,
         } => {
@@ -1166,6 +1443,9 @@ async fn main() -> Result<()> {
                 &test_file,
                 contract_path.as_deref(),
                 junit.as_deref(),
+                report.as_deref(),
+                retries,
+                parallel,
                 coverage,
                 verbose,
             )
@@ -1404,6 +1684,23 @@ async fn main() -> Result<()> {
                 log::debug!("Command: webhook retry | delivery_id={}", delivery_id);
                 webhook::retry_delivery(&cli.api_url, &delivery_id).await?;
             }
+            WebhookCommands::Dispatch {
+                webhook_id,
+                contract_id,
+                event,
+                payload,
+            } => {
+                log::debug!(
+                    "Command: webhook dispatch | id={} contract_id={} event={}",
+                    webhook_id,
+                    contract_id,
+                    event
+                );
+                let event: webhook::WebhookEvent = event.parse()?;
+                let payload: serde_json::Value = serde_json::from_str(&payload)
+                    .map_err(|e| anyhow::anyhow!("--payload must be valid JSON: {}", e))?;
+                webhook::dispatch(&cli.api_url, &webhook_id, &contract_id, &event, payload).await?;
+            }
             WebhookCommands::VerifySig { secret, payload, signature } => {
                 log::debug!("Command: webhook verify-sig");
                 webhook::verify_signature_cmd(&secret, &payload, &signature)?;
@@ -1,11 +1,21 @@
 #![allow(dead_code)]
 
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Version of the manifest format `export` writes. Bump it whenever the
+/// format changes and add the step from the previous version to
+/// `upgrade_manifest`.
+///
+/// 1. `schema_version: "1.0"` string
+/// 2. integer `manifest_version` replaces `schema_version`
+pub const MANIFEST_VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportManifest {
-    pub schema_version: String,
+    pub manifest_version: u32,
     pub contract_id: String,
     pub name: String,
     pub network: String,
@@ -44,7 +54,7 @@ pub struct AuditEntry {
 impl ExportManifest {
     pub fn new(contract_id: String, name: String, network: String) -> Self {
         Self {
-            schema_version: "1.0".into(),
+            manifest_version: MANIFEST_VERSION,
             contract_id,
             name,
             network,
@@ -60,6 +70,25 @@ impl ExportManifest {
         }
     }
 
+    /// Parse a manifest written by any CLI whose format this one can read,
+    /// upgrading older versions to `MANIFEST_VERSION`
+    pub fn from_json(mut value: Value) -> Result<Self> {
+        let version = manifest_version(&value)?;
+        if version > MANIFEST_VERSION {
+            bail!(
+                "archive manifest version {} is newer than this CLI supports ({}); \
+                 upgrade soroban-registry to import it",
+                version,
+                MANIFEST_VERSION
+            );
+        }
+        for from in version..MANIFEST_VERSION {
+            upgrade_manifest(&mut value, from)?;
+        }
+        serde_json::from_value(value)
+            .context("invalid archive: manifest.json is not a valid manifest")
+    }
+
     pub fn with_signature(mut self, sig_info: PackageSignatureInfo) -> Self {
         self.signature = Some(sig_info);
         self.audit_trail.push(AuditEntry {
@@ -70,3 +99,82 @@ impl ExportManifest {
         self
     }
 }
+
+/// Format version of a raw manifest; version 1 manifests only carry the
+/// `schema_version` string
+fn manifest_version(value: &Value) -> Result<u32> {
+    if let Some(version) = value.get("manifest_version") {
+        return version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0)
+            .with_context(|| format!("invalid archive: bad manifest_version {}", version));
+    }
+    match value.get("schema_version").and_then(Value::as_str) {
+        Some("1.0") => Ok(1),
+        Some(other) => bail!(
+            "invalid archive: unknown manifest schema_version '{}'",
+            other
+        ),
+        None => bail!("invalid archive: manifest.json has no manifest_version"),
+    }
+}
+
+/// Rewrite a manifest at version `from` into version `from + 1`
+fn upgrade_manifest(value: &mut Value, from: u32) -> Result<()> {
+    let Some(fields) = value.as_object_mut() else {
+        bail!("invalid archive: manifest.json is not an object");
+    };
+    match from {
+        1 => {
+            fields.remove("schema_version");
+        }
+        other => bail!("no upgrade path from manifest version {}", other),
+    }
+    fields.insert("manifest_version".into(), Value::from(from + 1));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn current() -> Value {
+        serde_json::to_value(ExportManifest::new(
+            "CTOKEN".into(),
+            "token".into(),
+            "testnet".into(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn current_manifest_imports() {
+        let manifest = ExportManifest::from_json(current()).unwrap();
+        assert_eq!(manifest.manifest_version, MANIFEST_VERSION);
+        assert_eq!(manifest.contract_id, "CTOKEN");
+    }
+
+    #[test]
+    fn version_one_manifest_is_upgraded() {
+        let mut v1 = current();
+        let fields = v1.as_object_mut().unwrap();
+        fields.remove("manifest_version");
+        fields.insert("schema_version".into(), json!("1.0"));
+
+        let manifest = ExportManifest::from_json(v1).unwrap();
+        assert_eq!(manifest.manifest_version, MANIFEST_VERSION);
+        assert_eq!(manifest.name, "token");
+    }
+
+    #[test]
+    fn newer_manifest_is_rejected() {
+        let mut future = current();
+        future["manifest_version"] = json!(MANIFEST_VERSION + 1);
+
+        let err = ExportManifest::from_json(future).unwrap_err().to_string();
+        assert!(err.contains(&format!("manifest version {}", MANIFEST_VERSION + 1)));
+        assert!(err.contains("upgrade soroban-registry"));
+    }
+}
@@ -3,6 +3,7 @@
 
 use anyhow::{Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 use serde_json::json;
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -85,34 +86,27 @@ pub async fn create_policy(
 // Create a new deployment proposal
 // ─────────────────────────────────────────────────────────────────────────────
 
-pub async fn create_proposal(
-    api_url: &str,
-    contract_name: &str,
-    contract_id: &str,
-    wasm_hash: &str,
-    network: &str,
-    policy_id: &str,
-    proposer: &str,
-    description: Option<&str>,
-) -> Result<()> {
+/// Body of a deployment proposal request
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct NewProposal<'a> {
+    pub contract_name: &'a str,
+    pub contract_id: &'a str,
+    pub wasm_hash: &'a str,
+    pub network: &'a str,
+    pub policy_id: &'a str,
+    pub proposer: &'a str,
+    pub description: Option<&'a str>,
+}
+
+pub async fn create_proposal(api_url: &str, proposal: &NewProposal<'_>) -> Result<()> {
     let client = reqwest::Client::new();
     let url = format!("{}/api/contracts/deploy-proposal", api_url);
 
-    let payload = json!({
-        "contract_name": contract_name,
-        "contract_id": contract_id,
-        "wasm_hash": wasm_hash,
-        "network": network,
-        "policy_id": policy_id,
-        "proposer": proposer,
-        "description": description,
-    });
-
     println!("\n{}", "Creating deployment proposal...".bold().cyan());
 
     let response = client
         .post(&url)
-        .json(&payload)
+        .json(proposal)
         .send()
         .await
         .context("Failed to create deployment proposal")?;
@@ -203,7 +197,16 @@ pub async fn sign_proposal(
         anyhow::bail!("API error ({}): {}", status, err);
     }
 
-    println!("{}", "✓ Signature recorded!".green().bold());
+    if body["already_signed"].as_bool().unwrap_or(false) {
+        println!(
+            "{}",
+            "✓ Already signed — signature was recorded earlier"
+                .green()
+                .bold()
+        );
+    } else {
+        println!("{}", "✓ Signature recorded!".green().bold());
+    }
 
     let collected = body["signatures_collected"].as_i64().unwrap_or(0);
     let needed = body["signatures_needed"].as_i64().unwrap_or(0);
@@ -276,6 +279,64 @@ pub async fn execute_proposal(api_url: &str, proposal_id: &str) -> Result<()> {
     Ok(())
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Cancel a proposal
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn cancel_proposal(
+    api_url: &str,
+    proposal_id: &str,
+    requested_by: &str,
+    reason: Option<&str>,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/contracts/{}/cancel", api_url, proposal_id);
+
+    let payload = json!({
+        "requested_by": requested_by,
+        "reason": reason,
+    });
+
+    println!("\n{}", "Cancelling proposal...".bold().cyan());
+    println!("  Proposal: {}", proposal_id.bright_black());
+
+    let response = client
+        .post(&url)
+        .json(&payload)
+        .send()
+        .await
+        .context("Failed to cancel proposal")?;
+
+    let status = response.status();
+    let body: serde_json::Value = response.json().await?;
+
+    if !status.is_success() {
+        let err = body["message"].as_str().unwrap_or("unknown error");
+        anyhow::bail!("API error ({}): {}", status, err);
+    }
+
+    println!("{}", "✓ Proposal cancelled".green().bold());
+    println!(
+        "  {}: {}",
+        "Cancelled by".bold(),
+        body["cancelled_by"]
+            .as_str()
+            .unwrap_or("?")
+            .bright_magenta()
+    );
+    println!(
+        "  {}: {}",
+        "Cancelled at".bold(),
+        body["cancelled_at"].as_str().unwrap_or("?")
+    );
+    if let Some(reason) = body["reason"].as_str() {
+        println!("  {}: {}", "Reason".bold(), reason);
+    }
+    println!();
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Get proposal info
 // ─────────────────────────────────────────────────────────────────────────────
@@ -308,7 +369,7 @@ pub async fn proposal_info(api_url: &str, proposal_id: &str) -> Result<()> {
     let status_colored = match status {
         "approved" => status.green().bold(),
         "executed" => status.bright_green().bold(),
-        "expired" | "rejected" => status.red().bold(),
+        "expired" | "rejected" | "cancelled" => status.red().bold(),
         _ => status.yellow().bold(),
     };
 
@@ -408,6 +469,7 @@ pub async fn proposal_info(api_url: &str, proposal_id: &str) -> Result<()> {
 pub async fn list_proposals(
     api_url: &str,
     status_filter: Option<&str>,
+    ready_only: bool,
     limit: usize,
 ) -> Result<()> {
     let client = reqwest::Client::new();
@@ -415,6 +477,9 @@ pub async fn list_proposals(
     if let Some(s) = status_filter {
         url.push_str(&format!("&status={}", s));
     }
+    if ready_only {
+        url.push_str("&ready_only=true");
+    }
 
     let response = client
         .get(&url)
@@ -443,7 +508,7 @@ pub async fn list_proposals(
         let status_colored = match status {
             "approved" => status.green(),
             "executed" => status.bright_green(),
-            "expired" | "rejected" => status.red(),
+            "expired" | "rejected" | "cancelled" => status.red(),
             _ => status.yellow(),
         };
 
@@ -463,6 +528,17 @@ pub async fn list_proposals(
             p["contract_id"].as_str().unwrap_or("?").bright_black(),
             p["expires_at"].as_str().unwrap_or("?")
         );
+        println!(
+            "     Signatures: {} collected | Weight: {}/{}{}",
+            p["signatures_collected"].as_i64().unwrap_or(0),
+            p["weight_collected"].as_i64().unwrap_or(0),
+            p["threshold"].as_i64().unwrap_or(0),
+            if p["ready"].as_bool().unwrap_or(false) {
+                format!(" {}", "ready to execute".green().bold())
+            } else {
+                String::new()
+            }
+        );
     }
 
     let total = data["total"].as_i64().unwrap_or(items.len() as i64);
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::patch::Severity;
+
+/// Which notifications a contract's publisher wants to receive. A contract
+/// with no stored preferences receives everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    /// Lowest patch severity delivered; `None` delivers every severity
+    #[serde(default)]
+    pub min_severity: Option<Severity>,
+    /// Webhook event types delivered (e.g. `contract.verified`); `None`
+    /// delivers every event type
+    #[serde(default)]
+    pub events: Option<Vec<String>>,
+}
+
+impl NotificationPreferences {
+    /// Whether a patch of `severity` meets the threshold
+    pub fn allows_severity(&self, severity: Severity) -> bool {
+        self.min_severity
+            .is_none_or(|min| severity.rank() >= min.rank())
+    }
+
+    /// Whether the publisher is subscribed to `event`
+    pub fn allows_event(&self, event: &str) -> bool {
+        self.events
+            .as_ref()
+            .is_none_or(|events| events.iter().any(|e| e == event))
+    }
+
+    /// GET /api/contracts/:id/notification-preferences; a contract without a
+    /// record gets the deliver-everything default
+    pub async fn fetch(api_url: &str, contract_id: &str) -> Result<Self> {
+        let response = reqwest::Client::new()
+            .get(format!(
+                "{}/api/contracts/{}/notification-preferences",
+                api_url, contract_id
+            ))
+            .send()
+            .await
+            .context("Failed to reach registry API")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Self::default());
+        }
+        if !response.status().is_success() {
+            let err = response.text().await?;
+            anyhow::bail!("API error: {}", err);
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_preferences_deliver_everything() {
+        let prefs = NotificationPreferences::default();
+        assert!(prefs.allows_severity(Severity::Low));
+        assert!(prefs.allows_event("version.created"));
+    }
+
+    #[test]
+    fn severity_threshold_suppresses_lower_severities() {
+        let prefs = NotificationPreferences {
+            min_severity: Some(Severity::High),
+            events: None,
+        };
+        assert!(!prefs.allows_severity(Severity::Low));
+        assert!(!prefs.allows_severity(Severity::Medium));
+        assert!(prefs.allows_severity(Severity::High));
+        assert!(prefs.allows_severity(Severity::Critical));
+    }
+
+    #[test]
+    fn unsubscribed_event_types_are_suppressed() {
+        let prefs = NotificationPreferences {
+            min_severity: None,
+            events: Some(vec!["contract.verified".to_string()]),
+        };
+        assert!(prefs.allows_event("contract.verified"));
+        assert!(!prefs.allows_event("version.created"));
+    }
+
+    #[test]
+    fn missing_fields_deserialize_to_all() {
+        let prefs: NotificationPreferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(prefs, NotificationPreferences::default());
+    }
+}
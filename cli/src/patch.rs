@@ -1,12 +1,17 @@
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
+use std::future::Future;
 use std::str::FromStr;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::{NetworkConfig, SemVer, VersionRange};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+use crate::notifications::NotificationPreferences;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Critical,
@@ -15,6 +20,20 @@ pub enum Severity {
     Low,
 }
 
+impl Severity {
+    pub const ALL: [Severity; 4] = [Self::Critical, Self::High, Self::Medium, Self::Low];
+
+    /// Ordering key, higher is more severe
+    pub fn rank(self) -> u8 {
+        match self {
+            Self::Critical => 3,
+            Self::High => 2,
+            Self::Medium => 1,
+            Self::Low => 0,
+        }
+    }
+}
+
 impl FromStr for Severity {
     type Err = anyhow::Error;
 
@@ -43,10 +62,142 @@ impl fmt::Display for Severity {
     }
 }
 
+/// Which component of a semver version a release increments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl FromStr for VersionBump {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            _ => bail!("invalid version bump: {} (expected major|minor|patch)", s),
+        }
+    }
+}
+
+impl VersionBump {
+    pub fn apply(self, version: &SemVer) -> SemVer {
+        match self {
+            Self::Major => SemVer {
+                major: version.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            Self::Minor => SemVer {
+                major: version.major,
+                minor: version.minor + 1,
+                patch: 0,
+            },
+            Self::Patch => SemVer {
+                major: version.major,
+                minor: version.minor,
+                patch: version.patch + 1,
+            },
+        }
+    }
+}
+
+/// How a patch's severity maps to the version bump of the release carrying it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionBumpPolicy {
+    bumps: HashMap<Severity, VersionBump>,
+}
+
+impl Default for VersionBumpPolicy {
+    /// Critical fixes are a major release, high a minor one, the rest patches
+    fn default() -> Self {
+        Self {
+            bumps: HashMap::from([
+                (Severity::Critical, VersionBump::Major),
+                (Severity::High, VersionBump::Minor),
+                (Severity::Medium, VersionBump::Patch),
+                (Severity::Low, VersionBump::Patch),
+            ]),
+        }
+    }
+}
+
+impl VersionBumpPolicy {
+    /// Build a policy, refusing one that leaves any severity unmapped
+    pub fn new(bumps: HashMap<Severity, VersionBump>) -> Result<Self> {
+        let missing: Vec<String> = Severity::ALL
+            .iter()
+            .filter(|severity| !bumps.contains_key(severity))
+            .map(|severity| severity.to_string())
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "version bump policy has no mapping for severity: {}",
+                missing.join(", ")
+            );
+        }
+        Ok(Self { bumps })
+    }
+
+    /// This policy with the given severities remapped
+    pub fn with_overrides(mut self, overrides: &HashMap<Severity, VersionBump>) -> Self {
+        self.bumps.extend(overrides);
+        self
+    }
+
+    /// Parse `severity=bump` pairs, e.g. `high=major,medium=minor`, as
+    /// given in `SOROBAN_REGISTRY_VERSION_BUMPS` or `--version-bumps`
+    pub fn parse_overrides(spec: &str) -> Result<HashMap<Severity, VersionBump>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (severity, bump) = pair
+                    .split_once('=')
+                    .with_context(|| format!("expected severity=bump, got '{}'", pair))?;
+                Ok((severity.trim().parse()?, bump.parse()?))
+            })
+            .collect()
+    }
+
+    pub fn bump_for(&self, severity: Severity) -> VersionBump {
+        // `new` guarantees every severity is mapped
+        self.bumps[&severity]
+    }
+}
+
+/// Computes the version a patch release should carry
+#[derive(Debug, Clone, Default)]
+pub struct VersionManager {
+    policy: VersionBumpPolicy,
+}
+
+impl VersionManager {
+    pub fn new(policy: VersionBumpPolicy) -> Self {
+        Self { policy }
+    }
+
+    pub fn policy(&self) -> &VersionBumpPolicy {
+        &self.policy
+    }
+
+    /// Next version after `current` for a patch of the given severity
+    pub fn next_version(&self, current: &SemVer, severity: Severity) -> SemVer {
+        self.policy.bump_for(severity).apply(current)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityPatch {
     pub id: Uuid,
     pub target_version: String,
+    /// Semver range of vulnerable versions; empty for hash-only patches
+    #[serde(default)]
+    pub vulnerable_range: String,
     pub severity: Severity,
     pub new_wasm_hash: String,
     pub rollout_percentage: u8,
@@ -54,12 +205,475 @@ pub struct SecurityPatch {
     pub created_at: DateTime<Utc>,
 }
 
+/// A patch to publish, as given on the command line
+#[derive(Debug, Clone, Copy)]
+pub struct NewPatch<'a> {
+    /// Target version, version range or WASM hash
+    pub version: &'a str,
+    pub vulnerable_range: &'a str,
+    /// WASM hash of the fixed build
+    pub hash: &'a str,
+    pub severity: Severity,
+    pub rollout: u8,
+}
+
+/// Registry response to a severity escalation
+#[derive(Debug, Clone, Deserialize)]
+pub struct EscalationRecord {
+    pub patch: SecurityPatch,
+    pub previous_severity: Severity,
+    /// Notified contracts that have not acknowledged the patch yet
+    pub pending_notifications: u64,
+}
+
+/// A completed escalation and what it changes downstream
+#[derive(Debug, Clone)]
+pub struct PatchEscalation {
+    pub record: EscalationRecord,
+    /// Version bump a release carrying the patch now needs
+    pub bump: VersionBump,
+    /// Bump the patch called for before it was escalated
+    pub previous_bump: VersionBump,
+    /// Targets re-evaluated at the escalated severity
+    pub distribution: DistributionManager,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatchAudit {
     pub id: Uuid,
     pub contract_id: Uuid,
     pub patch_id: Uuid,
     pub applied_at: DateTime<Utc>,
+    /// The application was reported as failed
+    #[serde(default)]
+    pub failed: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Stage of a staged patch rollout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloutStage {
+    Stage1,
+    Stage2,
+    Stage3,
+    Stage4,
+    Complete,
+}
+
+impl fmt::Display for RolloutStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stage1 => write!(f, "stage 1/4"),
+            Self::Stage2 => write!(f, "stage 2/4"),
+            Self::Stage3 => write!(f, "stage 3/4"),
+            Self::Stage4 => write!(f, "stage 4/4"),
+            Self::Complete => write!(f, "complete"),
+        }
+    }
+}
+
+/// Manual approval required before the rollout may advance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalGate {
+    NotRequired,
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl fmt::Display for ApprovalGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRequired => write!(f, "not required"),
+            Self::Pending => write!(f, "awaiting approval"),
+            Self::Approved => write!(f, "approved"),
+            Self::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// Rollout progress as reported by the registry's rollout engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutProgress {
+    pub patch_id: Uuid,
+    pub stage: RolloutStage,
+    /// Contracts targeted by the current stage
+    pub cohort_size: u64,
+    pub applied: u64,
+    pub failed: u64,
+    /// Fraction of attempted applications that failed (0.0–1.0)
+    pub failure_rate: f64,
+    pub approval_gate: ApprovalGate,
+    /// Overall completion across all stages (0–100)
+    pub progress_percentage: f64,
+}
+
+impl RolloutProgress {
+    /// No further changes are expected without operator action
+    pub fn is_settled(&self) -> bool {
+        self.stage == RolloutStage::Complete || self.approval_gate == ApprovalGate::Rejected
+    }
+}
+
+/// Which registered contracts a patch applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AffectedPredicate {
+    /// Exact vulnerable WASM hash
+    WasmHash(String),
+    /// Versions in `min..=max`
+    VersionRange { min: SemVer, max: SemVer },
+    /// Versions in a patch's vulnerable range, or the vulnerable WASM hash
+    Vulnerable {
+        range: VersionRange,
+        hash: Option<String>,
+    },
+}
+
+impl AffectedPredicate {
+    /// Interpret a patch target: `1.0.0..1.2.0` is an inclusive range, a bare
+    /// semver is a single version, anything else is a WASM hash.
+    pub fn parse_target(target: &str) -> Result<Self> {
+        let target = target.trim();
+        if let Some((min, max)) = target.split_once("..") {
+            let (Some(min), Some(max)) = (SemVer::parse(min.trim()), SemVer::parse(max.trim()))
+            else {
+                bail!("invalid version range: {} (expected X.Y.Z..X.Y.Z)", target);
+            };
+            if min > max {
+                bail!("invalid version range: {} (start is after end)", target);
+            }
+            return Ok(Self::VersionRange { min, max });
+        }
+        if let Some(version) = SemVer::parse(target) {
+            return Ok(Self::VersionRange {
+                min: version.clone(),
+                max: version,
+            });
+        }
+        if target.is_empty() {
+            bail!("patch target must not be empty");
+        }
+        Ok(Self::WasmHash(target.to_string()))
+    }
+
+    pub fn matches(&self, contract: &RegisteredContract) -> bool {
+        match self {
+            Self::WasmHash(hash) => contract.wasm_hash.eq_ignore_ascii_case(hash),
+            Self::VersionRange { min, max } => contract
+                .version
+                .as_deref()
+                .and_then(SemVer::parse)
+                .is_some_and(|v| &v >= min && &v <= max),
+            Self::Vulnerable { range, hash } => {
+                hash.as_ref()
+                    .is_some_and(|hash| contract.wasm_hash.eq_ignore_ascii_case(hash))
+                    || contract
+                        .version
+                        .as_deref()
+                        .and_then(SemVer::parse)
+                        .is_some_and(|v| range.contains(&v))
+            }
+        }
+    }
+}
+
+/// Errors raised by patch preconditions; returned inside `anyhow::Error` so
+/// callers can `downcast_ref` them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecurityPatchError {
+    /// The contract's current version is outside the patch's applicable range
+    VersionOutOfRange {
+        version: SemVer,
+        range: ApplicableRange,
+    },
+    /// The range is bounded but the contract has no published semver version
+    UnknownVersion { contract_id: String },
+    /// The contract's current version is not in the patch's vulnerable range
+    NotVulnerable { version: SemVer, range: String },
+    /// An escalation that would lower the patch's severity
+    SeverityDowngrade {
+        current: Severity,
+        requested: Severity,
+    },
+}
+
+impl fmt::Display for SecurityPatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VersionOutOfRange { version, range } => write!(
+                f,
+                "contract version {} is outside the patch's applicable range {}",
+                version, range
+            ),
+            Self::UnknownVersion { contract_id } => write!(
+                f,
+                "contract {} has no published version to check against the patch's range",
+                contract_id
+            ),
+            Self::NotVulnerable { version, range } => write!(
+                f,
+                "contract version {} is not in the patch's vulnerable range {}",
+                version, range
+            ),
+            Self::SeverityDowngrade { current, requested } => write!(
+                f,
+                "patch severity cannot be lowered (currently {}, requested {})",
+                current, requested
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SecurityPatchError {}
+
+/// Inclusive version bounds a patch may be applied within; `None` is unbounded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplicableRange {
+    pub min: Option<SemVer>,
+    pub max: Option<SemVer>,
+}
+
+impl ApplicableRange {
+    /// Bounds from the contract's network config narrowed by the patch's
+    /// own version target (a hash target adds no version bounds)
+    pub fn for_patch(patch: &SecurityPatch, config: Option<&NetworkConfig>) -> Result<Self> {
+        let parse = |bound: &Option<String>, name: &str| -> Result<Option<SemVer>> {
+            match bound.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+                None => Ok(None),
+                Some(raw) => match SemVer::parse(raw) {
+                    Some(v) => Ok(Some(v)),
+                    None => bail!("invalid {} in network config: {}", name, raw),
+                },
+            }
+        };
+        let mut range = Self {
+            min: parse(&config.and_then(|c| c.min_version.clone()), "min_version")?,
+            max: parse(&config.and_then(|c| c.max_version.clone()), "max_version")?,
+        };
+
+        if let AffectedPredicate::VersionRange { min, max } =
+            AffectedPredicate::parse_target(&patch.target_version)?
+        {
+            range.min = Some(match range.min {
+                Some(current) if current > min => current,
+                _ => min,
+            });
+            range.max = Some(match range.max {
+                Some(current) if current < max => current,
+                _ => max,
+            });
+        }
+        Ok(range)
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn contains(&self, version: &SemVer) -> bool {
+        self.min.as_ref().is_none_or(|min| version >= min)
+            && self.max.as_ref().is_none_or(|max| version <= max)
+    }
+
+    /// Refuse versions outside the range with `VersionOutOfRange`
+    pub fn check(&self, version: &SemVer) -> Result<(), SecurityPatchError> {
+        if self.contains(version) {
+            Ok(())
+        } else {
+            Err(SecurityPatchError::VersionOutOfRange {
+                version: version.clone(),
+                range: self.clone(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for ApplicableRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |v: &Option<SemVer>| v.as_ref().map_or("*".to_string(), |v| v.to_string());
+        write!(f, "[{}, {}]", bound(&self.min), bound(&self.max))
+    }
+}
+
+/// Everything `PatchManager::apply` checks before applying a patch; a dry
+/// run stops after building this
+#[derive(Debug, Clone)]
+pub struct ApplyPreconditions {
+    pub patch: SecurityPatch,
+    pub current_version: Option<SemVer>,
+    pub range: ApplicableRange,
+    pub applied: usize,
+    pub total: usize,
+}
+
+/// A published version of a registered contract
+#[derive(Debug, Clone)]
+pub struct RegisteredContract {
+    pub contract_id: String,
+    pub version: Option<String>,
+    pub wasm_hash: String,
+}
+
+/// Source of registered contract/version data for affected-contract matching
+pub trait ContractLookup {
+    fn registered_contracts(&self) -> impl Future<Output = Result<Vec<RegisteredContract>>> + Send;
+}
+
+/// Looks contracts and their published versions up through the registry API
+pub struct RegistryContractLookup {
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl RegistryContractLookup {
+    pub fn new(api_url: &str) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Published versions of each contract on a page, keyed by registry
+    /// row ID, in one request
+    async fn versions(&self, ids: &[&str]) -> Result<HashMap<String, Vec<serde_json::Value>>> {
+        let resp = self
+            .client
+            .get(format!("{}/api/contracts/versions", self.api_url))
+            .query(&[("ids", ids.join(","))])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            bail!("failed to list contract versions: {}", resp.text().await?);
+        }
+        resp.json().await.context("invalid contract version list")
+    }
+}
+
+impl ContractLookup for RegistryContractLookup {
+    async fn registered_contracts(&self) -> Result<Vec<RegisteredContract>> {
+        let mut registered = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let resp = self
+                .client
+                .get(format!(
+                    "{}/api/contracts?page={}&page_size=100",
+                    self.api_url, page
+                ))
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                bail!("failed to list contracts: {}", resp.text().await?);
+            }
+            let data: serde_json::Value = resp.json().await?;
+            let contracts = data["contracts"].as_array().cloned().unwrap_or_default();
+
+            // One version lookup per page; a failure fails the lookup rather
+            // than silently dropping targets
+            let mut ids = Vec::new();
+            for contract in &contracts {
+                let (Some(id), Some(contract_id)) =
+                    (contract["id"].as_str(), contract["contract_id"].as_str())
+                else {
+                    continue;
+                };
+                ids.push((id, contract_id));
+                registered.push(RegisteredContract {
+                    contract_id: contract_id.to_string(),
+                    version: None,
+                    wasm_hash: contract["wasm_hash"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                });
+            }
+            if !ids.is_empty() {
+                let row_ids: Vec<&str> = ids.iter().map(|(id, _)| *id).collect();
+                let mut versions = self.versions(&row_ids).await?;
+                for (id, contract_id) in ids {
+                    let published = versions.remove(id).unwrap_or_default();
+                    registered.extend(published.iter().map(|v| RegisteredContract {
+                        contract_id: contract_id.to_string(),
+                        version: v["version"].as_str().map(str::to_string),
+                        wasm_hash: v["wasm_hash"].as_str().unwrap_or_default().to_string(),
+                    }));
+                }
+            }
+
+            let pages = data["pages"].as_i64().unwrap_or(1);
+            if contracts.is_empty() || page >= pages {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(registered)
+    }
+}
+
+/// Notification targeting for a patch: the contracts it must reach
+#[derive(Debug, Clone)]
+pub struct DistributionManager {
+    pub patch_id: Uuid,
+    pub severity: Severity,
+    targets: BTreeSet<String>,
+}
+
+impl DistributionManager {
+    pub fn new(patch: &SecurityPatch, targets: BTreeSet<String>) -> Self {
+        Self {
+            patch_id: patch.id,
+            severity: patch.severity,
+            targets,
+        }
+    }
+
+    pub fn targets(&self) -> &BTreeSet<String> {
+        &self.targets
+    }
+
+    pub fn is_targeted(&self, contract_id: &str) -> bool {
+        self.targets.contains(contract_id)
+    }
+
+    /// Targets whose notification preferences accept this patch's severity;
+    /// targets without stored preferences are always notified
+    pub fn recipients(
+        &self,
+        preferences: &HashMap<String, NotificationPreferences>,
+    ) -> BTreeSet<String> {
+        self.targets
+            .iter()
+            .filter(|contract_id| {
+                preferences
+                    .get(*contract_id)
+                    .is_none_or(|prefs| prefs.allows_severity(self.severity))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Load the stored notification preferences of every target
+    pub async fn load_preferences(
+        &self,
+        api_url: &str,
+    ) -> Result<HashMap<String, NotificationPreferences>> {
+        let mut preferences = HashMap::new();
+        for contract_id in &self.targets {
+            let prefs = NotificationPreferences::fetch(api_url, contract_id).await?;
+            preferences.insert(contract_id.clone(), prefs);
+        }
+        Ok(preferences)
+    }
+}
+
+/// Parse a patch's vulnerable range, rejecting malformed or empty ranges
+fn parse_vulnerable_range(range: &str) -> Result<VersionRange> {
+    VersionRange::parse(range).map_err(|e| anyhow::anyhow!("invalid vulnerable range: {}", e))
 }
 
 pub struct PatchManager;
@@ -73,23 +687,78 @@ impl PatchManager {
         applied < max_allowed
     }
 
+    /// Registered contract IDs with a version or WASM hash matching `predicate`
+    pub async fn affected_contracts<L: ContractLookup>(
+        lookup: &L,
+        predicate: &AffectedPredicate,
+    ) -> Result<BTreeSet<String>> {
+        Ok(lookup
+            .registered_contracts()
+            .await?
+            .into_iter()
+            .filter(|contract| predicate.matches(contract))
+            .map(|contract| contract.contract_id)
+            .collect())
+    }
+
+    /// Whether `version` is one the patch fixes; patches without a
+    /// vulnerable range accept every version
+    pub fn check_vulnerable(
+        patch: &SecurityPatch,
+        version: &SemVer,
+    ) -> Result<(), SecurityPatchError> {
+        if patch.vulnerable_range.trim().is_empty() {
+            return Ok(());
+        }
+        match VersionRange::parse(&patch.vulnerable_range) {
+            Ok(range) if range.contains(version) => Ok(()),
+            _ => Err(SecurityPatchError::NotVulnerable {
+                version: version.clone(),
+                range: patch.vulnerable_range.clone(),
+            }),
+        }
+    }
+
+    /// Create a patch and target every registered contract it affects
+    pub async fn create_and_target<L: ContractLookup>(
+        api_url: &str,
+        admin_token: &str,
+        lookup: &L,
+        new: &NewPatch<'_>,
+    ) -> Result<(SecurityPatch, DistributionManager)> {
+        let predicate = AffectedPredicate::Vulnerable {
+            range: parse_vulnerable_range(new.vulnerable_range)?,
+            hash: match AffectedPredicate::parse_target(new.version)? {
+                AffectedPredicate::WasmHash(hash) => Some(hash),
+                _ => None,
+            },
+        };
+        // Resolve the targets first, so a failed lookup leaves no patch behind
+        let affected = Self::affected_contracts(lookup, &predicate).await?;
+        let patch = Self::create(api_url, admin_token, new).await?;
+        Ok((patch.clone(), DistributionManager::new(&patch, affected)))
+    }
+
+    /// Publish a patch; the registry only accepts this with an operator token
     pub async fn create(
         api_url: &str,
-        version: &str,
-        hash: &str,
-        severity: Severity,
-        rollout: u8,
+        admin_token: &str,
+        new: &NewPatch<'_>,
     ) -> Result<SecurityPatch> {
+        parse_vulnerable_range(new.vulnerable_range)?;
+
         let client = reqwest::Client::new();
         let payload = serde_json::json!({
-            "target_version": version,
-            "severity": severity,
-            "new_wasm_hash": hash,
-            "rollout_percentage": rollout,
+            "target_version": new.version,
+            "vulnerable_range": new.vulnerable_range.trim(),
+            "severity": new.severity,
+            "new_wasm_hash": new.hash,
+            "rollout_percentage": new.rollout,
         });
 
         let resp = client
             .post(format!("{}/api/patches", api_url))
+            .bearer_auth(admin_token)
             .json(&payload)
             .send()
             .await?;
@@ -101,6 +770,77 @@ impl PatchManager {
         Ok(resp.json().await?)
     }
 
+    /// Escalation never lowers severity; requesting the current one is a
+    /// no-op on the registry
+    pub fn check_escalation(
+        current: Severity,
+        requested: Severity,
+    ) -> Result<(), SecurityPatchError> {
+        if requested.rank() >= current.rank() {
+            Ok(())
+        } else {
+            Err(SecurityPatchError::SeverityDowngrade { current, requested })
+        }
+    }
+
+    /// Raise a patch's severity. The registry records the change in the
+    /// patch's audit trail; the release bump is recomputed with `versions`
+    /// and the affected contracts are re-targeted at the new severity, so
+    /// notification preferences that suppressed the patch before are
+    /// checked again.
+    pub async fn escalate<L: ContractLookup>(
+        api_url: &str,
+        admin_token: &str,
+        lookup: &L,
+        versions: &VersionManager,
+        patch_id: &str,
+        new_severity: Severity,
+    ) -> Result<PatchEscalation> {
+        let client = reqwest::Client::new();
+
+        let patch_resp = client
+            .get(format!("{}/api/patches/{}", api_url, patch_id))
+            .send()
+            .await?;
+        if !patch_resp.status().is_success() {
+            bail!("patch not found: {}", patch_id);
+        }
+        let patch: SecurityPatch = patch_resp.json().await?;
+        Self::check_escalation(patch.severity, new_severity)?;
+
+        let payload = serde_json::json!({ "severity": new_severity });
+        let resp = client
+            .post(format!("{}/api/patches/{}/escalate", api_url, patch_id))
+            .bearer_auth(admin_token)
+            .json(&payload)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            bail!("failed to escalate patch: {}", resp.text().await?);
+        }
+        let record: EscalationRecord = resp.json().await?;
+
+        let predicate = if record.patch.vulnerable_range.trim().is_empty() {
+            AffectedPredicate::parse_target(&record.patch.target_version)?
+        } else {
+            AffectedPredicate::Vulnerable {
+                range: parse_vulnerable_range(&record.patch.vulnerable_range)?,
+                hash: match AffectedPredicate::parse_target(&record.patch.target_version)? {
+                    AffectedPredicate::WasmHash(hash) => Some(hash),
+                    _ => None,
+                },
+            }
+        };
+        let affected = Self::affected_contracts(lookup, &predicate).await?;
+
+        Ok(PatchEscalation {
+            bump: versions.policy().bump_for(record.patch.severity),
+            previous_bump: versions.policy().bump_for(record.previous_severity),
+            distribution: DistributionManager::new(&record.patch, affected),
+            record,
+        })
+    }
+
     pub async fn find_vulnerable(
         api_url: &str,
         patch_id: &str,
@@ -132,7 +872,31 @@ impl PatchManager {
         Ok((patch, contracts))
     }
 
-    pub async fn apply(api_url: &str, contract_id: &str, patch_id: &str) -> Result<PatchAudit> {
+    pub async fn rollout_status(api_url: &str, patch_id: &str) -> Result<RolloutProgress> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(format!("{}/api/patches/{}/rollout", api_url, patch_id))
+            .send()
+            .await?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            bail!("no rollout found for patch: {}", patch_id);
+        }
+        if !resp.status().is_success() {
+            bail!("failed to fetch rollout status: {}", resp.text().await?);
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    /// Check that `patch_id` may be applied to `contract_id`: the contract's
+    /// current version must be in the patch's applicable range and the
+    /// rollout quota must not be exhausted
+    pub async fn preconditions(
+        api_url: &str,
+        contract_id: &str,
+        patch_id: &str,
+    ) -> Result<ApplyPreconditions> {
         let client = reqwest::Client::new();
 
         let patch_resp = client
@@ -146,6 +910,51 @@ impl PatchManager {
 
         let patch: SecurityPatch = patch_resp.json().await?;
 
+        let contract_resp = client
+            .get(format!("{}/api/contracts/{}", api_url, contract_id))
+            .send()
+            .await?;
+        if !contract_resp.status().is_success() {
+            bail!("contract not found: {}", contract_id);
+        }
+        let contract: serde_json::Value = contract_resp.json().await?;
+        let network_config = contract["network"].as_str().and_then(|network| {
+            serde_json::from_value::<NetworkConfig>(contract["network_configs"][network].clone())
+                .ok()
+        });
+        let range = ApplicableRange::for_patch(&patch, network_config.as_ref())?;
+
+        let versions_resp = client
+            .get(format!(
+                "{}/api/contracts/{}/versions",
+                api_url, contract_id
+            ))
+            .send()
+            .await?;
+        let versions: Vec<serde_json::Value> = if versions_resp.status().is_success() {
+            versions_resp.json().await?
+        } else {
+            Vec::new()
+        };
+        let current_version = versions
+            .iter()
+            .filter_map(|v| v["version"].as_str().and_then(SemVer::parse))
+            .max();
+
+        match &current_version {
+            Some(version) => {
+                range.check(version)?;
+                Self::check_vulnerable(&patch, version)?;
+            }
+            None if range.is_bounded() => {
+                return Err(SecurityPatchError::UnknownVersion {
+                    contract_id: contract_id.to_string(),
+                }
+                .into())
+            }
+            None => {}
+        }
+
         let audits_resp = client
             .get(format!("{}/api/patches/{}/audits", api_url, patch_id))
             .send()
@@ -175,13 +984,36 @@ impl PatchManager {
             );
         }
 
+        Ok(ApplyPreconditions {
+            patch,
+            current_version,
+            range,
+            applied,
+            total,
+        })
+    }
+
+    /// Record the patch as applied to `contract_id`, or as failed with
+    /// `error`. The registry admits new contracts through its rollout gate,
+    /// so this can be refused even when the local preconditions pass.
+    pub async fn apply(
+        api_url: &str,
+        api_key: &str,
+        contract_id: &str,
+        patch_id: &str,
+        error: Option<&str>,
+    ) -> Result<PatchAudit> {
+        Self::preconditions(api_url, contract_id, patch_id).await?;
+
+        let client = reqwest::Client::new();
         let payload = serde_json::json!({
             "contract_id": contract_id,
-            "patch_id": patch_id,
+            "error": error,
         });
 
         let resp = client
             .post(format!("{}/api/patches/{}/apply", api_url, patch_id))
+            .header("X-API-Key", api_key)
             .json(&payload)
             .send()
             .await?;
@@ -219,6 +1051,95 @@ mod tests {
         assert_eq!(Severity::Low.to_string(), "LOW");
     }
 
+    #[test]
+    fn default_bump_policy_keeps_existing_mapping() {
+        let manager = VersionManager::default();
+        let current = semver("1.4.2");
+        assert_eq!(
+            manager.next_version(&current, Severity::Critical),
+            semver("2.0.0")
+        );
+        assert_eq!(
+            manager.next_version(&current, Severity::High),
+            semver("1.5.0")
+        );
+        assert_eq!(
+            manager.next_version(&current, Severity::Medium),
+            semver("1.4.3")
+        );
+        assert_eq!(
+            manager.next_version(&current, Severity::Low),
+            semver("1.4.3")
+        );
+    }
+
+    #[test]
+    fn custom_bump_policy_changes_next_version() {
+        let policy = VersionBumpPolicy::new(HashMap::from([
+            (Severity::Critical, VersionBump::Major),
+            (Severity::High, VersionBump::Major),
+            (Severity::Medium, VersionBump::Minor),
+            (Severity::Low, VersionBump::Patch),
+        ]))
+        .unwrap();
+        let manager = VersionManager::new(policy);
+        let current = semver("1.4.2");
+        assert_eq!(
+            manager.next_version(&current, Severity::High),
+            semver("2.0.0")
+        );
+        assert_eq!(
+            manager.next_version(&current, Severity::Medium),
+            semver("1.5.0")
+        );
+    }
+
+    #[test]
+    fn partial_bump_policy_is_rejected() {
+        let err = VersionBumpPolicy::new(HashMap::from([
+            (Severity::Critical, VersionBump::Major),
+            (Severity::High, VersionBump::Major),
+        ]))
+        .unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("MEDIUM") && msg.contains("LOW"), "{}", msg);
+    }
+
+    #[test]
+    fn bump_overrides_remap_only_the_named_severities() {
+        let overrides = VersionBumpPolicy::parse_overrides(" high=major, Medium = minor ").unwrap();
+        let policy = VersionBumpPolicy::default().with_overrides(&overrides);
+        assert_eq!(policy.bump_for(Severity::High), VersionBump::Major);
+        assert_eq!(policy.bump_for(Severity::Medium), VersionBump::Minor);
+        assert_eq!(policy.bump_for(Severity::Low), VersionBump::Patch);
+
+        assert!(VersionBumpPolicy::parse_overrides("").unwrap().is_empty());
+        assert!(VersionBumpPolicy::parse_overrides("high").is_err());
+        assert!(VersionBumpPolicy::parse_overrides("urgent=major").is_err());
+        assert!(VersionBumpPolicy::parse_overrides("high=huge").is_err());
+    }
+
+    #[test]
+    fn escalation_raises_severity_and_bump() {
+        assert!(PatchManager::check_escalation(Severity::Medium, Severity::Critical).is_ok());
+
+        let policy = VersionBumpPolicy::default();
+        assert_eq!(policy.bump_for(Severity::Medium), VersionBump::Patch);
+        assert_eq!(policy.bump_for(Severity::Critical), VersionBump::Major);
+    }
+
+    #[test]
+    fn escalation_rejects_downgrade() {
+        assert_eq!(
+            PatchManager::check_escalation(Severity::Critical, Severity::Medium),
+            Err(SecurityPatchError::SeverityDowngrade {
+                current: Severity::Critical,
+                requested: Severity::Medium,
+            })
+        );
+        assert!(PatchManager::check_escalation(Severity::High, Severity::High).is_ok());
+    }
+
     #[test]
     fn rollout_zero_percent() {
         assert!(!PatchManager::check_rollout(0, 100, 0));
@@ -250,6 +1171,446 @@ mod tests {
         assert!(!PatchManager::check_rollout(0, 0, 100));
     }
 
+    struct StaticLookup(Vec<RegisteredContract>);
+
+    impl ContractLookup for StaticLookup {
+        async fn registered_contracts(&self) -> Result<Vec<RegisteredContract>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn patch_targeting(target: &str) -> SecurityPatch {
+        SecurityPatch {
+            id: Uuid::new_v4(),
+            target_version: target.to_string(),
+            vulnerable_range: String::new(),
+            severity: Severity::High,
+            new_wasm_hash: "ff".to_string(),
+            rollout_percentage: 100,
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn network_config(min: Option<&str>, max: Option<&str>) -> NetworkConfig {
+        NetworkConfig {
+            contract_id: "CA".to_string(),
+            is_verified: true,
+            min_version: min.map(str::to_string),
+            max_version: max.map(str::to_string),
+        }
+    }
+
+    fn semver(v: &str) -> SemVer {
+        SemVer::parse(v).unwrap()
+    }
+
+    #[test]
+    fn version_gate_accepts_in_range_target() {
+        let config = network_config(Some("1.0.0"), Some("2.0.0"));
+        let range =
+            ApplicableRange::for_patch(&patch_targeting("1.1.0..1.5.0"), Some(&config)).unwrap();
+
+        assert_eq!(range.min, Some(semver("1.1.0")));
+        assert_eq!(range.max, Some(semver("1.5.0")));
+        assert!(range.check(&semver("1.1.0")).is_ok());
+        assert!(range.check(&semver("1.5.0")).is_ok());
+    }
+
+    #[test]
+    fn version_gate_rejects_below_range_target() {
+        let config = network_config(Some("1.2.0"), None);
+        let range = ApplicableRange::for_patch(&patch_targeting("aa"), Some(&config)).unwrap();
+
+        let err = range.check(&semver("1.1.9")).unwrap_err();
+        assert!(matches!(err, SecurityPatchError::VersionOutOfRange { .. }));
+        assert_eq!(
+            err.to_string(),
+            "contract version 1.1.9 is outside the patch's applicable range [1.2.0, *]"
+        );
+    }
+
+    #[test]
+    fn version_gate_rejects_above_range_target() {
+        let config = network_config(None, Some("3.0.0"));
+        let range =
+            ApplicableRange::for_patch(&patch_targeting("1.0.0..2.0.0"), Some(&config)).unwrap();
+
+        // The patch's own range is tighter than the network config here
+        assert_eq!(range.max, Some(semver("2.0.0")));
+        let err = anyhow::Error::from(range.check(&semver("2.1.0")).unwrap_err());
+        assert!(matches!(
+            err.downcast_ref::<SecurityPatchError>(),
+            Some(SecurityPatchError::VersionOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn hash_target_without_config_is_unbounded() {
+        let range = ApplicableRange::for_patch(&patch_targeting("aa"), None).unwrap();
+        assert!(!range.is_bounded());
+        assert!(range.check(&semver("99.0.0")).is_ok());
+    }
+
+    fn registered(id: &str, version: &str, hash: &str) -> RegisteredContract {
+        RegisteredContract {
+            contract_id: id.to_string(),
+            version: Some(version.to_string()),
+            wasm_hash: hash.to_string(),
+        }
+    }
+
+    fn lookup() -> StaticLookup {
+        StaticLookup(vec![
+            registered("CA", "1.0.0", "aa"),
+            registered("CA", "1.3.0", "ab"),
+            registered("CB", "1.1.5", "bb"),
+            registered("CC", "2.0.0", "cc"),
+            registered("CD", "0.9.0", "dd"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn version_range_matches_subset() {
+        let predicate = AffectedPredicate::parse_target("1.0.0..1.2.0").unwrap();
+        let affected = PatchManager::affected_contracts(&lookup(), &predicate)
+            .await
+            .unwrap();
+        assert_eq!(
+            affected.into_iter().collect::<Vec<_>>(),
+            vec!["CA".to_string(), "CB".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn wasm_hash_matches_exactly() {
+        let predicate = AffectedPredicate::parse_target("CC").unwrap();
+        assert_eq!(predicate, AffectedPredicate::WasmHash("CC".to_string()));
+        let affected = PatchManager::affected_contracts(&lookup(), &predicate)
+            .await
+            .unwrap();
+        assert_eq!(affected.len(), 1);
+        assert!(affected.contains("CC"));
+    }
+
+    #[test]
+    fn vulnerable_range_checks_inside_boundary_and_outside() {
+        let patch = SecurityPatch {
+            vulnerable_range: ">=1.0.0, <1.2.0".to_string(),
+            ..patch_targeting("aa")
+        };
+        // Inside, and at the inclusive lower bound
+        assert!(PatchManager::check_vulnerable(&patch, &semver("1.1.4")).is_ok());
+        assert!(PatchManager::check_vulnerable(&patch, &semver("1.0.0")).is_ok());
+        // At the exclusive upper bound, and outside
+        for version in ["1.2.0", "0.9.9", "2.0.0"] {
+            let err = PatchManager::check_vulnerable(&patch, &semver(version)).unwrap_err();
+            assert!(matches!(err, SecurityPatchError::NotVulnerable { .. }));
+        }
+        assert_eq!(
+            PatchManager::check_vulnerable(&patch, &semver("1.2.0"))
+                .unwrap_err()
+                .to_string(),
+            "contract version 1.2.0 is not in the patch's vulnerable range >=1.0.0, <1.2.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn vulnerable_range_selects_affected_contracts() {
+        let predicate = AffectedPredicate::Vulnerable {
+            range: VersionRange::parse("^1.0.0, <1.3.0").unwrap(),
+            hash: Some("DD".to_string()),
+        };
+        let affected = PatchManager::affected_contracts(&lookup(), &predicate)
+            .await
+            .unwrap();
+        // CA 1.0.0 is in range (its 1.3.0 sits on the excluded upper bound);
+        // CD matches by hash
+        assert_eq!(
+            affected.into_iter().collect::<Vec<_>>(),
+            vec!["CA".to_string(), "CB".to_string(), "CD".to_string()]
+        );
+        assert!(parse_vulnerable_range("1.2.0..1.0.0").is_err());
+        assert!(parse_vulnerable_range("").is_err());
+    }
+
+    struct UnavailableLookup;
+
+    impl ContractLookup for UnavailableLookup {
+        async fn registered_contracts(&self) -> Result<Vec<RegisteredContract>> {
+            bail!("registry unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_target_lookup_creates_no_patch() {
+        // Nothing listens on this address, so reaching the create request
+        // would fail with a connection error instead
+        let err = PatchManager::create_and_target(
+            "http://127.0.0.1:9",
+            "token",
+            &UnavailableLookup,
+            &NewPatch {
+                version: "1.2.0",
+                vulnerable_range: ">=1.0.0, <1.2.0",
+                hash: "ff",
+                severity: Severity::High,
+                rollout: 100,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "registry unavailable");
+    }
+
+    /// Serves `body` for requests whose path starts with each prefix and
+    /// records every request target it was sent
+    async fn stub_registry(
+        routes: Vec<(String, serde_json::Value)>,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let target = request.split(' ').nth(1).unwrap_or_default().to_string();
+                let body = routes
+                    .iter()
+                    .find(|(prefix, _)| target.starts_with(prefix))
+                    .map(|(_, body)| body.to_string())
+                    .unwrap_or_else(|| "{}".to_string());
+                log.lock().unwrap().push(target);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, seen)
+    }
+
+    #[tokio::test]
+    async fn registry_lookup_fetches_a_page_of_versions_in_one_request() {
+        let (url, seen) = stub_registry(vec![
+            (
+                "/api/contracts/versions".to_string(),
+                serde_json::json!({
+                    "r1": [{"version": "1.0.0", "wasm_hash": "aa"}],
+                    "r2": [
+                        {"version": "1.1.0", "wasm_hash": "bb"},
+                        {"version": "1.0.0", "wasm_hash": "ba"}
+                    ]
+                }),
+            ),
+            (
+                "/api/contracts?".to_string(),
+                serde_json::json!({
+                    "contracts": [
+                        {"id": "r1", "contract_id": "CA", "wasm_hash": "aa"},
+                        {"id": "r2", "contract_id": "CB", "wasm_hash": "bb"},
+                        {"id": "r3", "contract_id": "CC", "wasm_hash": "cc"}
+                    ],
+                    "pages": 1
+                }),
+            ),
+        ])
+        .await;
+
+        let registered = RegistryContractLookup::new(&url)
+            .registered_contracts()
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen.len(), 2, "requests: {:?}", seen);
+        assert_eq!(seen[1], "/api/contracts/versions?ids=r1%2Cr2%2Cr3");
+        let versions: Vec<_> = registered
+            .iter()
+            .filter_map(|c| Some((c.contract_id.as_str(), c.version.as_deref()?)))
+            .collect();
+        assert_eq!(
+            versions,
+            vec![("CA", "1.0.0"), ("CB", "1.1.0"), ("CB", "1.0.0")]
+        );
+        assert_eq!(registered.iter().filter(|c| c.version.is_none()).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn escalate_reads_the_patch_then_posts_the_new_severity() {
+        let id = Uuid::new_v4();
+        let patch = |severity: &str| {
+            serde_json::json!({
+                "id": id,
+                "target_version": "1.0.0..1.2.0",
+                "vulnerable_range": ">=1.0.0, <1.2.0",
+                "severity": severity,
+                "new_wasm_hash": "ff",
+                "rollout_percentage": 100,
+                "description": null,
+                "publisher_id": null,
+                "created_at": "2026-01-01T00:00:00Z"
+            })
+        };
+        let escalate_path = format!("/api/patches/{}/escalate", id);
+        let patch_path = format!("/api/patches/{}", id);
+        let (url, seen) = stub_registry(vec![
+            (
+                escalate_path.clone(),
+                serde_json::json!({
+                    "patch": patch("critical"),
+                    "previous_severity": "medium",
+                    "audit": null,
+                    "pending_notifications": 2
+                }),
+            ),
+            (patch_path.clone(), patch("medium")),
+            (
+                "/api/contracts?".to_string(),
+                serde_json::json!({ "contracts": [], "pages": 1 }),
+            ),
+        ])
+        .await;
+
+        let escalation = PatchManager::escalate(
+            &url,
+            "token",
+            &StaticLookup(vec![]),
+            &VersionManager::default(),
+            &id.to_string(),
+            Severity::Critical,
+        )
+        .await
+        .unwrap();
+        assert_eq!(escalation.record.previous_severity, Severity::Medium);
+        assert_eq!(escalation.record.patch.severity, Severity::Critical);
+        assert_eq!(escalation.record.pending_notifications, 2);
+        assert_eq!(escalation.previous_bump, VersionBump::Patch);
+        assert_eq!(escalation.bump, VersionBump::Major);
+        assert_eq!(
+            seen.lock().unwrap().clone(),
+            vec![patch_path, escalate_path]
+        );
+
+        // Lowering is refused before anything is posted
+        let err = PatchManager::escalate(
+            &url,
+            "token",
+            &StaticLookup(vec![]),
+            &VersionManager::default(),
+            &id.to_string(),
+            Severity::Low,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot be lowered"), "{}", err);
+        assert_eq!(seen.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn invalid_range_rejected() {
+        assert!(AffectedPredicate::parse_target("2.0.0..1.0.0").is_err());
+        assert!(AffectedPredicate::parse_target("1.0.0..latest").is_err());
+    }
+
+    #[test]
+    fn distribution_targets_affected_contracts() {
+        let patch = SecurityPatch {
+            id: Uuid::new_v4(),
+            target_version: "1.0.0..1.2.0".to_string(),
+            vulnerable_range: ">=1.0.0, <=1.2.0".to_string(),
+            severity: Severity::High,
+            new_wasm_hash: "ff".to_string(),
+            rollout_percentage: 100,
+            description: None,
+            created_at: Utc::now(),
+        };
+        let dist = DistributionManager::new(&patch, BTreeSet::from(["CA".to_string()]));
+        assert!(dist.is_targeted("CA"));
+        assert!(!dist.is_targeted("CC"));
+        assert_eq!(dist.patch_id, patch.id);
+    }
+
+    #[test]
+    fn low_patch_is_suppressed_below_high_threshold() {
+        let patch = SecurityPatch {
+            id: Uuid::new_v4(),
+            target_version: "1.0.0".to_string(),
+            vulnerable_range: "=1.0.0".to_string(),
+            severity: Severity::Low,
+            new_wasm_hash: "ff".to_string(),
+            rollout_percentage: 100,
+            description: None,
+            created_at: Utc::now(),
+        };
+        let dist =
+            DistributionManager::new(&patch, BTreeSet::from(["CA".to_string(), "CB".to_string()]));
+        let preferences = HashMap::from([(
+            "CA".to_string(),
+            NotificationPreferences {
+                min_severity: Some(Severity::High),
+                events: None,
+            },
+        )]);
+
+        // CA opted out of anything below High; CB has no preferences
+        let recipients = dist.recipients(&preferences);
+        assert_eq!(recipients, BTreeSet::from(["CB".to_string()]));
+        assert!(dist.is_targeted("CA"));
+    }
+
+    #[test]
+    fn rollout_progress_parses_registry_payload() {
+        let progress: RolloutProgress = serde_json::from_value(serde_json::json!({
+            "patch_id": Uuid::nil(),
+            "stage": "stage2",
+            "cohort_size": 40,
+            "applied": 12,
+            "failed": 1,
+            "failure_rate": 0.0769,
+            "approval_gate": "pending",
+            "progress_percentage": 35.0
+        }))
+        .unwrap();
+
+        assert_eq!(progress.stage, RolloutStage::Stage2);
+        assert_eq!(progress.stage.to_string(), "stage 2/4");
+        assert_eq!(progress.approval_gate, ApprovalGate::Pending);
+        assert!(!progress.is_settled());
+    }
+
+    #[test]
+    fn patch_audit_parses_failed_application() {
+        let audit: PatchAudit = serde_json::from_value(serde_json::json!({
+            "id": Uuid::nil(),
+            "contract_id": Uuid::nil(),
+            "patch_id": Uuid::nil(),
+            "applied_at": "2026-03-27T00:00:00Z",
+            "failed": true,
+            "error": "out of gas"
+        }))
+        .unwrap();
+        assert!(audit.failed);
+        assert_eq!(audit.error.as_deref(), Some("out of gas"));
+    }
+
     #[test]
     fn rollout_one_contract() {
         assert!(PatchManager::check_rollout(0, 1, 1));
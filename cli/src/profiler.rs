@@ -1,10 +1,14 @@
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +75,12 @@ pub struct Profiler {
     overhead_total: Duration,
 }
 
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Profiler {
     pub fn new() -> Self {
         Self {
@@ -90,10 +100,11 @@ impl Profiler {
 
         self.call_stack.push((name.to_string(), Instant::now()));
 
-        if let Some(parent) = self.call_stack.get(self.call_stack.len().saturating_sub(2)) {
+        let depth = self.call_stack.len();
+        if let Some(parent) = depth.checked_sub(2).map(|idx| &self.call_stack[idx]) {
             self.call_graph
                 .entry(parent.0.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(name.to_string());
         }
     }
@@ -106,7 +117,7 @@ impl Profiler {
         self.call_stack.pop();
         self.function_stats
             .entry(name.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(duration);
     }
 
@@ -124,11 +135,10 @@ impl Profiler {
             .map(|(name, durations)| {
                 let total: Duration = durations.iter().sum();
                 let count = durations.len() as u64;
-                let avg = if count > 0 {
-                    Duration::from_nanos(total.as_nanos() as u64 / count)
-                } else {
-                    Duration::ZERO
-                };
+                let avg = (total.as_nanos() as u64)
+                    .checked_div(count)
+                    .map(Duration::from_nanos)
+                    .unwrap_or(Duration::ZERO);
                 let min = durations.iter().min().copied().unwrap_or(Duration::ZERO);
                 let max = durations.iter().max().copied().unwrap_or(Duration::ZERO);
                 let children = self.call_graph.get(&name).cloned().unwrap_or_default();
@@ -160,51 +170,37 @@ impl Profiler {
     }
 }
 
-pub fn profile_contract(contract_path: &str, method: Option<&str>) -> Result<ProfileData> {
+/// Profile `iterations` rounds of invocations of every function in the
+/// contract, or only of `method` when given. Repeated invocations of a
+/// function, across rounds or because it is defined more than once,
+/// aggregate into one `FunctionProfile` with their call count, total, average,
+/// min and max time. A `method` the contract does not define yields a profile
+/// with no functions.
+pub fn profile_contract(
+    contract_path: &str,
+    method: Option<&str>,
+    iterations: u32,
+) -> Result<ProfileData> {
     let path = Path::new(contract_path);
     let functions = parse_contract_functions(path)?;
+    let selected: Vec<&String> = functions
+        .iter()
+        .filter(|func| method.is_none_or(|m| *func == m))
+        .collect();
 
-    let start = Instant::now();
-    let mut function_profiles = HashMap::new();
-
-    for func in &functions {
-        if let Some(m) = method {
-            if func != m {
-                continue;
-            }
+    let mut profiler = Profiler::new();
+    for _ in 0..iterations.max(1) {
+        for func in &selected {
+            profiler.enter_function(func);
+            let func_start = Instant::now();
+            // Simulate function execution
+            let mut dummy_profiler = Profiler::new();
+            simulate_execution(path, Some(func), &mut dummy_profiler)?;
+            profiler.exit_function(func, func_start.elapsed());
         }
-
-        let func_start = Instant::now();
-        // Simulate function execution
-        let mut dummy_profiler = Profiler::new();
-        let _ = simulate_execution(path, Some(func), &mut dummy_profiler)?;
-        let func_duration = func_start.elapsed();
-
-        function_profiles.insert(
-            func.clone(),
-            FunctionProfile {
-                name: func.clone(),
-                total_time: func_duration,
-                call_count: 1,
-                avg_time: func_duration,
-                min_time: func_duration,
-                max_time: func_duration,
-                children: vec![],
-            },
-        );
     }
 
-    let total_duration = start.elapsed();
-
-    Ok(ProfileData {
-        contract_path: contract_path.to_string(),
-        method: method.map(|s| s.to_string()),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        total_duration,
-        functions: function_profiles,
-        call_stack: vec![],
-        overhead_percent: 0.0,
-    })
+    Ok(profiler.finish(contract_path.to_string(), method.map(|s| s.to_string())))
 }
 
 pub fn load_baseline(baseline_path: &str) -> Result<ProfileData> {
@@ -220,7 +216,7 @@ pub fn parse_contract_functions(contract_path: &Path) -> Result<Vec<String>> {
     let mut functions = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
 
-    for (i, line) in lines.iter().enumerate() {
+    for line in &lines {
         if line.trim().starts_with("pub fn ") || line.trim().starts_with("fn ") {
             if let Some(name_start) = line.find("fn ") {
                 let after_fn = &line[name_start + 3..];
@@ -299,9 +295,9 @@ pub fn generate_flame_graph(profile: &ProfileData, output_path: &Path) -> Result
     let width = 1200.0;
 
     let mut sorted_functions: Vec<_> = profile.functions.values().collect();
-    sorted_functions.sort_by(|a, b| b.total_time.cmp(&a.total_time));
+    sorted_functions.sort_by_key(|f| std::cmp::Reverse(f.total_time));
 
-    for (idx, func) in sorted_functions.iter().take(30).enumerate() {
+    for func in sorted_functions.iter().take(30) {
         let time_ratio = func.total_time.as_nanos() as f64 / max_time;
         let bar_width = width * time_ratio.min(1.0);
 
@@ -342,6 +338,161 @@ pub fn generate_flame_graph(profile: &ProfileData, output_path: &Path) -> Result
     Ok(())
 }
 
+/// Machine-readable formats `profile --output` can write
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// The full `ProfileData`
+    #[default]
+    Json,
+    /// One row of per-method metrics per profiled function
+    Csv,
+    /// Gzipped pprof protobuf, readable by `go tool pprof`
+    Pprof,
+}
+
+impl FromStr for ProfileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "pprof" => Ok(Self::Pprof),
+            other => bail!(
+                "Unknown profile format '{}' (expected json, csv or pprof)",
+                other
+            ),
+        }
+    }
+}
+
+/// Functions ordered by total time, slowest first, then by name
+pub fn functions_by_time(profile: &ProfileData) -> Vec<&FunctionProfile> {
+    let mut functions: Vec<_> = profile.functions.values().collect();
+    functions.sort_by(|a, b| {
+        b.total_time
+            .cmp(&a.total_time)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    functions
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render_csv(profile: &ProfileData) -> String {
+    let mut out =
+        String::from("function,call_count,total_time_ns,avg_time_ns,min_time_ns,max_time_ns\n");
+    for func in functions_by_time(profile) {
+        out.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&func.name),
+            func.call_count,
+            func.total_time.as_nanos(),
+            func.avg_time.as_nanos(),
+            func.min_time.as_nanos(),
+            func.max_time.as_nanos()
+        ));
+    }
+    out
+}
+
+/// Protobuf wire-format writer for the handful of pprof fields we emit
+#[derive(Default)]
+struct ProtoWriter(Vec<u8>);
+
+impl ProtoWriter {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn uint(&mut self, field: u32, value: u64) {
+        self.varint(u64::from(field) << 3);
+        self.varint(value);
+    }
+
+    fn bytes(&mut self, field: u32, bytes: &[u8]) {
+        self.varint((u64::from(field) << 3) | 2);
+        self.varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn message(&mut self, field: u32, build: impl FnOnce(&mut ProtoWriter)) {
+        let mut inner = ProtoWriter::default();
+        build(&mut inner);
+        self.bytes(field, &inner.0);
+    }
+}
+
+/// Encode `profile` as a gzipped pprof `Profile`: one sample per function
+/// carrying its call count and total time
+pub fn encode_pprof(profile: &ProfileData) -> Result<Vec<u8>> {
+    // string_table[0] must be the empty string
+    let mut strings: Vec<&str> = vec!["", "calls", "count", "time", "nanoseconds"];
+    let functions = functions_by_time(profile);
+    strings.extend(functions.iter().map(|f| f.name.as_str()));
+    let name_index = |idx: usize| (5 + idx) as u64;
+
+    let mut out = ProtoWriter::default();
+    for (kind, unit) in [(1, 2), (3, 4)] {
+        out.message(1, |vt| {
+            vt.uint(1, kind);
+            vt.uint(2, unit);
+        });
+    }
+    for (idx, func) in functions.iter().enumerate() {
+        let id = idx as u64 + 1;
+        out.message(2, |sample| {
+            sample.uint(1, id);
+            sample.uint(2, func.call_count);
+            sample.uint(2, func.total_time.as_nanos() as u64);
+        });
+        out.message(4, |location| {
+            location.uint(1, id);
+            location.message(4, |line| line.uint(1, id));
+        });
+        out.message(5, |function| {
+            function.uint(1, id);
+            function.uint(2, name_index(idx));
+            function.uint(3, name_index(idx));
+        });
+    }
+    for string in &strings {
+        out.bytes(6, string.as_bytes());
+    }
+    out.uint(10, profile.total_duration.as_nanos() as u64);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&out.0)?;
+    Ok(encoder.finish()?)
+}
+
+/// Write `profile` to `output_path` in `format`
+pub fn write_profile(
+    profile: &ProfileData,
+    format: ProfileFormat,
+    output_path: &Path,
+) -> Result<()> {
+    let bytes = match format {
+        ProfileFormat::Json => {
+            serde_json::to_vec_pretty(profile).context("Failed to serialize profile data")?
+        }
+        ProfileFormat::Csv => render_csv(profile).into_bytes(),
+        ProfileFormat::Pprof => encode_pprof(profile)?,
+    };
+    fs::write(output_path, bytes)
+        .with_context(|| format!("Failed to write profile output: {}", output_path.display()))
+}
+
 pub fn compare_profiles(profile1: &ProfileData, profile2: &ProfileData) -> Vec<ComparisonResult> {
     let mut results = Vec::new();
 
@@ -404,7 +555,7 @@ pub fn compare_profiles(profile1: &ProfileData, profile2: &ProfileData) -> Vec<C
         }
     }
 
-    results.sort_by(|a, b| b.time_diff_ns.abs().cmp(&a.time_diff_ns.abs()));
+    results.sort_by_key(|r| std::cmp::Reverse(r.time_diff_ns.abs()));
     results
 }
 
@@ -420,68 +571,115 @@ pub struct ComparisonResult {
     pub current_time: Duration,
 }
 
-pub fn generate_recommendations(profile: &ProfileData) -> Vec<String> {
-    let mut recommendations = Vec::new();
+/// Limits past which a method gets a recommendation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecommendationThresholds {
+    /// Share of the total runtime (0.0–1.0) one method may take
+    pub runtime_share: f64,
+    /// Average time per call
+    pub avg_time: Duration,
+    /// Calls to one method in a single profile
+    pub call_count: u64,
+    /// `(max - min) / avg` spread of a method's call times
+    pub variance_ratio: f64,
+    /// Profiler overhead as a percentage of the total runtime
+    pub overhead_percent: f64,
+}
 
-    let hot_functions: Vec<_> = profile
-        .functions
-        .values()
-        .filter(|f| f.total_time.as_nanos() as f64 > profile.total_duration.as_nanos() as f64 * 0.1)
-        .collect();
+impl Default for RecommendationThresholds {
+    fn default() -> Self {
+        Self {
+            runtime_share: 0.10,
+            avg_time: Duration::from_millis(1),
+            call_count: 1000,
+            variance_ratio: 2.0,
+            overhead_percent: 5.0,
+        }
+    }
+}
 
-    if !hot_functions.is_empty() {
-        recommendations.push(format!(
-            "Optimize hot functions: {}",
-            hot_functions
-                .iter()
-                .map(|f| f.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        ));
+impl RecommendationThresholds {
+    /// Thresholds from the `profile` command's flags: a percentage of the
+    /// runtime, milliseconds per call and a call count
+    pub fn from_limits(runtime_percent: f64, avg_ms: f64, call_count: u64) -> Result<Self> {
+        if !(runtime_percent > 0.0 && runtime_percent <= 100.0) {
+            bail!(
+                "--hot-threshold must be a percentage between 0 and 100, got {}",
+                runtime_percent
+            );
+        }
+        let avg_time = Duration::try_from_secs_f64(avg_ms / 1000.0).map_err(|_| {
+            anyhow::anyhow!(
+                "--slow-threshold-ms must be a non-negative number of milliseconds, got {}",
+                avg_ms
+            )
+        })?;
+        Ok(Self {
+            runtime_share: runtime_percent / 100.0,
+            avg_time,
+            call_count,
+            ..Self::default()
+        })
     }
+}
 
-    let high_call_count: Vec<_> = profile
-        .functions
-        .values()
-        .filter(|f| f.call_count > 1000)
-        .collect();
+pub fn generate_recommendations(profile: &ProfileData) -> Vec<String> {
+    recommend(profile, &RecommendationThresholds::default())
+}
 
-    if !high_call_count.is_empty() {
-        recommendations.push(format!(
-            "Consider caching for frequently called functions: {}",
-            high_call_count
-                .iter()
-                .map(|f| f.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        ));
-    }
+/// One recommendation per method and threshold it exceeds, slowest
+/// methods first
+pub fn recommend(profile: &ProfileData, thresholds: &RecommendationThresholds) -> Vec<String> {
+    let mut recommendations = Vec::new();
+    let total_ns = profile.total_duration.as_nanos() as f64;
 
-    let high_variance: Vec<_> = profile
-        .functions
-        .values()
-        .filter(|f| {
-            let variance = (f.max_time.as_nanos() as f64 - f.min_time.as_nanos() as f64)
-                / f.avg_time.as_nanos().max(1) as f64;
-            variance > 2.0
-        })
-        .collect();
+    for func in functions_by_time(profile) {
+        let share = if total_ns > 0.0 {
+            func.total_time.as_nanos() as f64 / total_ns
+        } else {
+            0.0
+        };
+        if share > thresholds.runtime_share {
+            recommendations.push(format!(
+                "{} dominates runtime ({:.1}% of {:.2}ms) — consider caching its results or moving work out of it",
+                func.name,
+                share * 100.0,
+                total_ns / 1_000_000.0
+            ));
+        }
 
-    if !high_variance.is_empty() {
-        recommendations.push(format!(
-            "Investigate high variance in execution time: {}",
-            high_variance
-                .iter()
-                .map(|f| f.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        ));
+        if func.avg_time > thresholds.avg_time {
+            recommendations.push(format!(
+                "{} averages {:.3}ms per call (limit {:.3}ms) — reduce storage reads and loop work in it",
+                func.name,
+                func.avg_time.as_secs_f64() * 1000.0,
+                thresholds.avg_time.as_secs_f64() * 1000.0
+            ));
+        }
+
+        if func.call_count > thresholds.call_count {
+            recommendations.push(format!(
+                "{} is called {} times (limit {}) — consider caching or batching these calls",
+                func.name, func.call_count, thresholds.call_count
+            ));
+        }
+
+        let variance = (func.max_time.as_nanos() as f64 - func.min_time.as_nanos() as f64)
+            / func.avg_time.as_nanos().max(1) as f64;
+        if variance > thresholds.variance_ratio {
+            recommendations.push(format!(
+                "{} varies widely in execution time ({:.3}ms to {:.3}ms) — check for input-dependent loops",
+                func.name,
+                func.min_time.as_secs_f64() * 1000.0,
+                func.max_time.as_secs_f64() * 1000.0
+            ));
+        }
     }
 
-    if profile.overhead_percent > 5.0 {
+    if profile.overhead_percent > thresholds.overhead_percent {
         recommendations.push(format!(
-            "Profiling overhead ({:.2}%) exceeds 5% threshold. Consider reducing instrumentation.",
-            profile.overhead_percent
+            "Profiling overhead ({:.2}%) exceeds {}% threshold. Consider reducing instrumentation.",
+            profile.overhead_percent, thresholds.overhead_percent
         ));
     }
 
@@ -491,3 +689,135 @@ pub fn generate_recommendations(profile: &ProfileData) -> Vec<String> {
 
     recommendations
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, calls: u64, total_ms: u64) -> FunctionProfile {
+        let total = Duration::from_millis(total_ms);
+        FunctionProfile {
+            name: name.to_string(),
+            total_time: total,
+            call_count: calls,
+            avg_time: total / calls as u32,
+            min_time: total / calls as u32,
+            max_time: total / calls as u32,
+            children: Vec::new(),
+        }
+    }
+
+    fn profile() -> ProfileData {
+        let functions = [
+            function("transfer", 2, 4),
+            function("balance", 5, 1),
+            function("mint, batch", 1, 2),
+        ];
+        ProfileData {
+            contract_path: "token.rs".to_string(),
+            method: None,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            total_duration: Duration::from_millis(10),
+            functions: functions.into_iter().map(|f| (f.name.clone(), f)).collect(),
+            call_stack: Vec::new(),
+            overhead_percent: 0.0,
+        }
+    }
+
+    #[test]
+    fn repeated_invocations_aggregate_per_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let contract = dir.path().join("token.rs");
+        fs::write(
+            &contract,
+            "pub fn transfer(from: Address) {}\npub fn balance(id: Address) -> i128 { 0 }\n",
+        )
+        .unwrap();
+        let contract = contract.to_string_lossy().into_owned();
+
+        let only_transfer = profile_contract(&contract, Some("transfer"), 3).unwrap();
+        assert_eq!(only_transfer.functions.len(), 1);
+        let transfer = &only_transfer.functions["transfer"];
+        assert_eq!(transfer.call_count, 3);
+        assert_eq!(transfer.avg_time, transfer.total_time / 3);
+        assert!(transfer.min_time <= transfer.avg_time && transfer.avg_time <= transfer.max_time);
+        // Top-level invocations are not their own children
+        assert!(transfer.children.is_empty());
+
+        let all = profile_contract(&contract, None, 2).unwrap();
+        assert_eq!(all.functions["transfer"].call_count, 2);
+        assert_eq!(all.functions["balance"].call_count, 2);
+
+        assert!(profile_contract(&contract, Some("burn"), 1)
+            .unwrap()
+            .functions
+            .is_empty());
+    }
+
+    #[test]
+    fn slow_method_gets_a_recommendation_and_fast_one_does_not() {
+        let mut profile = profile();
+        profile
+            .functions
+            .insert("settle".to_string(), function("settle", 1, 40));
+        profile.total_duration = Duration::from_millis(50);
+        let thresholds = RecommendationThresholds {
+            runtime_share: 0.5,
+            avg_time: Duration::from_millis(5),
+            ..RecommendationThresholds::default()
+        };
+
+        let recommendations = recommend(&profile, &thresholds);
+        assert_eq!(
+            recommendations,
+            [
+                "settle dominates runtime (80.0% of 50.00ms) — consider caching its results or moving work out of it",
+                "settle averages 40.000ms per call (limit 5.000ms) — reduce storage reads and loop work in it",
+            ]
+        );
+        assert!(!recommendations.iter().any(|r| r.contains("balance")));
+
+        // Raising the limits silences it
+        let lenient = RecommendationThresholds {
+            runtime_share: 0.9,
+            avg_time: Duration::from_millis(50),
+            ..thresholds
+        };
+        assert_eq!(
+            recommend(&profile, &lenient),
+            ["No optimization recommendations at this time."]
+        );
+    }
+
+    #[test]
+    fn csv_has_a_header_and_one_row_per_method() {
+        let csv = render_csv(&profile());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "function,call_count,total_time_ns,avg_time_ns,min_time_ns,max_time_ns",
+                "transfer,2,4000000,2000000,2000000,2000000",
+                "\"mint, batch\",1,2000000,2000000,2000000,2000000",
+                "balance,5,1000000,200000,200000,200000",
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_are_parsed_and_written() {
+        assert_eq!("CSV".parse::<ProfileFormat>().unwrap(), ProfileFormat::Csv);
+        assert!("xml".parse::<ProfileFormat>().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let pprof = dir.path().join("profile.pb.gz");
+        write_profile(&profile(), ProfileFormat::Pprof, &pprof).unwrap();
+        // gzip magic
+        assert_eq!(&fs::read(&pprof).unwrap()[..2], [0x1f, 0x8b]);
+
+        let json = dir.path().join("profile.json");
+        write_profile(&profile(), ProfileFormat::Json, &json).unwrap();
+        let parsed: ProfileData = serde_json::from_slice(&fs::read(&json).unwrap()).unwrap();
+        assert_eq!(parsed.functions.len(), 3);
+    }
+}
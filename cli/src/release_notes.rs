@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// How a function changed between two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FunctionChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Why a function change breaks callers, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakingKind {
+    Removed,
+    /// A parameter's type changed
+    SignatureChanged,
+    /// The number of parameters changed
+    ParamAdded,
+    ReturnChanged,
+    None,
+}
+
+impl BreakingKind {
+    /// Classify one `/api/contracts/breaking-changes` entry
+    fn from_report(severity: &str, category: &str) -> Self {
+        if severity != "breaking" {
+            return Self::None;
+        }
+        match category {
+            "function_removed" => Self::Removed,
+            "function_params_changed" => Self::ParamAdded,
+            "return_type_changed" => Self::ReturnChanged,
+            _ => Self::SignatureChanged,
+        }
+    }
+
+    pub fn is_breaking(self) -> bool {
+        self != Self::None
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Removed => "removed",
+            Self::SignatureChanged => "parameter type changed",
+            Self::ParamAdded => "parameter count changed",
+            Self::ReturnChanged => "return type changed",
+            Self::None => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionChange {
+    pub name: String,
+    pub kind: FunctionChangeKind,
+    /// The most severe reason the change breaks callers
+    pub breaking_kind: BreakingKind,
+    /// Same as `breaking_kind.is_breaking()`
+    pub breaking: bool,
+    /// Messages from the registry's ABI diff, one per detected change
+    pub details: Vec<String>,
+}
+
+/// Function-level API surface changes between two versions of a contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub contract_id: String,
+    pub from: String,
+    pub to: String,
+    pub breaking: bool,
+    pub functions: Vec<FunctionChange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportChange {
+    severity: String,
+    category: String,
+    message: String,
+    function: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BreakingChangeReport {
+    changes: Vec<ReportChange>,
+}
+
+impl DiffSummary {
+    /// Group a `/api/contracts/breaking-changes` report by function; type-only
+    /// changes are left out
+    pub fn from_report(
+        contract_id: &str,
+        from: &str,
+        to: &str,
+        report: serde_json::Value,
+    ) -> Result<Self> {
+        let report: BreakingChangeReport =
+            serde_json::from_value(report).context("Unexpected breaking-changes response")?;
+
+        let mut functions: BTreeMap<String, FunctionChange> = BTreeMap::new();
+        for change in report.changes {
+            let Some(name) = change.function else {
+                continue;
+            };
+            let kind = match change.category.as_str() {
+                "function_added" => FunctionChangeKind::Added,
+                "function_removed" => FunctionChangeKind::Removed,
+                _ => FunctionChangeKind::Modified,
+            };
+            let entry = functions
+                .entry(name.clone())
+                .or_insert_with(|| FunctionChange {
+                    name,
+                    kind,
+                    breaking_kind: BreakingKind::None,
+                    breaking: false,
+                    details: Vec::new(),
+                });
+            if kind != FunctionChangeKind::Modified {
+                entry.kind = kind;
+            }
+            entry.breaking_kind = entry.breaking_kind.min(BreakingKind::from_report(
+                &change.severity,
+                &change.category,
+            ));
+            entry.breaking = entry.breaking_kind.is_breaking();
+            entry.details.push(change.message);
+        }
+
+        let functions: Vec<FunctionChange> = functions.into_values().collect();
+        Ok(Self {
+            contract_id: contract_id.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            breaking: functions.iter().any(|f| f.breaking),
+            functions,
+        })
+    }
+}
+
+/// Fetch the function-level diff between `from` and `to` of `contract_id`
+pub async fn fetch_diff_summary(
+    api_url: &str,
+    contract_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<DiffSummary> {
+    let url = format!("{}/api/contracts/breaking-changes", api_url);
+    log::debug!("GET {}", url);
+
+    let resp = reqwest::Client::new()
+        .get(&url)
+        .query(&[
+            ("old_id", format!("{}@{}", contract_id, from)),
+            ("new_id", format!("{}@{}", contract_id, to)),
+        ])
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        bail!("Failed to diff {} → {} ({}): {}", from, to, status, body);
+    }
+
+    let report: serde_json::Value = resp
+        .json()
+        .await
+        .context("Failed to parse breaking-changes response")?;
+    DiffSummary::from_report(contract_id, from, to, report)
+}
+
+/// The `--diff-only` text output: one line per changed function
+pub fn render_diff_only(summary: &DiffSummary) -> String {
+    let mut out = format!(
+        "{} {} → {}\n",
+        summary.contract_id.bold(),
+        summary.from,
+        summary.to
+    );
+    if summary.functions.is_empty() {
+        out.push_str("  No function-level changes\n");
+        return out;
+    }
+
+    for function in &summary.functions {
+        let (sign, kind) = match function.kind {
+            FunctionChangeKind::Added => ("+".green(), "added"),
+            FunctionChangeKind::Removed => ("-".red(), "removed"),
+            FunctionChangeKind::Modified => ("~".yellow(), "modified"),
+        };
+        let marker = if function.breaking_kind.is_breaking() {
+            let label = if function.kind == FunctionChangeKind::Removed {
+                "[BREAKING]".to_string()
+            } else {
+                format!("[BREAKING: {}]", function.breaking_kind.describe())
+            };
+            format!(" {}", label.red().bold())
+        } else {
+            String::new()
+        };
+        out.push_str(&format!(
+            "  {} {} ({}){}\n",
+            sign,
+            function.name.bold(),
+            kind,
+            marker
+        ));
+        if function.kind == FunctionChangeKind::Modified {
+            for detail in &function.details {
+                out.push_str(&format!("      {}\n", detail.dimmed()));
+            }
+        }
+    }
+
+    let breaking = summary.functions.iter().filter(|f| f.breaking).count();
+    out.push_str(&format!(
+        "\n  {} function(s) changed, {} breaking\n",
+        summary.functions.len(),
+        breaking
+    ));
+    out
+}
+
+/// `release-notes` command. Only the `--diff-only` view is available from
+/// the CLI so far.
+pub async fn release_notes(
+    api_url: &str,
+    contract_id: &str,
+    from: &str,
+    to: &str,
+    diff_only: bool,
+    format: &str,
+) -> Result<()> {
+    if !diff_only {
+        bail!("Full release notes are not available from the CLI yet; pass --diff-only");
+    }
+    let summary = fetch_diff_summary(api_url, contract_id, from, to).await?;
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&summary)?),
+        "text" => print!("\n{}", render_diff_only(&summary)),
+        other => bail!("Unknown format '{}' (expected text or json)", other),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summary() -> DiffSummary {
+        let report = json!({
+            "old_id": "CA@1.0.0",
+            "new_id": "CA@2.0.0",
+            "breaking": true,
+            "breaking_count": 2,
+            "non_breaking_count": 2,
+            "changes": [
+                {
+                    "severity": "breaking",
+                    "category": "function_removed",
+                    "message": "Function 'burn' was removed",
+                    "function": "burn"
+                },
+                {
+                    "severity": "non_breaking",
+                    "category": "function_added",
+                    "message": "Function 'mint_batch' was added",
+                    "function": "mint_batch"
+                },
+                {
+                    "severity": "breaking",
+                    "category": "param_type_changed",
+                    "message": "Parameter 'amount' of 'transfer' changed type",
+                    "function": "transfer"
+                },
+                {
+                    "severity": "non_breaking",
+                    "category": "type_added",
+                    "message": "Type 'Allowance' was added",
+                    "type_name": "Allowance"
+                }
+            ]
+        });
+        DiffSummary::from_report("CA", "1.0.0", "2.0.0", report).unwrap()
+    }
+
+    #[test]
+    fn removed_function_is_marked_breaking() {
+        colored::control::set_override(false);
+        let summary = summary();
+        assert!(summary.breaking);
+        // Type-only changes are not function-level
+        assert_eq!(summary.functions.len(), 3);
+
+        let output = render_diff_only(&summary);
+        assert!(output.contains("- burn (removed) [BREAKING]"));
+        assert!(output.contains("+ mint_batch (added)\n"));
+        assert!(output.contains("~ transfer (modified) [BREAKING: parameter type changed]"));
+        assert!(output.contains("Parameter 'amount' of 'transfer' changed type"));
+        assert!(!output.contains("Allowance"));
+        assert!(output.contains("3 function(s) changed, 2 breaking"));
+    }
+
+    #[test]
+    fn json_output_is_the_raw_summary() {
+        let value = serde_json::to_value(summary()).unwrap();
+        assert_eq!(value["from"], "1.0.0");
+        assert_eq!(value["functions"][0]["name"], "burn");
+        assert_eq!(value["functions"][0]["kind"], "removed");
+        assert_eq!(value["functions"][0]["breaking"], true);
+    }
+
+    #[test]
+    fn breaking_changes_are_classified() {
+        let report = json!({
+            "changes": [
+                {
+                    "severity": "breaking",
+                    "category": "function_removed",
+                    "message": "Function 'burn' was removed",
+                    "function": "burn"
+                },
+                {
+                    "severity": "non_breaking",
+                    "category": "param_name_changed",
+                    "message": "Function 'approve' param name changed from 'who' to 'spender'",
+                    "function": "approve"
+                },
+                {
+                    "severity": "breaking",
+                    "category": "function_params_changed",
+                    "message": "Function 'approve' parameter count changed from 2 to 3",
+                    "function": "approve"
+                },
+                {
+                    "severity": "breaking",
+                    "category": "return_type_changed",
+                    "message": "Function 'balance' return type changed from 'i128' to 'u64'",
+                    "function": "balance"
+                },
+                {
+                    "severity": "non_breaking",
+                    "category": "function_added",
+                    "message": "Function 'mint' was added",
+                    "function": "mint"
+                }
+            ]
+        });
+        let summary = DiffSummary::from_report("CA", "1.0.0", "2.0.0", report).unwrap();
+        let kinds: Vec<_> = summary
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.breaking_kind, f.breaking))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("approve", BreakingKind::ParamAdded, true),
+                ("balance", BreakingKind::ReturnChanged, true),
+                ("burn", BreakingKind::Removed, true),
+                ("mint", BreakingKind::None, false),
+            ]
+        );
+
+        colored::control::set_override(false);
+        let output = render_diff_only(&summary);
+        assert!(output.contains("~ approve (modified) [BREAKING: parameter count changed]"));
+        assert!(output.contains("~ balance (modified) [BREAKING: return type changed]"));
+    }
+}
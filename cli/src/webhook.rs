@@ -8,6 +8,8 @@ use sha2::Sha256;
 use std::time::Duration;
 use uuid::Uuid;
 
+use crate::notifications::NotificationPreferences;
+
 type HmacSha256 = Hmac<Sha256>;
 
 // ── Event types ───────────────────────────────────────────────────────────────
@@ -202,8 +204,77 @@ pub async fn deliver_with_retry(
     })
 }
 
+/// Deliver a contract event unless the contract's notification preferences
+/// unsubscribe from it; `None` means the delivery was suppressed.
+pub async fn dispatch_event(
+    webhook: &WebhookSubscription,
+    preferences: &NotificationPreferences,
+    event: &WebhookEvent,
+    payload: serde_json::Value,
+) -> Result<Option<WebhookDelivery>> {
+    let event = event.to_string();
+    if !preferences.allows_event(&event) {
+        return Ok(None);
+    }
+    deliver_with_retry(webhook, &event, payload).await.map(Some)
+}
+
 // ── API helpers ───────────────────────────────────────────────────────────────
 
+/// Look up a webhook subscription by ID.
+pub async fn get_webhook(api_url: &str, webhook_id: &str) -> Result<WebhookSubscription> {
+    let response = reqwest::Client::new()
+        .get(format!("{}/api/webhooks/{}", api_url, webhook_id))
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        let err = response.text().await?;
+        anyhow::bail!("API error: {}", err);
+    }
+    Ok(response.json().await?)
+}
+
+/// Deliver `event` about `contract_id` to a webhook, unless the contract's
+/// notification preferences unsubscribe from it.
+pub async fn dispatch(
+    api_url: &str,
+    webhook_id: &str,
+    contract_id: &str,
+    event: &WebhookEvent,
+    payload: serde_json::Value,
+) -> Result<()> {
+    let webhook = get_webhook(api_url, webhook_id).await?;
+    let preferences = NotificationPreferences::fetch(api_url, contract_id).await?;
+
+    match dispatch_event(&webhook, &preferences, event, payload).await? {
+        None => println!(
+            "{} {} suppressed by the notification preferences of {}.",
+            "○".yellow(),
+            event.to_string().bold(),
+            contract_id.bright_black()
+        ),
+        Some(delivery) if delivery.status == DeliveryStatus::Delivered => println!(
+            "{} {} delivered to webhook {} (attempt {}).",
+            "✓".green(),
+            event.to_string().bold(),
+            webhook_id.bright_black(),
+            delivery.attempt
+        ),
+        Some(delivery) => {
+            anyhow::bail!(
+                "Delivery to webhook {} failed after {} attempts: {}",
+                webhook_id,
+                delivery.attempt,
+                delivery.error.unwrap_or_default()
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Create a new webhook subscription.
 pub async fn create_webhook(
     api_url: &str,
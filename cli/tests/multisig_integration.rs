@@ -40,6 +40,7 @@ fn test_multisig_help() {
     assert!(stdout.contains("create-proposal"));
     assert!(stdout.contains("sign"));
     assert!(stdout.contains("execute"));
+    assert!(stdout.contains("cancel"));
     assert!(stdout.contains("list-proposals"));
 }
 
@@ -0,0 +1,13 @@
+-- Compile-cache lookups for verification re-checks
+--
+-- source_hash identifies the (source, compiler_version, build_params) input so a
+-- previously verified artifact can be reused. Every check is recorded as its own
+-- row; from_cache marks rows that were satisfied without a rebuild.
+
+ALTER TABLE verifications
+    ADD COLUMN source_hash TEXT,
+    ADD COLUMN from_cache BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_verifications_contract_source_hash
+    ON verifications(contract_id, source_hash)
+    WHERE status = 'verified';
@@ -0,0 +1,15 @@
+-- Store the requesting client's IP as a first-class audit log column instead of
+-- embedding it in new_value, so it no longer pollutes diffs and can be queried.
+
+ALTER TABLE contract_audit_log
+    ADD COLUMN ip_address VARCHAR(45);
+
+-- Backfill rows that carried the address inside the JSON payload. new_value is
+-- left untouched because it is covered by the audit hash chain.
+UPDATE contract_audit_log
+   SET ip_address = new_value->>'_ip_address'
+ WHERE new_value ? '_ip_address';
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_ip_address
+    ON contract_audit_log(ip_address)
+    WHERE ip_address IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Publisher rate-limit tiers
+--
+-- Authenticated writes from a publisher are limited according to its tier;
+-- anonymous requests keep the global limits.
+ALTER TABLE publishers
+    ADD COLUMN IF NOT EXISTS rate_limit_tier VARCHAR(32) NOT NULL DEFAULT 'default';
+
+ALTER TABLE publishers
+    ADD CONSTRAINT publishers_rate_limit_tier_check
+    CHECK (rate_limit_tier IN ('default', 'verified_partner'));
@@ -0,0 +1,30 @@
+-- Bulk re-verification after a compiler (soroban-sdk) upgrade
+--
+-- One row per job plus one result row per contract that was verified when the
+-- job was enqueued. Result rows with a NULL outcome are still pending, which
+-- lets an interrupted job resume where it left off.
+CREATE TABLE IF NOT EXISTS reverification_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    compiler_version VARCHAR(32) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- At most one job may run at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reverification_jobs_single_running
+    ON reverification_jobs ((status)) WHERE status = 'running';
+
+CREATE TABLE IF NOT EXISTS reverification_results (
+    job_id UUID NOT NULL REFERENCES reverification_jobs(id) ON DELETE CASCADE,
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    outcome VARCHAR(20)
+        CHECK (outcome IN ('still_verified', 'downgraded', 'skipped', 'errored')),
+    error_message TEXT,
+    processed_at TIMESTAMPTZ,
+    PRIMARY KEY (job_id, contract_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reverification_results_pending
+    ON reverification_results(job_id) WHERE outcome IS NULL;
@@ -0,0 +1,11 @@
+-- Draft contract versions: staged rows that stay hidden from public listings
+-- until promoted via POST /api/contracts/:id/versions/:version/publish
+
+CREATE TYPE version_status AS ENUM ('draft', 'published');
+
+ALTER TABLE contract_versions
+    ADD COLUMN status version_status NOT NULL DEFAULT 'published';
+
+CREATE INDEX idx_contract_versions_published
+    ON contract_versions(contract_id)
+    WHERE status = 'published';
@@ -0,0 +1,13 @@
+-- One dependency edge per (contract, resolved dependency contract). The
+-- existing UNIQUE(contract_id, dependency_name) does not stop two different
+-- names that resolve to the same contract from double-counting an edge.
+
+DELETE FROM contract_dependencies d
+USING contract_dependencies keep
+WHERE d.contract_id = keep.contract_id
+  AND d.dependency_contract_id = keep.dependency_contract_id
+  AND (d.created_at, d.id) < (keep.created_at, keep.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contract_dependencies_unique_target
+    ON contract_dependencies(contract_id, dependency_contract_id)
+    WHERE dependency_contract_id IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Interactions are recorded as analytics events so they can be pushed to
+-- GET /api/contracts/:id/events/stream subscribers
+ALTER TYPE analytics_event_type ADD VALUE IF NOT EXISTS 'interaction_recorded';
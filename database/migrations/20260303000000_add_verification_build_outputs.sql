@@ -0,0 +1,10 @@
+-- Build outputs recorded per verification run
+--
+-- built_wasm_hash is the SHA-256 of the artifact the run compiled, and
+-- resolved_dependencies maps each crate in the build's lockfile to the version
+-- it resolved to. Comparing two runs on these shows why a build stopped
+-- reproducing.
+
+ALTER TABLE verifications
+    ADD COLUMN built_wasm_hash TEXT,
+    ADD COLUMN resolved_dependencies JSONB;
@@ -0,0 +1,24 @@
+-- Per-method deprecation with a sunset date
+--
+-- Calls to a method after its sunset are still recorded, but flagged with
+-- contract_interactions.deprecated_call and counted as a
+-- 'deprecated_method_called' analytics event.
+
+CREATE TABLE contract_method_deprecations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    method VARCHAR(255) NOT NULL,
+    sunset_at TIMESTAMPTZ NOT NULL,
+    replacement_method VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(contract_id, method)
+);
+
+ALTER TABLE contract_interactions
+    ADD COLUMN deprecated_call BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_contract_interactions_deprecated_calls
+    ON contract_interactions(contract_id, method)
+    WHERE deprecated_call;
+
+ALTER TYPE analytics_event_type ADD VALUE IF NOT EXISTS 'deprecated_method_called';
@@ -0,0 +1,24 @@
+-- Operator-maintained list of contract IDs refused at publish and interaction
+-- time. Contract IDs are stored normalized (trimmed, uppercase).
+--
+-- Every block and unblock is recorded in blocked_contract_audit, which keeps
+-- its history after an entry is removed.
+
+CREATE TABLE blocked_contracts (
+    contract_id VARCHAR(56) PRIMARY KEY,
+    reason TEXT NOT NULL,
+    blocked_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE blocked_contract_audit (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id VARCHAR(56) NOT NULL,
+    action VARCHAR(16) NOT NULL CHECK (action IN ('blocked', 'unblocked')),
+    reason TEXT,
+    actor VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_blocked_contract_audit_contract
+    ON blocked_contract_audit(contract_id, created_at DESC);
@@ -0,0 +1,15 @@
+-- Machine-readable outcome of a verification build, alongside the
+-- human-readable error_message. NULL while the verification is pending.
+
+CREATE TYPE verification_reason_code AS ENUM (
+    'HASH_MATCH',
+    'HASH_MISMATCH',
+    'COMPILE_ERROR',
+    'TIMEOUT',
+    'TOOLCHAIN_MISSING',
+    'INVALID_INPUT',
+    'INTERNAL_ERROR'
+);
+
+ALTER TABLE verifications
+    ADD COLUMN reason_code verification_reason_code;
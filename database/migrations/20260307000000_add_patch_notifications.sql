@@ -0,0 +1,18 @@
+-- Security patch notices delivered to affected contracts, and the owner's
+-- acknowledgement of each. A contract can only acknowledge a patch it was
+-- notified about.
+
+CREATE TABLE patch_notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    patch_id UUID NOT NULL REFERENCES security_patches(id) ON DELETE CASCADE,
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    notified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    acknowledged_at TIMESTAMPTZ,
+    acknowledged_by VARCHAR(255),
+    -- Base64 Ed25519 signature by one of the publisher's signing keys, when
+    -- the owner proved ownership
+    ack_signature TEXT,
+    UNIQUE(patch_id, contract_id)
+);
+
+CREATE INDEX idx_patch_notifications_patch_id ON patch_notifications(patch_id);
@@ -0,0 +1,8 @@
+-- Source lineage for precompiled (wasm_base64) verifications
+--
+-- The submitted bytes are hashed as-is; the git URL and commit they were
+-- built from are recorded for provenance only and are not built.
+
+ALTER TABLE verifications
+    ADD COLUMN source_git_url TEXT,
+    ADD COLUMN source_commit VARCHAR(64);
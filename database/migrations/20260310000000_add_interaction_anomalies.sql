@@ -0,0 +1,18 @@
+-- Hours in which a contract's interactions spiked far above its trailing
+-- daily average. At most one row per contract and hour.
+
+ALTER TYPE analytics_event_type ADD VALUE IF NOT EXISTS 'interaction_anomaly';
+
+CREATE TABLE interaction_anomalies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    window_start TIMESTAMPTZ NOT NULL,
+    observed_count BIGINT NOT NULL,
+    baseline_hourly DOUBLE PRECISION NOT NULL,
+    multiplier DOUBLE PRECISION NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, window_start)
+);
+
+CREATE INDEX idx_interaction_anomalies_contract
+    ON interaction_anomalies(contract_id, window_start DESC);
@@ -0,0 +1,16 @@
+-- Semver range of the versions a security patch fixes, e.g. '>=1.0.0, <1.2.0'.
+-- Existing patches get the range their version target already implied;
+-- patches that target a WASM hash keep an empty range.
+
+ALTER TABLE security_patches
+    ADD COLUMN vulnerable_range VARCHAR(255) NOT NULL DEFAULT '';
+
+UPDATE security_patches
+SET vulnerable_range = CASE
+    WHEN target_version ~ '^\s*\d+\.\d+\.\d+\s*\.\.\s*\d+\.\d+\.\d+\s*$'
+        THEN '>=' || trim(split_part(target_version, '..', 1))
+             || ', <=' || trim(split_part(target_version, '..', 2))
+    WHEN target_version ~ '^\s*\d+\.\d+\.\d+\s*$'
+        THEN '=' || trim(target_version)
+    ELSE ''
+END;
@@ -0,0 +1,8 @@
+-- Build settings a verification actually ran with (profile, features,
+-- optimize flag, timeout, toolchain, SDK version), defaults applied.
+-- Stored next to the caller-supplied build_params so a run can be
+-- reproduced even after the verifier's defaults change. NULL for runs that
+-- never built and for runs recorded before this column existed.
+
+ALTER TABLE verifications
+    ADD COLUMN effective_build_params JSONB;
@@ -0,0 +1,9 @@
+-- Receivers of a patch notice confirm processing it asynchronously by
+-- posting a signed delivery receipt. Every notice starts out delivered;
+-- a valid receipt moves it to confirmed.
+
+CREATE TYPE patch_delivery_status AS ENUM ('delivered', 'confirmed');
+
+ALTER TABLE patch_notifications
+    ADD COLUMN delivery_status patch_delivery_status NOT NULL DEFAULT 'delivered',
+    ADD COLUMN confirmed_at TIMESTAMPTZ;
@@ -0,0 +1,16 @@
+-- soroban-sdk / compiler version a contract was first verified with. Later
+-- verifications supplying a different compiler_version are warned about or,
+-- with VERIFY_SDK_MISMATCH=reject, refused.
+
+ALTER TABLE contracts
+    ADD COLUMN verified_sdk_version VARCHAR(100);
+
+UPDATE contracts c
+SET verified_sdk_version = first_verified.compiler_version
+FROM (
+    SELECT DISTINCT ON (contract_id) contract_id, compiler_version
+    FROM verifications
+    WHERE status = 'verified' AND compiler_version IS NOT NULL
+    ORDER BY contract_id, verified_at ASC NULLS LAST
+) first_verified
+WHERE c.id = first_verified.contract_id;
@@ -0,0 +1,7 @@
+-- Publisher that issued a security patch. Patches created before this
+-- column existed have no recorded publisher and stay NULL.
+
+ALTER TABLE security_patches
+    ADD COLUMN publisher_id UUID REFERENCES publishers(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_security_patches_publisher_id ON security_patches(publisher_id);
@@ -0,0 +1,8 @@
+-- Let proposers withdraw multisig deployment proposals before execution
+ALTER TYPE proposal_status ADD VALUE IF NOT EXISTS 'cancelled';
+
+ALTER TABLE deploy_proposals
+    ADD COLUMN cancelled_at  TIMESTAMPTZ,
+    -- Stellar address of the proposer or policy creator who cancelled it
+    ADD COLUMN cancelled_by  VARCHAR(56),
+    ADD COLUMN cancel_reason TEXT;
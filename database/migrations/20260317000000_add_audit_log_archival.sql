@@ -0,0 +1,40 @@
+-- Archive old contract_audit_log entries and optionally prune them from the
+-- hot table without breaking the hash chain.
+
+-- Chain head of each contract's archived prefix. Verification of the rows
+-- still in contract_audit_log starts from last_hash instead of NULL.
+CREATE TABLE IF NOT EXISTS audit_log_archive_checkpoints (
+    contract_id       UUID PRIMARY KEY,
+    -- Timestamp of the newest archived entry
+    archived_through  TIMESTAMPTZ NOT NULL,
+    -- hash of the newest archived entry
+    last_hash         VARCHAR(64),
+    archived_count    BIGINT NOT NULL DEFAULT 0,
+    -- Archive file the newest entries were written to
+    archive_file      TEXT NOT NULL,
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Snapshots of pruned entries keep pointing at them; the entry lives on in
+-- the archive.
+ALTER TABLE contract_snapshots
+    DROP CONSTRAINT IF EXISTS contract_snapshots_audit_log_id_fkey;
+
+-- The log stays append-only, except that the retention job may delete
+-- entries it has archived, and only inside a transaction that sets
+-- audit_log.pruning.
+CREATE OR REPLACE FUNCTION enforce_append_only_audit_log()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE'
+       AND current_setting('audit_log.pruning', true) = 'on'
+       AND EXISTS (
+           SELECT 1 FROM audit_log_archive_checkpoints c
+            WHERE c.contract_id = OLD.contract_id
+              AND OLD.timestamp <= c.archived_through
+       ) THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'Updates and deletions are strictly prohibited on contract_audit_log to ensure immutability.';
+END;
+$$ LANGUAGE plpgsql;
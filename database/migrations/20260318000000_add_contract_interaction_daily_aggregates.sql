@@ -0,0 +1,17 @@
+-- Per-contract daily rollup of contract_interactions.
+--
+-- Maintained hourly by the aggregation task for the last two days; older days
+-- can be recomputed from the raw rows with
+-- POST /api/admin/contracts/:id/aggregates/rebuild.
+
+CREATE TABLE IF NOT EXISTS contract_interaction_daily_aggregates (
+    contract_id        UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    date               DATE NOT NULL,
+    interaction_count  BIGINT NOT NULL DEFAULT 0,
+    unique_users       BIGINT NOT NULL DEFAULT 0,
+    updated_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (contract_id, date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_interaction_daily_aggregates_date
+    ON contract_interaction_daily_aggregates(date);
@@ -0,0 +1,16 @@
+-- Audit trail of changes made to a security patch after it was published
+CREATE TYPE patch_audit_action AS ENUM ('severity_escalated');
+
+CREATE TABLE security_patch_audit_log (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    patch_id    UUID NOT NULL REFERENCES security_patches(id) ON DELETE CASCADE,
+    action      patch_audit_action NOT NULL,
+    old_value   JSONB,
+    new_value   JSONB,
+    changed_by  VARCHAR(255) NOT NULL,
+    reason      TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_security_patch_audit_log_patch_id
+    ON security_patch_audit_log(patch_id, created_at);
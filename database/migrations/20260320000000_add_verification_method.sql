@@ -0,0 +1,7 @@
+-- How a verification established its result: by building the submitted
+-- source, or by hashing a WASM artifact built elsewhere (e.g. in CI)
+
+CREATE TYPE verification_method AS ENUM ('build', 'artifact');
+
+ALTER TABLE verifications
+    ADD COLUMN method verification_method NOT NULL DEFAULT 'build';
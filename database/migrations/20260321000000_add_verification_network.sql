@@ -0,0 +1,5 @@
+-- Which network a verification applies to. A contract row may carry several
+-- networks in network_configs; NULL (older rows) means the row's own network.
+
+ALTER TABLE verifications
+    ADD COLUMN network network_type;
@@ -0,0 +1,15 @@
+-- Audit trail of privacy erasures of contract interactions. The erased
+-- account is kept only as a SHA-256 hash and the erased rows are not copied.
+
+CREATE TABLE interaction_erasure_audit (
+    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id          UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    account_hash         VARCHAR(64) NOT NULL,
+    interactions_erased  BIGINT NOT NULL,
+    days_corrected       INTEGER NOT NULL,
+    erased_by            VARCHAR(255) NOT NULL,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_interaction_erasure_audit_contract_id
+    ON interaction_erasure_audit(contract_id, created_at);
@@ -0,0 +1,8 @@
+-- A re-verification job whose results could not all be recorded is marked
+-- 'failed' so it no longer holds the single-running-job slot.
+ALTER TABLE reverification_jobs
+    DROP CONSTRAINT IF EXISTS reverification_jobs_status_check;
+
+ALTER TABLE reverification_jobs
+    ADD CONSTRAINT reverification_jobs_status_check
+        CHECK (status IN ('running', 'completed', 'failed'));
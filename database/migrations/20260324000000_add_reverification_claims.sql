@@ -0,0 +1,6 @@
+-- Result rows are claimed by the process rebuilding them, so replicas that all
+-- resume the same job split its contracts instead of rebuilding each one.
+-- A claim older than the lease (its process died mid-build) may be taken over.
+ALTER TABLE reverification_results
+    ADD COLUMN IF NOT EXISTS claimed_by UUID,
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
@@ -0,0 +1,10 @@
+-- Pending verifications are rebuilt after a restart. `claimed_at` marks the
+-- process building the row (refreshed when its build starts) so a replica
+-- starting up only takes over builds whose owner is gone, and `callback_url`
+-- lets the resumed build still notify the submitter.
+ALTER TABLE verifications
+    ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS callback_url TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_verifications_pending
+    ON verifications(created_at) WHERE status = 'pending';
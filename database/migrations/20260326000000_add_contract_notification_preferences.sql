@@ -0,0 +1,11 @@
+-- What a contract's publisher wants to be notified about. A contract without
+-- a row receives every patch notice and webhook event.
+
+CREATE TABLE contract_notification_preferences (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    -- Lowest patch severity notified; NULL notifies every severity
+    min_severity patch_severity,
+    -- Webhook event types delivered; NULL delivers every event type
+    events TEXT[],
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
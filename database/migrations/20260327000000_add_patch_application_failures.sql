@@ -0,0 +1,7 @@
+-- Failed patch applications count against a rollout's failure-rate gate; a
+-- retry that succeeds overwrites the failure.
+ALTER TABLE patch_audits
+    ADD COLUMN failed BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN error TEXT;
+
+CREATE INDEX idx_patch_audits_patch_id_failed ON patch_audits(patch_id, failed);
@@ -0,0 +1,24 @@
+-- Archiving resumes after the (timestamp, id) of the newest archived entry,
+-- so entries sharing that timestamp are not skipped or archived twice.
+
+-- id of the newest archived entry. NULL on checkpoints written before this
+-- column, which cover every entry at archived_through.
+ALTER TABLE audit_log_archive_checkpoints
+    ADD COLUMN IF NOT EXISTS archived_through_id UUID;
+
+CREATE OR REPLACE FUNCTION enforce_append_only_audit_log()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE'
+       AND current_setting('audit_log.pruning', true) = 'on'
+       AND EXISTS (
+           SELECT 1 FROM audit_log_archive_checkpoints c
+            WHERE c.contract_id = OLD.contract_id
+              AND (OLD.timestamp, OLD.id) <= (c.archived_through,
+                   COALESCE(c.archived_through_id, 'ffffffff-ffff-ffff-ffff-ffffffffffff'::uuid))
+       ) THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'Updates and deletions are strictly prohibited on contract_audit_log to ensure immutability.';
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,14 @@
+-- A notice carries the severity it is delivered at. Escalating a patch
+-- raises the priority of its unacknowledged notices so receivers handle
+-- them ahead of lower-severity work.
+
+ALTER TABLE patch_notifications
+    ADD COLUMN priority patch_severity;
+
+UPDATE patch_notifications n
+SET priority = p.severity
+FROM security_patches p
+WHERE p.id = n.patch_id;
+
+ALTER TABLE patch_notifications
+    ALTER COLUMN priority SET NOT NULL;
@@ -86,6 +86,7 @@ export interface InteractionsListResponse {
   total: number;
   limit: number;
   offset: number;
+  is_trending: boolean;
 }
 
 /** Analytics timeline entry (one day) */